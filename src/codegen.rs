@@ -0,0 +1,334 @@
+use std::fmt;
+
+use crate::model::{
+    constraint::ForeignKey,
+    data_type, list_items,
+    naming::{self, Case},
+    Column, ConstraintKind, Project, Table, UserType, UserTypeKind,
+};
+
+pub mod diesel;
+pub mod parse;
+pub mod sea_orm;
+pub mod sqlx;
+
+/// Data access crates code can be generated for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Target {
+    #[default]
+    Sqlx,
+    Diesel,
+    SeaOrm,
+}
+
+impl Target {
+    pub const ALL: [Target; 3] = [Target::Sqlx, Target::Diesel, Target::SeaOrm];
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Target::Sqlx => "sqlx FromRow",
+            Target::Diesel => "diesel",
+            Target::SeaOrm => "SeaORM",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub target: Target,
+    /// Derive `Serialize` and `Deserialize` too.
+    pub serde: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            target: Target::default(),
+            serde: true,
+        }
+    }
+}
+
+/// Rust code for `tables` and the enums their columns use.
+pub fn generate(project: &Project, tables: &[usize], options: &Options) -> String {
+    let tables: Vec<&Table> = tables.iter().map(|&i| &project.tables[i]).collect();
+    let enums = enums(project, &tables);
+    match options.target {
+        Target::Sqlx => sqlx::generate(project, &tables, &enums, options.serde),
+        Target::Diesel => diesel::generate(project, &tables, &enums, options.serde),
+        Target::SeaOrm => sea_orm::generate(project, &tables, &enums, options.serde),
+    }
+}
+
+/// What a column type comes down to once domains are looked through.
+enum Base<'a> {
+    /// Canonical name of a built-in type.
+    Builtin(String),
+    Enum(&'a UserType),
+    Composite,
+}
+
+/// The base of `data_type` and how many array levels wrap it.
+fn resolve<'a>(project: &'a Project, schema: &str, data_type: &str) -> (Base<'a>, usize) {
+    let mut data_type = data_type.to_string();
+    let mut schema = schema.to_string();
+    let mut arrays = 0;
+    // Domains over domains, but not forever.
+    for _ in 0..8 {
+        arrays += data_type.matches("[]").count();
+        let Some(i) = project.resolve_type(&data_type, &schema) else {
+            break;
+        };
+        let ty = &project.types[i];
+        match &ty.kind {
+            UserTypeKind::Enum { .. } => return (Base::Enum(ty), arrays),
+            UserTypeKind::Composite { .. } => return (Base::Composite, arrays),
+            UserTypeKind::Domain {
+                data_type: base, ..
+            } => {
+                data_type = base.clone();
+                schema = ty.schema.clone();
+            }
+        }
+    }
+    let canonical = data_type::canonical(&data_type);
+    (
+        Base::Builtin(canonical.trim_end_matches("[]").to_string()),
+        arrays,
+    )
+}
+
+/// Rust type for values of `data_type`, without `Option`.
+fn rust_type(project: &Project, schema: &str, data_type: &str, target: Target) -> String {
+    let (base, arrays) = resolve(project, schema, data_type);
+    let mut rust = match base {
+        Base::Enum(ty) => enum_name(ty),
+        Base::Composite => String::from("String"),
+        Base::Builtin(name) => builtin(&name, target).to_string(),
+    };
+    for _ in 0..arrays {
+        rust = format!("Vec<{rust}>");
+    }
+    rust
+}
+
+fn builtin(name: &str, target: Target) -> &'static str {
+    let sea_orm = target == Target::SeaOrm;
+    match name {
+        "smallint" => "i16",
+        "integer" => "i32",
+        "bigint" => "i64",
+        "real" => "f32",
+        "double precision" => "f64",
+        "boolean" => "bool",
+        "numeric" => match target {
+            Target::Sqlx => "rust_decimal::Decimal",
+            Target::Diesel => "bigdecimal::BigDecimal",
+            Target::SeaOrm => "Decimal",
+        },
+        "bytea" => "Vec<u8>",
+        "uuid" if sea_orm => "Uuid",
+        "uuid" => "uuid::Uuid",
+        "timestamp with time zone" if sea_orm => "DateTimeWithTimeZone",
+        "timestamp with time zone" => "chrono::DateTime<chrono::Utc>",
+        "timestamp without time zone" if sea_orm => "DateTime",
+        "timestamp without time zone" => "chrono::NaiveDateTime",
+        "date" if sea_orm => "Date",
+        "date" => "chrono::NaiveDate",
+        "time without time zone" if sea_orm => "Time",
+        "time without time zone" => "chrono::NaiveTime",
+        "json" | "jsonb" if sea_orm => "Json",
+        "json" | "jsonb" => "serde_json::Value",
+        "interval" => match target {
+            Target::Sqlx => "sqlx::postgres::types::PgInterval",
+            Target::Diesel => "diesel::pg::data_types::PgInterval",
+            Target::SeaOrm => "String",
+        },
+        "money" => match target {
+            Target::Sqlx => "sqlx::postgres::types::PgMoney",
+            Target::Diesel => "diesel::data_types::Cents",
+            Target::SeaOrm => "Decimal",
+        },
+        "inet" | "cidr" if !sea_orm => "ipnetwork::IpNetwork",
+        "macaddr" => match target {
+            Target::Sqlx => "mac_address::MacAddress",
+            Target::Diesel => "[u8; 6]",
+            Target::SeaOrm => "String",
+        },
+        _ => "String",
+    }
+}
+
+/// The field type of a column: its override or mapped type, in an
+/// `Option` if it can be NULL.
+fn column_type(project: &Project, table: &Table, column: &Column, target: Target) -> String {
+    let rust = column
+        .rust_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|rust| !rust.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| rust_type(project, &table.schema, &column.data_type, target));
+    if nullable(table, column) {
+        format!("Option<{rust}>")
+    } else {
+        rust
+    }
+}
+
+/// The Rust type a column maps to without an override, as shown next to
+/// the override.
+pub fn mapped_type(project: &Project, table: &Table, column: &Column, target: Target) -> String {
+    let rust = rust_type(project, &table.schema, &column.data_type, target);
+    if nullable(table, column) {
+        format!("Option<{rust}>")
+    } else {
+        rust
+    }
+}
+
+fn nullable(table: &Table, column: &Column) -> bool {
+    !column.not_null
+        && column.identity.is_none()
+        && !primary_key(table).contains(&column.name.as_str())
+}
+
+fn primary_key(table: &Table) -> Vec<&str> {
+    match table.primary_key().map(|pk| &pk.kind) {
+        Some(ConstraintKind::PrimaryKey { columns, .. }) => list_items(columns).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Enums the columns of `tables` use, directly, in arrays or through
+/// domains, in model order.
+fn enums<'a>(project: &'a Project, tables: &[&Table]) -> Vec<&'a UserType> {
+    let mut enums: Vec<&UserType> = Vec::new();
+    for table in tables {
+        for column in &table.columns {
+            if let (Base::Enum(ty), _) = resolve(project, &table.schema, &column.data_type)
+                && !enums.iter().any(|e| std::ptr::eq(*e, ty))
+            {
+                enums.push(ty);
+            }
+        }
+    }
+    enums.sort_by_key(|ty| project.types.iter().position(|t| std::ptr::eq(t, *ty)));
+    enums
+}
+
+/// Foreign keys of `table` as its columns, the referenced table, the
+/// referenced columns, which are its primary key if the key names none, and
+/// the key itself.
+fn foreign_keys<'a>(
+    project: &'a Project,
+    table: &'a Table,
+) -> Vec<(Vec<&'a str>, &'a Table, Vec<&'a str>, &'a ForeignKey)> {
+    table
+        .constraints
+        .iter()
+        .filter_map(|constraint| match &constraint.kind {
+            ConstraintKind::ForeignKey(fk) => {
+                let parent = project.table(&fk.ref_schema, &fk.ref_table)?;
+                let mut ref_columns: Vec<&str> = list_items(&fk.ref_columns).collect();
+                if ref_columns.is_empty() {
+                    ref_columns = primary_key(parent);
+                }
+                Some((list_items(&fk.columns).collect(), parent, ref_columns, fk))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn struct_name(table: &Table) -> String {
+    Case::Pascal.convert(&naming::singular_name(&table.name))
+}
+
+fn enum_name(ty: &UserType) -> String {
+    ident(Case::Pascal.convert(&ty.name))
+}
+
+/// The name of a table or column as a Rust identifier.
+fn field_name(name: &str) -> String {
+    ident(Case::Snake.convert(name))
+}
+
+/// Enum variants for the labels, unique and valid as identifiers.
+fn variants(labels: &[String]) -> Vec<(String, &str)> {
+    let mut variants: Vec<(String, &str)> = Vec::with_capacity(labels.len());
+    for label in labels {
+        let mut variant = Case::Pascal.convert(label);
+        if variant.is_empty() {
+            variant = String::from("Empty");
+        } else if variant.starts_with(|c: char| c.is_ascii_digit()) {
+            variant.insert(0, 'V');
+        }
+        let mut unique = variant.clone();
+        let mut n = 2;
+        while variants.iter().any(|(v, _)| *v == unique) {
+            unique = format!("{variant}{n}");
+            n += 1;
+        }
+        variants.push((ident(unique), label));
+    }
+    variants
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// `name` made a valid identifier, raw if it is a keyword.
+fn ident(name: String) -> String {
+    if name.is_empty() {
+        String::from("field")
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if matches!(name.as_str(), "self" | "Self" | "super" | "crate") {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
+
+/// The identifier without `r#`, as derive macros name things after it.
+fn unraw(ident: &str) -> &str {
+    ident.strip_prefix("r#").unwrap_or(ident)
+}
+
+/// `#[derive(...)]` of `derives`, with serde's added.
+fn derive(derives: &[&str], serde: bool) -> String {
+    let mut derives = derives.to_vec();
+    if serde {
+        derives.extend(["Serialize", "Deserialize"]);
+    }
+    format!("#[derive({})]", derives.join(", "))
+}
+
+/// `///` lines of a comment, indented.
+fn doc(comment: Option<&str>, indent: &str) -> String {
+    comment
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty())
+        .map(|comment| {
+            comment
+                .lines()
+                .map(|line| format!("{indent}/// {}\n", line.trim_end()).replace("///  ", "/// "))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A Rust string literal.
+fn literal(value: &str) -> String {
+    format!("{value:?}")
+}
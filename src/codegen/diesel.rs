@@ -0,0 +1,243 @@
+use super::{
+    column_type, derive, doc, enum_name, field_name, foreign_keys, literal, nullable, primary_key,
+    resolve, struct_name, variants, Base, Target,
+};
+use crate::model::{Project, Table, UserType, UserTypeKind, DEFAULT_SCHEMA};
+
+/// A `schema.rs` with a `table!` per table and a `models.rs` with structs
+/// deriving `Queryable`, `Selectable` and `Insertable`.
+pub fn generate(project: &Project, tables: &[&Table], enums: &[&UserType], serde: bool) -> String {
+    let mut out = String::from("// schema.rs\n");
+    if !enums.is_empty() {
+        out.push_str("\npub mod sql_types {\n");
+        for (i, ty) in enums.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(
+                "    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]\n",
+            );
+            if ty.schema == DEFAULT_SCHEMA {
+                out.push_str(&format!(
+                    "    #[diesel(postgres_type(name = {}))]\n",
+                    literal(&ty.name)
+                ));
+            } else {
+                out.push_str(&format!(
+                    "    #[diesel(postgres_type(name = {}, schema = {}))]\n",
+                    literal(&ty.name),
+                    literal(&ty.schema)
+                ));
+            }
+            out.push_str(&format!("    pub struct {};\n", enum_name(ty)));
+        }
+        out.push_str("}\n");
+    }
+
+    // diesel can't do without a primary key.
+    let (tables, skipped): (Vec<&Table>, Vec<&Table>) = tables
+        .iter()
+        .partition(|table| !primary_key(table).is_empty());
+    for table in &skipped {
+        out.push_str(&format!(
+            "\n// {}.{} is left out as it has no primary key\n",
+            table.schema, table.name
+        ));
+    }
+
+    for table in &tables {
+        let table_name = diesel_name(&table.name);
+        let mut types: Vec<String> = Vec::new();
+        let columns: Vec<(String, String)> = table
+            .columns
+            .iter()
+            .map(|column| {
+                let mut sql = sql_type(project, &table.schema, &column.data_type, &mut types);
+                if nullable(table, column) {
+                    sql = format!("Nullable<{sql}>");
+                }
+                (diesel_name(&column.name), sql)
+            })
+            .collect();
+
+        out.push_str("\ndiesel::table! {\n    use diesel::sql_types::*;\n");
+        for ty in &types {
+            out.push_str(&format!("    use super::sql_types::{ty};\n"));
+        }
+        out.push('\n');
+        out.push_str(&doc(table.comment.as_deref(), "    "));
+        if table_name != table.name {
+            out.push_str(&format!("    #[sql_name = {}]\n", literal(&table.name)));
+        }
+        let keys: Vec<String> = primary_key(table).into_iter().map(diesel_name).collect();
+        let path = if table.schema == DEFAULT_SCHEMA {
+            table_name.clone()
+        } else {
+            format!("{}.{table_name}", table.schema)
+        };
+        out.push_str(&format!("    {path} ({}) {{\n", keys.join(", ")));
+        for (column, (field, sql)) in table.columns.iter().zip(&columns) {
+            out.push_str(&doc(column.comment.as_deref(), "        "));
+            if *field != column.name {
+                out.push_str(&format!(
+                    "        #[sql_name = {}]\n",
+                    literal(&column.name)
+                ));
+            }
+            out.push_str(&format!("        {field} -> {sql},\n"));
+        }
+        out.push_str("    }\n}\n");
+    }
+
+    // Only single column keys, and one per pair of tables.
+    let mut joins: Vec<(String, String, String)> = Vec::new();
+    for table in &tables {
+        for (columns, parent, _, _) in foreign_keys(project, table) {
+            let join = (
+                diesel_name(&table.name),
+                diesel_name(&parent.name),
+                columns
+                    .first()
+                    .map(|column| diesel_name(column))
+                    .unwrap_or_default(),
+            );
+            if columns.len() == 1
+                && !std::ptr::eq(*table, parent)
+                && tables.iter().any(|t| std::ptr::eq(*t, parent))
+                && !joins.iter().any(|(c, p, _)| *c == join.0 && *p == join.1)
+            {
+                joins.push(join);
+            }
+        }
+    }
+    if !joins.is_empty() {
+        out.push('\n');
+    }
+    for (child, parent, column) in &joins {
+        out.push_str(&format!(
+            "diesel::joinable!({child} -> {parent} ({column}));\n"
+        ));
+    }
+    if tables.len() > 1 {
+        let names: Vec<String> = tables
+            .iter()
+            .map(|table| diesel_name(&table.name))
+            .collect();
+        out.push_str(&format!(
+            "\ndiesel::allow_tables_to_appear_in_same_query!({});\n",
+            names.join(", ")
+        ));
+    }
+
+    out.push_str("\n// models.rs\n\nuse diesel::prelude::*;\n");
+    if serde {
+        out.push_str("use serde::{Deserialize, Serialize};\n");
+    }
+    for ty in enums {
+        let UserTypeKind::Enum { labels } = &ty.kind else {
+            continue;
+        };
+        let name = enum_name(ty);
+        out.push('\n');
+        out.push_str(&doc(ty.comment.as_deref(), ""));
+        out.push_str(&derive(
+            &[
+                "Debug",
+                "Clone",
+                "Copy",
+                "PartialEq",
+                "Eq",
+                "diesel_derive_enum::DbEnum",
+            ],
+            serde,
+        ));
+        out.push_str(&format!(
+            "\n#[ExistingTypePath = \"crate::schema::sql_types::{name}\"]\npub enum {name} {{\n"
+        ));
+        for (variant, label) in variants(labels) {
+            out.push_str(&format!("    #[db_rename = {}]\n", literal(label)));
+            if serde {
+                out.push_str(&format!("    #[serde(rename = {})]\n", literal(label)));
+            }
+            out.push_str(&format!("    {variant},\n"));
+        }
+        out.push_str("}\n");
+    }
+    for table in &tables {
+        out.push('\n');
+        out.push_str(&doc(table.comment.as_deref(), ""));
+        out.push_str(&derive(
+            &["Debug", "Clone", "Queryable", "Selectable", "Insertable"],
+            serde,
+        ));
+        out.push_str(&format!(
+            "\n#[diesel(table_name = crate::schema::{})]\n#[diesel(check_for_backend(diesel::pg::Pg))]\npub struct {} {{\n",
+            diesel_name(&table.name),
+            struct_name(table)
+        ));
+        for column in &table.columns {
+            out.push_str(&doc(column.comment.as_deref(), "    "));
+            out.push_str(&format!(
+                "    pub {}: {},\n",
+                diesel_name(&column.name),
+                column_type(project, table, column, Target::Diesel)
+            ));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// The diesel SQL type of `data_type`, noting the enums it uses in `types`.
+fn sql_type(project: &Project, schema: &str, data_type: &str, types: &mut Vec<String>) -> String {
+    let (base, arrays) = resolve(project, schema, data_type);
+    let mut sql = match base {
+        Base::Enum(ty) => {
+            let name = enum_name(ty);
+            if !types.contains(&name) {
+                types.push(name.clone());
+            }
+            name
+        }
+        Base::Composite => String::from("Text"),
+        Base::Builtin(name) => String::from(match name.as_str() {
+            "smallint" => "Int2",
+            "integer" => "Int4",
+            "bigint" => "Int8",
+            "real" => "Float4",
+            "double precision" => "Float8",
+            "numeric" => "Numeric",
+            "boolean" => "Bool",
+            "character varying" => "Varchar",
+            "character" => "Bpchar",
+            "bytea" => "Bytea",
+            "uuid" => "Uuid",
+            "timestamp with time zone" => "Timestamptz",
+            "timestamp without time zone" => "Timestamp",
+            "date" => "Date",
+            "time without time zone" => "Time",
+            "interval" => "Interval",
+            "json" => "Json",
+            "jsonb" => "Jsonb",
+            "inet" => "Inet",
+            "cidr" => "Cidr",
+            "macaddr" => "MacAddr",
+            "money" => "Money",
+            _ => "Text",
+        }),
+    };
+    for _ in 0..arrays {
+        sql = format!("Array<{sql}>");
+    }
+    sql
+}
+
+/// A table or column name as diesel wants it, which takes a trailing
+/// underscore rather than a raw identifier for keywords.
+fn diesel_name(name: &str) -> String {
+    let name = field_name(name);
+    match name.strip_prefix("r#") {
+        Some(keyword) => format!("{keyword}_"),
+        None => name,
+    }
+}
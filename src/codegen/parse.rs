@@ -0,0 +1,1137 @@
+use crate::{
+    model::{
+        constraint::ForeignKey, data_type, naming::Case, Column, Constraint, ConstraintKind,
+        ConstraintType, Project, ReferentialAction, Table, UserType, UserTypeKind, DEFAULT_SCHEMA,
+    },
+    sql::parse::{Parsed, Skipped},
+};
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Reads the tables of a diesel `schema.rs`: its `table!` macros, the
+/// custom SQL types in `sql_types` and the foreign keys `joinable!` gives.
+pub fn diesel(source: &str) -> Parsed {
+    let tokens = tokenize(source);
+    let mut reader = Diesel::default();
+    reader.items(Cursor::new(&tokens));
+    reader.finish()
+}
+
+/// Reads SeaORM entities, as sea-orm-cli generates them into a directory
+/// with a file per table. `files` are the names and contents of the files,
+/// whose names without `.rs` are the modules relations refer to.
+pub fn sea_orm(files: &[(String, String)]) -> Parsed {
+    let mut reader = SeaOrm::default();
+    for (name, source) in files {
+        let tokens = tokenize(source);
+        let module = name.strip_suffix(".rs").unwrap_or(name);
+        reader.items(Cursor::new(&tokens), module);
+    }
+    reader.finish()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// An identifier or keyword, raw ones without their `r#`.
+    Ident(String),
+    /// The value of a string literal.
+    Str(String),
+    Number(String),
+    Punct(char),
+    /// The text of a `///` comment.
+    Doc(String),
+}
+
+/// Splits Rust source into tokens, dropping comments other than doc
+/// comments as well as char literals and lifetimes.
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && at(i + 1) == '/' {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == '\n')
+                .unwrap_or(chars.len());
+            let line: String = chars[i..end].iter().collect();
+            if let Some(doc) = line.strip_prefix("///")
+                && !doc.starts_with('/')
+            {
+                tokens.push(Token::Doc(doc.trim().to_string()));
+            }
+            i = end;
+        } else if c == '/' && at(i + 1) == '*' {
+            i = (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && at(j + 1) == '/')
+                .map_or(chars.len(), |j| j + 2);
+        } else if c == 'r' && (at(i + 1) == '"' || (at(i + 1) == '#' && !is_ident(at(i + 2)))) {
+            // r"..." and r#"..."#
+            let hashes = (i + 1..chars.len())
+                .take_while(|&j| chars[j] == '#')
+                .count();
+            let start = i + 2 + hashes;
+            let close: String = std::iter::once('"')
+                .chain(std::iter::repeat_n('#', hashes))
+                .collect();
+            let rest: String = chars.get(start..).unwrap_or_default().iter().collect();
+            let end = rest.find(&close).unwrap_or(rest.len());
+            tokens.push(Token::Str(rest[..end].to_string()));
+            i = start + rest[..end].chars().count() + close.chars().count();
+        } else if c == 'r' && at(i + 1) == '#' {
+            let end = (i + 2..chars.len())
+                .find(|&j| !is_ident(chars[j]))
+                .unwrap_or(chars.len());
+            tokens.push(Token::Ident(chars[i + 2..end].iter().collect()));
+            i = end;
+        } else if c == 'b' && at(i + 1) == '"' {
+            i += 1;
+        } else if c == '"' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                    match at(i) {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        '\n' => {
+                            while at(i + 1).is_whitespace() {
+                                i += 1;
+                            }
+                        }
+                        other => value.push(other),
+                    }
+                } else {
+                    value.push(chars[i]);
+                }
+                i += 1;
+            }
+            tokens.push(Token::Str(value));
+            i += 1;
+        } else if c == '\'' {
+            // 'x', '\n' or a lifetime.
+            if at(i + 2) == '\'' {
+                i += 3;
+            } else if at(i + 1) == '\\' {
+                i = (i + 2..chars.len())
+                    .find(|&j| chars[j] == '\'')
+                    .map_or(chars.len(), |j| j + 1);
+            } else {
+                i = (i + 1..chars.len())
+                    .find(|&j| !is_ident(chars[j]))
+                    .unwrap_or(chars.len());
+            }
+        } else if c.is_ascii_digit() {
+            let end = (i..chars.len())
+                .find(|&j| !is_ident(chars[j]))
+                .unwrap_or(chars.len());
+            tokens.push(Token::Number(chars[i..end].iter().collect()));
+            i = end;
+        } else if is_ident(c) {
+            let end = (i..chars.len())
+                .find(|&j| !is_ident(chars[j]))
+                .unwrap_or(chars.len());
+            tokens.push(Token::Ident(chars[i..end].iter().collect()));
+            i = end;
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+#[derive(Clone, Copy)]
+struct Cursor<'a> {
+    tokens: &'a [Token],
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, at: 0 }
+    }
+
+    fn done(&self) -> bool {
+        self.at >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.at);
+        self.at += 1;
+        token
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let eaten = self.is_punct(c);
+        if eaten {
+            self.at += 1;
+        }
+        eaten
+    }
+
+    fn eat_path_separator(&mut self) -> bool {
+        let eaten = self.is_punct(':') && self.tokens.get(self.at + 1) == Some(&Token::Punct(':'));
+        if eaten {
+            self.at += 2;
+        }
+        eaten
+    }
+
+    fn eat_ident(&mut self, word: &str) -> bool {
+        let eaten = matches!(self.peek(), Some(Token::Ident(ident)) if ident == word);
+        if eaten {
+            self.at += 1;
+        }
+        eaten
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                self.at += 1;
+                Some(ident.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(format!("Expected {c} {}", self.position()))
+        }
+    }
+
+    /// The tokens inside the bracket the cursor is at, moving past its
+    /// closing one.
+    fn group(&mut self) -> Option<Cursor<'a>> {
+        if !matches!(self.peek(), Some(Token::Punct('(' | '[' | '{'))) {
+            return None;
+        }
+        let start = self.at + 1;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(Cursor::new(&self.tokens[start..self.at - 1]));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(Cursor::new(&self.tokens[start..]))
+    }
+
+    /// Skips a token, or a whole group if it is at one.
+    fn skip(&mut self) {
+        if self.group().is_none() {
+            self.at += 1;
+        }
+    }
+
+    /// Skips to the next `c` outside of groups and past it.
+    fn skip_past(&mut self, c: char) {
+        while !self.done() && !self.eat_punct(c) {
+            self.skip();
+        }
+    }
+
+    /// Where the cursor is, to say where something went wrong.
+    fn position(&self) -> String {
+        match self.excerpt() {
+            excerpt if excerpt.is_empty() => String::from("at the end"),
+            excerpt => format!("at {excerpt}"),
+        }
+    }
+
+    /// The next few tokens.
+    fn excerpt(&self) -> String {
+        let tokens: Vec<String> = self.tokens[self.at.min(self.tokens.len())..]
+            .iter()
+            .take(8)
+            .map(|token| match token {
+                Token::Ident(text) | Token::Number(text) => text.clone(),
+                Token::Str(text) => format!("{text:?}"),
+                Token::Punct(c) => c.to_string(),
+                Token::Doc(_) => String::from("///"),
+            })
+            .collect();
+        tokens.join(" ")
+    }
+
+    /// Doc comments and outer attributes before an item.
+    fn attributes(&mut self) -> (Vec<String>, Vec<Setting>) {
+        let mut docs = Vec::new();
+        let mut attributes = Vec::new();
+        loop {
+            if let Some(Token::Doc(doc)) = self.peek() {
+                docs.push(doc.clone());
+                self.at += 1;
+            } else if self.is_punct('#') {
+                self.at += 1;
+                let inner = self.eat_punct('!');
+                if let Some(group) = self.group()
+                    && !inner
+                {
+                    attributes.extend(settings(group));
+                }
+            } else {
+                return (docs, attributes);
+            }
+        }
+    }
+
+    /// Skips `pub` and `pub(crate)`.
+    fn visibility(&mut self) {
+        if self.eat_ident("pub") && self.is_punct('(') {
+            self.group();
+        }
+    }
+}
+
+/// `key`, `key = value` or `key(...)`, as attributes are written.
+#[derive(Clone, Debug)]
+struct Setting {
+    key: String,
+    value: Value,
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    None,
+    Text(String),
+    List(Vec<Setting>),
+}
+
+/// The comma separated settings in an attribute. Paths count by their last
+/// segment.
+fn settings(mut cursor: Cursor) -> Vec<Setting> {
+    let mut read = Vec::new();
+    while !cursor.done() {
+        let Some(mut key) = cursor.ident() else {
+            cursor.skip();
+            continue;
+        };
+        while cursor.eat_path_separator() {
+            if let Some(segment) = cursor.ident() {
+                key = segment;
+            }
+        }
+        let value = if cursor.eat_punct('=') {
+            match cursor.next() {
+                Some(Token::Str(text) | Token::Number(text) | Token::Ident(text)) => {
+                    Value::Text(text.clone())
+                }
+                _ => Value::None,
+            }
+        } else if let Some(group) = cursor.group() {
+            Value::List(settings(group))
+        } else {
+            Value::None
+        };
+        read.push(Setting { key, value });
+        cursor.skip_past(',');
+    }
+    read
+}
+
+fn setting<'a>(settings: &'a [Setting], key: &str) -> Option<&'a Value> {
+    settings
+        .iter()
+        .find(|setting| setting.key == key)
+        .map(|setting| &setting.value)
+}
+
+fn text<'a>(settings: &'a [Setting], key: &str) -> Option<&'a str> {
+    match setting(settings, key) {
+        Some(Value::Text(text)) => Some(text),
+        _ => None,
+    }
+}
+
+fn list<'a>(settings: &'a [Setting], key: &str) -> &'a [Setting] {
+    match setting(settings, key) {
+        Some(Value::List(list)) => list,
+        _ => &[],
+    }
+}
+
+/// A type by the last segment of its path, with its generic arguments.
+#[derive(Clone, Debug)]
+struct Ty {
+    name: String,
+    args: Vec<Ty>,
+}
+
+fn ty(cursor: &mut Cursor) -> Result<Ty> {
+    if cursor.eat_punct('&') {
+        cursor.eat_ident("mut");
+    }
+    if cursor.is_punct('[') {
+        let mut group = cursor.group().unwrap_or(Cursor::new(&[]));
+        let element = ty(&mut group)?;
+        return Ok(Ty {
+            name: String::from("[]"),
+            args: vec![element],
+        });
+    }
+    if cursor.is_punct('(') {
+        cursor.group();
+        return Ok(Ty {
+            name: String::from("()"),
+            args: Vec::new(),
+        });
+    }
+    let Some(mut name) = cursor.ident() else {
+        return Err(format!("Expected a type {}", cursor.position()));
+    };
+    while cursor.eat_path_separator() {
+        name = cursor
+            .ident()
+            .ok_or_else(|| format!("Expected a type {}", cursor.position()))?;
+    }
+    let mut args = Vec::new();
+    if cursor.eat_punct('<') {
+        loop {
+            args.push(ty(cursor)?);
+            if cursor.eat_punct('>') {
+                break;
+            }
+            cursor.expect(',')?;
+        }
+    }
+    Ok(Ty { name, args })
+}
+
+fn comment(docs: &[String]) -> Option<String> {
+    (!docs.is_empty()).then(|| docs.join("\n"))
+}
+
+fn primary_key(table: &Table, columns: Vec<String>) -> Constraint {
+    Constraint::new(
+        table.constraint_name(ConstraintType::PrimaryKey),
+        ConstraintKind::PrimaryKey {
+            columns,
+            include: Vec::new(),
+        },
+    )
+}
+
+#[derive(Default)]
+struct Diesel {
+    project: Project,
+    skipped: Vec<Skipped>,
+    /// Rust names of the structs in `sql_types` and the PostgreSQL types
+    /// they stand for.
+    sql_types: Vec<(String, String)>,
+    /// `table!` names of the tables read, by index.
+    tables: Vec<String>,
+    /// Column names of the tables by their `table!` names.
+    fields: Vec<Vec<(String, String)>>,
+    /// Child, parent and column of each `joinable!`.
+    joins: Vec<(String, String, String)>,
+}
+
+impl Diesel {
+    fn items(&mut self, mut cursor: Cursor) {
+        while !cursor.done() {
+            let (_, attributes) = cursor.attributes();
+            cursor.visibility();
+            if cursor.eat_ident("mod") {
+                cursor.ident();
+                if let Some(group) = cursor.group() {
+                    self.items(group);
+                }
+            } else if cursor.eat_ident("struct") {
+                let Some(name) = cursor.ident() else {
+                    continue;
+                };
+                let postgres_type = list(list(&attributes, "diesel"), "postgres_type");
+                if let Some(type_name) = text(postgres_type, "name") {
+                    let schema = text(postgres_type, "schema").unwrap_or(DEFAULT_SCHEMA);
+                    self.sql_type(&name, schema, type_name);
+                }
+                cursor.skip_past(';');
+            } else if let Some(mut name) = cursor.ident() {
+                while cursor.eat_path_separator() {
+                    name = cursor.ident().unwrap_or_default();
+                }
+                if !cursor.eat_punct('!') {
+                    continue;
+                }
+                let Some(group) = cursor.group() else {
+                    continue;
+                };
+                let result = match name.as_str() {
+                    "table" => self.table(group),
+                    "joinable" => self.joinable(group),
+                    _ => Ok(()),
+                };
+                if let Err(reason) = result {
+                    self.skipped.push(Skipped {
+                        statement: format!("{name}! {{ {} }}", group.excerpt()),
+                        reason,
+                    });
+                }
+            } else {
+                cursor.skip();
+            }
+        }
+    }
+
+    /// A type from `sql_types`. diesel only knows its name, so enums come
+    /// without their labels.
+    fn sql_type(&mut self, rust: &str, schema: &str, name: &str) {
+        let mut ty = UserType::new(schema, name, UserTypeKind::Enum { labels: Vec::new() });
+        ty.comment = Some(String::from(
+            "Imported from a diesel schema, which does not list the labels",
+        ));
+        self.sql_types
+            .push((rust.to_string(), Project::type_name(&ty)));
+        if self.project.user_type(schema, name).is_none() {
+            self.project.types.push(ty);
+        }
+    }
+
+    fn table(&mut self, mut cursor: Cursor) -> Result<()> {
+        let (docs, attributes) = loop {
+            let (docs, attributes) = cursor.attributes();
+            if cursor.eat_ident("use") {
+                cursor.skip_past(';');
+            } else {
+                break (docs, attributes);
+            }
+        };
+        let mut ident = cursor
+            .ident()
+            .ok_or_else(|| format!("Expected a table name {}", cursor.position()))?;
+        let mut schema = String::from(DEFAULT_SCHEMA);
+        if cursor.eat_punct('.') {
+            schema = ident;
+            ident = cursor
+                .ident()
+                .ok_or_else(|| format!("Expected a table name {}", cursor.position()))?;
+        }
+        let name = text(&attributes, "sql_name").unwrap_or(&ident);
+        let mut table = Table::new(schema, name);
+        table.comment = comment(&docs);
+
+        // The primary key is `id` unless listed.
+        let mut keys = Vec::new();
+        if cursor.is_punct('(')
+            && let Some(mut group) = cursor.group()
+        {
+            while let Some(key) = group.ident() {
+                keys.push(key);
+                group.eat_punct(',');
+            }
+        }
+        if keys.is_empty() {
+            keys.push(String::from("id"));
+        }
+        let mut body = cursor
+            .group()
+            .ok_or_else(|| format!("Expected the columns {}", cursor.position()))?;
+
+        let mut fields = Vec::new();
+        while !body.done() {
+            let (docs, attributes) = body.attributes();
+            let field = body
+                .ident()
+                .ok_or_else(|| format!("Expected a column {}", body.position()))?;
+            body.expect('-')?;
+            body.expect('>')?;
+            let sql = ty(&mut body)?;
+            body.eat_punct(',');
+
+            let name = text(&attributes, "sql_name").unwrap_or(&field).to_string();
+            let (data_type, nullable) = self.data_type(&sql, text(&attributes, "max_length"));
+            let mut column = Column::new(&name, data_type);
+            column.not_null = !nullable || keys.contains(&field);
+            column.comment = comment(&docs);
+            fields.push((field, name));
+            table.columns.push(column);
+        }
+
+        let columns = keys
+            .iter()
+            .map(|key| {
+                fields
+                    .iter()
+                    .find(|(field, _)| field == key)
+                    .map(|(_, name)| name.clone())
+                    .ok_or_else(|| format!("The primary key column {key} is not in the table"))
+            })
+            .collect::<Result<Vec<String>>>()?;
+        table.constraints.push(primary_key(&table, columns));
+
+        self.tables.push(ident);
+        self.fields.push(fields);
+        self.project.tables.push(table);
+        Ok(())
+    }
+
+    /// The PostgreSQL type of a diesel SQL type and whether it is nullable.
+    fn data_type(&self, ty: &Ty, max_length: Option<&str>) -> (String, bool) {
+        let inner = || ty.args.first().map(|arg| self.data_type(arg, max_length));
+        match ty.name.as_str() {
+            "Nullable" => (inner().map(|(inner, _)| inner).unwrap_or_default(), true),
+            "Array" => (
+                inner().map(|(inner, _)| inner).unwrap_or_default() + "[]",
+                false,
+            ),
+            name => {
+                if let Some((_, data_type)) = self.sql_types.iter().find(|(rust, _)| rust == name) {
+                    return (data_type.clone(), false);
+                }
+                let data_type = match name {
+                    "Int2" | "SmallInt" => "smallint",
+                    "Int4" | "Integer" => "integer",
+                    "Int8" | "BigInt" => "bigint",
+                    "Float4" | "Float" => "real",
+                    "Float8" | "Double" => "double precision",
+                    "Numeric" | "Decimal" => "numeric",
+                    "Bool" => "boolean",
+                    "Varchar" | "VarChar" => {
+                        return (
+                            max_length.map_or(String::from("varchar"), |n| format!("varchar({n})")),
+                            false,
+                        );
+                    }
+                    "Bpchar" | "Char" => {
+                        return (
+                            max_length.map_or(String::from("char"), |n| format!("char({n})")),
+                            false,
+                        );
+                    }
+                    "Bytea" | "Binary" | "Blob" => "bytea",
+                    "Timestamptz" => "timestamptz",
+                    "Timestamp" => "timestamp",
+                    "MacAddr" => "macaddr",
+                    "Int4range" => "int4range",
+                    "Int8range" => "int8range",
+                    "Tsrange" => "tsrange",
+                    "Tstzrange" => "tstzrange",
+                    "Daterange" => "daterange",
+                    "Numrange" => "numrange",
+                    // Uuid, Date, Time, Interval, Json, Jsonb, Inet, Cidr,
+                    // Money, Oid, Citext, Text and the like.
+                    other => return (other.to_lowercase(), false),
+                };
+                (data_type.to_string(), false)
+            }
+        }
+    }
+
+    fn joinable(&mut self, mut cursor: Cursor) -> Result<()> {
+        let child = cursor
+            .ident()
+            .ok_or_else(|| format!("Expected a table {}", cursor.position()))?;
+        cursor.expect('-')?;
+        cursor.expect('>')?;
+        let parent = cursor
+            .ident()
+            .ok_or_else(|| format!("Expected a table {}", cursor.position()))?;
+        let column = cursor
+            .group()
+            .and_then(|mut group| group.ident())
+            .ok_or_else(|| format!("Expected a column {}", cursor.position()))?;
+        self.joins.push((child, parent, column));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Parsed {
+        for (child, parent, field) in std::mem::take(&mut self.joins) {
+            let find = |name: &str| self.tables.iter().position(|table| table == name);
+            let (Some(c), Some(p)) = (find(&child), find(&parent)) else {
+                self.skipped.push(Skipped {
+                    statement: format!("joinable!({child} -> {parent} ({field}))"),
+                    reason: String::from("The tables are not in the schema"),
+                });
+                continue;
+            };
+            let Some((_, column)) = self.fields[c].iter().find(|(f, _)| *f == field) else {
+                self.skipped.push(Skipped {
+                    statement: format!("joinable!({child} -> {parent} ({field}))"),
+                    reason: format!("{child} has no column {field}"),
+                });
+                continue;
+            };
+            let parent = &self.project.tables[p];
+            let ref_columns = match parent.primary_key().map(|pk| &pk.kind) {
+                Some(ConstraintKind::PrimaryKey { columns, .. }) => columns.clone(),
+                _ => Vec::new(),
+            };
+            let fk = ForeignKey {
+                columns: vec![column.clone()],
+                ref_schema: parent.schema.clone(),
+                ref_table: parent.name.clone(),
+                ref_columns,
+                ..ForeignKey::default()
+            };
+            let table = &mut self.project.tables[c];
+            let constraint = Constraint::new(
+                table.column_constraint_name(ConstraintType::ForeignKey, &fk.columns),
+                ConstraintKind::ForeignKey(fk),
+            );
+            table.constraints.push(constraint);
+        }
+        Parsed {
+            project: self.project,
+            skipped: self.skipped,
+        }
+    }
+}
+
+/// A `Model` struct as read, before its types are known.
+struct Entity {
+    module: String,
+    schema: String,
+    name: String,
+    comment: Option<String>,
+    fields: Vec<Field>,
+    /// The settings of the `belongs_to` variants of its `Relation`.
+    relations: Vec<Vec<Setting>>,
+}
+
+struct Field {
+    name: String,
+    ty: Ty,
+    settings: Vec<Setting>,
+    comment: Option<String>,
+}
+
+#[derive(Default)]
+struct SeaOrm {
+    entities: Vec<Entity>,
+    /// Rust names of active enums and their types.
+    enums: Vec<(String, UserType)>,
+    /// The `belongs_to` relations of each module, given to its entity once
+    /// all are read.
+    relations: Vec<(String, Vec<Vec<Setting>>)>,
+    skipped: Vec<Skipped>,
+}
+
+impl SeaOrm {
+    fn items(&mut self, mut cursor: Cursor, module: &str) {
+        while !cursor.done() {
+            let (docs, attributes) = cursor.attributes();
+            cursor.visibility();
+            if cursor.eat_ident("mod") {
+                let Some(name) = cursor.ident() else {
+                    continue;
+                };
+                if let Some(group) = cursor.group() {
+                    self.items(group, &name);
+                }
+            } else if cursor.eat_ident("struct") {
+                let Some(name) = cursor.ident() else {
+                    continue;
+                };
+                let Some(body) = cursor.group() else {
+                    continue;
+                };
+                if name != "Model" {
+                    continue;
+                }
+                let settings = list(&attributes, "sea_orm");
+                let Some(table_name) = text(settings, "table_name") else {
+                    self.skipped.push(Skipped {
+                        statement: format!("{module}::Model"),
+                        reason: String::from("It has no #[sea_orm(table_name = ...)]"),
+                    });
+                    continue;
+                };
+                match fields(body) {
+                    Ok(fields) => self.entities.push(Entity {
+                        module: module.to_string(),
+                        schema: text(settings, "schema_name")
+                            .unwrap_or(DEFAULT_SCHEMA)
+                            .to_string(),
+                        name: table_name.to_string(),
+                        comment: comment(&docs),
+                        fields,
+                        relations: Vec::new(),
+                    }),
+                    Err(reason) => self.skipped.push(Skipped {
+                        statement: format!("{module}::Model"),
+                        reason,
+                    }),
+                }
+            } else if cursor.eat_ident("enum") {
+                let Some(name) = cursor.ident() else {
+                    continue;
+                };
+                let Some(body) = cursor.group() else {
+                    continue;
+                };
+                let variants = variants(body);
+                if name == "Relation" {
+                    let relations = variants
+                        .into_iter()
+                        .map(|(_, settings)| settings)
+                        .filter(|settings| setting(settings, "belongs_to").is_some())
+                        .collect();
+                    self.relations.push((module.to_string(), relations));
+                } else if let Some(enum_name) = text(list(&attributes, "sea_orm"), "enum_name") {
+                    let labels = variants
+                        .into_iter()
+                        .map(|(variant, settings)| {
+                            text(&settings, "string_value").map_or(variant, String::from)
+                        })
+                        .collect();
+                    let mut ty =
+                        UserType::new(DEFAULT_SCHEMA, enum_name, UserTypeKind::Enum { labels });
+                    ty.comment = comment(&docs);
+                    self.enums.push((name, ty));
+                }
+            } else {
+                cursor.skip();
+            }
+        }
+    }
+
+    fn finish(mut self) -> Parsed {
+        let mut project = Project::default();
+        for (_, ty) in &self.enums {
+            if project.user_type(&ty.schema, &ty.name).is_none() {
+                project.types.push(ty.clone());
+            }
+        }
+        for (module, relations) in std::mem::take(&mut self.relations) {
+            if let Some(entity) = self.entities.iter_mut().find(|e| e.module == module) {
+                entity.relations = relations;
+            }
+        }
+
+        for entity in &self.entities {
+            let mut table = Table::new(&entity.schema, &entity.name);
+            table.comment = entity.comment.clone();
+            let mut keys = Vec::new();
+            let mut unique = Vec::new();
+            for field in &entity.fields {
+                let settings = list(&field.settings, "sea_orm");
+                if setting(settings, "ignore").is_some() {
+                    continue;
+                }
+                let name = text(settings, "column_name").unwrap_or(&field.name);
+                let (ty, optional) = match field.ty.name.as_str() {
+                    "Option" if !field.ty.args.is_empty() => (&field.ty.args[0], true),
+                    _ => (&field.ty, false),
+                };
+                let mut data_type = text(settings, "column_type")
+                    .and_then(column_type)
+                    .unwrap_or_else(|| self.data_type(ty));
+                let key = setting(settings, "primary_key").is_some();
+                if key
+                    && text(settings, "auto_increment") != Some("false")
+                    && matches!(data_type.as_str(), "smallint" | "integer" | "bigint")
+                {
+                    data_type = data_type::serial_for(&data_type).to_string();
+                }
+                let mut column = Column::new(name, data_type);
+                column.not_null = !optional && setting(settings, "nullable").is_none();
+                column.comment = field.comment.clone();
+                if key {
+                    keys.push(name.to_string());
+                }
+                if setting(settings, "unique").is_some() {
+                    unique.push(name.to_string());
+                }
+                table.columns.push(column);
+            }
+            if !keys.is_empty() {
+                table.constraints.push(primary_key(&table, keys));
+            }
+            for column in unique {
+                let constraint = Constraint::new(
+                    table.column_constraint_name(
+                        ConstraintType::Unique,
+                        std::slice::from_ref(&column),
+                    ),
+                    ConstraintKind::Unique {
+                        columns: vec![column],
+                        include: Vec::new(),
+                        nulls_not_distinct: false,
+                    },
+                );
+                table.constraints.push(constraint);
+            }
+            project.tables.push(table);
+        }
+
+        for (i, entity) in self.entities.iter().enumerate() {
+            for settings in &entity.relations {
+                match self.foreign_key(entity, settings) {
+                    Ok(fk) => {
+                        let table = &mut project.tables[i];
+                        let constraint = Constraint::new(
+                            table.column_constraint_name(ConstraintType::ForeignKey, &fk.columns),
+                            ConstraintKind::ForeignKey(fk),
+                        );
+                        table.constraints.push(constraint);
+                    }
+                    Err(reason) => self.skipped.push(Skipped {
+                        statement: format!(
+                            "{}::Relation belongs_to {}",
+                            entity.module,
+                            text(settings, "belongs_to").unwrap_or_default()
+                        ),
+                        reason,
+                    }),
+                }
+            }
+        }
+        Parsed {
+            project,
+            skipped: self.skipped,
+        }
+    }
+
+    /// The PostgreSQL type of a Rust field type, without its `Option`.
+    fn data_type(&self, ty: &Ty) -> String {
+        let data_type = match ty.name.as_str() {
+            "i8" | "i16" | "u8" => "smallint",
+            "i32" | "u16" => "integer",
+            "i64" | "u32" | "u64" => "bigint",
+            "f32" => "real",
+            "f64" => "double precision",
+            "bool" => "boolean",
+            "Vec" | "[]" => {
+                return match ty.args.first() {
+                    Some(arg) if arg.name == "u8" => String::from("bytea"),
+                    Some(arg) => self.data_type(arg) + "[]",
+                    None => String::from("text"),
+                };
+            }
+            "Uuid" => "uuid",
+            "DateTimeWithTimeZone" | "DateTimeUtc" | "DateTimeLocal" | "OffsetDateTime" => {
+                "timestamptz"
+            }
+            "DateTime" if !ty.args.is_empty() => "timestamptz",
+            "DateTime" | "NaiveDateTime" | "PrimitiveDateTime" => "timestamp",
+            "Date" | "NaiveDate" => "date",
+            "Time" | "NaiveTime" => "time",
+            "Decimal" | "BigDecimal" => "numeric",
+            "Json" | "Value" => "json",
+            "IpNetwork" => "inet",
+            name => {
+                return self
+                    .enums
+                    .iter()
+                    .find(|(rust, _)| rust == name)
+                    .map_or(String::from("text"), |(_, ty)| Project::type_name(ty));
+            }
+        };
+        data_type.to_string()
+    }
+
+    /// The foreign key of a `belongs_to` relation.
+    fn foreign_key(&self, entity: &Entity, settings: &[Setting]) -> Result<ForeignKey> {
+        let belongs_to = text(settings, "belongs_to").unwrap_or_default();
+        // `super::users::Entity`, or `Entity` for the entity itself.
+        let segments: Vec<&str> = belongs_to.split("::").map(str::trim).collect();
+        let module = match segments.as_slice() {
+            [.., module, "Entity"] if *module != "super" => *module,
+            _ => entity.module.as_str(),
+        };
+        let parent = self
+            .entities
+            .iter()
+            .find(|e| e.module == module)
+            .ok_or_else(|| format!("There is no entity {module}"))?;
+        let from = columns(entity, text(settings, "from").unwrap_or_default())?;
+        let to = columns(parent, text(settings, "to").unwrap_or_default())?;
+        if from.is_empty() || from.len() != to.len() {
+            return Err(String::from("from and to don't name as many columns"));
+        }
+        let action = |key: &str| match text(settings, key) {
+            Some("Cascade") => ReferentialAction::Cascade,
+            Some("SetNull") => ReferentialAction::SetNull,
+            Some("SetDefault") => ReferentialAction::SetDefault,
+            Some("Restrict") => ReferentialAction::Restrict,
+            _ => ReferentialAction::NoAction,
+        };
+        Ok(ForeignKey {
+            columns: from,
+            ref_schema: parent.schema.clone(),
+            ref_table: parent.name.clone(),
+            ref_columns: to,
+            on_delete: action("on_delete"),
+            on_update: action("on_update"),
+        })
+    }
+}
+
+/// The fields of a struct.
+fn fields(mut cursor: Cursor) -> Result<Vec<Field>> {
+    let mut fields = Vec::new();
+    while !cursor.done() {
+        let (docs, settings) = cursor.attributes();
+        cursor.visibility();
+        let name = cursor
+            .ident()
+            .ok_or_else(|| format!("Expected a field {}", cursor.position()))?;
+        cursor.expect(':')?;
+        let ty = ty(&mut cursor)?;
+        cursor.eat_punct(',');
+        fields.push(Field {
+            name,
+            ty,
+            settings,
+            comment: comment(&docs),
+        });
+    }
+    Ok(fields)
+}
+
+/// The variants of an enum with the settings of their `#[sea_orm]`.
+fn variants(mut cursor: Cursor) -> Vec<(String, Vec<Setting>)> {
+    let mut variants = Vec::new();
+    while !cursor.done() {
+        let (_, attributes) = cursor.attributes();
+        let Some(name) = cursor.ident() else {
+            cursor.skip();
+            continue;
+        };
+        variants.push((name, list(&attributes, "sea_orm").to_vec()));
+        cursor.skip_past(',');
+    }
+    variants
+}
+
+/// Column names of `Column::UserId` or `(Column::A, Column::B)`, with any
+/// path before `Column` ignored.
+fn columns(entity: &Entity, value: &str) -> Result<Vec<String>> {
+    value
+        .trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())
+        .split(',')
+        .map(str::trim)
+        .filter(|column| !column.is_empty())
+        .map(|column| {
+            let variant = column.rsplit("::").next().unwrap_or(column);
+            entity
+                .fields
+                .iter()
+                .find(|field| Case::Pascal.convert(&field.name) == variant)
+                .map(|field| {
+                    text(list(&field.settings, "sea_orm"), "column_name")
+                        .unwrap_or(&field.name)
+                        .to_string()
+                })
+                .ok_or_else(|| format!("{} has no column {variant}", entity.module))
+        })
+        .collect()
+}
+
+/// The PostgreSQL type of a `column_type` such as `Text`,
+/// `String(StringLen::N(255))` or `Decimal(Some((10, 2)))`.
+fn column_type(value: &str) -> Option<String> {
+    let (name, args) = value.split_once('(').unwrap_or((value, ""));
+    let numbers: Vec<&str> = args
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|number| !number.is_empty())
+        .collect();
+    let sized = |name: &str| match numbers.as_slice() {
+        [] => name.to_string(),
+        numbers => format!("{name}({})", numbers.join(", ")),
+    };
+    Some(match name.trim() {
+        "Text" => String::from("text"),
+        "String" => sized("varchar"),
+        "Char" => sized("char"),
+        "TinyInteger" | "SmallInteger" => String::from("smallint"),
+        "Integer" => String::from("integer"),
+        "BigInteger" => String::from("bigint"),
+        "Float" => String::from("real"),
+        "Double" => String::from("double precision"),
+        "Decimal" => sized("numeric"),
+        "Money" => String::from("money"),
+        "DateTime" | "Timestamp" => String::from("timestamp"),
+        "TimestampWithTimeZone" => String::from("timestamptz"),
+        "Date" => String::from("date"),
+        "Time" => String::from("time"),
+        "Interval" => String::from("interval"),
+        "Binary" | "VarBinary" | "Blob" => String::from("bytea"),
+        "Boolean" => String::from("boolean"),
+        "Json" => String::from("json"),
+        "JsonBinary" => String::from("jsonb"),
+        "Uuid" => String::from("uuid"),
+        "Cidr" => String::from("cidr"),
+        "Inet" => String::from("inet"),
+        "MacAddr" => String::from("macaddr"),
+        // Custom("citext".to_owned())
+        "Custom" => args.split('"').nth(1)?.to_string(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joinable_gives_foreign_keys_named_like_postgres() {
+        let parsed = diesel(
+            "diesel::table! {
+                users (id) {
+                    id -> Int4,
+                }
+            }
+
+            diesel::table! {
+                posts (id) {
+                    id -> Int4,
+                    author_id -> Int4,
+                    editor_id -> Nullable<Int4>,
+                }
+            }
+
+            diesel::joinable!(posts -> users (author_id));
+            diesel::joinable!(posts -> users (editor_id));
+            diesel::joinable!(posts -> tags (tag_id));",
+        );
+        let posts = &parsed.project.tables[1];
+        let foreign_keys: Vec<(&str, &ForeignKey)> = posts
+            .constraints
+            .iter()
+            .filter_map(|c| match &c.kind {
+                ConstraintKind::ForeignKey(fk) => Some((c.name.as_str(), fk)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(foreign_keys.len(), 2);
+        assert_eq!(foreign_keys[0].0, "posts_author_id_fkey");
+        assert_eq!(foreign_keys[1].0, "posts_editor_id_fkey");
+        let fk = foreign_keys[0].1;
+        assert_eq!(
+            (fk.ref_table.as_str(), fk.columns.as_slice()),
+            ("users", &[String::from("author_id")][..])
+        );
+        assert_eq!(fk.ref_columns, ["id"]);
+        assert!(!posts.columns[2].not_null);
+        assert_eq!(parsed.skipped.len(), 1);
+        assert_eq!(
+            parsed.skipped[0].statement,
+            "joinable!(posts -> tags (tag_id))"
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use super::{
+    column_type, derive, doc, enum_name, field_name, foreign_keys, ident, literal, primary_key,
+    resolve, unraw, variants, Base, Target,
+};
+use crate::model::{
+    naming::Case, ConstraintKind, Generation, Project, ReferentialAction, Table, UserType,
+    UserTypeKind, DEFAULT_SCHEMA,
+};
+
+/// An entity module per table, with enums deriving `DeriveActiveEnum` in
+/// `sea_orm_active_enums` as sea-orm-cli lays them out.
+pub fn generate(project: &Project, tables: &[&Table], enums: &[&UserType], serde: bool) -> String {
+    let mut out = String::new();
+    let prelude = if serde {
+        "    use sea_orm::entity::prelude::*;\n    use serde::{Deserialize, Serialize};\n"
+    } else {
+        "    use sea_orm::entity::prelude::*;\n"
+    };
+
+    if !enums.is_empty() {
+        out.push_str("pub mod sea_orm_active_enums {\n");
+        out.push_str(prelude);
+        for ty in enums {
+            let UserTypeKind::Enum { labels } = &ty.kind else {
+                continue;
+            };
+            out.push('\n');
+            out.push_str(&doc(ty.comment.as_deref(), "    "));
+            out.push_str(&format!(
+                "    {}\n",
+                derive(
+                    &[
+                        "Debug",
+                        "Clone",
+                        "PartialEq",
+                        "Eq",
+                        "EnumIter",
+                        "DeriveActiveEnum"
+                    ],
+                    serde
+                )
+            ));
+            out.push_str(&format!(
+                "    #[sea_orm(rs_type = \"String\", db_type = \"Enum\", enum_name = {})]\n    pub enum {} {{\n",
+                literal(&ty.name),
+                enum_name(ty)
+            ));
+            for (variant, label) in variants(labels) {
+                out.push_str(&format!(
+                    "        #[sea_orm(string_value = {})]\n",
+                    literal(label)
+                ));
+                if serde {
+                    out.push_str(&format!("        #[serde(rename = {})]\n", literal(label)));
+                }
+                out.push_str(&format!("        {variant},\n"));
+            }
+            out.push_str("    }\n");
+        }
+        out.push_str("}\n");
+    }
+
+    // Entities need a primary key.
+    let (tables, skipped): (Vec<&Table>, Vec<&Table>) = tables
+        .iter()
+        .partition(|table| !primary_key(table).is_empty());
+    for table in &skipped {
+        out.push_str(&format!(
+            "\n// {}.{} is left out as it has no primary key\n",
+            table.schema, table.name
+        ));
+    }
+
+    for table in &tables {
+        out.push_str(&format!("\npub mod {} {{\n", field_name(&table.name)));
+        out.push_str(prelude);
+        let mut used: Vec<String> = Vec::new();
+        for column in &table.columns {
+            if let (Base::Enum(ty), _) = resolve(project, &table.schema, &column.data_type)
+                && !used.contains(&enum_name(ty))
+            {
+                used.push(enum_name(ty));
+            }
+        }
+        match used.as_slice() {
+            [] => {}
+            [name] => out.push_str(&format!("    use super::sea_orm_active_enums::{name};\n")),
+            names => out.push_str(&format!(
+                "    use super::sea_orm_active_enums::{{{}}};\n",
+                names.join(", ")
+            )),
+        }
+
+        out.push('\n');
+        out.push_str(&doc(table.comment.as_deref(), "    "));
+        out.push_str(&format!(
+            "    {}\n",
+            derive(&["Clone", "Debug", "PartialEq", "DeriveEntityModel"], serde)
+        ));
+        if table.schema == DEFAULT_SCHEMA {
+            out.push_str(&format!(
+                "    #[sea_orm(table_name = {})]\n",
+                literal(&table.name)
+            ));
+        } else {
+            out.push_str(&format!(
+                "    #[sea_orm(table_name = {}, schema_name = {})]\n",
+                literal(&table.name),
+                literal(&table.schema)
+            ));
+        }
+        out.push_str("    pub struct Model {\n");
+        let keys = primary_key(table);
+        let unique: Vec<&str> = table
+            .constraints
+            .iter()
+            .filter_map(|constraint| match &constraint.kind {
+                ConstraintKind::Unique { columns, .. } => match columns.as_slice() {
+                    [column] => Some(column.as_str()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        for column in &table.columns {
+            let field = field_name(&column.name);
+            let mut attributes = Vec::new();
+            if keys.contains(&column.name.as_str()) {
+                attributes.push("primary_key");
+                if matches!(column.generation(), Generation::None | Generation::Stored) {
+                    attributes.push("auto_increment = false");
+                }
+            }
+            if unique.contains(&column.name.as_str()) {
+                attributes.push("unique");
+            }
+            let column_name = format!("column_name = {}", literal(&column.name));
+            if unraw(&field) != column.name {
+                attributes.push(&column_name);
+            }
+            out.push_str(&doc(column.comment.as_deref(), "        "));
+            if !attributes.is_empty() {
+                out.push_str(&format!("        #[sea_orm({})]\n", attributes.join(", ")));
+            }
+            out.push_str(&format!(
+                "        pub {field}: {},\n",
+                column_type(project, table, column, Target::SeaOrm)
+            ));
+        }
+        out.push_str("    }\n\n");
+
+        let relations = relations(project, table, &tables);
+        out.push_str(
+            "    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]\n    pub enum Relation {",
+        );
+        if !relations.is_empty() {
+            out.push('\n');
+        }
+        for relation in &relations {
+            out.push_str(&relation.attribute);
+            out.push_str(&format!("        {},\n", relation.variant));
+        }
+        out.push_str(if relations.is_empty() {
+            "}\n"
+        } else {
+            "    }\n"
+        });
+
+        // Related only works when one relation leads to the entity.
+        for relation in &relations {
+            if let Some(entity) = &relation.entity
+                && relations
+                    .iter()
+                    .filter(|other| other.entity.as_ref() == Some(entity))
+                    .count()
+                    == 1
+            {
+                out.push_str(&format!(
+                    "\n    impl Related<{entity}> for Entity {{\n        fn to() -> RelationDef {{\n            Relation::{}.def()\n        }}\n    }}\n",
+                    relation.variant
+                ));
+            }
+        }
+        out.push_str("\n    impl ActiveModelBehavior for ActiveModel {}\n}\n");
+    }
+    out
+}
+
+struct Relation {
+    variant: String,
+    /// The `#[sea_orm(...)]` line or lines, indented.
+    attribute: String,
+    /// The other entity, if it is not this one.
+    entity: Option<String>,
+}
+
+/// `belongs_to` for the foreign keys of `table` and `has_many` for the ones
+/// to it, among `tables`.
+fn relations(project: &Project, table: &Table, tables: &[&Table]) -> Vec<Relation> {
+    let included = |other: &Table| tables.iter().any(|t| std::ptr::eq(*t, other));
+    let columns = |prefix: &str, columns: &[&str]| {
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{prefix}Column::{}",
+                    Case::Pascal.convert(unraw(&field_name(column)))
+                )
+            })
+            .collect();
+        if columns.len() == 1 {
+            columns[0].clone()
+        } else {
+            format!("({})", columns.join(", "))
+        }
+    };
+
+    let mut relations: Vec<Relation> = Vec::new();
+    let mut push = |variant: String, attribute: String, entity: Option<String>| {
+        let mut unique = variant.clone();
+        let mut n = 2;
+        while relations.iter().any(|r| r.variant == unique) {
+            unique = format!("{variant}{n}");
+            n += 1;
+        }
+        relations.push(Relation {
+            variant: unique,
+            attribute,
+            entity,
+        });
+    };
+
+    for (from, parent, to, fk) in foreign_keys(project, table) {
+        if !included(parent) {
+            continue;
+        }
+        let (variant, entity, prefix) = if std::ptr::eq(table, parent) {
+            (
+                String::from("SelfRef"),
+                String::from("Entity"),
+                String::new(),
+            )
+        } else {
+            let module = field_name(&parent.name);
+            (
+                ident(Case::Pascal.convert(&parent.name)),
+                format!("super::{module}::Entity"),
+                format!("super::{module}::"),
+            )
+        };
+        let mut settings = vec![
+            format!("belongs_to = {}", literal(&entity)),
+            format!("from = {}", literal(&columns("", &from))),
+            format!("to = {}", literal(&columns(&prefix, &to))),
+        ];
+        for (key, action) in [("on_update", fk.on_update), ("on_delete", fk.on_delete)] {
+            let action = match action {
+                ReferentialAction::NoAction => continue,
+                ReferentialAction::Restrict => "Restrict",
+                ReferentialAction::Cascade => "Cascade",
+                ReferentialAction::SetNull => "SetNull",
+                ReferentialAction::SetDefault => "SetDefault",
+            };
+            settings.push(format!("{key} = \"{action}\""));
+        }
+        let attribute = format!(
+            "        #[sea_orm(\n            {}\n        )]\n",
+            settings.join(",\n            ")
+        );
+        let related = (!prefix.is_empty()).then_some(entity);
+        push(variant, attribute, related);
+    }
+
+    for child in tables {
+        if std::ptr::eq(*child, table) {
+            continue;
+        }
+        for (_, parent, _, _) in foreign_keys(project, child) {
+            if std::ptr::eq(parent, table) {
+                let entity = format!("super::{}::Entity", field_name(&child.name));
+                let attribute = format!("        #[sea_orm(has_many = {})]\n", literal(&entity));
+                push(
+                    ident(Case::Pascal.convert(&child.name)),
+                    attribute,
+                    Some(entity),
+                );
+            }
+        }
+    }
+    relations
+}
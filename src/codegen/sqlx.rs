@@ -0,0 +1,61 @@
+use super::{
+    column_type, derive, doc, enum_name, field_name, literal, struct_name, unraw, variants, Target,
+};
+use crate::model::{Project, Table, UserType, UserTypeKind};
+
+/// Structs deriving `FromRow`, with enums deriving `sqlx::Type`.
+pub fn generate(project: &Project, tables: &[&Table], enums: &[&UserType], serde: bool) -> String {
+    let mut out = String::new();
+    if serde {
+        out.push_str("use serde::{Deserialize, Serialize};\n");
+    }
+    out.push_str("use sqlx::FromRow;\n");
+
+    for ty in enums {
+        let UserTypeKind::Enum { labels } = &ty.kind else {
+            continue;
+        };
+        out.push('\n');
+        out.push_str(&doc(ty.comment.as_deref(), ""));
+        out.push_str(&derive(
+            &["Debug", "Clone", "Copy", "PartialEq", "Eq", "sqlx::Type"],
+            serde,
+        ));
+        out.push_str(&format!(
+            "\n#[sqlx(type_name = {})]\npub enum {} {{\n",
+            literal(&Project::type_name(ty)),
+            enum_name(ty)
+        ));
+        for (variant, label) in variants(labels) {
+            out.push_str(&format!("    #[sqlx(rename = {})]\n", literal(label)));
+            if serde {
+                out.push_str(&format!("    #[serde(rename = {})]\n", literal(label)));
+            }
+            out.push_str(&format!("    {variant},\n"));
+        }
+        out.push_str("}\n");
+    }
+
+    for table in tables {
+        out.push('\n');
+        out.push_str(&doc(table.comment.as_deref(), ""));
+        out.push_str(&derive(&["Debug", "Clone", "FromRow"], serde));
+        out.push_str(&format!("\npub struct {} {{\n", struct_name(table)));
+        for column in &table.columns {
+            let field = field_name(&column.name);
+            out.push_str(&doc(column.comment.as_deref(), "    "));
+            if unraw(&field) != column.name {
+                out.push_str(&format!(
+                    "    #[sqlx(rename = {})]\n",
+                    literal(&column.name)
+                ));
+            }
+            out.push_str(&format!(
+                "    pub {field}: {},\n",
+                column_type(project, table, column, Target::Sqlx)
+            ));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
@@ -6,3 +6,8 @@ pub const BTN_PRIMARY_HOVER: iced::Color = iced::Color::from_rgb8(0x3b, 0x83, 0x
 pub const ICON_SIZE: f32 = 36.0;
 pub const ICON_PADDING: f32 = 4.0;
 pub const BAR_SIZE: f32 = 62.0;
+
+pub const TABLE_HEADER: iced::Color = iced::Color::from_rgb8(0x50, 0x90, 0xff);
+pub const VIEW_HEADER: iced::Color = iced::Color::from_rgb8(0x9b, 0x6b, 0xdf);
+pub const MATVIEW_HEADER: iced::Color = iced::Color::from_rgb8(0xd0, 0x87, 0x70);
+pub const AREA: iced::Color = iced::Color::from_rgb8(0x4c, 0xaf, 0x82);
@@ -0,0 +1,82 @@
+use std::{path::PathBuf, time::Duration};
+
+use sqlx::{
+    postgres::{PgConnectOptions, PgPool, PgPoolOptions},
+    Row,
+};
+
+pub mod introspect;
+pub mod migrate;
+pub mod profile;
+pub mod secret;
+
+#[derive(Clone, Debug)]
+pub enum DbError {
+    Connection(String),
+    Query(String),
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Connection(message) => write!(f, "connection failed: {message}"),
+            DbError::Query(message) => write!(f, "query failed: {message}"),
+        }
+    }
+}
+
+impl From<sqlx::Error> for DbError {
+    fn from(error: sqlx::Error) -> Self {
+        DbError::Query(error.to_string())
+    }
+}
+
+/// `table-forge` in the per-user configuration directory, where profiles
+/// and the migration log live.
+pub fn config_dir() -> Option<PathBuf> {
+    let dir = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        PathBuf::from(dir)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(std::env::var_os("HOME")?).join("Library/Application Support")
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?).join(".config")
+    };
+    Some(dir.join("table-forge"))
+}
+
+/// Opens a small pool, as one page of the app needs at most a couple of
+/// connections.
+pub async fn connect(options: PgConnectOptions) -> Result<PgPool, DbError> {
+    PgPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(Duration::from_secs(10))
+        .connect_with(options)
+        .await
+        .map_err(|e| DbError::Connection(e.to_string()))
+}
+
+/// Connects, asks for the server version and disconnects again.
+pub async fn test(options: PgConnectOptions) -> Result<String, DbError> {
+    let pool = connect(options).await?;
+    let version = sqlx::query("SELECT version() AS version")
+        .fetch_one(&pool)
+        .await?
+        .try_get("version")?;
+    pool.close().await;
+    Ok(version)
+}
+
+/// Schemas that can be imported, without the system ones.
+pub async fn schemas(pool: PgPool) -> Result<Vec<String>, DbError> {
+    let rows = sqlx::query(
+        "SELECT nspname::text AS name
+         FROM pg_namespace
+         WHERE nspname !~ '^pg_' AND nspname <> 'information_schema'
+         ORDER BY 1",
+    )
+    .fetch_all(&pool)
+    .await?;
+    rows.iter().map(|row| Ok(row.try_get("name")?)).collect()
+}
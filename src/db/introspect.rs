@@ -0,0 +1,734 @@
+use std::collections::HashMap;
+
+use sqlx::{postgres::PgPool, Row};
+
+use super::DbError;
+use crate::model::{
+    column::Identity, constraint::ForeignKey, data_type, Attribute, Column, Constraint,
+    ConstraintKind, DomainCheck, ExclusionElement, Extension, Grant, Index, IndexElement,
+    IndexMethod, NullsOrder, Partition, PgVersion, Policy, PolicyCommand, Privilege, Project,
+    ReferentialAction, Role, Routine, RoutineKind, Sequence, SortOrder, Table, Trigger,
+    TriggerTiming, UserType, UserTypeKind, View, Volatility, DEFAULT_SCHEMA,
+};
+use crate::sql::{
+    parse::{partition_bound, partitioning},
+    take_group, unwrap_parens,
+};
+
+/// Objects that belong to an extension are left out, the extension itself is
+/// imported instead.
+const NOT_FROM_EXTENSION: &str =
+    "NOT EXISTS (SELECT 1 FROM pg_depend e WHERE e.objid = {oid} AND e.deptype = 'e')";
+
+const TABLES: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS name,
+       obj_description(c.oid, 'pg_class') AS comment,
+       pg_get_partkeydef(c.oid) AS partition_key,
+       c.relrowsecurity AS row_security, c.relforcerowsecurity AS force_row_security
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition AND n.nspname::text = ANY($1)
+  AND {not_from_extension}
+ORDER BY 1, 2";
+
+const COLUMNS: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS table_name, a.attname::text AS name,
+       format_type(a.atttypid, a.atttypmod) AS data_type, a.attnotnull AS not_null,
+       pg_get_expr(d.adbin, d.adrelid) AS default_value,
+       a.attidentity::text AS identity, a.attgenerated::text AS generated,
+       col_description(c.oid, a.attnum) AS comment
+FROM pg_attribute a
+JOIN pg_class c ON c.oid = a.attrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition AND n.nspname::text = ANY($1)
+  AND a.attnum > 0 AND NOT a.attisdropped
+ORDER BY 1, 2, a.attnum";
+
+const PARTITIONS: &str = "
+SELECT n.nspname::text AS schema, p.relname::text AS parent, c.relname::text AS name,
+       pg_get_expr(c.relpartbound, c.oid) AS bound
+FROM pg_inherits i
+JOIN pg_class c ON c.oid = i.inhrelid
+JOIN pg_class p ON p.oid = i.inhparent
+JOIN pg_namespace n ON n.oid = p.relnamespace
+WHERE c.relispartition AND n.nspname::text = ANY($1)
+ORDER BY 1, 2, 3";
+
+const SEQUENCES: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS name,
+       format_type(s.seqtypid, NULL) AS data_type,
+       s.seqstart AS start, s.seqincrement AS increment, s.seqmin AS min_value,
+       s.seqmax AS max_value, s.seqcache AS cache, s.seqcycle AS cycle,
+       obj_description(c.oid, 'pg_class') AS comment,
+       d.deptype::text AS dependency, tn.nspname::text AS owner_schema,
+       t.relname::text AS owner_table, a.attname::text AS owner_column
+FROM pg_sequence s
+JOIN pg_class c ON c.oid = s.seqrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+LEFT JOIN pg_depend d ON d.objid = c.oid AND d.classid = 'pg_class'::regclass
+     AND d.refclassid = 'pg_class'::regclass AND d.deptype IN ('a', 'i')
+LEFT JOIN pg_class t ON t.oid = d.refobjid
+LEFT JOIN pg_namespace tn ON tn.oid = t.relnamespace
+LEFT JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+WHERE n.nspname::text = ANY($1) AND {not_from_extension}
+ORDER BY 1, 2";
+
+const CONSTRAINTS: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS table_name, con.conname::text AS name,
+       con.contype::text AS kind, con.condeferrable AS deferrable,
+       con.condeferred AS initially_deferred,
+       ARRAY(SELECT a.attname::text
+             FROM unnest(con.conkey) WITH ORDINALITY k(attnum, i)
+             JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+             ORDER BY k.i) AS columns,
+       ARRAY(SELECT a.attname::text
+             FROM pg_index ix
+             CROSS JOIN generate_series(ix.indnkeyatts + 1, ix.indnatts) k
+             JOIN pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = ix.indkey[k - 1]
+             WHERE ix.indexrelid = con.conindid
+               AND con.contype IN ('p', 'u', 'x')
+             ORDER BY k) AS include,
+       pg_get_constraintdef(con.oid) LIKE 'UNIQUE NULLS NOT DISTINCT%' AS nulls_not_distinct,
+       fn.nspname::text AS ref_schema, f.relname::text AS ref_table,
+       ARRAY(SELECT a.attname::text
+             FROM unnest(con.confkey) WITH ORDINALITY k(attnum, i)
+             JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+             ORDER BY k.i) AS ref_columns,
+       con.confupdtype::text AS on_update, con.confdeltype::text AS on_delete,
+       pg_get_expr(con.conbin, con.conrelid) AS expression, con.connoinherit AS no_inherit,
+       am.amname::text AS method,
+       ARRAY(SELECT pg_get_indexdef(con.conindid, k, true)
+             FROM generate_series(1, cardinality(con.conexclop)) k
+             ORDER BY k) AS elements,
+       ARRAY(SELECT o.oprname::text
+             FROM unnest(con.conexclop) WITH ORDINALITY e(op, k)
+             JOIN pg_operator o ON o.oid = e.op
+             ORDER BY e.k) AS operators,
+       pg_get_expr(x.indpred, x.indrelid) AS predicate
+FROM pg_constraint con
+JOIN pg_class c ON c.oid = con.conrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+LEFT JOIN pg_class f ON f.oid = con.confrelid
+LEFT JOIN pg_namespace fn ON fn.oid = f.relnamespace
+LEFT JOIN pg_index x ON x.indexrelid = con.conindid AND con.contype = 'x'
+LEFT JOIN pg_class ic ON ic.oid = x.indexrelid
+LEFT JOIN pg_am am ON am.oid = ic.relam
+WHERE con.contype IN ('p', 'u', 'c', 'f', 'x') AND con.conislocal
+  AND NOT c.relispartition AND n.nspname::text = ANY($1)
+ORDER BY 1, 2, 3";
+
+const INDEXES: &str = "
+SELECT n.nspname::text AS schema, t.relname::text AS relation, ic.relname::text AS name,
+       x.indisunique AS is_unique, am.amname::text AS method,
+       pg_get_indexdef(x.indexrelid) LIKE '% NULLS NOT DISTINCT%' AS nulls_not_distinct,
+       ARRAY(SELECT pg_get_indexdef(x.indexrelid, k, true)
+             FROM generate_series(1, x.indnkeyatts) k ORDER BY k) AS elements,
+       ARRAY(SELECT x.indoption[k - 1]
+             FROM generate_series(1, x.indnkeyatts) k ORDER BY k) AS options,
+       ARRAY(SELECT CASE WHEN o.opcdefault THEN '' ELSE o.opcname::text END
+             FROM generate_series(1, x.indnkeyatts) k
+             JOIN pg_opclass o ON o.oid = x.indclass[k - 1]
+             ORDER BY k) AS opclasses,
+       ARRAY(SELECT pg_get_indexdef(x.indexrelid, k, true)
+             FROM generate_series(x.indnkeyatts + 1, x.indnatts) k ORDER BY k) AS include,
+       pg_get_expr(x.indpred, x.indrelid) AS predicate
+FROM pg_index x
+JOIN pg_class ic ON ic.oid = x.indexrelid
+JOIN pg_class t ON t.oid = x.indrelid
+JOIN pg_namespace n ON n.oid = t.relnamespace
+JOIN pg_am am ON am.oid = ic.relam
+WHERE t.relkind IN ('r', 'p', 'm') AND NOT t.relispartition AND n.nspname::text = ANY($1)
+  AND NOT EXISTS (SELECT 1 FROM pg_constraint con
+                  WHERE con.conindid = x.indexrelid AND con.contype IN ('p', 'u', 'x'))
+ORDER BY 1, 2, 3";
+
+const VIEWS: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS name,
+       c.relkind = 'm' AS materialized, pg_get_viewdef(c.oid, true) AS definition,
+       c.relispopulated AS with_data, obj_description(c.oid, 'pg_class') AS comment
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE c.relkind IN ('v', 'm') AND n.nspname::text = ANY($1) AND {not_from_extension}
+ORDER BY 1, 2";
+
+const TYPES: &str = "
+SELECT n.nspname::text AS schema, t.typname::text AS name, t.typtype::text AS kind,
+       obj_description(t.oid, 'pg_type') AS comment,
+       ARRAY(SELECT e.enumlabel::text FROM pg_enum e
+             WHERE e.enumtypid = t.oid ORDER BY e.enumsortorder) AS labels,
+       ARRAY(SELECT a.attname::text FROM pg_attribute a
+             WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum) AS attribute_names,
+       ARRAY(SELECT format_type(a.atttypid, a.atttypmod) FROM pg_attribute a
+             WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum) AS attribute_types,
+       format_type(t.typbasetype, t.typtypmod) AS base_type,
+       t.typnotnull AS not_null, t.typdefault AS default_value,
+       ARRAY(SELECT con.conname::text FROM pg_constraint con
+             WHERE con.contypid = t.oid AND con.contype = 'c'
+             ORDER BY con.conname) AS check_names,
+       ARRAY(SELECT pg_get_expr(con.conbin, 0) FROM pg_constraint con
+             WHERE con.contypid = t.oid AND con.contype = 'c'
+             ORDER BY con.conname) AS check_expressions
+FROM pg_type t
+JOIN pg_namespace n ON n.oid = t.typnamespace
+LEFT JOIN pg_class r ON r.oid = t.typrelid
+WHERE n.nspname::text = ANY($1) AND {not_from_extension}
+  AND (t.typtype IN ('e', 'd') OR (t.typtype = 'c' AND r.relkind = 'c'))
+ORDER BY 1, 2";
+
+const ROUTINES: &str = "
+SELECT n.nspname::text AS schema, p.proname::text AS name, p.prokind::text AS kind,
+       pg_get_function_arguments(p.oid) AS arguments,
+       pg_get_function_result(p.oid) AS returns, l.lanname::text AS language,
+       p.provolatile::text AS volatility, p.prosrc AS body,
+       obj_description(p.oid, 'pg_proc') AS comment
+FROM pg_proc p
+JOIN pg_namespace n ON n.oid = p.pronamespace
+JOIN pg_language l ON l.oid = p.prolang
+WHERE p.prokind IN ('f', 'p') AND n.nspname::text = ANY($1) AND {not_from_extension}
+ORDER BY 1, 2";
+
+const TRIGGERS: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS table_name, t.tgname::text AS name,
+       t.tgtype::int AS kind,
+       ARRAY(SELECT a.attname::text
+             FROM unnest(t.tgattr::int2[]) WITH ORDINALITY k(attnum, i)
+             JOIN pg_attribute a ON a.attrelid = t.tgrelid AND a.attnum = k.attnum
+             ORDER BY k.i) AS update_columns,
+       pg_get_triggerdef(t.oid, true) AS definition,
+       pn.nspname::text AS function_schema, p.proname::text AS function_name
+FROM pg_trigger t
+JOIN pg_class c ON c.oid = t.tgrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_proc p ON p.oid = t.tgfoid
+JOIN pg_namespace pn ON pn.oid = p.pronamespace
+WHERE NOT t.tgisinternal AND c.relkind IN ('r', 'p') AND NOT c.relispartition
+  AND n.nspname::text = ANY($1)
+ORDER BY 1, 2, 3";
+
+const POLICIES: &str = "
+SELECT schemaname::text AS schema, tablename::text AS table_name, policyname::text AS name,
+       permissive = 'PERMISSIVE' AS permissive, roles::text[] AS roles, cmd AS command,
+       qual AS using_expression, with_check
+FROM pg_policies
+WHERE schemaname::text = ANY($1)
+ORDER BY 1, 2, 3";
+
+/// Privileges in table and column ACLs, except the owner's own.
+const GRANTS: &str = "
+SELECT n.nspname::text AS schema, c.relname::text AS table_name,
+       CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END AS grantee,
+       a.privilege_type AS privilege, a.is_grantable AS grantable, NULL::text AS column_name
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+CROSS JOIN LATERAL aclexplode(c.relacl) a
+WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition AND n.nspname::text = ANY($1)
+  AND a.grantee <> c.relowner
+UNION ALL
+SELECT n.nspname::text, c.relname::text,
+       CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END,
+       a.privilege_type, a.is_grantable, att.attname::text
+FROM pg_attribute att
+JOIN pg_class c ON c.oid = att.attrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+CROSS JOIN LATERAL aclexplode(att.attacl) a
+WHERE c.relkind IN ('r', 'p') AND NOT c.relispartition AND n.nspname::text = ANY($1)
+  AND att.attnum > 0 AND NOT att.attisdropped AND a.grantee <> c.relowner
+ORDER BY 1, 2, 3, 6";
+
+/// Roles other than superusers and the predefined `pg_*` ones.
+const ROLES: &str = "
+SELECT r.rolname::text AS name, r.rolcanlogin AS login,
+       shobj_description(r.oid, 'pg_authid') AS comment,
+       ARRAY(SELECT g.rolname::text
+             FROM pg_auth_members m
+             JOIN pg_roles g ON g.oid = m.roleid
+             WHERE m.member = r.oid
+             ORDER BY 1) AS member_of
+FROM pg_roles r
+WHERE r.rolname !~ '^pg_' AND NOT r.rolsuper
+ORDER BY 1";
+
+/// Reads the catalog of the given schemas into a new project targeting the
+/// server's version.
+pub async fn import(pool: PgPool, schemas: Vec<String>) -> Result<Project, DbError> {
+    let major: i32 =
+        sqlx::query("SELECT current_setting('server_version_num')::int / 10000 AS major")
+            .fetch_one(&pool)
+            .await?
+            .try_get("major")?;
+    let mut project = Project {
+        version: PgVersion::from_major(major.max(0) as u32),
+        ..Project::default()
+    };
+
+    let fetch = |sql: &str, oid: &str| {
+        let sql = sql
+            .replace("{not_from_extension}", NOT_FROM_EXTENSION)
+            .replace("{oid}", oid);
+        let pool = pool.clone();
+        let schemas = schemas.clone();
+        async move { sqlx::query(&sql).bind(schemas).fetch_all(&pool).await }
+    };
+
+    for row in fetch(TABLES, "c.oid").await? {
+        let mut table = Table::new(
+            row.try_get::<String, _>("schema")?,
+            row.try_get::<String, _>("name")?,
+        );
+        table.comment = row.try_get("comment")?;
+        table.row_security = row.try_get("row_security")?;
+        table.force_row_security = row.try_get("force_row_security")?;
+        table.partitioning = row
+            .try_get::<Option<String>, _>("partition_key")?
+            .as_deref()
+            .and_then(partitioning);
+        project.tables.push(table);
+    }
+    let tables: HashMap<(String, String), usize> = project
+        .tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| ((table.schema.clone(), table.name.clone()), i))
+        .collect();
+    let table = |row: &sqlx::postgres::PgRow, name: &str| -> Result<Option<usize>, DbError> {
+        let key: (String, String) = (row.try_get("schema")?, row.try_get(name)?);
+        Ok(tables.get(&key).copied())
+    };
+
+    for row in fetch(COLUMNS, "c.oid").await? {
+        let Some(i) = table(&row, "table_name")? else {
+            continue;
+        };
+        let mut column = Column::new(
+            row.try_get::<String, _>("name")?,
+            row.try_get::<String, _>("data_type")?,
+        );
+        column.not_null = row.try_get("not_null")?;
+        column.comment = row.try_get("comment")?;
+        let default: Option<String> = row.try_get("default_value")?;
+        match (
+            row.try_get::<String, _>("identity")?.as_str(),
+            row.try_get::<String, _>("generated")?.as_str(),
+        ) {
+            ("a", _) => column.identity = Some(Identity::Always),
+            ("d", _) => column.identity = Some(Identity::ByDefault),
+            (_, "s") => column.generated = default.as_deref().map(unwrap_parens),
+            _ => column.default = default,
+        }
+        project.tables[i].columns.push(column);
+    }
+
+    for row in fetch(PARTITIONS, "c.oid").await? {
+        let Some(i) = table(&row, "parent")? else {
+            continue;
+        };
+        let bound: String = row.try_get("bound")?;
+        if let (Some(partitioning), Some(bound)) = (
+            project.tables[i].partitioning.as_mut(),
+            partition_bound(&bound),
+        ) {
+            partitioning.partitions.push(Partition {
+                name: row.try_get("name")?,
+                bound,
+            });
+        }
+    }
+
+    for row in fetch(SEQUENCES, "c.oid").await? {
+        let dependency: Option<String> = row.try_get("dependency")?;
+        // Identity columns own their sequence.
+        if dependency.as_deref() == Some("i") {
+            continue;
+        }
+        let mut sequence = Sequence::new(
+            row.try_get::<String, _>("schema")?,
+            row.try_get::<String, _>("name")?,
+        );
+        sequence.comment = row.try_get("comment")?;
+        sequence.data_type = row.try_get("data_type")?;
+        sequence.increment = row.try_get("increment")?;
+        sequence.cache = row.try_get("cache")?;
+        sequence.cycle = row.try_get("cycle")?;
+        let (start, min_value, max_value): (i64, i64, i64) = (
+            row.try_get("start")?,
+            row.try_get("min_value")?,
+            row.try_get("max_value")?,
+        );
+        let (default_min, default_max) = sequence.default_bounds();
+        sequence.min_value = (min_value != default_min).then_some(min_value);
+        sequence.max_value = (max_value != default_max).then_some(max_value);
+        let default_start = if sequence.increment > 0 {
+            min_value
+        } else {
+            max_value
+        };
+        sequence.start = (start != default_start).then_some(start);
+
+        let owner = match (
+            row.try_get::<Option<String>, _>("owner_schema")?,
+            row.try_get::<Option<String>, _>("owner_table")?,
+            row.try_get::<Option<String>, _>("owner_column")?,
+        ) {
+            (Some(schema), Some(table), Some(column)) => Some((schema, table, column)),
+            _ => None,
+        };
+        if let Some((schema, table, column)) = &owner {
+            let serial = tables
+                .get(&(schema.clone(), table.clone()))
+                .and_then(|&i| {
+                    project.tables[i]
+                        .columns
+                        .iter_mut()
+                        .find(|c| &c.name == column)
+                })
+                .filter(|column| sequence.backs_serial(column));
+            if let Some(column) = serial {
+                column.data_type = data_type::serial_for(&column.data_type).to_string();
+                column.default = None;
+                continue;
+            }
+            sequence.owned_by = Some(if schema == DEFAULT_SCHEMA {
+                format!("{table}.{column}")
+            } else {
+                format!("{schema}.{table}.{column}")
+            });
+        }
+        project.sequences.push(sequence);
+    }
+
+    for row in fetch(CONSTRAINTS, "con.oid").await? {
+        let Some(i) = table(&row, "table_name")? else {
+            continue;
+        };
+        let columns: Vec<String> = row.try_get("columns")?;
+        let include: Vec<String> = row.try_get("include")?;
+        let kind = match row.try_get::<String, _>("kind")?.as_str() {
+            "p" => ConstraintKind::PrimaryKey { columns, include },
+            "u" => ConstraintKind::Unique {
+                columns,
+                include,
+                nulls_not_distinct: row.try_get("nulls_not_distinct")?,
+            },
+            "c" => ConstraintKind::Check {
+                expression: row
+                    .try_get::<Option<String>, _>("expression")?
+                    .as_deref()
+                    .map(unwrap_parens)
+                    .unwrap_or_default(),
+                no_inherit: row.try_get("no_inherit")?,
+            },
+            "f" => ConstraintKind::ForeignKey(ForeignKey {
+                columns,
+                ref_schema: row
+                    .try_get::<Option<String>, _>("ref_schema")?
+                    .unwrap_or_default(),
+                ref_table: row
+                    .try_get::<Option<String>, _>("ref_table")?
+                    .unwrap_or_default(),
+                ref_columns: row.try_get("ref_columns")?,
+                on_update: referential_action(&row.try_get::<String, _>("on_update")?),
+                on_delete: referential_action(&row.try_get::<String, _>("on_delete")?),
+            }),
+            _ => {
+                let Some(method) = row
+                    .try_get::<Option<String>, _>("method")?
+                    .as_deref()
+                    .and_then(index_method)
+                else {
+                    continue;
+                };
+                let elements: Vec<String> = row.try_get("elements")?;
+                let operators: Vec<String> = row.try_get("operators")?;
+                ConstraintKind::Exclusion {
+                    method,
+                    elements: elements
+                        .into_iter()
+                        .zip(operators)
+                        .map(|(element, operator)| ExclusionElement { element, operator })
+                        .collect(),
+                    include,
+                    predicate: row.try_get("predicate")?,
+                }
+            }
+        };
+        let mut constraint = Constraint::new(row.try_get::<String, _>("name")?, kind);
+        constraint.deferrable = row.try_get("deferrable")?;
+        constraint.initially_deferred = row.try_get("initially_deferred")?;
+        project.tables[i].constraints.push(constraint);
+    }
+
+    for row in fetch(VIEWS, "c.oid").await? {
+        let mut view = View::new(
+            row.try_get::<String, _>("schema")?,
+            row.try_get::<String, _>("name")?,
+            row.try_get("materialized")?,
+        );
+        view.definition = row
+            .try_get::<String, _>("definition")?
+            .trim()
+            .trim_end_matches(';')
+            .to_string();
+        view.with_data = row.try_get("with_data")?;
+        view.comment = row.try_get("comment")?;
+        project.views.push(view);
+    }
+
+    for row in fetch(INDEXES, "x.indexrelid").await? {
+        let Some(method) = index_method(&row.try_get::<String, _>("method")?) else {
+            continue;
+        };
+        let mut index = Index::new(row.try_get::<String, _>("name")?);
+        index.unique = row.try_get("is_unique")?;
+        index.nulls_not_distinct = row.try_get("nulls_not_distinct")?;
+        index.method = method;
+        index.include = row.try_get("include")?;
+        index.predicate = row
+            .try_get::<Option<String>, _>("predicate")?
+            .as_deref()
+            .map(unwrap_parens);
+        let elements: Vec<String> = row.try_get("elements")?;
+        let options: Vec<i16> = row.try_get("options")?;
+        let opclasses: Vec<String> = row.try_get("opclasses")?;
+        for (i, expression) in elements.into_iter().enumerate() {
+            let mut element = IndexElement::new(expression);
+            element.opclass = opclasses.get(i).filter(|o| !o.is_empty()).cloned();
+            // pg_index.indoption bits: 1 = DESC, 2 = NULLS FIRST.
+            let option = options.get(i).copied().unwrap_or_default();
+            let descending = option & 1 != 0;
+            let nulls_first = option & 2 != 0;
+            if descending {
+                element.order = SortOrder::Desc;
+            }
+            element.nulls = match (descending, nulls_first) {
+                (false, true) => NullsOrder::First,
+                (true, false) => NullsOrder::Last,
+                _ => NullsOrder::Default,
+            };
+            index.elements.push(element);
+        }
+
+        let schema: String = row.try_get("schema")?;
+        let relation: String = row.try_get("relation")?;
+        if let Some(&i) = tables.get(&(schema.clone(), relation.clone())) {
+            project.tables[i].indexes.push(index);
+        } else if let Some(view) = project
+            .views
+            .iter_mut()
+            .find(|v| v.schema == schema && v.name == relation)
+        {
+            view.indexes.push(index);
+        }
+    }
+
+    for row in fetch(TYPES, "t.oid").await? {
+        let kind = match row.try_get::<String, _>("kind")?.as_str() {
+            "e" => UserTypeKind::Enum {
+                labels: row.try_get("labels")?,
+            },
+            "c" => {
+                let names: Vec<String> = row.try_get("attribute_names")?;
+                let types: Vec<String> = row.try_get("attribute_types")?;
+                UserTypeKind::Composite {
+                    attributes: names
+                        .into_iter()
+                        .zip(types)
+                        .map(|(name, data_type)| Attribute { name, data_type })
+                        .collect(),
+                }
+            }
+            _ => {
+                let names: Vec<String> = row.try_get("check_names")?;
+                let expressions: Vec<String> = row.try_get("check_expressions")?;
+                UserTypeKind::Domain {
+                    data_type: row
+                        .try_get::<Option<String>, _>("base_type")?
+                        .unwrap_or_default(),
+                    not_null: row.try_get("not_null")?,
+                    default: row.try_get("default_value")?,
+                    checks: names
+                        .into_iter()
+                        .zip(expressions)
+                        .map(|(name, expression)| DomainCheck {
+                            name,
+                            expression: unwrap_parens(&expression),
+                        })
+                        .collect(),
+                }
+            }
+        };
+        let mut ty = UserType::new(
+            row.try_get::<String, _>("schema")?,
+            row.try_get::<String, _>("name")?,
+            kind,
+        );
+        ty.comment = row.try_get("comment")?;
+        project.types.push(ty);
+    }
+
+    for row in fetch(ROUTINES, "p.oid").await? {
+        let kind = match row.try_get::<String, _>("kind")?.as_str() {
+            "p" => RoutineKind::Procedure,
+            _ => RoutineKind::Function,
+        };
+        let mut routine = Routine::new(
+            row.try_get::<String, _>("schema")?,
+            row.try_get::<String, _>("name")?,
+            kind,
+        );
+        routine.arguments = row.try_get("arguments")?;
+        routine.returns = row
+            .try_get::<Option<String>, _>("returns")?
+            .unwrap_or_default();
+        routine.language = row.try_get("language")?;
+        routine.volatility = match row.try_get::<String, _>("volatility")?.as_str() {
+            "i" => Volatility::Immutable,
+            "s" => Volatility::Stable,
+            _ => Volatility::Volatile,
+        };
+        routine.body = row.try_get::<String, _>("body")?.trim().to_string();
+        routine.comment = row.try_get("comment")?;
+        project.routines.push(routine);
+    }
+
+    for row in fetch(TRIGGERS, "t.oid").await? {
+        let Some(i) = table(&row, "table_name")? else {
+            continue;
+        };
+        let schema: String = row.try_get("schema")?;
+        let function_schema: String = row.try_get("function_schema")?;
+        let function_name: String = row.try_get("function_name")?;
+        let function = if function_schema == schema || function_schema == DEFAULT_SCHEMA {
+            function_name
+        } else {
+            format!("{function_schema}.{function_name}")
+        };
+        // pg_trigger.tgtype bits: 1 = ROW, 2 = BEFORE, 4 = INSERT,
+        // 8 = DELETE, 16 = UPDATE, 32 = TRUNCATE, 64 = INSTEAD OF.
+        let kind: i32 = row.try_get("kind")?;
+        let mut trigger = Trigger::new(row.try_get::<String, _>("name")?, function);
+        trigger.for_each_row = kind & 1 != 0;
+        trigger.timing = if kind & 2 != 0 {
+            TriggerTiming::Before
+        } else {
+            TriggerTiming::After
+        };
+        trigger.insert = kind & 4 != 0;
+        trigger.delete = kind & 8 != 0;
+        trigger.update = kind & 16 != 0;
+        trigger.truncate = kind & 32 != 0;
+        trigger.update_columns = row.try_get("update_columns")?;
+        trigger.condition = trigger_condition(&row.try_get::<String, _>("definition")?);
+        project.tables[i].triggers.push(trigger);
+    }
+
+    for row in fetch(POLICIES, "").await? {
+        let Some(i) = table(&row, "table_name")? else {
+            continue;
+        };
+        let mut policy = Policy::new(row.try_get::<String, _>("name")?);
+        policy.permissive = row.try_get("permissive")?;
+        let command: String = row.try_get("command")?;
+        policy.command = PolicyCommand::ALL
+            .into_iter()
+            .find(|c| c.to_string() == command)
+            .unwrap_or_default();
+        let roles: Vec<String> = row.try_get("roles")?;
+        policy.roles = roles.into_iter().filter(|r| r != "public").collect();
+        policy.using = row.try_get("using_expression")?;
+        policy.with_check = row.try_get("with_check")?;
+        project.tables[i].policies.push(policy);
+    }
+
+    // Column privileges are merged into one grant per role and privilege
+    // set, so `SELECT (a), SELECT (b)` reads back as `SELECT (a, b)`.
+    let mut grants: Vec<(usize, Grant)> = Vec::new();
+    for row in fetch(GRANTS, "").await? {
+        let Some(i) = table(&row, "table_name")? else {
+            continue;
+        };
+        let privilege: String = row.try_get("privilege")?;
+        let Some(privilege) = Privilege::ALL
+            .into_iter()
+            .find(|p| p.to_string() == privilege)
+        else {
+            continue;
+        };
+        let role: String = row.try_get("grantee")?;
+        let grantable: bool = row.try_get("grantable")?;
+        let column: Option<String> = row.try_get("column_name")?;
+        let columns: Vec<String> = column.into_iter().collect();
+        match grants.iter_mut().find(|(t, g)| {
+            *t == i && g.role == role && g.with_grant_option == grantable && g.columns == columns
+        }) {
+            Some((_, grant)) => grant.privileges.push(privilege),
+            None => {
+                let mut grant = Grant::new(role);
+                grant.privileges = vec![privilege];
+                grant.columns = columns;
+                grant.with_grant_option = grantable;
+                grants.push((i, grant));
+            }
+        }
+    }
+    for (i, mut grant) in grants {
+        grant.privileges.sort();
+        let table = &mut project.tables[i];
+        match table.grants.iter_mut().find(|g| {
+            !g.columns.is_empty()
+                && !grant.columns.is_empty()
+                && g.role == grant.role
+                && g.with_grant_option == grant.with_grant_option
+                && g.privileges == grant.privileges
+        }) {
+            Some(existing) => existing.columns.extend(grant.columns),
+            None => table.grants.push(grant),
+        }
+    }
+
+    for row in sqlx::query(ROLES).fetch_all(&pool).await? {
+        let mut role = Role::new(row.try_get::<String, _>("name")?);
+        role.login = row.try_get("login")?;
+        role.comment = row.try_get("comment")?;
+        role.member_of = row.try_get("member_of")?;
+        project.roles.push(role);
+    }
+
+    // plpgsql comes with every database.
+    for row in sqlx::query(
+        "SELECT extname::text AS name FROM pg_extension WHERE extname <> 'plpgsql' ORDER BY 1",
+    )
+    .fetch_all(&pool)
+    .await?
+    {
+        let name: String = row.try_get("name")?;
+        project.extensions.push(Extension::named(&name));
+    }
+
+    Ok(project)
+}
+
+/// The `WHEN (...)` condition of a `pg_get_triggerdef()` definition.
+fn trigger_condition(definition: &str) -> Option<String> {
+    let (_, rest) = definition.split_once(" WHEN ")?;
+    let (condition, _) = take_group(rest.trim())?;
+    Some(condition.trim().to_string())
+}
+
+fn referential_action(code: &str) -> ReferentialAction {
+    match code {
+        "r" => ReferentialAction::Restrict,
+        "c" => ReferentialAction::Cascade,
+        "n" => ReferentialAction::SetNull,
+        "d" => ReferentialAction::SetDefault,
+        _ => ReferentialAction::NoAction,
+    }
+}
+
+fn index_method(name: &str) -> Option<IndexMethod> {
+    IndexMethod::ALL
+        .into_iter()
+        .find(|method| method.as_str() == name)
+}
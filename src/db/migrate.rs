@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use iced::futures::{stream, Stream};
+use sqlx::{
+    postgres::{PgPool, Postgres},
+    Transaction,
+};
+use tokio::io::AsyncWriteExt;
+
+use super::DbError;
+use crate::datetime;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Apply,
+    /// Runs every statement and rolls back, to see whether the script works.
+    DryRun,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Committed,
+    RolledBack,
+    /// A statement failed and everything was rolled back.
+    Failed,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Outcome::Committed => "committed",
+            Outcome::RolledBack => "rolled back",
+            Outcome::Failed => "failed",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Progress {
+    /// The next statement ran, affecting that many rows, or failed.
+    Statement(Result<u64, String>),
+    /// The run is over. `log` is where it was recorded, if that worked.
+    Finished {
+        outcome: Result<Outcome, DbError>,
+        log: Option<PathBuf>,
+    },
+}
+
+struct Run {
+    pool: PgPool,
+    statements: Vec<String>,
+    mode: Mode,
+    /// Database the script runs against, for the log.
+    target: String,
+    results: Vec<Result<u64, String>>,
+}
+
+enum State {
+    Begin(Run),
+    Running(Run, Transaction<'static, Postgres>),
+    Done,
+}
+
+/// Runs `statements` in one transaction, reporting each one as it
+/// completes. The first error rolls everything back. Every run is appended
+/// to the local migration log.
+pub fn run(
+    pool: PgPool,
+    statements: Vec<String>,
+    mode: Mode,
+    target: String,
+) -> impl Stream<Item = Progress> {
+    let run = Run {
+        pool,
+        statements,
+        mode,
+        target,
+        results: Vec::new(),
+    };
+    stream::unfold(State::Begin(run), |state| async move {
+        match state {
+            State::Begin(run) => match run.pool.begin().await {
+                Ok(transaction) => Some(step(run, transaction).await),
+                Err(e) => Some((
+                    Progress::Finished {
+                        outcome: Err(DbError::Connection(e.to_string())),
+                        log: None,
+                    },
+                    State::Done,
+                )),
+            },
+            State::Running(run, transaction) => Some(step(run, transaction).await),
+            State::Done => None,
+        }
+    })
+}
+
+async fn step(mut run: Run, mut transaction: Transaction<'static, Postgres>) -> (Progress, State) {
+    let failed = matches!(run.results.last(), Some(Err(_)));
+    if failed || run.results.len() == run.statements.len() {
+        let outcome = if failed {
+            transaction.rollback().await.map(|_| Outcome::Failed)
+        } else if run.mode == Mode::DryRun {
+            transaction.rollback().await.map(|_| Outcome::RolledBack)
+        } else {
+            transaction.commit().await.map(|_| Outcome::Committed)
+        }
+        .map_err(DbError::from);
+        let log = log(&run, &outcome).await;
+        return (Progress::Finished { outcome, log }, State::Done);
+    }
+
+    let statement = &run.statements[run.results.len()];
+    // Notes like the ones in generated scripts are not worth a round trip.
+    let result = if statement
+        .lines()
+        .all(|line| line.trim_start().starts_with("--"))
+    {
+        Ok(0)
+    } else {
+        sqlx::raw_sql(statement)
+            .execute(&mut *transaction)
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(|e| e.to_string())
+    };
+    run.results.push(result.clone());
+    (
+        Progress::Statement(result),
+        State::Running(run, transaction),
+    )
+}
+
+/// `migrations.log` next to the connection profiles.
+fn log_path() -> Option<PathBuf> {
+    Some(super::config_dir()?.join("migrations.log"))
+}
+
+async fn log(run: &Run, outcome: &Result<Outcome, DbError>) -> Option<PathBuf> {
+    let path = log_path()?;
+    let mut entry = format!(
+        "== {} UTC {} {} {}\n",
+        datetime::date_time(datetime::now()),
+        match run.mode {
+            Mode::Apply => "apply",
+            Mode::DryRun => "dry-run",
+        },
+        run.target,
+        match outcome {
+            Ok(outcome) => outcome.to_string(),
+            Err(e) => e.to_string(),
+        }
+    );
+    for (statement, result) in run.statements.iter().zip(&run.results) {
+        match result {
+            Ok(rows) => entry.push_str(&format!("-- ok, {rows} rows\n")),
+            Err(e) => entry.push_str(&format!("-- error: {e}\n")),
+        }
+        entry.push_str(statement);
+        entry.push('\n');
+    }
+    entry.push('\n');
+
+    tokio::fs::create_dir_all(path.parent()?).await.ok()?;
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .ok()?;
+    file.write_all(entry.as_bytes()).await.ok()?;
+    Some(path)
+}
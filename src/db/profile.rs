@@ -0,0 +1,240 @@
+use std::{fmt, path::PathBuf};
+
+use sqlx::postgres::{PgConnectOptions, PgSslMode};
+
+use super::secret::{self, Key};
+use crate::model::{join_list, split_list};
+
+/// Written into the file and decrypted on unlock to check the passphrase.
+const VERIFIER: &str = "table-forge";
+
+/// Saved connection settings. The password is only ever kept encrypted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub database: String,
+    pub user: String,
+    pub ssl_mode: SslMode,
+    /// Schemas offered for import. Empty means all of them.
+    pub schemas: Vec<String>,
+    pub password: Option<Vec<u8>>,
+}
+
+impl Profile {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            host: String::from("localhost"),
+            port: 5432,
+            database: String::from("postgres"),
+            user: String::from("postgres"),
+            ssl_mode: SslMode::default(),
+            schemas: Vec::new(),
+            password: None,
+        }
+    }
+
+    pub fn connect_options(&self, password: &str) -> PgConnectOptions {
+        let mut options = PgConnectOptions::new()
+            .host(&self.host)
+            .port(self.port)
+            .database(&self.database)
+            .username(&self.user)
+            .ssl_mode(self.ssl_mode.into())
+            .application_name("table-forge");
+        if !password.is_empty() {
+            options = options.password(password);
+        }
+        options
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SslMode {
+    Disable,
+    #[default]
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    pub const ALL: [SslMode; 5] = [
+        SslMode::Disable,
+        SslMode::Prefer,
+        SslMode::Require,
+        SslMode::VerifyCa,
+        SslMode::VerifyFull,
+    ];
+}
+
+impl fmt::Display for SslMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        })
+    }
+}
+
+impl From<SslMode> for PgSslMode {
+    fn from(mode: SslMode) -> Self {
+        match mode {
+            SslMode::Disable => PgSslMode::Disable,
+            SslMode::Prefer => PgSslMode::Prefer,
+            SslMode::Require => PgSslMode::Require,
+            SslMode::VerifyCa => PgSslMode::VerifyCa,
+            SslMode::VerifyFull => PgSslMode::VerifyFull,
+        }
+    }
+}
+
+/// The per-user profile file. `salt` and `verifier` are empty until a master
+/// passphrase has been chosen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profiles {
+    pub salt: Vec<u8>,
+    pub verifier: Vec<u8>,
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ProfileError {
+    NoConfigDir,
+    Io(std::io::ErrorKind),
+    WrongPassphrase,
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::NoConfigDir => f.write_str("no configuration directory"),
+            ProfileError::Io(kind) => write!(f, "{kind}"),
+            ProfileError::WrongPassphrase => f.write_str("wrong passphrase"),
+        }
+    }
+}
+
+impl Profiles {
+    /// Derives the key from `passphrase`, choosing the passphrase if none has
+    /// been set yet.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<Key, ProfileError> {
+        if self.salt.is_empty() {
+            self.salt = secret::salt();
+            let key = Key::derive(passphrase, &self.salt);
+            self.verifier = key.encrypt(VERIFIER);
+            return Ok(key);
+        }
+        let key = Key::derive(passphrase, &self.salt);
+        match key.decrypt(&self.verifier) {
+            Some(verifier) if verifier == VERIFIER => Ok(key),
+            _ => Err(ProfileError::WrongPassphrase),
+        }
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut profiles = Profiles::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "[profile]" {
+                profiles.profiles.push(Profile::new(""));
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            let Some(profile) = profiles.profiles.last_mut() else {
+                match name {
+                    "salt" => profiles.salt = secret::from_hex(value).unwrap_or_default(),
+                    "verifier" => profiles.verifier = secret::from_hex(value).unwrap_or_default(),
+                    _ => {}
+                }
+                continue;
+            };
+            match name {
+                "name" => profile.name = value.to_string(),
+                "host" => profile.host = value.to_string(),
+                "port" => profile.port = value.parse().unwrap_or(5432),
+                "database" => profile.database = value.to_string(),
+                "user" => profile.user = value.to_string(),
+                "ssl_mode" => {
+                    profile.ssl_mode = SslMode::ALL
+                        .into_iter()
+                        .find(|mode| mode.to_string() == value)
+                        .unwrap_or_default()
+                }
+                "schemas" => profile.schemas = split_list(value),
+                "password" => profile.password = secret::from_hex(value),
+                _ => {}
+            }
+        }
+        profiles
+    }
+}
+
+impl fmt::Display for Profiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# TableForge connection profiles.")?;
+        writeln!(f, "# Passwords are encrypted with the master passphrase.")?;
+        writeln!(f, "salt = {}", secret::to_hex(&self.salt))?;
+        writeln!(f, "verifier = {}", secret::to_hex(&self.verifier))?;
+        for profile in &self.profiles {
+            writeln!(f)?;
+            writeln!(f, "[profile]")?;
+            writeln!(f, "name = {}", profile.name)?;
+            writeln!(f, "host = {}", profile.host)?;
+            writeln!(f, "port = {}", profile.port)?;
+            writeln!(f, "database = {}", profile.database)?;
+            writeln!(f, "user = {}", profile.user)?;
+            writeln!(f, "ssl_mode = {}", profile.ssl_mode)?;
+            writeln!(f, "schemas = {}", join_list(&profile.schemas))?;
+            if let Some(password) = &profile.password {
+                writeln!(f, "password = {}", secret::to_hex(password))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `connections.conf` in the configuration directory.
+fn path() -> Option<PathBuf> {
+    Some(super::config_dir()?.join("connections.conf"))
+}
+
+/// Reads the profile file. A missing file is an empty set of profiles.
+pub async fn load() -> Result<Profiles, ProfileError> {
+    let path = path().ok_or(ProfileError::NoConfigDir)?;
+    match tokio::fs::read_to_string(&path).await {
+        Ok(text) => Ok(Profiles::parse(&text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Profiles::default()),
+        Err(e) => Err(ProfileError::Io(e.kind())),
+    }
+}
+
+pub async fn save(profiles: Profiles) -> Result<PathBuf, ProfileError> {
+    let path = path().ok_or(ProfileError::NoConfigDir)?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| ProfileError::Io(e.kind()))?;
+    }
+    tokio::fs::write(&path, profiles.to_string())
+        .await
+        .map_err(|e| ProfileError::Io(e.kind()))?;
+    Ok(path)
+}
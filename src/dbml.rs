@@ -0,0 +1,343 @@
+use crate::model::{
+    constraint::ForeignKey, list_items, ConstraintKind, Index, IndexMethod, Project,
+    ReferentialAction, Table, UserTypeKind, DEFAULT_SCHEMA,
+};
+
+pub mod parse;
+
+/// The model in DBML, as dbdiagram.io reads it: enums, tables with their
+/// keys, indexes and notes, refs for foreign keys and table groups for
+/// subject areas. Domains, composite types, views and checks have no DBML
+/// counterpart and are left out.
+pub fn generate(project: &Project) -> String {
+    let mut blocks = Vec::new();
+    for ty in &project.types {
+        let UserTypeKind::Enum { labels } = &ty.kind else {
+            continue;
+        };
+        let mut block = format!("Enum {} {{\n", name(&ty.schema, &ty.name));
+        for label in labels {
+            block += &format!("  {}\n", ident(label));
+        }
+        if let Some(comment) = &ty.comment {
+            // Enums take notes on their values only.
+            block.insert_str(0, &comments(comment));
+        }
+        blocks.push(block + "}");
+    }
+    for table in &project.tables {
+        blocks.push(table_block(table));
+    }
+
+    let refs: Vec<String> = project
+        .tables
+        .iter()
+        .flat_map(|table| {
+            table
+                .constraints
+                .iter()
+                .filter_map(move |constraint| match &constraint.kind {
+                    ConstraintKind::ForeignKey(fk) => {
+                        Some(reference(project, table, &constraint.name, fk))
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+    if !refs.is_empty() {
+        blocks.push(refs.join("\n"));
+    }
+
+    for subject_area in &project.areas {
+        let mut block = format!("TableGroup {} {{\n", ident(&subject_area.name));
+        for member in &subject_area.tables {
+            let (schema, table) = member.split_once('.').unwrap_or((DEFAULT_SCHEMA, member));
+            if project.table(schema, table).is_some() {
+                block += &format!("  {}\n", name(schema, table));
+            }
+        }
+        if let Some(comment) = &subject_area.comment {
+            block += &format!("\n  Note: {}\n", string(comment));
+        }
+        blocks.push(block + "}");
+    }
+
+    if blocks.is_empty() {
+        return String::new();
+    }
+    blocks.join("\n\n") + "\n"
+}
+
+fn table_block(table: &Table) -> String {
+    let primary_key: Vec<&str> = match table.primary_key().map(|pk| &pk.kind) {
+        Some(ConstraintKind::PrimaryKey { columns, .. }) => list_items(columns).collect(),
+        _ => Vec::new(),
+    };
+    let unique: Vec<&str> = table
+        .constraints
+        .iter()
+        .filter_map(|constraint| match &constraint.kind {
+            ConstraintKind::Unique { columns, .. } if columns.len() == 1 => {
+                list_items(columns).next()
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut block = format!("Table {} {{\n", name(&table.schema, &table.name));
+    for column in &table.columns {
+        let mut settings = Vec::new();
+        if primary_key == [column.name.as_str()] {
+            settings.push(String::from("pk"));
+        }
+        if column.identity.is_some() {
+            settings.push(String::from("increment"));
+        }
+        if column.not_null && primary_key != [column.name.as_str()] {
+            settings.push(String::from("not null"));
+        }
+        if unique.contains(&column.name.as_str()) {
+            settings.push(String::from("unique"));
+        }
+        if let Some(default) = &column.default {
+            settings.push(format!("default: {}", value(default)));
+        }
+        if let Some(comment) = &column.comment {
+            settings.push(format!("note: {}", string(comment)));
+        }
+        block += &format!(
+            "  {} {}{}\n",
+            ident(&column.name),
+            data_type(&column.data_type),
+            list(&settings)
+        );
+    }
+
+    let mut indexes = Vec::new();
+    for constraint in &table.constraints {
+        match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns, .. } if primary_key.len() > 1 => {
+                indexes.push(format!(
+                    "{} [pk, name: {}]",
+                    group(list_items(columns)),
+                    string(&constraint.name)
+                ));
+            }
+            ConstraintKind::Unique { columns, .. } if columns.len() > 1 => {
+                indexes.push(format!(
+                    "{} [unique, name: {}]",
+                    group(list_items(columns)),
+                    string(&constraint.name)
+                ));
+            }
+            _ => {}
+        }
+    }
+    indexes.extend(table.indexes.iter().map(index));
+    if !indexes.is_empty() {
+        block += "\n  Indexes {\n";
+        for index in indexes {
+            block += &format!("    {index}\n");
+        }
+        block += "  }\n";
+    }
+    let checks: Vec<String> = table
+        .constraints
+        .iter()
+        .filter_map(|constraint| match &constraint.kind {
+            ConstraintKind::Check { expression, .. } => Some(format!(
+                "`{}` [name: {}]",
+                expression.trim(),
+                string(&constraint.name)
+            )),
+            _ => None,
+        })
+        .collect();
+    if !checks.is_empty() {
+        block += "\n  checks {\n";
+        for check in checks {
+            block += &format!("    {check}\n");
+        }
+        block += "  }\n";
+    }
+    if let Some(comment) = &table.comment {
+        block += &format!("\n  Note: {}\n", string(comment));
+    }
+    block + "}"
+}
+
+fn index(index: &Index) -> String {
+    let keys: Vec<String> = index
+        .elements
+        .iter()
+        .map(|element| {
+            if element.is_column() {
+                ident(element.expression.trim())
+            } else {
+                format!("`{}`", element.expression.trim())
+            }
+        })
+        .collect();
+    let keys = match keys.as_slice() {
+        [key] => key.clone(),
+        keys => format!("({})", keys.join(", ")),
+    };
+    let mut settings = vec![format!("name: {}", string(&index.name))];
+    if index.unique {
+        settings.push(String::from("unique"));
+    }
+    if index.method != IndexMethod::Btree {
+        settings.push(format!("type: {}", index.method.as_str()));
+    }
+    format!("{keys}{}", list(&settings))
+}
+
+/// A `Ref` for a foreign key, one-to-one when its columns are unique.
+fn reference(project: &Project, table: &Table, constraint: &str, fk: &ForeignKey) -> String {
+    let columns: Vec<&str> = list_items(&fk.columns).collect();
+    let mut ref_columns: Vec<&str> = list_items(&fk.ref_columns).collect();
+    if ref_columns.is_empty()
+        && let Some(ConstraintKind::PrimaryKey { columns, .. }) = project
+            .table(&fk.ref_schema, &fk.ref_table)
+            .and_then(Table::primary_key)
+            .map(|pk| &pk.kind)
+    {
+        ref_columns = list_items(columns).collect();
+    }
+    let one_to_one = table
+        .constraints
+        .iter()
+        .any(|constraint| match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns: keys, .. }
+            | ConstraintKind::Unique { columns: keys, .. } => {
+                list_items(keys).collect::<Vec<_>>() == columns
+            }
+            _ => false,
+        });
+
+    let mut settings = Vec::new();
+    if fk.on_delete != ReferentialAction::NoAction {
+        settings.push(format!(
+            "delete: {}",
+            fk.on_delete.to_string().to_lowercase()
+        ));
+    }
+    if fk.on_update != ReferentialAction::NoAction {
+        settings.push(format!(
+            "update: {}",
+            fk.on_update.to_string().to_lowercase()
+        ));
+    }
+    format!(
+        "Ref {}: {}.{} {} {}.{}{}",
+        ident(constraint),
+        name(&table.schema, &table.name),
+        group(columns.into_iter()),
+        if one_to_one { "-" } else { ">" },
+        name(&fk.ref_schema, &fk.ref_table),
+        group(ref_columns.into_iter()),
+        list(&settings)
+    )
+}
+
+/// `schema.name`, or just `name` in `public`.
+fn name(schema: &str, name: &str) -> String {
+    if schema == DEFAULT_SCHEMA {
+        ident(name)
+    } else {
+        format!("{}.{}", ident(schema), ident(name))
+    }
+}
+
+/// A name, in double quotes unless it is a plain word.
+fn ident(name: &str) -> String {
+    if !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\\\""))
+    }
+}
+
+/// A column name, or several in parentheses.
+fn group<'a>(columns: impl Iterator<Item = &'a str>) -> String {
+    let columns: Vec<String> = columns.map(ident).collect();
+    match columns.as_slice() {
+        [column] => column.clone(),
+        columns => format!("({})", columns.join(", ")),
+    }
+}
+
+/// A type, quoted when it has spaces in it, like `timestamp with time zone`.
+/// Spaces between modifiers are dropped, so `numeric(10, 2)` stays plain.
+fn data_type(data_type: &str) -> String {
+    let mut depth = 0;
+    let compact: String = data_type
+        .chars()
+        .filter(|&c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            !(c == ' ' && depth > 0)
+        })
+        .collect();
+    if compact.contains(' ') {
+        format!("\"{data_type}\"")
+    } else {
+        compact
+    }
+}
+
+/// ` [a, b]` for settings, or nothing without any.
+fn list(settings: &[String]) -> String {
+    if settings.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", settings.join(", "))
+    }
+}
+
+/// A note, in triple quotes and indented when it runs over several lines.
+fn string(text: &str) -> String {
+    if text.contains('\n') {
+        let lines: Vec<String> = text
+            .replace("'''", "\\'''")
+            .lines()
+            .map(|line| format!("    {line}").trim_end().to_string())
+            .collect();
+        format!("'''\n{}\n  '''", lines.join("\n"))
+    } else {
+        format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// A column default: numbers, booleans, `null` and string literals as they
+/// are, anything else as an expression in backticks.
+fn value(default: &str) -> String {
+    let default = default.trim();
+    let literal = default
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .filter(|inner| !inner.replace("''", "").contains('\''));
+    if let Some(inner) = literal {
+        string(&inner.replace("''", "'"))
+    } else if default.parse::<f64>().is_ok()
+        || ["true", "false", "null"].contains(&default.to_lowercase().as_str())
+    {
+        default.to_lowercase()
+    } else {
+        format!("`{default}`")
+    }
+}
+
+/// `//` lines of a comment.
+fn comments(comment: &str) -> String {
+    comment
+        .lines()
+        .map(|line| format!("// {}\n", line.trim_end()).replace("//  ", "// "))
+        .collect()
+}
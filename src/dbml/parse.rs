@@ -0,0 +1,1047 @@
+use crate::{
+    model::{
+        area, column::Identity, constraint::ForeignKey, data_type, Column, Constraint,
+        ConstraintKind, ConstraintType, Index, IndexElement, IndexMethod, Project,
+        ReferentialAction, SubjectArea, Table, UserType, UserTypeKind, DEFAULT_SCHEMA,
+    },
+    sql::{
+        parse::{Parsed, Skipped},
+        quote_literal,
+    },
+};
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Reads a DBML document: enums, tables with their columns, indexes and
+/// notes, refs as foreign keys and table groups as subject areas. Project
+/// settings and sticky notes are passed over.
+pub fn read(source: &str) -> Parsed {
+    let tokens = tokenize(source);
+    let mut reader = Reader::default();
+    reader.document(Cursor::new(source, &tokens));
+    reader.finish()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    /// A name, keyword or number.
+    Word,
+    /// A `"quoted name"`, without its quotes.
+    Quoted,
+    /// A `'string'` or `'''multi-line string'''`, unescaped.
+    Str,
+    /// A `` `backtick expression` ``, without its backticks.
+    Expr,
+    Punct,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    text: String,
+    /// Byte offsets into the source.
+    start: usize,
+    end: usize,
+    line: usize,
+}
+
+impl Token {
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == Kind::Punct && self.text.starts_with(c)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.kind == Kind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+}
+
+/// Splits DBML into tokens, dropping `//` and `/* */` comments.
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let at = |i: usize| chars.get(i).map_or('\0', |&(_, c)| c);
+    let offset = |i: usize| chars.get(i).map_or(source.len(), |&(offset, _)| offset);
+    let mut tokens = Vec::new();
+    let mut line = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = at(i);
+        let start = i;
+        let first_line = line;
+        let (kind, text) = if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        } else if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c == '/' && at(i + 1) == '/' {
+            while i < chars.len() && at(i) != '\n' {
+                i += 1;
+            }
+            continue;
+        } else if c == '/' && at(i + 1) == '*' {
+            i += 2;
+            while i < chars.len() && !(at(i) == '*' && at(i + 1) == '/') {
+                if at(i) == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        } else if c == '\'' && at(i + 1) == '\'' && at(i + 2) == '\'' {
+            i += 3;
+            let mut text = String::new();
+            while i < chars.len() && !(at(i) == '\'' && at(i + 1) == '\'' && at(i + 2) == '\'') {
+                if at(i) == '\\' && at(i + 1) == '\'' {
+                    i += 1;
+                }
+                if at(i) == '\n' {
+                    line += 1;
+                }
+                text.push(at(i));
+                i += 1;
+            }
+            i += 3;
+            (Kind::Str, dedent(&text))
+        } else if matches!(c, '\'' | '"' | '`') {
+            i += 1;
+            let mut text = String::new();
+            while i < chars.len() && at(i) != c {
+                if at(i) == '\\' && matches!(at(i + 1), '\'' | '"' | '\\' | '`') {
+                    i += 1;
+                } else if at(i) == '\n' {
+                    line += 1;
+                }
+                text.push(at(i));
+                i += 1;
+            }
+            i += 1;
+            let kind = match c {
+                '\'' => Kind::Str,
+                '"' => Kind::Quoted,
+                _ => Kind::Expr,
+            };
+            (kind, text)
+        } else if c.is_alphanumeric() || c == '_' {
+            while i < chars.len() && (at(i).is_alphanumeric() || at(i) == '_') {
+                i += 1;
+            }
+            // Decimals such as 1.5.
+            if c.is_ascii_digit() && at(i) == '.' && at(i + 1).is_ascii_digit() {
+                i += 1;
+                while at(i).is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            (Kind::Word, source[offset(start)..offset(i)].to_string())
+        } else if c == '<' && at(i + 1) == '>' {
+            i += 2;
+            (Kind::Punct, String::from("<>"))
+        } else {
+            i += 1;
+            (Kind::Punct, c.to_string())
+        };
+        tokens.push(Token {
+            kind,
+            text,
+            start: offset(start),
+            end: offset(i.min(chars.len())),
+            line: first_line,
+        });
+    }
+    tokens
+}
+
+/// A multi-line string without the indentation its lines share and the
+/// blank lines around it.
+fn dedent(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+#[derive(Clone, Copy)]
+struct Cursor<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(source: &'a str, tokens: &'a [Token]) -> Self {
+        Self {
+            source,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&'a Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek().is_some_and(|token| token.is_punct(c))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let matches = self.is_punct(c);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let matches = self.peek().is_some_and(|token| token.is_word(word));
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    /// A plain or quoted name.
+    fn name(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(token) if matches!(token.kind, Kind::Word | Kind::Quoted) => {
+                self.pos += 1;
+                Ok(token.text.clone())
+            }
+            _ => Err(self.unexpected("expected a name")),
+        }
+    }
+
+    /// Names joined by dots, as in `schema.table.column`.
+    fn path(&mut self) -> Result<Vec<String>> {
+        let mut names = vec![self.name()?];
+        while self.is_punct('.') && !self.peek_at(1).is_some_and(|t| t.is_punct('(')) {
+            self.pos += 1;
+            names.push(self.name()?);
+        }
+        Ok(names)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(token) if token.kind == Kind::Str => {
+                self.pos += 1;
+                Ok(token.text.clone())
+            }
+            _ => Err(self.unexpected("expected a string")),
+        }
+    }
+
+    /// What is between the bracket that comes next and its match.
+    fn group(&mut self, open: char) -> Result<Cursor<'a>> {
+        let close = match open {
+            '(' => ')',
+            '[' => ']',
+            _ => '}',
+        };
+        if !self.is_punct(open) {
+            return Err(self.unexpected(&format!("expected \"{open}\"")));
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            if token.is_punct(open) {
+                depth += 1;
+            } else if token.is_punct(close) {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(Cursor {
+                        source: self.source,
+                        tokens: &self.tokens[start..self.pos - 1],
+                        pos: 0,
+                    });
+                }
+            }
+        }
+        Err(format!("unbalanced \"{open}\""))
+    }
+
+    /// Skips up to and past the next block, for what is not read.
+    fn skip_block(&mut self) {
+        while !self.done() && !self.is_punct('{') {
+            self.pos += 1;
+        }
+        let _ = self.group('{');
+    }
+
+    /// The remaining tokens split at top level commas.
+    fn split(&mut self) -> Vec<Cursor<'a>> {
+        let mut parts = Vec::new();
+        let mut depth = 0;
+        let mut start = self.pos;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.pos) {
+            if token.is_punct('(') || token.is_punct('[') {
+                depth += 1;
+            } else if token.is_punct(')') || token.is_punct(']') {
+                depth -= 1;
+            } else if depth == 0 && token.is_punct(',') {
+                parts.push(self.slice(start, i));
+                start = i + 1;
+            }
+        }
+        if start < self.tokens.len() {
+            parts.push(self.slice(start, self.tokens.len()));
+        }
+        self.pos = self.tokens.len();
+        parts
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Cursor<'a> {
+        Cursor {
+            source: self.source,
+            tokens: &self.tokens[start..end],
+            pos: 0,
+        }
+    }
+
+    /// Source text of the tokens from `start` up to the current position.
+    fn text_from(&self, start: usize) -> String {
+        match (self.tokens.get(start), self.tokens.get(self.pos.max(1) - 1)) {
+            (Some(first), Some(last)) if start < self.pos => {
+                self.source[first.start..last.end].trim().to_string()
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!(
+                "{expected} near \"{}\" on line {}",
+                &self.source[token.start..token.end],
+                token.line + 1
+            ),
+            None => format!("{expected} at the end"),
+        }
+    }
+}
+
+/// `key: value` or a bare `key` in `[...]`. Keys are lower case with single
+/// spaces, as in `not null`.
+struct Setting<'a> {
+    key: String,
+    value: Option<Cursor<'a>>,
+}
+
+fn settings<'a>(cursor: &mut Cursor<'a>) -> Result<Vec<Setting<'a>>> {
+    if !cursor.is_punct('[') {
+        return Ok(Vec::new());
+    }
+    let mut settings = Vec::new();
+    for mut part in cursor.group('[')?.split() {
+        let start = part.pos;
+        while !part.done() && !part.is_punct(':') {
+            part.pos += 1;
+        }
+        let key = part.slice(start, part.pos);
+        let key = key
+            .tokens
+            .iter()
+            .map(|token| token.text.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = part
+            .eat_punct(':')
+            .then(|| part.slice(part.pos, part.tokens.len()));
+        settings.push(Setting { key, value });
+    }
+    Ok(settings)
+}
+
+fn setting<'a, 'b>(settings: &'b [Setting<'a>], key: &str) -> Option<&'b Setting<'a>> {
+    settings.iter().find(|setting| setting.key == key)
+}
+
+/// The string a setting such as `note: '...'` is set to.
+fn text(settings: &[Setting], key: &str) -> Option<String> {
+    let mut value = setting(settings, key)?.value?;
+    value.string().ok()
+}
+
+/// A setting's value as written, with strings and names unquoted.
+fn words(settings: &[Setting], key: &str) -> Option<String> {
+    let value = setting(settings, key)?.value?;
+    Some(
+        value
+            .tokens
+            .iter()
+            .map(|token| token.text.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// A ref waiting for all tables to be read.
+struct Reference {
+    statement: String,
+    name: Option<String>,
+    from: Endpoint,
+    operator: String,
+    to: Endpoint,
+    on_delete: ReferentialAction,
+    on_update: ReferentialAction,
+}
+
+/// `table.column` or `table.(a, b)`, the table maybe qualified or an
+/// alias.
+#[derive(Clone)]
+struct Endpoint {
+    table: Vec<String>,
+    columns: Vec<String>,
+}
+
+#[derive(Default)]
+struct Reader {
+    project: Project,
+    /// Aliases from `Table name as alias` and the schema and name they
+    /// stand for.
+    aliases: Vec<(String, (String, String))>,
+    references: Vec<Reference>,
+    /// Table group members, resolved once every alias is known.
+    groups: Vec<(usize, Vec<Vec<String>>)>,
+    skipped: Vec<Skipped>,
+}
+
+impl Reader {
+    fn document(&mut self, mut cursor: Cursor) {
+        while !cursor.done() {
+            let start = cursor.pos;
+            let line = cursor.peek().is_some_and(|t| t.is_word("ref"));
+            let result = if cursor.eat_word("table") {
+                self.table(&mut cursor)
+            } else if cursor.eat_word("enum") {
+                self.enumeration(&mut cursor)
+            } else if cursor.eat_word("ref") {
+                self.reference(&mut cursor, start)
+            } else if cursor.eat_word("tablegroup") {
+                self.table_group(&mut cursor)
+            } else if cursor.eat_word("project") || cursor.eat_word("note") {
+                cursor.skip_block();
+                Ok(())
+            } else {
+                let what = cursor.peek().map(|t| t.text.clone()).unwrap_or_default();
+                cursor.skip_block();
+                Err(format!("{what} is not supported"))
+            };
+            if let Err(reason) = result {
+                // Resume after the block the error happened in, or the line
+                // of a short ref.
+                if !line
+                    && !cursor.tokens[start..cursor.pos]
+                        .iter()
+                        .any(|t| t.is_punct('}'))
+                {
+                    cursor.skip_block();
+                }
+                self.skipped.push(Skipped {
+                    statement: first_line(&cursor.text_from(start)),
+                    reason,
+                });
+            }
+        }
+    }
+
+    /// A table name, which may be an alias.
+    fn resolve(&self, path: &[String]) -> Result<(String, String)> {
+        match path {
+            [name] => Ok(self
+                .aliases
+                .iter()
+                .find(|(alias, _)| alias == name)
+                .map(|(_, table)| table.clone())
+                .unwrap_or_else(|| (DEFAULT_SCHEMA.to_string(), name.clone()))),
+            [schema, name] => Ok((schema.clone(), name.clone())),
+            _ => Err(format!("unexpected name {}", path.join("."))),
+        }
+    }
+
+    fn table(&mut self, cursor: &mut Cursor) -> Result<()> {
+        let (schema, name) = self.resolve(&cursor.path()?)?;
+        if self.project.table(&schema, &name).is_some() {
+            cursor.skip_block();
+            return Err(format!("{schema}.{name} is defined twice"));
+        }
+        if cursor.eat_word("as") {
+            let alias = cursor.name()?;
+            self.aliases.push((alias, (schema.clone(), name.clone())));
+        }
+        let table_settings = settings(cursor)?;
+        let mut table = Table::new(schema, name);
+        table.comment = text(&table_settings, "note");
+
+        let mut body = cursor.group('{')?;
+        let mut keys = Vec::new();
+        let mut unique = Vec::new();
+        while !body.done() {
+            if body.peek().is_some_and(|t| t.is_word("note"))
+                && body
+                    .peek_at(1)
+                    .is_some_and(|t| t.is_punct(':') || t.is_punct('{'))
+            {
+                body.pos += 1;
+                table.comment = Some(note(&mut body)?);
+            } else if body.peek().is_some_and(|t| t.is_word("indexes"))
+                && body.peek_at(1).is_some_and(|t| t.is_punct('{'))
+            {
+                body.pos += 1;
+                let mut indexes = body.group('{')?;
+                while !indexes.done() {
+                    index(&mut indexes, &mut table)?;
+                }
+            } else if body.peek().is_some_and(|t| t.is_word("checks"))
+                && body.peek_at(1).is_some_and(|t| t.is_punct('{'))
+            {
+                body.pos += 1;
+                let mut checks = body.group('{')?;
+                while !checks.done() {
+                    check(&mut checks, &mut table)?;
+                }
+            } else if body.is_punct('~') {
+                return Err(String::from("table partials are not supported"));
+            } else {
+                let column = self.column(&mut body, &table)?;
+                if column.1 {
+                    keys.push(column.0.name.clone());
+                }
+                if column.2 {
+                    unique.push(column.0.name.clone());
+                }
+                table.columns.push(column.0);
+            }
+        }
+
+        if !keys.is_empty() {
+            if table.primary_key().is_some() {
+                return Err(String::from("the primary key is given twice"));
+            }
+            let constraint = Constraint::new(
+                table.constraint_name(ConstraintType::PrimaryKey),
+                ConstraintKind::PrimaryKey {
+                    columns: keys,
+                    include: Vec::new(),
+                },
+            );
+            table.constraints.insert(0, constraint);
+        }
+        for column in unique {
+            let constraint = Constraint::new(
+                table.column_constraint_name(ConstraintType::Unique, std::slice::from_ref(&column)),
+                ConstraintKind::Unique {
+                    columns: vec![column],
+                    include: Vec::new(),
+                    nulls_not_distinct: false,
+                },
+            );
+            table.constraints.push(constraint);
+        }
+        self.project.tables.push(table);
+        Ok(())
+    }
+
+    /// A column with whether it is the primary key and unique. Inline refs
+    /// are kept for later.
+    fn column(&mut self, cursor: &mut Cursor, table: &Table) -> Result<(Column, bool, bool)> {
+        let start = cursor.pos;
+        let name = cursor.name()?;
+        let data_type = column_type(cursor)?;
+        let settings = settings(cursor)?;
+        let statement = cursor.text_from(start);
+
+        let mut column = Column::new(&name, data_type);
+        let key = setting(&settings, "pk").is_some() || setting(&settings, "primary key").is_some();
+        column.not_null = setting(&settings, "not null").is_some();
+        // Integers count up as serials, anything else as an identity.
+        if setting(&settings, "increment").is_some()
+            && data_type::serial_base(&column.data_type).is_none()
+        {
+            if matches!(
+                data_type::canonical(&column.data_type).as_str(),
+                "smallint" | "integer" | "bigint"
+            ) {
+                column.data_type = data_type::serial_for(&column.data_type).to_string();
+            } else {
+                column.identity = Some(Identity::ByDefault);
+                column.not_null = true;
+            }
+        }
+        if let Some(mut value) = setting(&settings, "default").and_then(|s| s.value) {
+            column.default = Some(default(&mut value)?);
+        }
+        column.comment = text(&settings, "note");
+
+        if let Some(mut value) = setting(&settings, "ref").and_then(|s| s.value) {
+            let operator = value
+                .next()
+                .filter(|token| token.kind == Kind::Punct)
+                .map(|token| token.text.clone())
+                .ok_or_else(|| value.unexpected("expected <, >, - or <>"))?;
+            let to = endpoint(&mut value)?;
+            let here = Endpoint {
+                table: if table.schema == DEFAULT_SCHEMA {
+                    vec![table.name.clone()]
+                } else {
+                    vec![table.schema.clone(), table.name.clone()]
+                },
+                columns: vec![name],
+            };
+            self.references.push(Reference {
+                statement,
+                name: None,
+                from: here,
+                operator,
+                to,
+                on_delete: ReferentialAction::NoAction,
+                on_update: ReferentialAction::NoAction,
+            });
+        }
+        let unique = setting(&settings, "unique").is_some();
+        Ok((column, key, unique))
+    }
+
+    fn enumeration(&mut self, cursor: &mut Cursor) -> Result<()> {
+        let (schema, name) = self.resolve(&cursor.path()?)?;
+        let mut body = cursor.group('{')?;
+        let mut labels = Vec::new();
+        while !body.done() {
+            labels.push(body.name()?);
+            settings(&mut body)?;
+        }
+        self.project
+            .types
+            .push(UserType::new(schema, name, UserTypeKind::Enum { labels }));
+        Ok(())
+    }
+
+    /// `Ref name: a > b [...]` or the same in a block.
+    fn reference(&mut self, cursor: &mut Cursor, start: usize) -> Result<()> {
+        let name = if cursor.is_punct(':') || cursor.is_punct('{') {
+            None
+        } else {
+            Some(cursor.name()?)
+        };
+        let mut body = if cursor.eat_punct(':') {
+            let end = cursor.peek().map(|t| t.line);
+            let from = cursor.pos;
+            // To the end of the line, or of its settings.
+            while let Some(token) = cursor.peek() {
+                if Some(token.line) != end {
+                    break;
+                }
+                if token.is_punct('[') {
+                    cursor.group('[')?;
+                    break;
+                }
+                cursor.pos += 1;
+            }
+            cursor.slice(from, cursor.pos)
+        } else {
+            cursor.group('{')?
+        };
+        let statement = cursor.text_from(start);
+
+        let from = endpoint(&mut body)?;
+        let operator = body
+            .next()
+            .filter(|token| token.kind == Kind::Punct)
+            .map(|token| token.text.clone())
+            .ok_or_else(|| body.unexpected("expected <, >, - or <>"))?;
+        let to = endpoint(&mut body)?;
+        let settings = settings(&mut body)?;
+        if !body.done() {
+            return Err(body.unexpected("unexpected text"));
+        }
+        self.references.push(Reference {
+            statement,
+            name,
+            from,
+            operator,
+            to,
+            on_delete: action(words(&settings, "delete"))?,
+            on_update: action(words(&settings, "update"))?,
+        });
+        Ok(())
+    }
+
+    fn table_group(&mut self, cursor: &mut Cursor) -> Result<()> {
+        let name = cursor.path()?.join(".");
+        let group_settings = settings(cursor)?;
+        let mut area = SubjectArea::new(name);
+        area.comment = text(&group_settings, "note");
+        let mut body = cursor.group('{')?;
+        let mut members = Vec::new();
+        while !body.done() {
+            if body.peek().is_some_and(|t| t.is_word("note"))
+                && body
+                    .peek_at(1)
+                    .is_some_and(|t| t.is_punct(':') || t.is_punct('{'))
+            {
+                body.pos += 1;
+                area.comment = Some(note(&mut body)?);
+            } else {
+                members.push(body.path()?);
+            }
+        }
+        self.groups.push((self.project.areas.len(), members));
+        self.project.areas.push(area);
+        Ok(())
+    }
+
+    fn foreign_key(&self, reference: &Reference) -> Result<(usize, Constraint)> {
+        let (from, to) = match reference.operator.as_str() {
+            ">" | "-" => (&reference.from, &reference.to),
+            "<" => (&reference.to, &reference.from),
+            "<>" => return Err(String::from("many-to-many refs have no foreign key")),
+            operator => return Err(format!("unknown relationship {operator}")),
+        };
+        let (schema, name) = self.resolve(&from.table)?;
+        let (ref_schema, ref_table) = self.resolve(&to.table)?;
+        let i = self
+            .project
+            .tables
+            .iter()
+            .position(|t| t.schema == schema && t.name == name)
+            .ok_or_else(|| format!("unknown table {}", area::member(&schema, &name)))?;
+        let parent = self
+            .project
+            .table(&ref_schema, &ref_table)
+            .ok_or_else(|| format!("unknown table {}", area::member(&ref_schema, &ref_table)))?;
+        let table = &self.project.tables[i];
+        for (table, columns) in [(table, &from.columns), (parent, &to.columns)] {
+            if let Some(column) = columns
+                .iter()
+                .find(|c| !table.columns.iter().any(|column| column.name == **c))
+            {
+                return Err(format!("{} has no column {column}", table.name));
+            }
+        }
+        if from.columns.len() != to.columns.len() {
+            return Err(String::from("both ends need as many columns"));
+        }
+
+        let name = reference.name.clone().unwrap_or_else(|| {
+            table.column_constraint_name(ConstraintType::ForeignKey, &from.columns)
+        });
+        let constraint = Constraint::new(
+            name,
+            ConstraintKind::ForeignKey(ForeignKey {
+                columns: from.columns.clone(),
+                ref_schema,
+                ref_table,
+                ref_columns: to.columns.clone(),
+                on_delete: reference.on_delete,
+                on_update: reference.on_update,
+            }),
+        );
+        Ok((i, constraint))
+    }
+
+    fn finish(mut self) -> Parsed {
+        for reference in std::mem::take(&mut self.references) {
+            match self.foreign_key(&reference) {
+                Ok((i, constraint)) => self.project.tables[i].constraints.push(constraint),
+                Err(reason) => self.skipped.push(Skipped {
+                    statement: first_line(&reference.statement),
+                    reason,
+                }),
+            }
+        }
+        for (i, members) in std::mem::take(&mut self.groups) {
+            for member in members {
+                match self.resolve(&member) {
+                    Ok((schema, name)) if self.project.table(&schema, &name).is_some() => {
+                        self.project.areas[i]
+                            .tables
+                            .push(area::member(&schema, &name));
+                    }
+                    _ => self.skipped.push(Skipped {
+                        statement: format!("TableGroup {}", self.project.areas[i].name),
+                        reason: format!("unknown table {}", member.join(".")),
+                    }),
+                }
+            }
+        }
+        Parsed {
+            project: self.project,
+            skipped: self.skipped,
+        }
+    }
+}
+
+/// `: 'text'` or `{ 'text' }` after `Note`.
+fn note(cursor: &mut Cursor) -> Result<String> {
+    if cursor.eat_punct(':') {
+        cursor.string()
+    } else {
+        cursor.group('{')?.string()
+    }
+}
+
+/// The type after a column name: everything up to its settings or the end
+/// of the line, so that both `varchar(255)` and `"double precision"` read.
+fn column_type(cursor: &mut Cursor) -> Result<String> {
+    let first = cursor
+        .peek()
+        .ok_or_else(|| cursor.unexpected("expected a type"))?;
+    if first.kind == Kind::Quoted {
+        cursor.pos += 1;
+        let mut data_type = first.text.clone();
+        while cursor.is_punct('[') && cursor.peek_at(1).is_some_and(|t| t.is_punct(']')) {
+            cursor.pos += 2;
+            data_type += "[]";
+        }
+        return Ok(data_type);
+    }
+    let start = cursor.pos;
+    while let Some(token) = cursor.peek() {
+        if token.line != first.line
+            || (token.is_punct('[') && !cursor.peek_at(1).is_some_and(|t| t.is_punct(']')))
+        {
+            break;
+        }
+        if token.is_punct('(') {
+            cursor.group('(')?;
+        } else {
+            cursor.pos += 1;
+        }
+    }
+    let data_type = cursor
+        .text_from(start)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if data_type.is_empty() {
+        return Err(cursor.unexpected("expected a type"));
+    }
+    // Names dbdiagram.io offers that PostgreSQL doesn't know.
+    Ok(match data_type.to_lowercase().as_str() {
+        "datetime" => String::from("timestamp"),
+        "string" => String::from("text"),
+        _ => data_type,
+    })
+}
+
+/// A column default as SQL.
+fn default(value: &mut Cursor) -> Result<String> {
+    let start = value.pos;
+    let token = value
+        .next()
+        .ok_or_else(|| value.unexpected("expected a default"))?;
+    let default = match token.kind {
+        Kind::Str => quote_literal(&token.text),
+        Kind::Expr => token.text.trim().to_string(),
+        _ => {
+            value.pos = value.tokens.len();
+            value.text_from(start)
+        }
+    };
+    Ok(default)
+}
+
+/// `table.column`, `schema.table.column` or `table.(a, b)`.
+fn endpoint(cursor: &mut Cursor) -> Result<Endpoint> {
+    let mut path = cursor.path()?;
+    let columns = if cursor.eat_punct('.') {
+        cursor
+            .group('(')?
+            .split()
+            .into_iter()
+            .map(|mut column| column.name())
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        if path.len() < 2 {
+            return Err(cursor.unexpected("expected table.column"));
+        }
+        vec![path.pop().unwrap_or_default()]
+    };
+    Ok(Endpoint {
+        table: path,
+        columns,
+    })
+}
+
+fn action(value: Option<String>) -> Result<ReferentialAction> {
+    Ok(match value.as_deref() {
+        None | Some("no action") => ReferentialAction::NoAction,
+        Some("cascade") => ReferentialAction::Cascade,
+        Some("restrict") => ReferentialAction::Restrict,
+        Some("set null") => ReferentialAction::SetNull,
+        Some("set default") => ReferentialAction::SetDefault,
+        Some(other) => return Err(format!("unknown referential action {other}")),
+    })
+}
+
+/// An entry of `Indexes { ... }`, which becomes the primary key with
+/// `[pk]` and an index otherwise.
+fn index(cursor: &mut Cursor, table: &mut Table) -> Result<()> {
+    let mut elements = Vec::new();
+    if cursor.is_punct('(') {
+        for mut part in cursor.group('(')?.split() {
+            elements.push(index_element(&mut part)?);
+        }
+    } else {
+        elements.push(index_element(cursor)?);
+    }
+    let settings = settings(cursor)?;
+    let name = text(&settings, "name");
+
+    if setting(&settings, "pk").is_some() {
+        if table.primary_key().is_some() {
+            return Err(String::from("the primary key is given twice"));
+        }
+        let constraint = Constraint::new(
+            name.unwrap_or_else(|| table.constraint_name(ConstraintType::PrimaryKey)),
+            ConstraintKind::PrimaryKey {
+                columns: elements.into_iter().map(|e| e.expression).collect(),
+                include: Vec::new(),
+            },
+        );
+        table.constraints.insert(0, constraint);
+        return Ok(());
+    }
+
+    let mut index = Index::new(name.unwrap_or_else(|| table.default_index_name(&elements)));
+    index.elements = elements;
+    index.unique = setting(&settings, "unique").is_some();
+    if let Some(method) = words(&settings, "type") {
+        index.method = IndexMethod::ALL
+            .into_iter()
+            .find(|m| m.as_str() == method)
+            .ok_or_else(|| format!("unknown index type {method}"))?;
+    }
+    table.indexes.push(index);
+    Ok(())
+}
+
+fn index_element(cursor: &mut Cursor) -> Result<IndexElement> {
+    match cursor.peek() {
+        Some(token) if token.kind == Kind::Expr => {
+            cursor.pos += 1;
+            Ok(IndexElement::new(token.text.trim()))
+        }
+        _ => Ok(IndexElement::new(cursor.name()?)),
+    }
+}
+
+/// An entry of `checks { ... }`.
+fn check(cursor: &mut Cursor, table: &mut Table) -> Result<()> {
+    let expression = match cursor.next() {
+        Some(token) if token.kind == Kind::Expr => token.text.trim().to_string(),
+        _ => return Err(cursor.unexpected("expected a `check`")),
+    };
+    let settings = settings(cursor)?;
+    let kind = ConstraintKind::Check {
+        expression,
+        no_inherit: false,
+    };
+    let name = text(&settings, "name").unwrap_or_else(|| table.default_constraint_name(&kind));
+    table.constraints.push(Constraint::new(name, kind));
+    Ok(())
+}
+
+fn first_line(statement: &str) -> String {
+    statement.lines().next().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dbml, sql::diff};
+
+    #[test]
+    fn documents_survive_a_round_trip() {
+        let source = "
+            Project shop { database_type: 'PostgreSQL' }
+
+            Enum order_status {
+              new
+              paid [note: 'Money received']
+            }
+
+            Table users as U {
+              id integer [pk, increment]
+              email varchar(255) [not null, unique]
+              created_at timestamp [default: `now()`]
+              Note: 'People who can sign in'
+            }
+
+            Table orders {
+              id integer [pk]
+              user_id integer [not null]
+              status order_status [not null, default: 'new']
+              total \"numeric(10,2)\"
+
+              indexes {
+                (user_id, status) [name: 'orders_user_status_idx']
+                status [type: hash]
+              }
+            }
+
+            Ref: orders.user_id > U.id [delete: cascade]
+
+            TableGroup sales {
+              users
+              orders
+            }
+        ";
+        let first = read(source);
+        assert_eq!(first.skipped, []);
+        let written = dbml::generate(&first.project);
+        let second = read(&written);
+        assert_eq!(second.skipped, []);
+        assert_eq!(dbml::generate(&second.project), written);
+
+        let schemas = [String::from(DEFAULT_SCHEMA)];
+        assert!(diff::compare(&first.project, &second.project, &schemas).is_empty());
+        assert_eq!(second.project.areas.len(), 1);
+        assert_eq!(second.project.areas[0].tables.len(), 2);
+        let users = &first.project.tables[0];
+        assert!(users
+            .constraints
+            .iter()
+            .any(|c| c.name == "users_email_key"));
+        let orders = &first.project.tables[1];
+        assert_eq!(orders.indexes[1].name, "orders_status_idx");
+        assert!(orders
+            .constraints
+            .iter()
+            .any(|c| c.name == "orders_user_id_fkey"));
+
+        let orders = &second.project.tables[1];
+        assert_eq!(orders.indexes.len(), 2);
+        assert!(orders.constraints.iter().any(|c| matches!(
+            &c.kind,
+            ConstraintKind::ForeignKey(fk)
+                if fk.ref_table == "users" && fk.on_delete == ReferentialAction::Cascade
+        )));
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+
+use crate::model::{list_items, Column, ConstraintKind, Project, Table, DEFAULT_SCHEMA};
+
+pub mod graphviz;
+pub mod mermaid;
+pub mod plantuml;
+
+/// How much of each table the diagram shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewMode {
+    /// Every column with its type.
+    #[default]
+    Physical,
+    /// Primary, foreign and unique key columns only.
+    Keys,
+    /// Table names only.
+    Names,
+}
+
+impl ViewMode {
+    pub const ALL: [ViewMode; 3] = [ViewMode::Physical, ViewMode::Keys, ViewMode::Names];
+}
+
+impl fmt::Display for ViewMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ViewMode::Physical => "Physical",
+            ViewMode::Keys => "Keys only",
+            ViewMode::Names => "Table names",
+        })
+    }
+}
+
+/// Text diagram languages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Mermaid,
+    PlantUml,
+    Graphviz,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Mermaid, Format::PlantUml, Format::Graphviz];
+
+    /// File name to save the diagram as.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Format::Mermaid => "schema.mmd",
+            Format::PlantUml => "schema.puml",
+            Format::Graphviz => "schema.dot",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Mermaid => "Mermaid",
+            Format::PlantUml => "PlantUML",
+            Format::Graphviz => "Graphviz DOT",
+        })
+    }
+}
+
+/// A column as the diagram shows it.
+pub struct Attribute<'a> {
+    pub column: &'a Column,
+    pub primary: bool,
+    pub foreign: bool,
+    pub unique: bool,
+}
+
+impl Attribute<'_> {
+    /// `PK`, `FK` or `UK`, the first that applies.
+    pub fn marker(&self) -> Option<&'static str> {
+        if self.primary {
+            Some("PK")
+        } else if self.foreign {
+            Some("FK")
+        } else if self.unique {
+            Some("UK")
+        } else {
+            None
+        }
+    }
+}
+
+/// The columns of `table` shown in `mode`, in table order.
+pub fn attributes(table: &Table, mode: ViewMode) -> Vec<Attribute<'_>> {
+    if mode == ViewMode::Names {
+        return Vec::new();
+    }
+    let mut primary = Vec::new();
+    let mut foreign = Vec::new();
+    let mut unique = Vec::new();
+    for constraint in &table.constraints {
+        match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns, .. } => primary.extend(list_items(columns)),
+            ConstraintKind::ForeignKey(fk) => foreign.extend(list_items(&fk.columns)),
+            ConstraintKind::Unique { columns, .. } => unique.extend(list_items(columns)),
+            _ => {}
+        }
+    }
+    table
+        .columns
+        .iter()
+        .map(|column| {
+            let name = column.name.as_str();
+            Attribute {
+                column,
+                primary: primary.contains(&name),
+                foreign: foreign.contains(&name),
+                unique: unique.contains(&name),
+            }
+        })
+        .filter(|a| mode == ViewMode::Physical || a.primary || a.foreign || a.unique)
+        .collect()
+}
+
+/// A table in a text diagram.
+struct Entity<'a> {
+    /// Identifier the diagram refers to the table by.
+    id: String,
+    title: String,
+    table: &'a Table,
+    attributes: Vec<Attribute<'a>>,
+}
+
+/// A foreign key between two tables of the diagram.
+struct Relationship<'a> {
+    name: &'a str,
+    /// Entity with the foreign key.
+    child: usize,
+    /// Entity it references.
+    parent: usize,
+    columns: Vec<&'a str>,
+    ref_columns: Vec<&'a str>,
+    /// A child row can go without a parent, as a foreign key column is
+    /// nullable and not part of the primary key.
+    optional: bool,
+    /// A parent row has at most one child, as the foreign key is unique.
+    single: bool,
+    /// The foreign key is part of the child's primary key.
+    identifying: bool,
+}
+
+/// `tables` in `format`, showing what `mode` shows. Foreign keys are drawn
+/// when both of their tables are in.
+pub fn generate(project: &Project, tables: &[usize], mode: ViewMode, format: Format) -> String {
+    let entities: Vec<Entity> = tables
+        .iter()
+        .map(|&i| {
+            let table = &project.tables[i];
+            Entity {
+                id: id(&table.schema, &table.name),
+                title: title(&table.schema, &table.name),
+                table,
+                attributes: attributes(table, mode),
+            }
+        })
+        .collect();
+    let relationships = relationships(project, &entities);
+    match format {
+        Format::Mermaid => mermaid::generate(&entities, &relationships, mode),
+        Format::PlantUml => plantuml::generate(&entities, &relationships, mode),
+        Format::Graphviz => graphviz::generate(&entities, &relationships, mode),
+    }
+}
+
+fn relationships<'a>(project: &'a Project, entities: &[Entity<'a>]) -> Vec<Relationship<'a>> {
+    let mut relationships = Vec::new();
+    for (child, entity) in entities.iter().enumerate() {
+        let table = entity.table;
+        let keys = |kind: fn(&ConstraintKind) -> Option<&Vec<String>>| -> Vec<Vec<&str>> {
+            table
+                .constraints
+                .iter()
+                .filter_map(|c| kind(&c.kind))
+                .map(|columns| list_items(columns).collect())
+                .collect()
+        };
+        let primary_key = keys(|kind| match kind {
+            ConstraintKind::PrimaryKey { columns, .. } => Some(columns),
+            _ => None,
+        });
+        let unique = keys(|kind| match kind {
+            ConstraintKind::PrimaryKey { columns, .. } | ConstraintKind::Unique { columns, .. } => {
+                Some(columns)
+            }
+            _ => None,
+        });
+
+        for constraint in &table.constraints {
+            let ConstraintKind::ForeignKey(fk) = &constraint.kind else {
+                continue;
+            };
+            let Some(parent) = entities
+                .iter()
+                .position(|e| e.table.schema == fk.ref_schema && e.table.name == fk.ref_table)
+            else {
+                continue;
+            };
+            let columns: Vec<&str> = list_items(&fk.columns).collect();
+            let mut ref_columns: Vec<&str> = list_items(&fk.ref_columns).collect();
+            if ref_columns.is_empty()
+                && let Some(ConstraintKind::PrimaryKey { columns, .. }) = project
+                    .table(&fk.ref_schema, &fk.ref_table)
+                    .and_then(Table::primary_key)
+                    .map(|pk| &pk.kind)
+            {
+                ref_columns = list_items(columns).collect();
+            }
+            let optional = columns.iter().any(|name| {
+                !primary_key.iter().flatten().any(|c| c == name)
+                    && table
+                        .columns
+                        .iter()
+                        .any(|column| column.name == *name && !column.not_null)
+            });
+            relationships.push(Relationship {
+                name: &constraint.name,
+                child,
+                parent,
+                single: unique.contains(&columns),
+                identifying: primary_key
+                    .first()
+                    .is_some_and(|pk| columns.iter().all(|c| pk.contains(c))),
+                optional,
+                columns,
+                ref_columns,
+            });
+        }
+    }
+    relationships
+}
+
+/// `schema.name`, or just `name` in `public`.
+fn title(schema: &str, name: &str) -> String {
+    if schema == DEFAULT_SCHEMA {
+        name.to_string()
+    } else {
+        format!("{schema}.{name}")
+    }
+}
+
+/// The title with anything but letters, digits and underscores replaced,
+/// which all three languages take as a bare identifier.
+fn id(schema: &str, name: &str) -> String {
+    let id: String = title(schema, name)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if id.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{id}")
+    } else {
+        id
+    }
+}
+
+/// A type without the spaces between its modifiers, `numeric(10,2)`.
+fn compact(data_type: &str) -> String {
+    let mut depth = 0;
+    data_type
+        .chars()
+        .filter(|&c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            !(c == ' ' && depth > 0)
+        })
+        .collect()
+}
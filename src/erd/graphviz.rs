@@ -0,0 +1,93 @@
+use super::{Entity, Relationship, ViewMode};
+
+/// A Graphviz digraph with a record node per table, one field per column.
+/// Foreign keys run from their first column to the column it references
+/// when both are shown, with crow's feet at the many end and a dashed line
+/// when they are not identifying.
+pub(super) fn generate(
+    entities: &[Entity],
+    relationships: &[Relationship],
+    mode: ViewMode,
+) -> String {
+    let mut out = String::from(
+        "digraph schema {\n  \
+         graph [rankdir=LR, fontname=\"Helvetica\"];\n  \
+         node [shape=record, fontname=\"Helvetica\", fontsize=10];\n  \
+         edge [dir=both, arrowtail=crowodot, arrowhead=tee, fontname=\"Helvetica\", fontsize=8];\n",
+    );
+    if !entities.is_empty() {
+        out += "\n";
+    }
+    for entity in entities {
+        // With the graph laid out left to right, top level fields stack.
+        let mut fields = vec![escape(&entity.title)];
+        if mode != ViewMode::Names {
+            fields.extend(entity.attributes.iter().enumerate().map(|(i, attribute)| {
+                let column = attribute.column;
+                let marker = attribute
+                    .marker()
+                    .map(|marker| format!("{marker} "))
+                    .unwrap_or_default();
+                let field = format!("{marker}{} : {}", column.name, column.data_type);
+                format!("<c{i}> {}\\l", escape(&field))
+            }));
+        }
+        out += &format!("  {} [label=\"{}\"];\n", entity.id, fields.join("|"));
+    }
+
+    if !relationships.is_empty() {
+        out += "\n";
+    }
+    for relationship in relationships {
+        let child = &entities[relationship.child];
+        let parent = &entities[relationship.parent];
+        let mut attributes = vec![format!(
+            "label=\"{}\"",
+            relationship.name.replace('"', "\\\"")
+        )];
+        if relationship.single {
+            attributes.push(String::from("arrowtail=teeodot"));
+        }
+        if relationship.optional {
+            attributes.push(String::from("arrowhead=teeodot"));
+        }
+        if !relationship.identifying {
+            attributes.push(String::from("style=dashed"));
+        }
+        out += &format!(
+            "  {}{} -> {}{} [{}];\n",
+            child.id,
+            port(child, relationship.columns.first()),
+            parent.id,
+            port(parent, relationship.ref_columns.first()),
+            attributes.join(", ")
+        );
+    }
+    out + "}\n"
+}
+
+/// `:c<n>` for the field of `column`, if the node shows it.
+fn port(entity: &Entity, column: Option<&&str>) -> String {
+    column
+        .and_then(|name| {
+            entity
+                .attributes
+                .iter()
+                .position(|attribute| attribute.column.name == **name)
+        })
+        .map(|i| format!(":c{i}"))
+        .unwrap_or_default()
+}
+
+/// Text inside a record label, where braces, bars and angle brackets have a
+/// meaning of their own.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '"' | '{' | '}' | '|' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,72 @@
+use super::{compact, Entity, Relationship, ViewMode};
+
+/// A Mermaid `erDiagram`. Tables outside `public` get an alias with their
+/// schema, and a comment goes after its column in quotes.
+pub(super) fn generate(
+    entities: &[Entity],
+    relationships: &[Relationship],
+    mode: ViewMode,
+) -> String {
+    let mut out = String::from("erDiagram\n");
+    for entity in entities {
+        let mut declaration = entity.id.clone();
+        if entity.title != entity.id {
+            declaration += &format!("[\"{}\"]", entity.title.replace('"', "'"));
+        }
+        if mode == ViewMode::Names {
+            out += &format!("    {declaration}\n");
+            continue;
+        }
+        out += &format!("    {declaration} {{\n");
+        for attribute in &entity.attributes {
+            let column = attribute.column;
+            let mut line = format!("        {} {}", word(&column.data_type), word(&column.name));
+            let keys: Vec<&str> = [
+                (attribute.primary, "PK"),
+                (attribute.foreign, "FK"),
+                (attribute.unique && !attribute.primary, "UK"),
+            ]
+            .into_iter()
+            .filter_map(|(applies, key)| applies.then_some(key))
+            .collect();
+            if !keys.is_empty() {
+                line += &format!(" {}", keys.join(", "));
+            }
+            if let Some(comment) = &column.comment {
+                line += &format!(
+                    " \"{}\"",
+                    comment.lines().next().unwrap_or_default().replace('"', "'")
+                );
+            }
+            out += &line;
+            out += "\n";
+        }
+        out += "    }\n";
+    }
+
+    for relationship in relationships {
+        out += &format!(
+            "    {} {}{}{} {} : \"{}\"\n",
+            entities[relationship.parent].id,
+            if relationship.optional { "|o" } else { "||" },
+            if relationship.identifying { "--" } else { ".." },
+            if relationship.single { "o|" } else { "o{" },
+            entities[relationship.child].id,
+            relationship.name.replace('"', "'")
+        );
+    }
+    out
+}
+
+/// Types and names as one word, which is all Mermaid reads: spaces become
+/// underscores and the comma of `numeric(10,2)` a dash.
+fn word(text: &str) -> String {
+    compact(text)
+        .chars()
+        .map(|c| match c {
+            ' ' => '_',
+            ',' => '-',
+            c => c,
+        })
+        .collect()
+}
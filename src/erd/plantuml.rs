@@ -0,0 +1,80 @@
+use super::{Entity, Relationship, ViewMode};
+
+/// A PlantUML entity diagram in information engineering notation. Key
+/// columns come first, above a separator, and `*` marks mandatory columns.
+pub(super) fn generate(
+    entities: &[Entity],
+    relationships: &[Relationship],
+    mode: ViewMode,
+) -> String {
+    let mut out = String::from("@startuml\nhide circle\nskinparam linetype ortho\n");
+    for entity in entities {
+        let declaration = format!(
+            "entity \"{}\" as {}",
+            entity.title.replace('"', "'"),
+            entity.id
+        );
+        if mode == ViewMode::Names {
+            out += &format!("\n{declaration}\n");
+            continue;
+        }
+        out += &format!("\n{declaration} {{\n");
+        let (keys, rest): (Vec<_>, Vec<_>) = entity
+            .attributes
+            .iter()
+            .partition(|attribute| attribute.primary);
+        for attribute in &keys {
+            out += &line(attribute);
+        }
+        if !keys.is_empty() && !rest.is_empty() {
+            out += "  --\n";
+        }
+        for attribute in &rest {
+            out += &line(attribute);
+        }
+        out += "}\n";
+    }
+
+    if !relationships.is_empty() {
+        out += "\n";
+    }
+    for relationship in relationships {
+        out += &format!(
+            "{} {}{}{} {} : {}\n",
+            entities[relationship.parent].id,
+            if relationship.optional { "|o" } else { "||" },
+            if relationship.identifying { "--" } else { ".." },
+            if relationship.single { "o|" } else { "o{" },
+            entities[relationship.child].id,
+            relationship.name
+        );
+    }
+    out + "@enduml\n"
+}
+
+fn line(attribute: &super::Attribute) -> String {
+    let column = attribute.column;
+    let mut line = format!(
+        "  {}{} : {}",
+        if column.not_null || attribute.primary {
+            "* "
+        } else {
+            ""
+        },
+        column.name,
+        column.data_type
+    );
+    for (applies, key) in [
+        (attribute.primary, "PK"),
+        (attribute.foreign, "FK"),
+        (attribute.unique && !attribute.primary, "UK"),
+    ] {
+        if applies {
+            line += &format!(" <<{key}>>");
+        }
+    }
+    if let Some(comment) = &column.comment {
+        line += &format!(" // {}", comment.lines().next().unwrap_or_default());
+    }
+    line + "\n"
+}
@@ -1,7 +1,17 @@
 use iced::{widget::row, Alignment::Center, Element, Font, Size, Task, Theme};
-use ui::{Primary, PrimaryMessage, Sidebar, SidebarMessage};
+use ui::{
+    primary::{problems::ProblemsMessage, Page},
+    Primary, PrimaryMessage, Sidebar, SidebarMessage,
+};
 
+mod codegen;
 mod constants;
+mod datetime;
+mod db;
+mod dbml;
+mod erd;
+mod model;
+mod sql;
 mod ui;
 mod utils;
 
@@ -37,8 +47,28 @@ impl<'a> TableForge<'a> {
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::Sidebar(message) => self.sidebar.update(message).map(Message::Sidebar),
-            Message::Primary(message) => self.primary.update(message).map(Message::Primary),
+            Message::Sidebar(message) => {
+                match message {
+                    SidebarMessage::Diagram => self.primary.show(Page::Diagram),
+                    SidebarMessage::Database => self.primary.show(Page::Database),
+                    SidebarMessage::Script => self.primary.show(Page::Script),
+                    SidebarMessage::Export => self.primary.show(Page::Export),
+                    _ => {}
+                }
+                self.sidebar.update(message).map(Message::Sidebar)
+            }
+            Message::Primary(message) => {
+                // Problems are shown on the diagram, which the sidebar has to
+                // follow.
+                let task = if let PrimaryMessage::Problems(ProblemsMessage::Select(_)) = &message {
+                    self.sidebar
+                        .update(SidebarMessage::Diagram)
+                        .map(Message::Sidebar)
+                } else {
+                    Task::none()
+                };
+                Task::batch([task, self.primary.update(message).map(Message::Primary)])
+            }
         }
     }
 
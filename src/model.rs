@@ -0,0 +1,579 @@
+pub mod area;
+pub mod column;
+pub mod constraint;
+pub mod data_type;
+pub mod extension;
+pub mod history;
+pub mod index;
+pub mod layout;
+pub mod naming;
+pub mod partition;
+pub mod routine;
+pub mod security;
+pub mod sequence;
+pub mod table;
+pub mod trigger;
+pub mod user_type;
+pub mod version;
+pub mod view;
+
+pub use area::SubjectArea;
+pub use column::{Column, Generation};
+pub use constraint::{
+    Constraint, ConstraintKind, ConstraintType, ExclusionElement, ReferentialAction,
+};
+pub use extension::Extension;
+pub use index::{Index, IndexElement, IndexMethod, NullsOrder, SortOrder};
+pub use naming::Conventions;
+pub use partition::{Partition, PartitionBound, PartitionStrategy, Partitioning};
+pub use routine::{Routine, RoutineKind, Volatility};
+pub use security::{Grant, Policy, PolicyCommand, Privilege, Privileges, Role};
+pub use sequence::Sequence;
+pub use table::Table;
+pub use trigger::{Trigger, TriggerTiming};
+pub use user_type::{Attribute, DomainCheck, TypeCategory, UserType, UserTypeKind};
+pub use version::{Feature, PgVersion};
+pub use view::View;
+
+pub const DEFAULT_SCHEMA: &str = "public";
+
+/// Top left corner of an object on the diagram.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Position {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Slot `n` of the grid new objects are dropped on, so that they don't
+    /// pile up on top of each other.
+    pub fn slot(n: usize) -> Self {
+        const COLUMNS: usize = 5;
+        Self::new(
+            40.0 + (n % COLUMNS) as f32 * 280.0,
+            40.0 + (n / COLUMNS) as f32 * 240.0,
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Project {
+    pub tables: Vec<Table>,
+    pub types: Vec<UserType>,
+    pub views: Vec<View>,
+    pub routines: Vec<Routine>,
+    pub sequences: Vec<Sequence>,
+    pub roles: Vec<Role>,
+    /// Enabled extensions, in the order they were enabled.
+    pub extensions: Vec<Extension>,
+    pub areas: Vec<SubjectArea>,
+    pub version: PgVersion,
+    /// Naming rules that validation checks, if the project has any.
+    pub conventions: Option<Box<Conventions>>,
+}
+
+impl Project {
+    pub fn table(&self, schema: &str, name: &str) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|table| table.schema == schema && table.name == name)
+    }
+
+    pub fn add_table(&mut self) -> usize {
+        let mut n = self.tables.len() + 1;
+        while self.table(DEFAULT_SCHEMA, &format!("table_{n}")).is_some() {
+            n += 1;
+        }
+        let mut table = Table::new(DEFAULT_SCHEMA, format!("table_{n}"));
+        table.position = Position::slot(self.tables.len() + self.views.len());
+        self.tables.push(table);
+        self.tables.len() - 1
+    }
+
+    pub fn view(&self, schema: &str, name: &str) -> Option<&View> {
+        self.views
+            .iter()
+            .find(|view| view.schema == schema && view.name == name)
+    }
+
+    pub fn add_view(&mut self, materialized: bool) -> usize {
+        let prefix = if materialized { "mv" } else { "view" };
+        let mut n = self.views.len() + 1;
+        while self
+            .view(DEFAULT_SCHEMA, &format!("{prefix}_{n}"))
+            .is_some()
+        {
+            n += 1;
+        }
+        let mut view = View::new(DEFAULT_SCHEMA, format!("{prefix}_{n}"), materialized);
+        view.position = Position::slot(self.tables.len() + self.views.len());
+        self.views.push(view);
+        self.views.len() - 1
+    }
+
+    pub fn add_type(&mut self, category: TypeCategory) -> usize {
+        let mut n = self.types.len() + 1;
+        while self
+            .user_type(DEFAULT_SCHEMA, &format!("{category}_{n}"))
+            .is_some()
+        {
+            n += 1;
+        }
+        self.types.push(UserType::new(
+            DEFAULT_SCHEMA,
+            format!("{category}_{n}"),
+            category.empty(),
+        ));
+        self.types.len() - 1
+    }
+
+    pub fn routine(&self, schema: &str, name: &str) -> Option<&Routine> {
+        self.routines
+            .iter()
+            .find(|routine| routine.schema == schema && routine.name == name)
+    }
+
+    pub fn add_routine(&mut self, kind: RoutineKind) -> usize {
+        let mut n = self.routines.len() + 1;
+        while self
+            .routine(DEFAULT_SCHEMA, &format!("{kind}_{n}"))
+            .is_some()
+        {
+            n += 1;
+        }
+        self.routines
+            .push(Routine::new(DEFAULT_SCHEMA, format!("{kind}_{n}"), kind));
+        self.routines.len() - 1
+    }
+
+    /// Functions a trigger can execute, qualified unless in `public`.
+    pub fn trigger_functions(&self) -> Vec<String> {
+        self.routines
+            .iter()
+            .filter(|r| r.is_trigger_function())
+            .map(|r| {
+                if r.schema == DEFAULT_SCHEMA {
+                    r.name.clone()
+                } else {
+                    format!("{}.{}", r.schema, r.name)
+                }
+            })
+            .collect()
+    }
+
+    pub fn role(&self, name: &str) -> Option<&Role> {
+        self.roles.iter().find(|role| role.name == name)
+    }
+
+    pub fn add_role(&mut self) -> usize {
+        let mut n = self.roles.len() + 1;
+        while self.role(&format!("role_{n}")).is_some() {
+            n += 1;
+        }
+        self.roles.push(Role::new(format!("role_{n}")));
+        self.roles.len() - 1
+    }
+
+    /// Roles offered by grant and policy editors, `PUBLIC` included.
+    pub fn role_names(&self) -> Vec<String> {
+        std::iter::once(String::from("PUBLIC"))
+            .chain(self.roles.iter().map(|role| role.name.clone()))
+            .collect()
+    }
+
+    pub fn sequence(&self, schema: &str, name: &str) -> Option<&Sequence> {
+        self.sequences
+            .iter()
+            .find(|sequence| sequence.schema == schema && sequence.name == name)
+    }
+
+    pub fn add_sequence(&mut self) -> usize {
+        let mut n = self.sequences.len() + 1;
+        while self.sequence(DEFAULT_SCHEMA, &format!("seq_{n}")).is_some() {
+            n += 1;
+        }
+        self.sequences
+            .push(Sequence::new(DEFAULT_SCHEMA, format!("seq_{n}")));
+        self.sequences.len() - 1
+    }
+
+    /// Every `table.column`, as a sequence can be owned by.
+    pub fn column_names(&self) -> Vec<String> {
+        self.tables
+            .iter()
+            .flat_map(|table| {
+                let prefix = if table.schema == DEFAULT_SCHEMA {
+                    table.name.clone()
+                } else {
+                    format!("{}.{}", table.schema, table.name)
+                };
+                table
+                    .columns
+                    .iter()
+                    .map(move |column| format!("{prefix}.{}", column.name))
+            })
+            .collect()
+    }
+
+    pub fn user_type(&self, schema: &str, name: &str) -> Option<&UserType> {
+        self.types
+            .iter()
+            .find(|ty| ty.schema == schema && ty.name == name)
+    }
+
+    /// Finds the user type a column or attribute type refers to. Unqualified
+    /// names are looked up in `schema` first and then in `public`, like a
+    /// default `search_path` would.
+    pub fn resolve_type(&self, data_type: &str, schema: &str) -> Option<usize> {
+        let (qualifier, name) = data_type::split_qualified(data_type::base_name(data_type));
+        let position = |schema: &str| {
+            self.types
+                .iter()
+                .position(|ty| ty.schema == schema && ty.name == name)
+        };
+        match qualifier {
+            Some(qualifier) => position(qualifier),
+            None => position(schema).or_else(|| position(DEFAULT_SCHEMA)),
+        }
+    }
+
+    /// Name to refer to a user type by, qualified unless it lives in `public`.
+    pub fn type_name(ty: &UserType) -> String {
+        if ty.schema == DEFAULT_SCHEMA {
+            ty.name.clone()
+        } else {
+            format!("{}.{}", ty.schema, ty.name)
+        }
+    }
+
+    /// Types offered by the column editor: user types first, then the ones
+    /// of enabled extensions and built-ins.
+    pub fn type_names(&self) -> Vec<String> {
+        self.types
+            .iter()
+            .map(Project::type_name)
+            .chain(
+                self.extensions
+                    .iter()
+                    .flat_map(|extension| extension.types())
+                    .chain(data_type::BUILTIN)
+                    .map(|name| name.to_string()),
+            )
+            .collect()
+    }
+
+    /// Defaults offered by the column editor, including functions of enabled
+    /// extensions.
+    pub fn default_suggestions(&self) -> Vec<String> {
+        let builtin = self.version.builtin_functions();
+        self.extensions
+            .iter()
+            .flat_map(|extension| extension.functions())
+            .filter(|function| !builtin.contains(function))
+            .chain(builtin)
+            .chain(&[
+                "now()",
+                "CURRENT_TIMESTAMP",
+                "CURRENT_DATE",
+                "true",
+                "false",
+                "0",
+                "''",
+            ])
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// Extension a column needs for its type or default but which isn't
+    /// enabled.
+    pub fn missing_extension(&self, column: &Column) -> Option<Extension> {
+        Extension::providing_type(&column.data_type)
+            .or_else(|| {
+                column
+                    .default
+                    .as_deref()
+                    .and_then(|default| Extension::providing_function(default, self.version))
+            })
+            .filter(|extension| !self.extensions.contains(extension))
+    }
+
+    /// Indexes of [`Project::types`] ordered so that every type comes after
+    /// the types it is built from. Declaration order is kept otherwise.
+    pub fn sorted_types(&self) -> Vec<usize> {
+        topo_sort(self.types.len(), |i| {
+            let ty = &self.types[i];
+            ty.referenced_types()
+                .into_iter()
+                .filter_map(|reference| self.resolve_type(reference, &ty.schema))
+                .collect()
+        })
+    }
+
+    /// Adds everything in `other`, replacing objects with the same schema
+    /// and name. Replaced tables and views keep their place on the diagram.
+    pub fn merge(&mut self, other: Project) {
+        fn replace<T>(items: &mut Vec<T>, item: T, same: impl Fn(&T, &T) -> bool) {
+            match items.iter_mut().find(|existing| same(existing, &item)) {
+                Some(existing) => *existing = item,
+                None => items.push(item),
+            }
+        }
+
+        for mut table in other.tables {
+            if let Some(existing) = self.table(&table.schema, &table.name) {
+                table.position = existing.position;
+            }
+            replace(&mut self.tables, table, |a, b| {
+                a.schema == b.schema && a.name == b.name
+            });
+        }
+        for mut view in other.views {
+            if let Some(existing) = self.view(&view.schema, &view.name) {
+                view.position = existing.position;
+            }
+            replace(&mut self.views, view, |a, b| {
+                a.schema == b.schema && a.name == b.name
+            });
+        }
+        for ty in other.types {
+            replace(&mut self.types, ty, |a, b| {
+                a.schema == b.schema && a.name == b.name
+            });
+        }
+        for routine in other.routines {
+            replace(&mut self.routines, routine, |a, b| {
+                a.schema == b.schema && a.name == b.name
+            });
+        }
+        for sequence in other.sequences {
+            replace(&mut self.sequences, sequence, |a, b| {
+                a.schema == b.schema && a.name == b.name
+            });
+        }
+        for role in other.roles {
+            replace(&mut self.roles, role, |a, b| a.name == b.name);
+        }
+        for extension in other.extensions {
+            if !self.extensions.contains(&extension) {
+                self.extensions.push(extension);
+            }
+        }
+        for area in other.areas {
+            replace(&mut self.areas, area, |a, b| a.name == b.name);
+        }
+    }
+
+    /// Swaps `old`, a part of this project, for `new`, which is what that
+    /// part became after editing its DDL. Objects with the same schema and
+    /// name stay in place, and when one object of a kind lost its match on
+    /// both sides it is taken to be renamed. Tables keep their place on the
+    /// diagram along with the triggers and security that scripts are not
+    /// read for; new ones are dropped on the next free slot.
+    pub fn replace(&mut self, old: &Project, new: Project) {
+        fn swap<T>(
+            items: &mut Vec<T>,
+            old: &[T],
+            new: Vec<T>,
+            same: impl Fn(&T, &T) -> bool,
+            keep: impl Fn(&T, &mut T),
+            mut place: impl FnMut(&mut T),
+        ) {
+            let unmatched_old: Vec<&T> = old
+                .iter()
+                .filter(|o| !new.iter().any(|n| same(o, n)))
+                .collect();
+            let unmatched_new = new
+                .iter()
+                .filter(|n| !old.iter().any(|o| same(o, n)))
+                .count();
+            let renamed = match unmatched_old[..] {
+                [renamed] if unmatched_new == 1 => Some(renamed),
+                _ => None,
+            };
+
+            let mut added = Vec::new();
+            for mut item in new {
+                let previous = old.iter().find(|o| same(o, &item)).or(renamed);
+                match previous.and_then(|p| items.iter().position(|e| same(e, p))) {
+                    Some(i) => {
+                        keep(&items[i], &mut item);
+                        items[i] = item;
+                    }
+                    None => {
+                        place(&mut item);
+                        added.push(item);
+                    }
+                }
+            }
+            items.retain(|e| !unmatched_old.iter().any(|o| same(o, e)));
+            items.extend(added);
+        }
+
+        let same_table = |a: &Table, b: &Table| a.schema == b.schema && a.name == b.name;
+        let same_view = |a: &View, b: &View| a.schema == b.schema && a.name == b.name;
+        let same_type = |a: &UserType, b: &UserType| a.schema == b.schema && a.name == b.name;
+        let same_sequence = |a: &Sequence, b: &Sequence| a.schema == b.schema && a.name == b.name;
+
+        let mut slot = self.tables.len() + self.views.len();
+        let mut next_slot = || {
+            slot += 1;
+            Position::slot(slot - 1)
+        };
+        swap(
+            &mut self.tables,
+            &old.tables,
+            new.tables,
+            same_table,
+            |from, to| {
+                to.position = from.position;
+                to.triggers = from.triggers.clone();
+                to.row_security = from.row_security;
+                to.force_row_security = from.force_row_security;
+                to.policies = from.policies.clone();
+                to.grants = from.grants.clone();
+            },
+            |table| table.position = next_slot(),
+        );
+        swap(
+            &mut self.views,
+            &old.views,
+            new.views,
+            same_view,
+            |from, to| to.position = from.position,
+            |view| view.position = next_slot(),
+        );
+        swap(
+            &mut self.types,
+            &old.types,
+            new.types,
+            same_type,
+            |_, _| {},
+            |_| {},
+        );
+        swap(
+            &mut self.sequences,
+            &old.sequences,
+            new.sequences,
+            same_sequence,
+            |_, _| {},
+            |_| {},
+        );
+    }
+
+    pub fn schemas(&self) -> Vec<&str> {
+        let mut schemas: Vec<&str> = self
+            .tables
+            .iter()
+            .map(|t| t.schema.as_str())
+            .chain(self.types.iter().map(|t| t.schema.as_str()))
+            .chain(self.views.iter().map(|v| v.schema.as_str()))
+            .chain(self.routines.iter().map(|r| r.schema.as_str()))
+            .chain(self.sequences.iter().map(|s| s.schema.as_str()))
+            .collect();
+        schemas.sort();
+        schemas.dedup();
+        schemas
+    }
+}
+
+/// Splits a comma separated list as typed in the inspector. Pieces are kept
+/// untrimmed so that [`join_list`] gives back exactly what was typed.
+pub fn split_list(value: &str) -> Vec<String> {
+    if value.is_empty() {
+        return Vec::new();
+    }
+    value.split(',').map(String::from).collect()
+}
+
+pub fn join_list(items: &[String]) -> String {
+    items.join(",")
+}
+
+/// `0..count` ordered so that every item comes after the items
+/// `dependencies` lists for it, and in its own place otherwise. Cycles are
+/// cut where they close.
+pub fn topo_sort(count: usize, dependencies: impl Fn(usize) -> Vec<usize>) -> Vec<usize> {
+    fn visit(
+        i: usize,
+        dependencies: &dyn Fn(usize) -> Vec<usize>,
+        state: &mut [u8],
+        sorted: &mut Vec<usize>,
+    ) {
+        // 0 = unvisited, 1 = in progress, 2 = done
+        if state[i] != 0 {
+            return;
+        }
+        state[i] = 1;
+        for dependency in dependencies(i) {
+            visit(dependency, dependencies, state, sorted);
+        }
+        state[i] = 2;
+        sorted.push(i);
+    }
+
+    let mut state = vec![0; count];
+    let mut sorted = Vec::with_capacity(count);
+    for i in 0..count {
+        visit(i, &dependencies, &mut state, &mut sorted);
+    }
+    sorted
+}
+
+/// `<relation>_<parts>_<label>` the way PostgreSQL names what it is left to
+/// name, as in `orders_customer_id_fkey`. The relation and parts are cut
+/// short, the longer one first, to keep within 63 bytes, and a number goes
+/// after the label while `taken` says the name is in use.
+pub fn object_name(
+    relation: &str,
+    parts: &[String],
+    label: &str,
+    taken: impl Fn(&str) -> bool,
+) -> String {
+    const MAX_NAME: usize = 63;
+    let parts = parts.join("_");
+    let shorten = |text: &str, mut len: usize| {
+        while !text.is_char_boundary(len) {
+            len -= 1;
+        }
+        text[..len].to_string()
+    };
+    let mut n = 0;
+    loop {
+        let label = if n == 0 {
+            label.to_string()
+        } else {
+            format!("{label}{n}")
+        };
+        let overhead = label.len() + 1 + if parts.is_empty() { 0 } else { 1 };
+        let available = MAX_NAME.saturating_sub(overhead);
+        let (mut relation_len, mut parts_len) = (relation.len(), parts.len());
+        while relation_len + parts_len > available {
+            if relation_len > parts_len {
+                relation_len -= 1;
+            } else {
+                parts_len -= 1;
+            }
+        }
+        let mut name = shorten(relation, relation_len);
+        if !parts.is_empty() {
+            name = format!("{name}_{}", shorten(&parts, parts_len));
+        }
+        name = format!("{name}_{label}");
+        if !taken(&name) {
+            return name;
+        }
+        n += 1;
+    }
+}
+
+/// Iterates the meaningful entries of a list edited through [`split_list`].
+pub fn list_items(items: &[String]) -> impl Iterator<Item = &str> {
+    items
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+}
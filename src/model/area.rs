@@ -0,0 +1,37 @@
+use super::DEFAULT_SCHEMA;
+
+/// A named group of tables drawn together on the diagram, such as a DBML
+/// table group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectArea {
+    pub name: String,
+    pub comment: Option<String>,
+    /// The tables in the area, qualified with the schema unless they are in
+    /// `public`.
+    pub tables: Vec<String>,
+}
+
+impl SubjectArea {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            comment: None,
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, schema: &str, name: &str) -> bool {
+        self.tables
+            .iter()
+            .any(|table| *table == member(schema, name))
+    }
+}
+
+/// How an area refers to the table `schema.name`.
+pub fn member(schema: &str, name: &str) -> String {
+    if schema == DEFAULT_SCHEMA {
+        name.to_string()
+    } else {
+        format!("{schema}.{name}")
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use super::data_type;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    pub default: Option<String>,
+    pub comment: Option<String>,
+    pub identity: Option<Identity>,
+    /// Expression of a `GENERATED ALWAYS AS (...) STORED` column.
+    pub generated: Option<String>,
+    /// Rust type to generate for the column instead of the one its SQL type
+    /// maps to.
+    pub rust_type: Option<String>,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            data_type: data_type.into(),
+            not_null: false,
+            default: None,
+            comment: None,
+            identity: None,
+            generated: None,
+            rust_type: None,
+        }
+    }
+
+    pub fn generation(&self) -> Generation {
+        match (self.identity, &self.generated) {
+            (Some(Identity::Always), _) => Generation::IdentityAlways,
+            (Some(Identity::ByDefault), _) => Generation::IdentityByDefault,
+            (None, Some(_)) => Generation::Stored,
+            (None, None) if data_type::serial_base(&self.data_type).is_some() => Generation::Serial,
+            (None, None) => Generation::None,
+        }
+    }
+
+    /// Converts the column to another way of generating its values. The
+    /// type follows along, so `bigint` becomes `bigserial` and back, and
+    /// defaults are dropped since generated columns can't have one.
+    pub fn set_generation(&mut self, generation: Generation) {
+        if let Some(base) = data_type::serial_base(&self.data_type) {
+            self.data_type = base.to_string();
+        }
+        self.identity = None;
+        match generation {
+            Generation::None => self.generated = None,
+            Generation::Serial => {
+                self.data_type = data_type::serial_for(&self.data_type).to_string();
+                self.generated = None;
+                self.default = None;
+            }
+            Generation::IdentityAlways | Generation::IdentityByDefault => {
+                self.identity = Some(if generation == Generation::IdentityAlways {
+                    Identity::Always
+                } else {
+                    Identity::ByDefault
+                });
+                self.generated = None;
+                self.default = None;
+                // Identity columns are implicitly NOT NULL.
+                self.not_null = true;
+            }
+            Generation::Stored => {
+                self.generated.get_or_insert_with(String::new);
+                self.default = None;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Identity {
+    Always,
+    ByDefault,
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Identity::Always => "ALWAYS",
+            Identity::ByDefault => "BY DEFAULT",
+        })
+    }
+}
+
+/// How a column gets its values, as offered by the column editor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Generation {
+    #[default]
+    None,
+    Serial,
+    IdentityAlways,
+    IdentityByDefault,
+    Stored,
+}
+
+impl Generation {
+    pub const ALL: [Generation; 5] = [
+        Generation::None,
+        Generation::Serial,
+        Generation::IdentityAlways,
+        Generation::IdentityByDefault,
+        Generation::Stored,
+    ];
+}
+
+impl fmt::Display for Generation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Generation::None => "plain",
+            Generation::Serial => "serial",
+            Generation::IdentityAlways => "identity always",
+            Generation::IdentityByDefault => "identity by default",
+            Generation::Stored => "generated stored",
+        })
+    }
+}
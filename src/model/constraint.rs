@@ -0,0 +1,177 @@
+use std::fmt;
+
+use super::IndexMethod;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constraint {
+    pub name: String,
+    pub kind: ConstraintKind,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstraintKind {
+    PrimaryKey {
+        columns: Vec<String>,
+        include: Vec<String>,
+    },
+    Unique {
+        columns: Vec<String>,
+        include: Vec<String>,
+        nulls_not_distinct: bool,
+    },
+    Check {
+        expression: String,
+        no_inherit: bool,
+    },
+    ForeignKey(ForeignKey),
+    Exclusion {
+        method: IndexMethod,
+        elements: Vec<ExclusionElement>,
+        include: Vec<String>,
+        predicate: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ForeignKey {
+    pub columns: Vec<String>,
+    pub ref_schema: String,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    pub on_delete: ReferentialAction,
+    pub on_update: ReferentialAction,
+}
+
+/// `element WITH operator` of an exclusion constraint, e.g. `during WITH &&`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExclusionElement {
+    pub element: String,
+    pub operator: String,
+}
+
+impl Constraint {
+    pub fn new(name: impl Into<String>, kind: ConstraintKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            deferrable: false,
+            initially_deferred: false,
+        }
+    }
+
+    pub fn constraint_type(&self) -> ConstraintType {
+        match self.kind {
+            ConstraintKind::PrimaryKey { .. } => ConstraintType::PrimaryKey,
+            ConstraintKind::Unique { .. } => ConstraintType::Unique,
+            ConstraintKind::Check { .. } => ConstraintType::Check,
+            ConstraintKind::ForeignKey(_) => ConstraintType::ForeignKey,
+            ConstraintKind::Exclusion { .. } => ConstraintType::Exclusion,
+        }
+    }
+
+    /// Check constraints are never deferrable in PostgreSQL.
+    pub fn can_defer(&self) -> bool {
+        !matches!(self.kind, ConstraintKind::Check { .. })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintType {
+    PrimaryKey,
+    Unique,
+    Check,
+    ForeignKey,
+    Exclusion,
+}
+
+impl ConstraintType {
+    pub const ALL: [ConstraintType; 5] = [
+        ConstraintType::PrimaryKey,
+        ConstraintType::Unique,
+        ConstraintType::Check,
+        ConstraintType::ForeignKey,
+        ConstraintType::Exclusion,
+    ];
+
+    pub fn empty(&self) -> ConstraintKind {
+        match self {
+            ConstraintType::PrimaryKey => ConstraintKind::PrimaryKey {
+                columns: Vec::new(),
+                include: Vec::new(),
+            },
+            ConstraintType::Unique => ConstraintKind::Unique {
+                columns: Vec::new(),
+                include: Vec::new(),
+                nulls_not_distinct: false,
+            },
+            ConstraintType::Check => ConstraintKind::Check {
+                expression: String::new(),
+                no_inherit: false,
+            },
+            ConstraintType::ForeignKey => ConstraintKind::ForeignKey(ForeignKey::default()),
+            ConstraintType::Exclusion => ConstraintKind::Exclusion {
+                method: IndexMethod::Gist,
+                elements: vec![ExclusionElement::default()],
+                include: Vec::new(),
+                predicate: None,
+            },
+        }
+    }
+
+    /// Suffix PostgreSQL itself uses when naming a constraint of this type.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            ConstraintType::PrimaryKey => "pkey",
+            ConstraintType::Unique => "key",
+            ConstraintType::Check => "check",
+            ConstraintType::ForeignKey => "fkey",
+            ConstraintType::Exclusion => "excl",
+        }
+    }
+}
+
+impl fmt::Display for ConstraintType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConstraintType::PrimaryKey => "PRIMARY KEY",
+            ConstraintType::Unique => "UNIQUE",
+            ConstraintType::Check => "CHECK",
+            ConstraintType::ForeignKey => "FOREIGN KEY",
+            ConstraintType::Exclusion => "EXCLUDE",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReferentialAction {
+    #[default]
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+impl ReferentialAction {
+    pub const ALL: [ReferentialAction; 5] = [
+        ReferentialAction::NoAction,
+        ReferentialAction::Restrict,
+        ReferentialAction::Cascade,
+        ReferentialAction::SetNull,
+        ReferentialAction::SetDefault,
+    ];
+}
+
+impl fmt::Display for ReferentialAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::SetDefault => "SET DEFAULT",
+        })
+    }
+}
@@ -0,0 +1,123 @@
+/// Built-in PostgreSQL types offered by the column editor.
+pub const BUILTIN: &[&str] = &[
+    "bigint",
+    "bigserial",
+    "boolean",
+    "bytea",
+    "char(1)",
+    "cidr",
+    "date",
+    "double precision",
+    "inet",
+    "integer",
+    "interval",
+    "json",
+    "jsonb",
+    "macaddr",
+    "money",
+    "numeric(12,2)",
+    "real",
+    "serial",
+    "smallint",
+    "smallserial",
+    "text",
+    "time",
+    "timestamp",
+    "timestamptz",
+    "tsvector",
+    "uuid",
+    "varchar(255)",
+    "xml",
+];
+
+/// Strips array brackets and type modifiers, so `public.mood[]` gives
+/// `public.mood` and `varchar(20)` gives `varchar`.
+pub fn base_name(data_type: &str) -> &str {
+    let mut name = data_type.trim();
+    while let Some(stripped) = name.strip_suffix("[]") {
+        name = stripped.trim_end();
+    }
+    if let Some(open) = name.find('(') {
+        name = name[..open].trim_end();
+    }
+    name
+}
+
+/// The name PostgreSQL uses for a type, lower case and without modifiers,
+/// so that `INT4`, `serial` and `integer` all give `integer`. Arrays keep
+/// their brackets.
+pub fn canonical(data_type: &str) -> String {
+    let mut name = data_type.trim();
+    let mut arrays = 0;
+    while let Some(stripped) = name.strip_suffix("[]") {
+        name = stripped.trim_end();
+        arrays += 1;
+    }
+    // Modifiers can sit in the middle, as in `timestamp(3) with time zone`.
+    let mut words = String::new();
+    let mut depth = 0;
+    for c in name.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if depth == 0 => words.push(c.to_ascii_lowercase()),
+            _ => {}
+        }
+    }
+    let words = words.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name = match serial_base(&words) {
+        Some(base) => base,
+        None => match words.as_str() {
+            "int" | "int4" => "integer",
+            "int2" => "smallint",
+            "int8" => "bigint",
+            "bool" => "boolean",
+            "float4" => "real",
+            "float" | "float8" => "double precision",
+            "decimal" => "numeric",
+            "varchar" => "character varying",
+            "char" | "bpchar" => "character",
+            "timestamp" => "timestamp without time zone",
+            "timestamptz" => "timestamp with time zone",
+            "time" => "time without time zone",
+            "timetz" => "time with time zone",
+            "varbit" => "bit varying",
+            other => other,
+        },
+    };
+    name.to_string() + &"[]".repeat(arrays)
+}
+
+/// Integer type behind a serial pseudo-type, e.g. `bigint` for `bigserial`.
+pub fn serial_base(data_type: &str) -> Option<&'static str> {
+    match data_type.trim().to_lowercase().as_str() {
+        "smallserial" | "serial2" => Some("smallint"),
+        "serial" | "serial4" => Some("integer"),
+        "bigserial" | "serial8" => Some("bigint"),
+        _ => None,
+    }
+}
+
+/// Serial pseudo-type for an integer type, falling back to `serial`.
+pub fn serial_for(data_type: &str) -> &'static str {
+    match data_type.trim().to_lowercase().as_str() {
+        "smallint" | "int2" => "smallserial",
+        "bigint" | "int8" => "bigserial",
+        _ => "serial",
+    }
+}
+
+/// Splits `schema.name` into its parts, unquoting them.
+pub fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('.') {
+        Some((schema, name)) => (Some(unquote(schema)), unquote(name)),
+        None => (None, unquote(name)),
+    }
+}
+
+fn unquote(part: &str) -> &str {
+    let part = part.trim();
+    part.strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(part)
+}
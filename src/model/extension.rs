@@ -0,0 +1,94 @@
+use std::fmt;
+
+use super::PgVersion;
+
+/// Extensions of a database. The model knows the types and functions of
+/// the first few, and others only by name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Extension {
+    UuidOssp,
+    Pgcrypto,
+    Citext,
+    Postgis,
+    Hstore,
+    Other(String),
+}
+
+impl Extension {
+    pub const ALL: [Extension; 5] = [
+        Extension::UuidOssp,
+        Extension::Pgcrypto,
+        Extension::Citext,
+        Extension::Postgis,
+        Extension::Hstore,
+    ];
+
+    /// The extension called `name`, known or not.
+    pub fn named(name: &str) -> Extension {
+        Extension::ALL
+            .into_iter()
+            .find(|extension| extension.name() == name)
+            .unwrap_or_else(|| Extension::Other(name.to_string()))
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Extension::UuidOssp => "uuid-ossp",
+            Extension::Pgcrypto => "pgcrypto",
+            Extension::Citext => "citext",
+            Extension::Postgis => "postgis",
+            Extension::Hstore => "hstore",
+            Extension::Other(name) => name,
+        }
+    }
+
+    pub fn types(&self) -> &'static [&'static str] {
+        match self {
+            Extension::UuidOssp | Extension::Pgcrypto | Extension::Other(_) => &[],
+            Extension::Citext => &["citext"],
+            Extension::Postgis => &["geography", "geometry", "box2d", "box3d"],
+            Extension::Hstore => &["hstore"],
+        }
+    }
+
+    /// Functions worth offering as column defaults.
+    pub fn functions(&self) -> &'static [&'static str] {
+        match self {
+            Extension::UuidOssp => &["uuid_generate_v4()", "uuid_generate_v1mc()"],
+            Extension::Pgcrypto => &["gen_random_uuid()", "gen_random_bytes(16)"],
+            Extension::Citext | Extension::Hstore | Extension::Other(_) => &[],
+            Extension::Postgis => &["ST_GeomFromText('POINT(0 0)', 4326)"],
+        }
+    }
+
+    /// Extension providing the type of a column, if any.
+    pub fn providing_type(data_type: &str) -> Option<Extension> {
+        let (_, name) = super::data_type::split_qualified(super::data_type::base_name(data_type));
+        let name = name.to_lowercase();
+        Extension::ALL
+            .into_iter()
+            .find(|extension| extension.types().contains(&name.as_str()))
+    }
+
+    /// Extension providing a function called in `expression`, if any.
+    /// Functions built into `version` don't need their extension.
+    pub fn providing_function(expression: &str, version: PgVersion) -> Option<Extension> {
+        let expression = expression.to_lowercase();
+        let builtin = version.builtin_functions();
+        Extension::ALL.into_iter().find(|extension| {
+            extension.functions().iter().any(|function| {
+                if builtin.contains(function) {
+                    return false;
+                }
+                let name = &function[..function.find('(').unwrap_or(function.len())];
+                expression.contains(&format!("{}(", name.to_lowercase()))
+            })
+        })
+    }
+}
+
+impl fmt::Display for Extension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
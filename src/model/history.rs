@@ -0,0 +1,41 @@
+use super::Project;
+
+/// How many changes can be undone.
+const LIMIT: usize = 100;
+
+/// Earlier states of a project for undo, and the undone ones for redo.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Project>,
+    redo: Vec<Project>,
+}
+
+impl History {
+    /// Remembers `before`, the state a change started from. Anything undone
+    /// can't be redone after that.
+    pub fn record(&mut self, before: Project) {
+        if self.undo.len() == LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Puts `project` back to the previous state, returning whether there
+    /// was one.
+    pub fn undo(&mut self, project: &mut Project) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(project, previous));
+        true
+    }
+
+    pub fn redo(&mut self, project: &mut Project) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(project, next));
+        true
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+
+use crate::sql::lexer::{self, Token};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    pub name: String,
+    pub unique: bool,
+    pub nulls_not_distinct: bool,
+    pub method: IndexMethod,
+    pub elements: Vec<IndexElement>,
+    pub include: Vec<String>,
+    pub predicate: Option<String>,
+}
+
+impl Index {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unique: false,
+            nulls_not_distinct: false,
+            method: IndexMethod::default(),
+            elements: Vec::new(),
+            include: Vec::new(),
+            predicate: None,
+        }
+    }
+
+    pub fn on(mut self, expression: impl Into<String>) -> Self {
+        self.elements.push(IndexElement::new(expression));
+        self
+    }
+}
+
+/// One key of an index: either a column name or an arbitrary expression such
+/// as `lower(email)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexElement {
+    pub expression: String,
+    pub opclass: Option<String>,
+    pub order: SortOrder,
+    pub nulls: NullsOrder,
+}
+
+impl IndexElement {
+    pub fn new(expression: impl Into<String>) -> Self {
+        Self {
+            expression: expression.into(),
+            opclass: None,
+            order: SortOrder::default(),
+            nulls: NullsOrder::default(),
+        }
+    }
+
+    pub fn is_column(&self) -> bool {
+        is_identifier(self.expression.trim())
+    }
+
+    /// What PostgreSQL calls the element in the name of an index: the
+    /// column, the function an expression is a call of, or `expr`.
+    pub fn name_part(&self) -> String {
+        let expression = self.expression.trim();
+        if self.is_column() {
+            return lexer::tokenize(expression)
+                .first()
+                .and_then(Token::ident)
+                .unwrap_or_default();
+        }
+        let tokens = lexer::tokenize(expression);
+        let call = tokens.iter().position(|t| t.is_symbol("("));
+        if let Some(open) = call
+            && open > 0
+            && tokens[..open].iter().enumerate().all(|(k, t)| {
+                if k % 2 == 0 {
+                    t.ident().is_some()
+                } else {
+                    t.is_symbol(".")
+                }
+            })
+            && close(&tokens, open) == Some(tokens.len() - 1)
+        {
+            return tokens[open - 1].ident().unwrap_or_default();
+        }
+        String::from("expr")
+    }
+}
+
+/// Position of the parenthesis closing the one at `open`.
+fn close(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (k, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol("(") {
+            depth += 1;
+        } else if token.is_symbol(")") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(k);
+            }
+        }
+    }
+    None
+}
+
+fn is_identifier(value: &str) -> bool {
+    if value.len() > 1 && value.starts_with('"') && value.ends_with('"') {
+        return !value[1..value.len() - 1].contains('"');
+    }
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        }
+        _ => false,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexMethod {
+    #[default]
+    Btree,
+    Hash,
+    Gist,
+    Spgist,
+    Gin,
+    Brin,
+}
+
+impl IndexMethod {
+    pub const ALL: [IndexMethod; 6] = [
+        IndexMethod::Btree,
+        IndexMethod::Hash,
+        IndexMethod::Gist,
+        IndexMethod::Spgist,
+        IndexMethod::Gin,
+        IndexMethod::Brin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexMethod::Btree => "btree",
+            IndexMethod::Hash => "hash",
+            IndexMethod::Gist => "gist",
+            IndexMethod::Spgist => "spgist",
+            IndexMethod::Gin => "gin",
+            IndexMethod::Brin => "brin",
+        }
+    }
+
+    /// Only btree indexes are ordered, so only they accept ASC/DESC and NULLS
+    /// FIRST/LAST, and only they can enforce uniqueness.
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, IndexMethod::Btree)
+    }
+
+    pub fn supports_include(&self) -> bool {
+        matches!(
+            self,
+            IndexMethod::Btree | IndexMethod::Gist | IndexMethod::Spgist
+        )
+    }
+}
+
+impl fmt::Display for IndexMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 2] = [SortOrder::Asc, SortOrder::Desc];
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NullsOrder {
+    #[default]
+    Default,
+    First,
+    Last,
+}
+
+impl NullsOrder {
+    pub const ALL: [NullsOrder; 3] = [NullsOrder::Default, NullsOrder::First, NullsOrder::Last];
+}
+
+impl fmt::Display for NullsOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NullsOrder::Default => "NULLS default",
+            NullsOrder::First => "NULLS FIRST",
+            NullsOrder::Last => "NULLS LAST",
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use super::{topo_sort, ConstraintKind, Position, Project};
+
+const LEFT: f32 = 40.0;
+const TOP: f32 = 40.0;
+pub const COLUMN_WIDTH: f32 = 300.0;
+const GAP: f32 = 40.0;
+/// Columns taller than this continue in a new column.
+const MAX_HEIGHT: f32 = 2000.0;
+/// Rough card size on the diagram: header, padding and one row per column.
+const CARD_HEADER: f32 = 40.0;
+const CARD_ROW: f32 = 20.0;
+/// Views don't list their columns in the model, so assume a few.
+const VIEW_ROWS: usize = 4;
+
+/// Places tables in columns by foreign key depth, referenced tables on the
+/// left and the tables pointing at them to their right, then views after the
+/// last column of tables.
+pub fn arrange(project: &mut Project) {
+    let depths = depths(project);
+    let mut layers: Vec<Vec<usize>> = Vec::new();
+    for (i, &depth) in depths.iter().enumerate() {
+        if layers.len() <= depth {
+            layers.resize(depth + 1, Vec::new());
+        }
+        layers[depth].push(i);
+    }
+
+    let mut column = 0;
+    for layer in &layers {
+        let mut y = TOP;
+        for &i in layer {
+            let height = card_height(project.tables[i].columns.len());
+            if y > TOP && y + height > MAX_HEIGHT {
+                column += 1;
+                y = TOP;
+            }
+            project.tables[i].position = Position::new(LEFT + column as f32 * COLUMN_WIDTH, y);
+            y += height + GAP;
+        }
+        column += 1;
+    }
+
+    let mut y = TOP;
+    for view in &mut project.views {
+        let height = card_height(VIEW_ROWS);
+        if y > TOP && y + height > MAX_HEIGHT {
+            column += 1;
+            y = TOP;
+        }
+        view.position = Position::new(LEFT + column as f32 * COLUMN_WIDTH, y);
+        y += height + GAP;
+    }
+}
+
+/// Moves every table and view `dx` to the right.
+pub fn shift(project: &mut Project, dx: f32) {
+    for table in &mut project.tables {
+        table.position.x += dx;
+    }
+    for view in &mut project.views {
+        view.position.x += dx;
+    }
+}
+
+fn card_height(rows: usize) -> f32 {
+    CARD_HEADER + CARD_ROW * rows.max(1) as f32
+}
+
+/// Longest chain of foreign keys starting at each table. Self references and
+/// cycles are cut where they close.
+fn depths(project: &Project) -> Vec<usize> {
+    let index: HashMap<(&str, &str), usize> = project
+        .tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| ((table.schema.as_str(), table.name.as_str()), i))
+        .collect();
+    let references: Vec<Vec<usize>> = project
+        .tables
+        .iter()
+        .map(|table| {
+            table
+                .constraints
+                .iter()
+                .filter_map(|constraint| match &constraint.kind {
+                    ConstraintKind::ForeignKey(fk) => index
+                        .get(&(fk.ref_schema.as_str(), fk.ref_table.as_str()))
+                        .copied(),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    // Referenced tables come first, but for the ones closing a cycle.
+    let order = topo_sort(project.tables.len(), |i| references[i].clone());
+    let mut place = vec![0; order.len()];
+    for (n, &i) in order.iter().enumerate() {
+        place[i] = n;
+    }
+    let mut depths = vec![0; project.tables.len()];
+    for &i in &order {
+        depths[i] = references[i]
+            .iter()
+            .filter(|&&referenced| place[referenced] < place[i])
+            .map(|&referenced| depths[referenced] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+    depths
+}
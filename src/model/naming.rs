@@ -0,0 +1,740 @@
+use std::fmt;
+
+use super::{data_type, ConstraintKind, Project};
+use crate::sql::{
+    lexer::{self, Token},
+    lineage::{self, Source},
+    quote_ident,
+};
+
+/// Longest name PostgreSQL keeps whole, in bytes.
+const MAX_NAME: usize = 63;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Case {
+    #[default]
+    Snake,
+    Camel,
+    Pascal,
+}
+
+impl Case {
+    pub const ALL: [Case; 3] = [Case::Snake, Case::Camel, Case::Pascal];
+
+    /// `name` in this case, whatever case it was in.
+    pub fn convert(self, name: &str) -> String {
+        self.apply(&words(name))
+    }
+
+    fn apply(self, words: &[String]) -> String {
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        };
+        match self {
+            Case::Snake => words.join("_"),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+            Case::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Case::Snake => "snake_case",
+            Case::Camel => "camelCase",
+            Case::Pascal => "PascalCase",
+        })
+    }
+}
+
+/// Grammatical number of table names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Number {
+    #[default]
+    Any,
+    Singular,
+    Plural,
+}
+
+impl Number {
+    pub const ALL: [Number; 3] = [Number::Any, Number::Singular, Number::Plural];
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Number::Any => "Singular or plural tables",
+            Number::Singular => "Singular tables",
+            Number::Plural => "Plural tables",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Template {
+    PrimaryKey,
+    ForeignKey,
+    Unique,
+    Check,
+    Index,
+}
+
+impl Template {
+    pub const ALL: [Template; 5] = [
+        Template::PrimaryKey,
+        Template::ForeignKey,
+        Template::Unique,
+        Template::Check,
+        Template::Index,
+    ];
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Template::PrimaryKey => "Primary key",
+            Template::ForeignKey => "Foreign key",
+            Template::Unique => "Unique",
+            Template::Check => "Check",
+            Template::Index => "Index",
+        })
+    }
+}
+
+/// Naming rules of a project. Templates fill in `{table}`, `{columns}` and,
+/// for foreign keys, `{referenced}`; an empty template leaves those names
+/// alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conventions {
+    /// Case of table and column names.
+    pub case: Case,
+    pub number: Number,
+    pub table_prefix: String,
+    pub table_suffix: String,
+    pub primary_key: String,
+    pub foreign_key: String,
+    pub unique: String,
+    pub check: String,
+    pub index: String,
+}
+
+impl Default for Conventions {
+    fn default() -> Self {
+        Self {
+            case: Case::default(),
+            number: Number::default(),
+            table_prefix: String::new(),
+            table_suffix: String::new(),
+            primary_key: String::from("pk_{table}"),
+            foreign_key: String::from("fk_{table}_{referenced}"),
+            unique: String::from("uq_{table}_{columns}"),
+            check: String::from("ck_{table}"),
+            index: String::from("idx_{table}_{columns}"),
+        }
+    }
+}
+
+/// An object whose name breaks the conventions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Table(usize),
+    /// Table and column.
+    Column(usize, usize),
+    /// Table and constraint.
+    Constraint(usize, usize),
+    /// Table and index.
+    Index(usize, usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rename {
+    pub target: Target,
+    pub from: String,
+    pub to: String,
+}
+
+impl Conventions {
+    pub fn template(&self, template: Template) -> &str {
+        match template {
+            Template::PrimaryKey => &self.primary_key,
+            Template::ForeignKey => &self.foreign_key,
+            Template::Unique => &self.unique,
+            Template::Check => &self.check,
+            Template::Index => &self.index,
+        }
+    }
+
+    pub fn template_mut(&mut self, template: Template) -> &mut String {
+        match template {
+            Template::PrimaryKey => &mut self.primary_key,
+            Template::ForeignKey => &mut self.foreign_key,
+            Template::Unique => &mut self.unique,
+            Template::Check => &mut self.check,
+            Template::Index => &mut self.index,
+        }
+    }
+
+    /// What `name` should be as a table name. Prefix and suffix are taken
+    /// off before the rest is reworked, so applying this twice changes
+    /// nothing.
+    pub fn table_name(&self, name: &str) -> String {
+        let mut stem = name.trim();
+        if !self.table_prefix.is_empty() {
+            stem = stem.strip_prefix(&self.table_prefix).unwrap_or(stem);
+        }
+        if !self.table_suffix.is_empty() {
+            stem = stem.strip_suffix(&self.table_suffix).unwrap_or(stem);
+        }
+        let mut words = words(stem);
+        if let Some(last) = words.last_mut() {
+            match self.number {
+                Number::Any => {}
+                Number::Singular => *last = singular(last),
+                Number::Plural => *last = plural(&singular(last)),
+            }
+        }
+        format!(
+            "{}{}{}",
+            self.table_prefix,
+            self.case.apply(&words),
+            self.table_suffix
+        )
+    }
+
+    pub fn column_name(&self, name: &str) -> String {
+        self.case.apply(&words(name))
+    }
+
+    /// Every object of `project` that is not named as it should be, with
+    /// the name it should have.
+    pub fn renames(&self, project: &Project) -> Vec<Rename> {
+        let mut renames = Vec::new();
+        let mut rename = |target: Target, from: &str, to: String| {
+            if from != to {
+                renames.push(Rename {
+                    target,
+                    from: from.to_string(),
+                    to,
+                });
+            }
+        };
+
+        for (i, table) in project.tables.iter().enumerate() {
+            let table_name = self.table_name(&table.name);
+            rename(Target::Table(i), &table.name, table_name.clone());
+            for (j, column) in table.columns.iter().enumerate() {
+                rename(
+                    Target::Column(i, j),
+                    &column.name,
+                    self.column_name(&column.name),
+                );
+            }
+
+            // Constraints and indexes share a namespace, so later ones get
+            // a number rather than a name that is already taken.
+            let mut taken: Vec<String> = Vec::new();
+            let mut unique_name = |name: String| {
+                let mut candidate = name.clone();
+                let mut n = 2;
+                while taken.contains(&candidate) {
+                    candidate = truncate(format!("{name}_{n}"));
+                    n += 1;
+                }
+                taken.push(candidate.clone());
+                candidate
+            };
+            let columns = |names: &[String]| -> Vec<String> {
+                names
+                    .iter()
+                    .map(|c| c.trim())
+                    .filter(|c| !c.is_empty())
+                    .map(|c| self.column_name(c))
+                    .collect()
+            };
+
+            for (j, constraint) in table.constraints.iter().enumerate() {
+                let (template, columns, referenced) = match &constraint.kind {
+                    ConstraintKind::PrimaryKey { columns: c, .. } => {
+                        (Template::PrimaryKey, columns(c), String::new())
+                    }
+                    ConstraintKind::Unique { columns: c, .. } => {
+                        (Template::Unique, columns(c), String::new())
+                    }
+                    ConstraintKind::Check { .. } => (Template::Check, Vec::new(), String::new()),
+                    ConstraintKind::ForeignKey(fk) => (
+                        Template::ForeignKey,
+                        columns(&fk.columns),
+                        self.table_name(&fk.ref_table),
+                    ),
+                    ConstraintKind::Exclusion { .. } => continue,
+                };
+                let template = self.template(template);
+                let to = if template.trim().is_empty() {
+                    constraint.name.clone()
+                } else {
+                    unique_name(fill(template, &table_name, &columns, &referenced))
+                };
+                rename(Target::Constraint(i, j), &constraint.name, to);
+            }
+            for (j, index) in table.indexes.iter().enumerate() {
+                let columns: Vec<String> = index
+                    .elements
+                    .iter()
+                    .filter(|e| e.is_column())
+                    .map(|e| self.column_name(data_type::split_qualified(&e.expression).1))
+                    .collect();
+                let to = if self.index.trim().is_empty() {
+                    index.name.clone()
+                } else {
+                    unique_name(fill(&self.index, &table_name, &columns, ""))
+                };
+                rename(Target::Index(i, j), &index.name, to);
+            }
+        }
+        renames
+    }
+
+    /// Renames everything [`Conventions::renames`] lists, along with the
+    /// references to tables and columns elsewhere in the model, views
+    /// included. Routines are SQL text and are left as they are.
+    pub fn apply(&self, project: &mut Project) {
+        let renames = self.renames(project);
+        for rename in &renames {
+            match rename.target {
+                Target::Column(i, j) => rename_column(project, i, j, &rename.to),
+                Target::Constraint(i, j) => {
+                    project.tables[i].constraints[j].name = rename.to.clone()
+                }
+                Target::Index(i, j) => project.tables[i].indexes[j].name = rename.to.clone(),
+                Target::Table(_) => {}
+            }
+        }
+        // Tables last, so that the renames above still find them by name.
+        for rename in &renames {
+            if let Target::Table(i) = rename.target {
+                rename_table(project, i, &rename.to);
+            }
+        }
+    }
+}
+
+/// Renames table `i` and points foreign keys, sequences, subject areas and
+/// the views reading from it at the new name.
+pub fn rename_table(project: &mut Project, i: usize, name: &str) {
+    let views = dependent_views(project, i, false);
+    let schema = project.tables[i].schema.clone();
+    let from = std::mem::replace(&mut project.tables[i].name, name.to_string());
+    for v in views {
+        rename_relation_in(&mut project.views[v].definition, &schema, &from, name);
+    }
+    for table in &mut project.tables {
+        for constraint in &mut table.constraints {
+            if let ConstraintKind::ForeignKey(fk) = &mut constraint.kind
+                && fk.ref_schema == schema
+                && fk.ref_table == from
+            {
+                fk.ref_table = name.to_string();
+            }
+        }
+    }
+    for sequence in &mut project.sequences {
+        if let Some(owned_by) = &mut sequence.owned_by
+            && let Some((table, column)) = owned_by.rsplit_once('.')
+            && owned(table, &schema, &from)
+        {
+            *owned_by = format!("{}.{column}", owner(&schema, name));
+        }
+    }
+    let (from, to) = (owner(&schema, &from), owner(&schema, name));
+    for area in &mut project.areas {
+        for table in &mut area.tables {
+            if *table == from {
+                *table = to.clone();
+            }
+        }
+    }
+}
+
+/// Renames column `j` of table `i` and the uses of it in keys, indexes,
+/// checks, generated columns, policies, triggers, grants and the partition
+/// key, in foreign keys pointing at it, in sequences owned by it and in the
+/// views reading it, along with the views on top of those.
+pub fn rename_column(project: &mut Project, i: usize, j: usize, name: &str) {
+    let views = dependent_views(project, i, true);
+    let (schema, table_name) = (
+        project.tables[i].schema.clone(),
+        project.tables[i].name.clone(),
+    );
+    let table = &mut project.tables[i];
+    let from = std::mem::replace(&mut table.columns[j].name, name.to_string());
+    let rename = |items: &mut Vec<String>| {
+        for item in items.iter_mut() {
+            if item.trim() == from {
+                *item = name.to_string();
+            }
+        }
+    };
+    let rewrite = |expression: &mut String| rename_in(expression, Some(&table_name), &from, name);
+
+    for constraint in &mut table.constraints {
+        match &mut constraint.kind {
+            ConstraintKind::PrimaryKey { columns, include }
+            | ConstraintKind::Unique {
+                columns, include, ..
+            } => {
+                rename(columns);
+                rename(include);
+            }
+            ConstraintKind::ForeignKey(fk) => rename(&mut fk.columns),
+            ConstraintKind::Exclusion {
+                elements,
+                include,
+                predicate,
+                ..
+            } => {
+                for element in elements {
+                    if element.element.trim() == from {
+                        element.element = name.to_string();
+                    } else {
+                        rewrite(&mut element.element);
+                    }
+                }
+                rename(include);
+                if let Some(expression) = predicate {
+                    rewrite(expression);
+                }
+            }
+            ConstraintKind::Check { expression, .. } => rewrite(expression),
+        }
+    }
+    for index in &mut table.indexes {
+        for element in &mut index.elements {
+            if element.is_column() {
+                if element.expression.trim() == from {
+                    element.expression = name.to_string();
+                }
+            } else {
+                rewrite(&mut element.expression);
+            }
+        }
+        rename(&mut index.include);
+        if let Some(expression) = &mut index.predicate {
+            rewrite(expression);
+        }
+    }
+    for column in &mut table.columns {
+        if let Some(expression) = &mut column.generated {
+            rewrite(expression);
+        }
+    }
+    for policy in &mut table.policies {
+        if let Some(expression) = &mut policy.using {
+            rewrite(expression);
+        }
+        if let Some(expression) = &mut policy.with_check {
+            rewrite(expression);
+        }
+    }
+    for trigger in &mut table.triggers {
+        rename(&mut trigger.update_columns);
+    }
+    for grant in &mut table.grants {
+        rename(&mut grant.columns);
+    }
+    if let Some(partitioning) = &mut table.partitioning {
+        for key in &mut partitioning.key {
+            if key.trim() == from {
+                *key = name.to_string();
+            } else {
+                rewrite(key);
+            }
+        }
+    }
+
+    for table in &mut project.tables {
+        for constraint in &mut table.constraints {
+            if let ConstraintKind::ForeignKey(fk) = &mut constraint.kind
+                && fk.ref_schema == schema
+                && fk.ref_table == table_name
+            {
+                rename(&mut fk.ref_columns);
+            }
+        }
+    }
+    for sequence in &mut project.sequences {
+        if let Some(owned_by) = &mut sequence.owned_by
+            && let Some((table, column)) = owned_by.rsplit_once('.')
+            && column.trim() == from
+            && owned(table, &schema, &table_name)
+        {
+            *owned_by = format!("{table}.{name}");
+        }
+    }
+    // Columns of views are named after the columns they select, so the
+    // views on top of them follow along, as do indexes on materialized ones.
+    let rewrite = |expression: &mut String| rename_in(expression, None, &from, name);
+    for v in views {
+        let view = &mut project.views[v];
+        rewrite(&mut view.definition);
+        for index in &mut view.indexes {
+            for element in &mut index.elements {
+                rewrite(&mut element.expression);
+            }
+            if let Some(expression) = &mut index.predicate {
+                rewrite(expression);
+            }
+        }
+    }
+}
+
+/// Indexes of the views reading from table `i`, and with `through_views`
+/// those reading from them in turn.
+fn dependent_views(project: &Project, i: usize, through_views: bool) -> Vec<usize> {
+    let mut dependent = vec![false; project.views.len()];
+    for v in lineage::sorted_views(project) {
+        dependent[v] = lineage::sources(project, &project.views[v])
+            .into_iter()
+            .any(|source| match source {
+                Source::Table(t) => t == i,
+                Source::View(w) => through_views && dependent[w],
+            });
+    }
+    (0..dependent.len()).filter(|&v| dependent[v]).collect()
+}
+
+/// Points the references to column `from` in `expression` at `to`, bare or
+/// qualified with `table`, or with anything when `table` is `None`. Strings,
+/// function and type names, aliases and columns of other tables are left as
+/// they are.
+fn rename_in(expression: &mut String, table: Option<&str>, from: &str, to: &str) {
+    let tokens = lexer::tokenize(expression);
+    for (k, token) in tokens.iter().enumerate().rev() {
+        let previous = |n: usize| k.checked_sub(n).map(|k| &tokens[k]);
+        let qualifier = previous(1)
+            .is_some_and(|t| t.is_symbol("."))
+            .then(|| previous(2).and_then(Token::ident));
+        if token.ident().as_deref() == Some(from)
+            && !tokens
+                .get(k + 1)
+                .is_some_and(|t| t.is_symbol("(") || t.is_symbol("."))
+            && !previous(1).is_some_and(|t| t.is_symbol("::") || t.is_keyword("as"))
+            && qualifier.is_none_or(|qualifier| {
+                table.is_none_or(|table| qualifier.as_deref() == Some(table))
+            })
+        {
+            expression.replace_range(token.start..token.end, &quote_ident(to));
+        }
+    }
+}
+
+/// Points the references to table `schema.from` in the query `definition`
+/// at `to`, where it is read from and where it qualifies columns.
+fn rename_relation_in(definition: &mut String, schema: &str, from: &str, to: &str) {
+    let tokens = lexer::tokenize(definition);
+    for (k, token) in tokens.iter().enumerate().rev() {
+        let previous = |n: usize| k.checked_sub(n).map(|k| &tokens[k]);
+        let qualified = previous(1).is_some_and(|t| t.is_symbol("."));
+        if token.ident().as_deref() == Some(from)
+            && !tokens.get(k + 1).is_some_and(|t| t.is_symbol("("))
+            && !previous(1).is_some_and(|t| t.is_symbol("::") || t.is_keyword("as"))
+            && (!qualified || previous(2).and_then(Token::ident).as_deref() == Some(schema))
+        {
+            definition.replace_range(token.start..token.end, &quote_ident(to));
+        }
+    }
+}
+
+/// Whether the `[schema.]table` part of `OWNED BY` is `schema.name`.
+fn owned(table: &str, schema: &str, name: &str) -> bool {
+    let (owner_schema, owner) = data_type::split_qualified(table);
+    owner == name && owner_schema.unwrap_or(super::DEFAULT_SCHEMA) == schema
+}
+
+fn owner(schema: &str, name: &str) -> String {
+    if schema == super::DEFAULT_SCHEMA {
+        name.to_string()
+    } else {
+        format!("{schema}.{name}")
+    }
+}
+
+fn fill(template: &str, table: &str, columns: &[String], referenced: &str) -> String {
+    truncate(
+        template
+            .replace("{table}", table)
+            .replace("{columns}", &columns.join("_"))
+            .replace("{referenced}", referenced),
+    )
+}
+
+/// Cuts `name` to what PostgreSQL would keep of it.
+fn truncate(mut name: String) -> String {
+    if name.len() > MAX_NAME {
+        let mut end = MAX_NAME;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+    name
+}
+
+/// Lower case words of a name in any case: `OrderItem`, `order_item` and
+/// `orderItem` all give `order`, `item`.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        // A capital starts a word after a lower case letter or digit, and
+        // ends an acronym when a lower case letter follows it: HTTPServer.
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        if c.is_uppercase()
+            && !word.is_empty()
+            && (previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || next.is_some_and(|n| n.is_lowercase()))
+        {
+            words.push(std::mem::take(&mut word));
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// `name` in snake case with its last word singular, so `OrderItems`
+/// gives `order_item`.
+pub fn singular_name(name: &str) -> String {
+    let mut words = words(name);
+    if let Some(last) = words.last_mut() {
+        *last = singular(last);
+    }
+    Case::Snake.apply(&words)
+}
+
+/// English singular, good enough for table names.
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if ["sses", "xes", "zes", "ches", "shes"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        word[..word.len() - 2].to_string()
+    } else if word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+fn plural(word: &str) -> String {
+    if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        format!("{word}es")
+    } else if let Some(stem) = word.strip_suffix('y')
+        && !stem.ends_with(['a', 'e', 'i', 'o', 'u'])
+    {
+        format!("{stem}ies")
+    } else {
+        format!("{word}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::Policy, sql::parse};
+
+    #[test]
+    fn views_follow_renamed_tables_and_columns() {
+        let mut project = parse::script(
+            r#"CREATE TABLE "UserAccount" ("Id" integer, "Name" text);
+             CREATE TABLE "Post" ("Id" integer, "UserAccountId" integer);
+             CREATE VIEW authors AS SELECT u."Id", "Name", public."Post"."Id" AS post FROM "UserAccount" u JOIN public."Post" ON "UserAccountId" = u."Id";
+             CREATE VIEW names AS SELECT "Name" AS "Id" FROM authors;"#,
+        )
+        .project;
+        Conventions::default().apply(&mut project);
+
+        assert_eq!(
+            project.views[0].definition,
+            "SELECT u.id, name, public.post.id AS post \
+             FROM user_account u JOIN public.post ON user_account_id = u.id"
+        );
+        assert_eq!(
+            project.views[1].definition,
+            "SELECT name AS \"Id\" FROM authors"
+        );
+    }
+
+    #[test]
+    fn renamed_columns_are_renamed_in_expressions() {
+        let mut project = parse::script(
+            "CREATE TABLE t (
+                 total numeric CHECK (total > 0 AND t.total < 100),
+                 doubled numeric GENERATED ALWAYS AS (total * 2) STORED,
+                 note text CHECK (note <> 'total')
+             );
+             CREATE INDEX t_idx ON t ((total + 1)) WHERE total > 10;",
+        )
+        .project;
+        let mut policy = Policy::new("p");
+        policy.using = Some(String::from("total > 0 AND total(t) > 0"));
+        project.tables[0].policies.push(policy);
+        rename_column(&mut project, 0, 0, "Grand Total");
+
+        let table = &project.tables[0];
+        let checks: Vec<&str> = table
+            .constraints
+            .iter()
+            .filter_map(|c| match &c.kind {
+                ConstraintKind::Check { expression, .. } => Some(expression.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                "\"Grand Total\" > 0 AND t.\"Grand Total\" < 100",
+                "note <> 'total'"
+            ]
+        );
+        assert_eq!(
+            table.columns[1].generated.as_deref(),
+            Some("\"Grand Total\" * 2")
+        );
+        assert_eq!(
+            table.indexes[0].elements[0].expression,
+            "\"Grand Total\" + 1"
+        );
+        assert_eq!(
+            table.indexes[0].predicate.as_deref(),
+            Some("\"Grand Total\" > 10")
+        );
+        assert_eq!(
+            table.policies[0].using.as_deref(),
+            Some("\"Grand Total\" > 0 AND total(t) > 0")
+        );
+    }
+}
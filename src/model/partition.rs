@@ -0,0 +1,157 @@
+use std::fmt;
+
+/// `PARTITION BY` clause of a table and the partitions attached to it.
+/// `key` holds columns or expressions, like index elements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Partitioning {
+    pub strategy: PartitionStrategy,
+    pub key: Vec<String>,
+    pub partitions: Vec<Partition>,
+}
+
+/// A child table, created in the schema of its parent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Partition {
+    pub name: String,
+    pub bound: PartitionBound,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PartitionBound {
+    /// `FROM (from) TO (to)`, both as typed, e.g. `'2024-01-01'`.
+    Range {
+        from: String,
+        to: String,
+    },
+    List {
+        values: String,
+    },
+    Hash {
+        modulus: u32,
+        remainder: u32,
+    },
+    Default,
+}
+
+impl Partitioning {
+    pub fn new(strategy: PartitionStrategy) -> Self {
+        Self {
+            strategy,
+            key: Vec::new(),
+            partitions: Vec::new(),
+        }
+    }
+
+    /// Switches strategy, resetting bounds that don't fit the new one.
+    pub fn set_strategy(&mut self, strategy: PartitionStrategy) {
+        if strategy == self.strategy {
+            return;
+        }
+        self.strategy = strategy;
+        let partitions = std::mem::take(&mut self.partitions);
+        for partition in partitions {
+            match partition.bound {
+                // Hash partitioning has no default partition.
+                PartitionBound::Default if strategy != PartitionStrategy::Hash => {
+                    self.partitions.push(partition)
+                }
+                _ => self.add_partition(partition.name),
+            }
+        }
+    }
+
+    /// Adds a partition after the existing ones. Range partitions continue
+    /// month by month when the last bound is a first-of-month date. Hash
+    /// partitions are renumbered so that they keep splitting rows evenly.
+    pub fn add_partition(&mut self, name: String) {
+        let bound = match self.strategy {
+            PartitionStrategy::Range => {
+                let last = self.partitions.iter().rev().find_map(|p| match &p.bound {
+                    PartitionBound::Range { to, .. } => Some(to.clone()),
+                    _ => None,
+                });
+                let to = last.as_deref().and_then(next_month).unwrap_or_default();
+                PartitionBound::Range {
+                    from: last.unwrap_or_default(),
+                    to,
+                }
+            }
+            PartitionStrategy::List => PartitionBound::List {
+                values: String::new(),
+            },
+            PartitionStrategy::Hash => PartitionBound::Hash {
+                modulus: 1,
+                remainder: 0,
+            },
+        };
+        self.partitions.push(Partition { name, bound });
+
+        if self.strategy == PartitionStrategy::Hash {
+            let modulus = self.partitions.len() as u32;
+            for (remainder, partition) in self.partitions.iter_mut().enumerate() {
+                partition.bound = PartitionBound::Hash {
+                    modulus,
+                    remainder: remainder as u32,
+                };
+            }
+        }
+    }
+}
+
+/// `'2024-01-01'` gives `'2024-02-01'`. Only first-of-month dates qualify.
+fn next_month(bound: &str) -> Option<String> {
+    let date = bound.trim().strip_prefix('\'')?.strip_suffix('\'')?;
+    let mut parts = date.splitn(3, '-');
+    let year: u32 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    if parts.next()? != "01" || !(1..=12).contains(&month) {
+        return None;
+    }
+    let (year, month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    Some(format!("'{year:04}-{month:02}-01'"))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PartitionStrategy {
+    #[default]
+    Range,
+    List,
+    Hash,
+}
+
+impl PartitionStrategy {
+    pub const ALL: [PartitionStrategy; 3] = [
+        PartitionStrategy::Range,
+        PartitionStrategy::List,
+        PartitionStrategy::Hash,
+    ];
+}
+
+impl fmt::Display for PartitionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PartitionStrategy::Range => "RANGE",
+            PartitionStrategy::List => "LIST",
+            PartitionStrategy::Hash => "HASH",
+        })
+    }
+}
+
+impl fmt::Display for PartitionBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartitionBound::Range { from, to } => {
+                write!(f, "FROM ({}) TO ({})", from.trim(), to.trim())
+            }
+            PartitionBound::List { values } => write!(f, "IN ({})", values.trim()),
+            PartitionBound::Hash { modulus, remainder } => {
+                write!(f, "WITH (MODULUS {modulus}, REMAINDER {remainder})")
+            }
+            PartitionBound::Default => f.write_str("DEFAULT"),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// A function or procedure. `arguments` is the parameter list exactly as it
+/// goes between the parentheses, e.g. `order_id bigint, note text`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Routine {
+    pub schema: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub kind: RoutineKind,
+    pub arguments: String,
+    /// Return type of a function, ignored for procedures.
+    pub returns: String,
+    pub language: String,
+    pub volatility: Volatility,
+    pub body: String,
+}
+
+impl Routine {
+    pub fn new(schema: impl Into<String>, name: impl Into<String>, kind: RoutineKind) -> Self {
+        let (returns, body) = match kind {
+            RoutineKind::Function => ("trigger", "BEGIN\n    RETURN NEW;\nEND;"),
+            RoutineKind::Procedure => ("", "BEGIN\nEND;"),
+        };
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+            comment: None,
+            kind,
+            arguments: String::new(),
+            returns: String::from(returns),
+            language: String::from("plpgsql"),
+            volatility: Volatility::default(),
+            body: String::from(body),
+        }
+    }
+
+    pub fn is_trigger_function(&self) -> bool {
+        self.kind == RoutineKind::Function && self.returns.trim().eq_ignore_ascii_case("trigger")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoutineKind {
+    Function,
+    Procedure,
+}
+
+impl RoutineKind {
+    pub const ALL: [RoutineKind; 2] = [RoutineKind::Function, RoutineKind::Procedure];
+}
+
+impl fmt::Display for RoutineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RoutineKind::Function => "function",
+            RoutineKind::Procedure => "procedure",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Volatility {
+    #[default]
+    Volatile,
+    Stable,
+    Immutable,
+}
+
+impl Volatility {
+    pub const ALL: [Volatility; 3] = [
+        Volatility::Volatile,
+        Volatility::Stable,
+        Volatility::Immutable,
+    ];
+}
+
+impl fmt::Display for Volatility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Volatility::Volatile => "VOLATILE",
+            Volatility::Stable => "STABLE",
+            Volatility::Immutable => "IMMUTABLE",
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use std::{collections::BTreeSet, fmt};
+
+use super::list_items;
+
+/// A database role. Roles live outside of schemas, so they are referred to
+/// by name alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub comment: Option<String>,
+    pub login: bool,
+    /// Roles this one is granted membership of.
+    pub member_of: Vec<String>,
+}
+
+impl Role {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            comment: None,
+            login: false,
+            member_of: Vec::new(),
+        }
+    }
+}
+
+/// Privileges granted to, or revoked from, a role on a table. With
+/// `columns` set the privileges only apply to those columns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grant {
+    pub role: String,
+    pub privileges: Vec<Privilege>,
+    pub columns: Vec<String>,
+    pub with_grant_option: bool,
+    pub revoke: bool,
+}
+
+/// Privileges on the whole table or one column, each with whether it
+/// comes with the grant option.
+pub type Privileges = BTreeSet<(Privilege, Option<String>, bool)>;
+
+impl Grant {
+    pub fn new(role: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            privileges: vec![Privilege::Select],
+            columns: Vec::new(),
+            with_grant_option: false,
+            revoke: false,
+        }
+    }
+
+    /// Grants or revokes on top of what the role holds. Revoking on the
+    /// whole table revokes on every column too, like PostgreSQL does.
+    pub fn apply(&self, held: &mut Privileges) {
+        let columns: Vec<&str> = list_items(&self.columns).collect();
+        if self.revoke {
+            held.retain(|(privilege, column, _)| {
+                !self.privileges.contains(privilege)
+                    || !(columns.is_empty()
+                        || column.as_deref().is_some_and(|c| columns.contains(&c)))
+            });
+            return;
+        }
+        for &privilege in &self.privileges {
+            if columns.is_empty() {
+                held.insert((privilege, None, self.with_grant_option));
+            } else if privilege.applies_to_columns() {
+                held.extend(
+                    columns
+                        .iter()
+                        .map(|c| (privilege, Some(c.to_string()), self.with_grant_option)),
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Truncate,
+    References,
+    Trigger,
+}
+
+impl Privilege {
+    pub const ALL: [Privilege; 7] = [
+        Privilege::Select,
+        Privilege::Insert,
+        Privilege::Update,
+        Privilege::Delete,
+        Privilege::Truncate,
+        Privilege::References,
+        Privilege::Trigger,
+    ];
+
+    pub fn applies_to_columns(self) -> bool {
+        matches!(
+            self,
+            Privilege::Select | Privilege::Insert | Privilege::Update | Privilege::References
+        )
+    }
+
+    /// Letter used for the privilege in `aclitem`s, as shown by `\dp`.
+    pub fn acl_char(self) -> char {
+        match self {
+            Privilege::Select => 'r',
+            Privilege::Insert => 'a',
+            Privilege::Update => 'w',
+            Privilege::Delete => 'd',
+            Privilege::Truncate => 'D',
+            Privilege::References => 'x',
+            Privilege::Trigger => 't',
+        }
+    }
+}
+
+impl fmt::Display for Privilege {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::Truncate => "TRUNCATE",
+            Privilege::References => "REFERENCES",
+            Privilege::Trigger => "TRIGGER",
+        })
+    }
+}
+
+/// A row level security policy. An empty role list applies to `PUBLIC`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    pub name: String,
+    pub permissive: bool,
+    pub command: PolicyCommand,
+    pub roles: Vec<String>,
+    pub using: Option<String>,
+    pub with_check: Option<String>,
+}
+
+impl Policy {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            permissive: true,
+            command: PolicyCommand::All,
+            roles: Vec::new(),
+            using: None,
+            with_check: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PolicyCommand {
+    #[default]
+    All,
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+impl PolicyCommand {
+    pub const ALL: [PolicyCommand; 5] = [
+        PolicyCommand::All,
+        PolicyCommand::Select,
+        PolicyCommand::Insert,
+        PolicyCommand::Update,
+        PolicyCommand::Delete,
+    ];
+
+    /// `SELECT` and `DELETE` policies only filter existing rows.
+    pub fn has_with_check(self) -> bool {
+        !matches!(self, PolicyCommand::Select | PolicyCommand::Delete)
+    }
+
+    /// `INSERT` policies only check new rows.
+    pub fn has_using(self) -> bool {
+        self != PolicyCommand::Insert
+    }
+}
+
+impl fmt::Display for PolicyCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PolicyCommand::All => "ALL",
+            PolicyCommand::Select => "SELECT",
+            PolicyCommand::Insert => "INSERT",
+            PolicyCommand::Update => "UPDATE",
+            PolicyCommand::Delete => "DELETE",
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use super::Column;
+
+/// A sequence created with `CREATE SEQUENCE`. Unset bounds and start use
+/// the PostgreSQL defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence {
+    pub schema: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub data_type: String,
+    pub start: Option<i64>,
+    pub increment: i64,
+    pub min_value: Option<i64>,
+    pub max_value: Option<i64>,
+    pub cache: i64,
+    pub cycle: bool,
+    /// `table.column` the sequence belongs to, qualified with the schema
+    /// unless the table is in `public`.
+    pub owned_by: Option<String>,
+}
+
+impl Sequence {
+    pub const TYPES: [&str; 3] = ["smallint", "integer", "bigint"];
+
+    pub fn new(schema: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+            comment: None,
+            data_type: String::from("bigint"),
+            start: None,
+            increment: 1,
+            min_value: None,
+            max_value: None,
+            cache: 1,
+            cycle: false,
+            owned_by: None,
+        }
+    }
+
+    /// Bounds the sequence gets when `MINVALUE` and `MAXVALUE` are left out.
+    pub fn default_bounds(&self) -> (i64, i64) {
+        let max = match self.data_type.as_str() {
+            "smallint" => i16::MAX as i64,
+            "integer" => i32::MAX as i64,
+            _ => i64::MAX,
+        };
+        if self.increment > 0 {
+            (1, max)
+        } else {
+            (-max - 1, -1)
+        }
+    }
+
+    /// Whether `column` is what `serial` expands to: an integer column
+    /// defaulting to the next value of this sequence, left plain.
+    pub fn backs_serial(&self, column: &Column) -> bool {
+        let default = column.default.as_deref().unwrap_or_default();
+        default.starts_with("nextval(")
+            && default.contains(&self.name)
+            && self.increment == 1
+            && self.start.is_none()
+            && self.min_value.is_none()
+            && self.max_value.is_none()
+            && !self.cycle
+            && Self::TYPES.contains(&column.data_type.as_str())
+    }
+}
@@ -0,0 +1,178 @@
+use super::{
+    list_items, object_name, Column, Constraint, ConstraintKind, ConstraintType, Grant, Index,
+    IndexElement, Partitioning, Policy, Position, Trigger,
+};
+use crate::sql::lexer;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub schema: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub columns: Vec<Column>,
+    pub constraints: Vec<Constraint>,
+    pub indexes: Vec<Index>,
+    pub triggers: Vec<Trigger>,
+    pub partitioning: Option<Partitioning>,
+    pub row_security: bool,
+    /// Apply policies to the table owner too.
+    pub force_row_security: bool,
+    pub policies: Vec<Policy>,
+    pub grants: Vec<Grant>,
+    pub position: Position,
+}
+
+impl Table {
+    pub fn new(schema: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+            comment: None,
+            columns: Vec::new(),
+            constraints: Vec::new(),
+            indexes: Vec::new(),
+            triggers: Vec::new(),
+            partitioning: None,
+            row_security: false,
+            force_row_security: false,
+            policies: Vec::new(),
+            grants: Vec::new(),
+            position: Position::default(),
+        }
+    }
+
+    pub fn primary_key(&self) -> Option<&Constraint> {
+        self.constraints
+            .iter()
+            .find(|c| c.constraint_type() == ConstraintType::PrimaryKey)
+    }
+
+    /// Name PostgreSQL would pick for a new constraint, e.g. `orders_pkey`.
+    pub fn constraint_name(&self, constraint_type: ConstraintType) -> String {
+        let base = format!("{}_{}", self.name, constraint_type.suffix());
+        let mut name = base.clone();
+        let mut n = 0;
+        while self.constraints.iter().any(|c| c.name == name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+
+    /// Name PostgreSQL gives a constraint on `columns` left unnamed, e.g.
+    /// `orders_customer_id_fkey`.
+    pub fn column_constraint_name(
+        &self,
+        constraint_type: ConstraintType,
+        columns: &[String],
+    ) -> String {
+        object_name(&self.name, columns, constraint_type.suffix(), |name| {
+            self.name_taken(name)
+        })
+    }
+
+    /// Name PostgreSQL gives a constraint of `kind` left unnamed: primary
+    /// keys after the table, checks after the one column they use if they
+    /// use one, and other keys after their columns.
+    pub fn default_constraint_name(&self, kind: &ConstraintKind) -> String {
+        let (constraint_type, columns): (ConstraintType, Vec<String>) = match kind {
+            ConstraintKind::PrimaryKey { .. } => (ConstraintType::PrimaryKey, Vec::new()),
+            ConstraintKind::Unique { columns, .. } => (
+                ConstraintType::Unique,
+                list_items(columns).map(String::from).collect(),
+            ),
+            ConstraintKind::ForeignKey(fk) => (
+                ConstraintType::ForeignKey,
+                list_items(&fk.columns).map(String::from).collect(),
+            ),
+            ConstraintKind::Check { expression, .. } => {
+                let mut used: Vec<String> = Vec::new();
+                let tokens = lexer::tokenize(expression);
+                for (k, token) in tokens.iter().enumerate() {
+                    if let Some(name) = token.ident()
+                        && !tokens.get(k + 1).is_some_and(|t| t.is_symbol("("))
+                        && self.columns.iter().any(|c| c.name == name)
+                        && !used.contains(&name)
+                    {
+                        used.push(name);
+                    }
+                }
+                if used.len() > 1 {
+                    used.clear();
+                }
+                (ConstraintType::Check, used)
+            }
+            ConstraintKind::Exclusion { elements, .. } => (
+                ConstraintType::Exclusion,
+                elements
+                    .iter()
+                    .map(|e| IndexElement::new(e.element.clone()).name_part())
+                    .collect(),
+            ),
+        };
+        self.column_constraint_name(constraint_type, &columns)
+    }
+
+    /// Name PostgreSQL gives an index left unnamed, after what it indexes:
+    /// `users_email_idx`, `users_lower_idx`.
+    pub fn default_index_name(&self, elements: &[IndexElement]) -> String {
+        let parts: Vec<String> = elements.iter().map(IndexElement::name_part).collect();
+        object_name(&self.name, &parts, "idx", |name| self.name_taken(name))
+    }
+
+    /// Whether a constraint or index has `name`. Indexes and the keys backed
+    /// by them share a namespace.
+    fn name_taken(&self, name: &str) -> bool {
+        self.constraints.iter().any(|c| c.name == name)
+            || self.indexes.iter().any(|i| i.name == name)
+    }
+
+    pub fn index_name(&self) -> String {
+        let base = format!("{}_idx", self.name);
+        let mut name = base.clone();
+        let mut n = 0;
+        while self.indexes.iter().any(|i| i.name == name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+
+    pub fn trigger_name(&self) -> String {
+        let base = format!("{}_trigger", self.name);
+        let mut name = base.clone();
+        let mut n = 0;
+        while self.triggers.iter().any(|t| t.name == name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+
+    pub fn partition_name(&self) -> String {
+        let partitions = self
+            .partitioning
+            .as_ref()
+            .map(|p| p.partitions.as_slice())
+            .unwrap_or_default();
+        let mut n = partitions.len() + 1;
+        while partitions
+            .iter()
+            .any(|p| p.name == format!("{}_p{n}", self.name))
+        {
+            n += 1;
+        }
+        format!("{}_p{n}", self.name)
+    }
+
+    pub fn policy_name(&self) -> String {
+        let base = format!("{}_policy", self.name);
+        let mut name = base.clone();
+        let mut n = 0;
+        while self.policies.iter().any(|p| p.name == name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// A trigger on a table. `function` names the trigger function, qualified
+/// unless it lives in the table's schema or `public`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+    pub name: String,
+    pub timing: TriggerTiming,
+    pub insert: bool,
+    pub update: bool,
+    pub delete: bool,
+    pub truncate: bool,
+    /// `UPDATE OF` columns; any column when empty.
+    pub update_columns: Vec<String>,
+    pub for_each_row: bool,
+    pub condition: Option<String>,
+    pub function: String,
+}
+
+impl Trigger {
+    pub fn new(name: impl Into<String>, function: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            timing: TriggerTiming::Before,
+            insert: true,
+            update: true,
+            delete: false,
+            truncate: false,
+            update_columns: Vec::new(),
+            for_each_row: true,
+            condition: None,
+            function: function.into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerTiming {
+    Before,
+    After,
+}
+
+impl TriggerTiming {
+    pub const ALL: [TriggerTiming; 2] = [TriggerTiming::Before, TriggerTiming::After];
+}
+
+impl fmt::Display for TriggerTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TriggerTiming::Before => "BEFORE",
+            TriggerTiming::After => "AFTER",
+        })
+    }
+}
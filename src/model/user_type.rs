@@ -0,0 +1,108 @@
+use std::fmt;
+
+/// A type created with `CREATE TYPE` or `CREATE DOMAIN`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserType {
+    pub schema: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub kind: UserTypeKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserTypeKind {
+    Enum {
+        labels: Vec<String>,
+    },
+    Composite {
+        attributes: Vec<Attribute>,
+    },
+    Domain {
+        data_type: String,
+        not_null: bool,
+        default: Option<String>,
+        checks: Vec<DomainCheck>,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainCheck {
+    pub name: String,
+    pub expression: String,
+}
+
+impl UserType {
+    pub fn new(schema: impl Into<String>, name: impl Into<String>, kind: UserTypeKind) -> Self {
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+            comment: None,
+            kind,
+        }
+    }
+
+    pub fn category(&self) -> TypeCategory {
+        match self.kind {
+            UserTypeKind::Enum { .. } => TypeCategory::Enum,
+            UserTypeKind::Composite { .. } => TypeCategory::Composite,
+            UserTypeKind::Domain { .. } => TypeCategory::Domain,
+        }
+    }
+
+    /// Types this one is built from, as written in the model.
+    pub fn referenced_types(&self) -> Vec<&str> {
+        match &self.kind {
+            UserTypeKind::Enum { .. } => Vec::new(),
+            UserTypeKind::Composite { attributes } => {
+                attributes.iter().map(|a| a.data_type.as_str()).collect()
+            }
+            UserTypeKind::Domain { data_type, .. } => vec![data_type.as_str()],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeCategory {
+    Enum,
+    Composite,
+    Domain,
+}
+
+impl TypeCategory {
+    pub const ALL: [TypeCategory; 3] = [
+        TypeCategory::Enum,
+        TypeCategory::Composite,
+        TypeCategory::Domain,
+    ];
+
+    pub fn empty(&self) -> UserTypeKind {
+        match self {
+            TypeCategory::Enum => UserTypeKind::Enum { labels: Vec::new() },
+            TypeCategory::Composite => UserTypeKind::Composite {
+                attributes: Vec::new(),
+            },
+            TypeCategory::Domain => UserTypeKind::Domain {
+                data_type: String::from("text"),
+                not_null: false,
+                default: None,
+                checks: Vec::new(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TypeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TypeCategory::Enum => "enum",
+            TypeCategory::Composite => "composite",
+            TypeCategory::Domain => "domain",
+        })
+    }
+}
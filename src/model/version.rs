@@ -0,0 +1,102 @@
+use std::fmt;
+
+/// PostgreSQL major version a project is generated for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PgVersion {
+    V12,
+    V13,
+    V14,
+    V15,
+    V16,
+    #[default]
+    V17,
+}
+
+impl PgVersion {
+    pub const ALL: [PgVersion; 6] = [
+        PgVersion::V12,
+        PgVersion::V13,
+        PgVersion::V14,
+        PgVersion::V15,
+        PgVersion::V16,
+        PgVersion::V17,
+    ];
+
+    pub fn major(self) -> u32 {
+        match self {
+            PgVersion::V12 => 12,
+            PgVersion::V13 => 13,
+            PgVersion::V14 => 14,
+            PgVersion::V15 => 15,
+            PgVersion::V16 => 16,
+            PgVersion::V17 => 17,
+        }
+    }
+
+    /// Closest supported version to a server's major version.
+    pub fn from_major(major: u32) -> Self {
+        PgVersion::ALL
+            .into_iter()
+            .rev()
+            .find(|version| version.major() <= major)
+            .unwrap_or(PgVersion::V12)
+    }
+
+    pub fn supports(self, feature: Feature) -> bool {
+        self.major() >= feature.since()
+    }
+
+    /// Functions that used to come from an extension but are built in as of
+    /// this version.
+    pub fn builtin_functions(self) -> &'static [&'static str] {
+        if self.supports(Feature::BuiltinGenRandomUuid) {
+            &["gen_random_uuid()"]
+        } else {
+            &[]
+        }
+    }
+}
+
+impl fmt::Display for PgVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PostgreSQL {}", self.major())
+    }
+}
+
+/// Features whose availability depends on the target version. Those every
+/// supported version has, such as identity and generated columns, are left
+/// out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    BuiltinGenRandomUuid,
+    BeforeRowTriggersOnPartitions,
+    Multiranges,
+    NullsNotDistinct,
+    Merge,
+    JsonTable,
+}
+
+impl Feature {
+    /// First major version with the feature.
+    pub fn since(self) -> u32 {
+        match self {
+            Feature::BuiltinGenRandomUuid | Feature::BeforeRowTriggersOnPartitions => 13,
+            Feature::Multiranges => 14,
+            Feature::NullsNotDistinct | Feature::Merge => 15,
+            Feature::JsonTable => 17,
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Feature::BuiltinGenRandomUuid => "built-in gen_random_uuid()",
+            Feature::BeforeRowTriggersOnPartitions => "BEFORE ROW triggers on partitioned tables",
+            Feature::Multiranges => "multirange types",
+            Feature::NullsNotDistinct => "NULLS NOT DISTINCT",
+            Feature::Merge => "MERGE",
+            Feature::JsonTable => "JSON_TABLE",
+        })
+    }
+}
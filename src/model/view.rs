@@ -0,0 +1,56 @@
+use super::{object_name, Index, IndexElement, Position};
+
+/// A view or materialized view. `definition` is the query after `AS`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+    pub schema: String,
+    pub name: String,
+    pub comment: Option<String>,
+    pub materialized: bool,
+    pub definition: String,
+    pub with_data: bool,
+    pub indexes: Vec<Index>,
+    pub position: Position,
+}
+
+impl View {
+    pub fn new(schema: impl Into<String>, name: impl Into<String>, materialized: bool) -> Self {
+        Self {
+            schema: schema.into(),
+            name: name.into(),
+            comment: None,
+            materialized,
+            definition: String::from("SELECT 1"),
+            with_data: true,
+            indexes: Vec::new(),
+            position: Position::default(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        if self.materialized {
+            "materialized view"
+        } else {
+            "view"
+        }
+    }
+
+    /// Name PostgreSQL gives an index left unnamed, after what it indexes.
+    pub fn default_index_name(&self, elements: &[IndexElement]) -> String {
+        let parts: Vec<String> = elements.iter().map(IndexElement::name_part).collect();
+        object_name(&self.name, &parts, "idx", |name| {
+            self.indexes.iter().any(|i| i.name == name)
+        })
+    }
+
+    pub fn index_name(&self) -> String {
+        let base = format!("{}_idx", self.name);
+        let mut name = base.clone();
+        let mut n = 0;
+        while self.indexes.iter().any(|i| i.name == name) {
+            n += 1;
+            name = format!("{base}{n}");
+        }
+        name
+    }
+}
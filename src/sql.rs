@@ -0,0 +1,171 @@
+use std::{borrow::Cow, cell::Cell, fmt};
+
+use crate::model::DEFAULT_SCHEMA;
+
+pub mod canonical;
+pub mod compat;
+pub mod ddl;
+pub mod diff;
+pub mod keywords;
+pub mod lexer;
+pub mod lineage;
+pub mod migration;
+pub mod parse;
+pub mod sample;
+pub mod validate;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Quoting {
+    /// Only where PostgreSQL would otherwise fold or reject the name.
+    #[default]
+    AsNeeded,
+    Always,
+}
+
+impl Quoting {
+    pub const ALL: [Quoting; 2] = [Quoting::AsNeeded, Quoting::Always];
+}
+
+impl fmt::Display for Quoting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Quoting::AsNeeded => "Quote as needed",
+            Quoting::Always => "Quote everything",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Qualify {
+    #[default]
+    Always,
+    /// Leaves out the default schema, which is on every search path.
+    NonDefault,
+}
+
+impl Qualify {
+    pub const ALL: [Qualify; 2] = [Qualify::Always, Qualify::NonDefault];
+}
+
+impl fmt::Display for Qualify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Qualify::Always => "Qualify every name",
+            Qualify::NonDefault => "Omit public schema",
+        })
+    }
+}
+
+thread_local! {
+    static NAMING: Cell<(Quoting, Qualify)> = Cell::default();
+}
+
+/// Runs `f` with [`quote_ident`] and [`qualified`] writing names the given
+/// way. Everything else gets the defaults, which is what diffs compare.
+pub fn with_naming<T>(quoting: Quoting, qualify: Qualify, f: impl FnOnce() -> T) -> T {
+    let previous = NAMING.replace((quoting, qualify));
+    let out = f();
+    NAMING.set(previous);
+    out
+}
+
+/// Quotes an identifier only when PostgreSQL would otherwise fold or reject
+/// it, the same way `quote_ident()` does, unless [`with_naming`] asks for
+/// everything to be quoted.
+pub fn quote_ident(name: &str) -> Cow<'_, str> {
+    let plain = NAMING.get().0 == Quoting::AsNeeded
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$')
+        && !keywords::is_reserved(name);
+
+    if plain {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("\"{}\"", name.replace('"', "\"\"")))
+    }
+}
+
+pub fn qualified(schema: &str, name: &str) -> String {
+    if schema.is_empty() || (NAMING.get().1 == Qualify::NonDefault && schema == DEFAULT_SCHEMA) {
+        quote_ident(name).into_owned()
+    } else {
+        format!("{}.{}", quote_ident(schema), quote_ident(name))
+    }
+}
+
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub fn ident_list<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    names
+        .into_iter()
+        .map(quote_ident)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Splits `(inner) rest` into `inner` and `rest`, skipping parentheses in
+/// quoted strings and identifiers.
+pub fn take_group(value: &str) -> Option<(&str, &str)> {
+    if !value.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in value.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&value[1..i], &value[i + 1..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits on commas outside parentheses and quotes.
+pub fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(value[start..].trim());
+    parts
+}
+
+/// Drops the parentheses the catalog wraps expressions in, but only when
+/// they enclose the whole expression.
+pub fn unwrap_parens(expression: &str) -> String {
+    let expression = expression.trim();
+    match take_group(expression) {
+        Some((inner, rest)) if rest.trim().is_empty() => inner.trim().to_string(),
+        _ => expression.to_string(),
+    }
+}
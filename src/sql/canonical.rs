@@ -0,0 +1,474 @@
+use super::{
+    keywords,
+    lexer::{self, Token, TokenKind},
+    quote_literal,
+};
+use crate::model::DEFAULT_SCHEMA;
+
+/// Words that end an expression on their left or start one on their right,
+/// so that parentheses next to them group nothing.
+const BEFORE: &[&str] = &[
+    "where", "on", "having", "when", "then", "else", "and", "or", "not", "select", "from", "join",
+    "default",
+];
+const AFTER: &[&str] = &[
+    "and",
+    "or",
+    "then",
+    "else",
+    "end",
+    "when",
+    "where",
+    "group",
+    "order",
+    "having",
+    "limit",
+    "offset",
+    "join",
+    "left",
+    "right",
+    "full",
+    "cross",
+    "on",
+    "union",
+    "except",
+    "intersect",
+    "as",
+    "asc",
+    "desc",
+    "nulls",
+    "not",
+    "null",
+    "collate",
+    "check",
+    "references",
+    "unique",
+    "primary",
+    "constraint",
+    "generated",
+    "comment",
+];
+/// Words of multi-word type names, which follow the first word of a cast.
+const TYPE_WORDS: &[&str] = &["varying", "precision", "with", "without", "time", "zone"];
+/// Type names written before a string to make a typed literal.
+const TYPED_LITERALS: &[&str] = &["interval", "date", "time", "timestamp", "timestamptz"];
+
+/// `sql` in the form it takes whether written by hand or printed by the
+/// catalog, for comparing the two. Layout, case and quotes that aren't
+/// needed go, as do casts of literals (`'x'::text`, `(0)::numeric`) and
+/// parentheses that group nothing. `IN`, `BETWEEN`, `LIKE` and `!=` are
+/// rewritten the way PostgreSQL stores them, and columns in queries lose
+/// the name of their table.
+pub fn canonical(sql: &str) -> String {
+    let mut tokens = tokens(sql);
+    rewrite_operators(&mut tokens);
+    loop {
+        let before = tokens.len();
+        strip_casts(&mut tokens);
+        strip_parens(&mut tokens);
+        if tokens.len() == before {
+            break;
+        }
+    }
+    strip_qualifiers(&mut tokens);
+    strip_aliases(&mut tokens);
+    render(&tokens)
+}
+
+/// Tokens with words folded to lower case, needless quotes dropped, unary
+/// minus joined to its number and numeric strings read as numbers.
+fn tokens(sql: &str) -> Vec<Token> {
+    let mut out: Vec<Token> = Vec::new();
+    for mut token in lexer::tokenize(sql) {
+        match token.kind {
+            TokenKind::Word => token.text = token.text.to_lowercase(),
+            TokenKind::Quoted if is_plain(&token.text) => token.kind = TokenKind::Word,
+            TokenKind::String if sql[token.start..].starts_with('$') => {
+                token.text = token.text.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+            TokenKind::String if is_number(&token.text) => token.kind = TokenKind::Number,
+            TokenKind::Number
+                if out.last().is_some_and(|t| t.is_symbol("-"))
+                    && out
+                        .len()
+                        .checked_sub(2)
+                        .is_none_or(|i| starts_operand(&out[i])) =>
+            {
+                out.pop();
+                token.text.insert(0, '-');
+            }
+            TokenKind::Symbol if token.text == "!=" => token.text = String::from("<>"),
+            TokenKind::Symbol
+                if token.text == ";" && out.last().is_some_and(|t| t.is_symbol(";")) =>
+            {
+                continue;
+            }
+            _ => {}
+        }
+        // `INNER JOIN` is a `JOIN`, `LEFT OUTER JOIN` a `LEFT JOIN`.
+        if token.is_keyword("outer") {
+            continue;
+        }
+        if token.is_keyword("join") && out.last().is_some_and(|t| t.is_keyword("inner")) {
+            out.pop();
+        }
+        // `interval '1 day'` reads back as `'1 day'::interval`, which loses
+        // its cast below.
+        if token.kind == TokenKind::String
+            && out.last().is_some_and(|t| {
+                t.kind == TokenKind::Word && TYPED_LITERALS.contains(&t.text.as_str())
+            })
+            && out
+                .len()
+                .checked_sub(2)
+                .is_none_or(|i| !out[i].is_symbol("::"))
+        {
+            out.pop();
+        }
+        out.push(token);
+    }
+    out
+}
+
+/// A name that reads the same without its quotes.
+fn is_plain(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !keywords::is_reserved(name)
+}
+
+fn is_number(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | 'e' | 'E'))
+        && text.parse::<f64>().is_ok()
+}
+
+/// Whether an expression can start right after `token`, making a following
+/// `-` unary.
+fn starts_operand(token: &Token) -> bool {
+    match token.kind {
+        TokenKind::Symbol => !matches!(token.text.as_str(), ")" | "]"),
+        TokenKind::Word => {
+            keywords::is_reserved(&token.text)
+                || ["between", "like", "ilike", "similar"].contains(&token.text.as_str())
+        }
+        _ => false,
+    }
+}
+
+fn word(text: &str) -> Token {
+    token(TokenKind::Word, text)
+}
+
+fn symbol(text: &str) -> Token {
+    token(TokenKind::Symbol, text)
+}
+
+fn token(kind: TokenKind, text: &str) -> Token {
+    Token {
+        kind,
+        text: text.to_string(),
+        start: 0,
+        end: 0,
+    }
+}
+
+/// Index of the parenthesis or bracket closing the one at `open`.
+fn closing(tokens: &[Token], open: usize) -> Option<usize> {
+    let (left, right) = if tokens[open].is_symbol("[") {
+        ("[", "]")
+    } else {
+        ("(", ")")
+    };
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol(left) {
+            depth += 1;
+        } else if token.is_symbol(right) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// The tokens of `tokens` outside any parentheses.
+fn top_level(tokens: &[Token]) -> impl Iterator<Item = &Token> {
+    let mut depth = 0;
+    tokens.iter().filter(move |token| {
+        if token.is_symbol("(") || token.is_symbol("[") {
+            depth += 1;
+        } else if token.is_symbol(")") || token.is_symbol("]") {
+            depth -= 1;
+            return false;
+        }
+        depth == 0
+    })
+}
+
+/// `x IN (a, b)` becomes `x = ANY (ARRAY[a, b])`, `NOT IN` becomes
+/// `<> ALL`, `x BETWEEN a AND b` becomes `x >= a AND x <= b` and `LIKE` its
+/// `~~` operator, all as the catalog prints them.
+fn rewrite_operators(tokens: &mut Vec<Token>) {
+    let mut i = 0;
+    while i < tokens.len() {
+        let negated = i > 0 && tokens[i - 1].is_keyword("not");
+        if tokens[i].is_keyword("in")
+            && tokens.get(i + 1).is_some_and(|t| t.is_symbol("("))
+            && let Some(close) = closing(tokens, i + 1)
+            && !tokens[i + 2..close]
+                .first()
+                .is_some_and(|t| t.is_keyword("select"))
+        {
+            tokens[close] = symbol("]");
+            let replacement = if negated {
+                [symbol("<>"), word("all")]
+            } else {
+                [symbol("="), word("any")]
+            };
+            tokens.insert(close + 1, symbol(")"));
+            tokens.splice(
+                i..i + 2,
+                replacement
+                    .into_iter()
+                    .chain([symbol("("), word("array"), symbol("[")]),
+            );
+            if negated {
+                tokens.remove(i - 1);
+                i -= 1;
+            }
+        } else if let Some(operator) = ["like", "ilike"]
+            .into_iter()
+            .find(|keyword| tokens[i].is_keyword(keyword))
+        {
+            let mut text = String::from(if negated { "!~~" } else { "~~" });
+            if operator == "ilike" {
+                text.push('*');
+            }
+            tokens[i] = symbol(&text);
+            if negated {
+                tokens.remove(i - 1);
+                i -= 1;
+            }
+        } else if tokens[i].is_keyword("between")
+            && i > 0
+            && tokens.get(i + 2).is_some_and(|t| t.is_keyword("and"))
+            && tokens.len() > i + 3
+        {
+            let subject = tokens[i - 1].clone();
+            let (low, high) = (tokens[i + 1].clone(), tokens[i + 3].clone());
+            tokens.splice(
+                i..i + 4,
+                [symbol(">="), low, word("and"), subject, symbol("<="), high],
+            );
+        }
+        i += 1;
+    }
+}
+
+/// Drops `::type` after a literal.
+fn strip_casts(tokens: &mut Vec<Token>) {
+    let mut i = 1;
+    while i < tokens.len() {
+        let literal = matches!(tokens[i - 1].kind, TokenKind::String | TokenKind::Number)
+            || ["null", "true", "false"]
+                .iter()
+                .any(|keyword| tokens[i - 1].is_keyword(keyword));
+        if !(literal && tokens[i].is_symbol("::")) {
+            i += 1;
+            continue;
+        }
+        let mut end = i + 1;
+        if tokens
+            .get(end)
+            .is_some_and(|t| matches!(t.kind, TokenKind::Word | TokenKind::Quoted))
+        {
+            end += 1;
+        }
+        if tokens.get(end).is_some_and(|t| t.is_symbol("."))
+            && tokens
+                .get(end + 1)
+                .is_some_and(|t| matches!(t.kind, TokenKind::Word | TokenKind::Quoted))
+        {
+            end += 2;
+        }
+        while tokens
+            .get(end)
+            .is_some_and(|t| t.kind == TokenKind::Word && TYPE_WORDS.contains(&t.text.as_str()))
+        {
+            end += 1;
+        }
+        if tokens.get(end).is_some_and(|t| t.is_symbol("("))
+            && let Some(close) = closing(tokens, end)
+        {
+            end = close + 1;
+        }
+        while tokens.get(end).is_some_and(|t| t.is_symbol("["))
+            && tokens.get(end + 1).is_some_and(|t| t.is_symbol("]"))
+        {
+            end += 2;
+        }
+        tokens.drain(i..end);
+    }
+}
+
+/// Drops parentheses around a single token, doubled ones, and ones between
+/// words that end and start expressions as long as the operators inside
+/// bind tighter than the ones outside.
+fn strip_parens(tokens: &mut Vec<Token>) {
+    let mut open = 0;
+    while open < tokens.len() {
+        if tokens[open].is_symbol("(")
+            && let Some(close) = closing(tokens, open)
+            && redundant(tokens, open, close)
+        {
+            tokens.remove(close);
+            tokens.remove(open);
+        } else {
+            open += 1;
+        }
+    }
+}
+
+fn redundant(tokens: &[Token], open: usize, close: usize) -> bool {
+    let inner = &tokens[open + 1..close];
+    if inner.is_empty() {
+        return false;
+    }
+    if inner[0].is_symbol("(") && closing(tokens, open + 1) == Some(close - 1) {
+        // `IN ((1, 2))` compares with a row, not a list.
+        return !top_level(&inner[1..inner.len() - 1]).any(|t| t.is_symbol(","));
+    }
+    let before = open.checked_sub(1).map(|i| &tokens[i]);
+    let after = tokens.get(close + 1);
+    let opens = |token: Option<&Token>| match token {
+        None => true,
+        Some(t) => t.is_symbol("(") || t.is_symbol(",") || BEFORE.iter().any(|k| t.is_keyword(k)),
+    };
+    if inner.len() == 1 {
+        // Not the arguments of a call or a type.
+        return !before.is_some_and(|t| {
+            matches!(t.kind, TokenKind::Word | TokenKind::Quoted) && !opens(Some(t))
+        });
+    }
+    let closes = |token: Option<&Token>| match token {
+        None => true,
+        Some(t) => {
+            t.is_symbol(")")
+                || t.is_symbol(",")
+                || t.is_symbol(";")
+                || AFTER.iter().any(|k| t.is_keyword(k))
+        }
+    };
+    if !opens(before) || !closes(after) {
+        return false;
+    }
+    let mut top = top_level(inner);
+    let (mut or, mut and) = (false, false);
+    for token in top.by_ref() {
+        if token.is_symbol(",") || token.is_keyword("select") || token.is_keyword("values") {
+            return false;
+        }
+        or |= token.is_keyword("or");
+        and |= token.is_keyword("and");
+    }
+    let next_to = |keyword: &str| {
+        before.is_some_and(|t| t.is_keyword(keyword))
+            || after.is_some_and(|t| t.is_keyword(keyword))
+    };
+    if before.is_some_and(|t| t.is_keyword("not")) {
+        !or && !and
+    } else if next_to("and") {
+        !or
+    } else {
+        true
+    }
+}
+
+/// Drops `public.` and the table names in front of columns in queries,
+/// which the catalog adds to every column it prints.
+fn strip_qualifiers(tokens: &mut Vec<Token>) {
+    let names = |t: &Token| matches!(t.kind, TokenKind::Word | TokenKind::Quoted);
+    let mut relations: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i].is_keyword("from") || tokens[i].is_keyword("join") {
+            let mut j = i + 1;
+            while tokens.get(j).is_some_and(names)
+                && tokens.get(j + 1).is_some_and(|t| t.is_symbol("."))
+            {
+                j += 2;
+            }
+            let Some(relation) = tokens.get(j).filter(|t| names(t)) else {
+                i += 1;
+                continue;
+            };
+            relations.push(relation.text.clone());
+            j += 1;
+            if tokens.get(j).is_some_and(|t| t.is_keyword("as")) {
+                j += 1;
+            }
+            if let Some(alias) = tokens.get(j).filter(|t| {
+                names(t) && !keywords::is_reserved(&t.text) && !AFTER.contains(&t.text.as_str())
+            }) {
+                relations.push(alias.text.clone());
+            }
+        }
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i + 2 < tokens.len() {
+        let qualifier = names(&tokens[i])
+            && (tokens[i].text == DEFAULT_SCHEMA || relations.contains(&tokens[i].text))
+            && tokens[i + 1].is_symbol(".")
+            && (names(&tokens[i + 2]) || tokens[i + 2].is_symbol("*"))
+            && (i == 0 || !tokens[i - 1].is_symbol("."));
+        if qualifier {
+            tokens.drain(i..i + 2);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Drops the `AS count` the catalog names `count(*)` with, and `AS x`
+/// after a column `x`.
+fn strip_aliases(tokens: &mut Vec<Token>) {
+    let mut i = 1;
+    while i + 1 < tokens.len() {
+        let alias = &tokens[i + 1];
+        let column = tokens[i - 1].kind == alias.kind && tokens[i - 1].text == alias.text;
+        let call = tokens[i - 1].is_symbol(")")
+            && (0..i - 1)
+                .rev()
+                .find(|&open| tokens[open].is_symbol("(") && closing(tokens, open) == Some(i - 1))
+                .and_then(|open| open.checked_sub(1))
+                .is_some_and(|name| {
+                    tokens[name].kind == TokenKind::Word && tokens[name].text == alias.text
+                });
+        if tokens[i].is_keyword("as") && (column || call) {
+            tokens.drain(i..i + 2);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+fn render(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token.kind {
+            TokenKind::String => quote_literal(&token.text),
+            TokenKind::Quoted => format!("\"{}\"", token.text.replace('"', "\"\"")),
+            _ => token.text.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
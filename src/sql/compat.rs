@@ -0,0 +1,125 @@
+use std::fmt;
+
+use super::lexer::tokenize;
+use crate::model::{data_type, ConstraintKind, Feature, Index, Project, TriggerTiming};
+
+const MULTIRANGES: &[&str] = &[
+    "int4multirange",
+    "int8multirange",
+    "nummultirange",
+    "datemultirange",
+    "tsmultirange",
+    "tstzmultirange",
+];
+
+/// An object using a feature the target version doesn't have.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Incompatibility {
+    pub feature: Feature,
+    /// `schema.relation`, followed by the column, constraint, index or
+    /// trigger name when the feature is used by one.
+    pub object: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} requires PostgreSQL {}",
+            self.object,
+            self.feature,
+            self.feature.since()
+        )
+    }
+}
+
+/// Uses of features that aren't available in the project's target version.
+pub fn check(project: &Project) -> Vec<Incompatibility> {
+    let version = project.version;
+    let mut found = Vec::new();
+    let mut report = |feature: Feature, object: String| {
+        if !version.supports(feature) {
+            found.push(Incompatibility { feature, object });
+        }
+    };
+
+    for table in &project.tables {
+        let relation = format!("{}.{}", table.schema, table.name);
+        for column in &table.columns {
+            let object = || format!("{relation}.{}", column.name);
+            let base = data_type::base_name(&column.data_type).to_lowercase();
+            if MULTIRANGES.contains(&base.as_str()) {
+                report(Feature::Multiranges, object());
+            }
+        }
+        for constraint in &table.constraints {
+            if let ConstraintKind::Unique {
+                nulls_not_distinct: true,
+                ..
+            } = constraint.kind
+            {
+                report(
+                    Feature::NullsNotDistinct,
+                    format!("{relation}.{}", constraint.name),
+                );
+            }
+        }
+        indexes(&relation, &table.indexes, &mut report);
+        if table.partitioning.is_some() {
+            for trigger in &table.triggers {
+                if trigger.timing == TriggerTiming::Before && trigger.for_each_row {
+                    report(
+                        Feature::BeforeRowTriggersOnPartitions,
+                        format!("{relation}.{}", trigger.name),
+                    );
+                }
+            }
+        }
+    }
+
+    for view in &project.views {
+        let relation = format!("{}.{}", view.schema, view.name);
+        indexes(&relation, &view.indexes, &mut report);
+        for feature in statement_features(&view.definition) {
+            report(feature, relation.clone());
+        }
+    }
+
+    for routine in &project.routines {
+        for feature in statement_features(&routine.body) {
+            report(feature, format!("{}.{}", routine.schema, routine.name));
+        }
+    }
+
+    found
+}
+
+fn indexes(relation: &str, indexes: &[Index], report: &mut impl FnMut(Feature, String)) {
+    for index in indexes {
+        if index.unique && index.nulls_not_distinct {
+            report(
+                Feature::NullsNotDistinct,
+                format!("{relation}.{}", index.name),
+            );
+        }
+    }
+}
+
+/// Version dependent statements and functions used in a query or body.
+fn statement_features(sql: &str) -> Vec<Feature> {
+    let tokens = tokenize(sql);
+    let mut features = Vec::new();
+    for pair in tokens.windows(2) {
+        let feature = if pair[0].is_keyword("merge") && pair[1].is_keyword("into") {
+            Feature::Merge
+        } else if pair[0].is_keyword("json_table") && pair[1].is_symbol("(") {
+            Feature::JsonTable
+        } else {
+            continue;
+        };
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    }
+    features
+}
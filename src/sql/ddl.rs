@@ -0,0 +1,968 @@
+use std::fmt::Write;
+
+use crate::model::{
+    data_type, list_items, Column, Constraint, ConstraintKind, ExclusionElement, Feature, Grant,
+    Index, IndexElement, NullsOrder, Partition, PartitionBound, PgVersion, Policy, Privilege,
+    Project, Role, Routine, RoutineKind, Sequence, SortOrder, Table, Trigger, UserType,
+    UserTypeKind, View, DEFAULT_SCHEMA,
+};
+
+use super::{
+    compat, ident_list, lineage, qualified, quote_ident, quote_literal, with_naming, Qualify,
+    Quoting,
+};
+
+/// What goes into a generated script besides the objects themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// `IF NOT EXISTS` or `OR REPLACE` wherever PostgreSQL has them.
+    pub if_not_exists: bool,
+    /// Drops every object first, dependents before what they depend on.
+    pub drop: bool,
+    pub comments: bool,
+    pub quoting: Quoting,
+    pub qualify: Qualify,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            if_not_exists: false,
+            drop: false,
+            comments: true,
+            quoting: Quoting::default(),
+            qualify: Qualify::default(),
+        }
+    }
+}
+
+/// Generates the DDL for a whole project. Extensions come first, then roles.
+/// Types come before and views after the tables, both in dependency order.
+/// Sequences and functions are created before the tables so that defaults
+/// and triggers can use them. Foreign keys are added once every table exists
+/// so that the script does not depend on table order.
+///
+/// The script targets [`Project::version`]. Uses of features it lacks are
+/// listed up front, and NULLS NOT DISTINCT is left out rather than making
+/// the whole script fail.
+pub fn project(project: &Project) -> String {
+    script(project, &Options::default())
+}
+
+/// [`project`] with the given options.
+pub fn script(project: &Project, options: &Options) -> String {
+    with_naming(options.quoting, options.qualify, || {
+        generate(project, options)
+    })
+}
+
+fn generate(project: &Project, options: &Options) -> String {
+    let version = project.version;
+    let mut out = format!("-- Target: {version}\n\n");
+
+    let incompatibilities = compat::check(project);
+    if !incompatibilities.is_empty() {
+        for incompatibility in &incompatibilities {
+            writeln!(out, "-- WARNING: {incompatibility}").unwrap();
+        }
+        out.push('\n');
+    }
+
+    if options.drop {
+        for statement in drops(project) {
+            writeln!(out, "{statement}\n").unwrap();
+        }
+    }
+
+    let create = |statement: String| {
+        if options.if_not_exists {
+            if_not_exists(statement)
+        } else {
+            statement
+        }
+    };
+
+    for extension in &project.extensions {
+        writeln!(
+            out,
+            "CREATE EXTENSION IF NOT EXISTS {};\n",
+            quote_ident(extension.name())
+        )
+        .unwrap();
+    }
+
+    for role in &project.roles {
+        writeln!(out, "{}\n", create_role(role)).unwrap();
+    }
+    for role in &project.roles {
+        for parent in list_items(&role.member_of) {
+            writeln!(
+                out,
+                "GRANT {} TO {};\n",
+                role_name(parent),
+                role_name(&role.name)
+            )
+            .unwrap();
+        }
+    }
+
+    for schema in project.schemas() {
+        if schema != DEFAULT_SCHEMA {
+            writeln!(
+                out,
+                "CREATE SCHEMA IF NOT EXISTS {};\n",
+                quote_ident(schema)
+            )
+            .unwrap();
+        }
+    }
+
+    for i in project.sorted_types() {
+        writeln!(out, "{}\n", create_type(&project.types[i])).unwrap();
+    }
+
+    for sequence in &project.sequences {
+        writeln!(out, "{}\n", create(create_sequence(sequence))).unwrap();
+    }
+
+    for routine in &project.routines {
+        writeln!(out, "{}\n", create(create_routine(routine))).unwrap();
+    }
+
+    for table in &project.tables {
+        writeln!(out, "{}\n", create(create_table(table, version))).unwrap();
+        for partition in table.partitioning.iter().flat_map(|p| &p.partitions) {
+            writeln!(out, "{}\n", create(create_partition(table, partition))).unwrap();
+        }
+    }
+
+    for i in lineage::sorted_views(project) {
+        writeln!(out, "{}\n", create(create_view(&project.views[i]))).unwrap();
+    }
+
+    for table in &project.tables {
+        for constraint in foreign_keys(table) {
+            writeln!(out, "{}\n", add_constraint(table, constraint, version)).unwrap();
+        }
+    }
+
+    // Ownership needs the column, so it can only be set once tables exist.
+    for sequence in &project.sequences {
+        if let Some(owned_by) = sequence.owned_by.as_deref().and_then(owned_by) {
+            writeln!(
+                out,
+                "ALTER SEQUENCE {} OWNED BY {owned_by};\n",
+                qualified(&sequence.schema, &sequence.name)
+            )
+            .unwrap();
+        }
+    }
+
+    for table in &project.tables {
+        for index in &table.indexes {
+            writeln!(
+                out,
+                "{}\n",
+                create(create_index(&table.schema, &table.name, index, version))
+            )
+            .unwrap();
+        }
+    }
+
+    for view in &project.views {
+        for index in &view.indexes {
+            writeln!(
+                out,
+                "{}\n",
+                create(create_index(&view.schema, &view.name, index, version))
+            )
+            .unwrap();
+        }
+    }
+
+    for table in &project.tables {
+        for trigger in &table.triggers {
+            writeln!(out, "{}\n", create_trigger(table, trigger)).unwrap();
+        }
+    }
+
+    for table in &project.tables {
+        let security = security(table);
+        if !security.is_empty() {
+            writeln!(out, "{security}").unwrap();
+        }
+    }
+
+    if options.comments {
+        out.push_str(&project_comments(project));
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+/// `COMMENT ON` every object that has one.
+fn project_comments(project: &Project) -> String {
+    let mut out = String::new();
+    for role in &project.roles {
+        if let Some(comment) = &role.comment {
+            writeln!(
+                out,
+                "COMMENT ON ROLE {} IS {};\n",
+                role_name(&role.name),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+
+    for ty in &project.types {
+        if let Some(comment) = &ty.comment {
+            let object = match ty.kind {
+                UserTypeKind::Domain { .. } => "DOMAIN",
+                _ => "TYPE",
+            };
+            writeln!(
+                out,
+                "COMMENT ON {object} {} IS {};\n",
+                qualified(&ty.schema, &ty.name),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+
+    for view in &project.views {
+        if let Some(comment) = &view.comment {
+            writeln!(
+                out,
+                "COMMENT ON {} {} IS {};\n",
+                view.kind().to_uppercase(),
+                qualified(&view.schema, &view.name),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+
+    for sequence in &project.sequences {
+        if let Some(comment) = &sequence.comment {
+            writeln!(
+                out,
+                "COMMENT ON SEQUENCE {} IS {};\n",
+                qualified(&sequence.schema, &sequence.name),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+
+    for routine in &project.routines {
+        if let Some(comment) = &routine.comment {
+            writeln!(
+                out,
+                "COMMENT ON {} {} IS {};\n",
+                routine.kind.to_string().to_uppercase(),
+                signature(routine),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+
+    for table in &project.tables {
+        let comments = comments(table);
+        if !comments.is_empty() {
+            writeln!(out, "{comments}").unwrap();
+        }
+    }
+    out
+}
+
+/// `DROP ... IF EXISTS` for everything [`project`] creates except schemas,
+/// roles and extensions, which may well be shared with other projects.
+fn drops(project: &Project) -> Vec<String> {
+    let mut out = Vec::new();
+    for i in lineage::sorted_views(project).into_iter().rev() {
+        let view = &project.views[i];
+        out.push(format!(
+            "DROP {} IF EXISTS {} CASCADE;",
+            view.kind().to_uppercase(),
+            qualified(&view.schema, &view.name)
+        ));
+    }
+    // Partitions go with their parent.
+    for table in project.tables.iter().rev() {
+        out.push(format!(
+            "DROP TABLE IF EXISTS {} CASCADE;",
+            qualified(&table.schema, &table.name)
+        ));
+    }
+    for routine in &project.routines {
+        out.push(format!(
+            "DROP {} IF EXISTS {} CASCADE;",
+            routine.kind.to_string().to_uppercase(),
+            signature(routine)
+        ));
+    }
+    for sequence in &project.sequences {
+        out.push(format!(
+            "DROP SEQUENCE IF EXISTS {} CASCADE;",
+            qualified(&sequence.schema, &sequence.name)
+        ));
+    }
+    for i in project.sorted_types().into_iter().rev() {
+        let ty = &project.types[i];
+        let object = match ty.kind {
+            UserTypeKind::Domain { .. } => "DOMAIN",
+            _ => "TYPE",
+        };
+        out.push(format!(
+            "DROP {object} IF EXISTS {} CASCADE;",
+            qualified(&ty.schema, &ty.name)
+        ));
+    }
+    out
+}
+
+/// Makes a `CREATE` statement safe to run when the object exists. Types,
+/// triggers and policies have no such form and are left alone, as are
+/// indexes without a name.
+fn if_not_exists(statement: String) -> String {
+    const FORMS: [(&str, &str); 8] = [
+        ("CREATE TABLE ", "CREATE TABLE IF NOT EXISTS "),
+        ("CREATE SEQUENCE ", "CREATE SEQUENCE IF NOT EXISTS "),
+        ("CREATE INDEX ", "CREATE INDEX IF NOT EXISTS "),
+        ("CREATE UNIQUE INDEX ", "CREATE UNIQUE INDEX IF NOT EXISTS "),
+        (
+            "CREATE MATERIALIZED VIEW ",
+            "CREATE MATERIALIZED VIEW IF NOT EXISTS ",
+        ),
+        ("CREATE VIEW ", "CREATE OR REPLACE VIEW "),
+        ("CREATE FUNCTION ", "CREATE OR REPLACE FUNCTION "),
+        ("CREATE PROCEDURE ", "CREATE OR REPLACE PROCEDURE "),
+    ];
+    for (form, replacement) in FORMS {
+        if let Some(rest) = statement.strip_prefix(form) {
+            if rest.starts_with("ON ") {
+                break;
+            }
+            return format!("{replacement}{rest}");
+        }
+    }
+    statement
+}
+
+pub fn create_type(ty: &UserType) -> String {
+    let name = qualified(&ty.schema, &ty.name);
+    match &ty.kind {
+        UserTypeKind::Enum { labels } => {
+            let labels = labels
+                .iter()
+                .map(|label| format!("\n    {}", quote_literal(label)))
+                .collect::<Vec<_>>()
+                .join(",");
+            if labels.is_empty() {
+                format!("CREATE TYPE {name} AS ENUM ();")
+            } else {
+                format!("CREATE TYPE {name} AS ENUM ({labels}\n);")
+            }
+        }
+        UserTypeKind::Composite { attributes } => {
+            let attributes = attributes
+                .iter()
+                .filter(|a| !a.name.trim().is_empty())
+                .map(|a| {
+                    format!(
+                        "\n    {} {}",
+                        quote_ident(a.name.trim()),
+                        a.data_type.trim()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            if attributes.is_empty() {
+                format!("CREATE TYPE {name} AS ();")
+            } else {
+                format!("CREATE TYPE {name} AS ({attributes}\n);")
+            }
+        }
+        UserTypeKind::Domain {
+            data_type,
+            not_null,
+            default,
+            checks,
+        } => {
+            let mut out = format!("CREATE DOMAIN {name} AS {}", data_type.trim());
+            if let Some(default) = default.as_deref().filter(|d| !d.trim().is_empty()) {
+                write!(out, "\n    DEFAULT {}", default.trim()).unwrap();
+            }
+            if *not_null {
+                out.push_str("\n    NOT NULL");
+            }
+            for check in checks.iter().filter(|c| !c.expression.trim().is_empty()) {
+                out.push_str("\n    ");
+                if !check.name.trim().is_empty() {
+                    write!(out, "CONSTRAINT {} ", quote_ident(check.name.trim())).unwrap();
+                }
+                write!(out, "CHECK ({})", check.expression.trim()).unwrap();
+            }
+            out.push(';');
+            out
+        }
+    }
+}
+
+/// `CREATE TABLE` with every constraint inline except foreign keys.
+pub fn create_table(table: &Table, version: PgVersion) -> String {
+    let mut lines: Vec<String> = table.columns.iter().map(column_definition).collect();
+    for constraint in &table.constraints {
+        if !matches!(constraint.kind, ConstraintKind::ForeignKey(_)) {
+            lines.push(constraint_clause(constraint, version));
+        }
+    }
+
+    let mut out = format!("CREATE TABLE {} (", qualified(&table.schema, &table.name));
+    for (i, line) in lines.iter().enumerate() {
+        let sep = if i + 1 < lines.len() { "," } else { "" };
+        write!(out, "\n    {line}{sep}").unwrap();
+    }
+    if !lines.is_empty() {
+        out.push('\n');
+    }
+    out.push(')');
+    if let Some(partitioning) = &table.partitioning {
+        let key = list_items(&partitioning.key)
+            .map(key_expression)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(out, "\nPARTITION BY {} ({key})", partitioning.strategy).unwrap();
+    }
+    out.push(';');
+    out
+}
+
+/// A column as it appears in `CREATE TABLE` or `ALTER TABLE ... ADD COLUMN`.
+pub fn column_definition(column: &Column) -> String {
+    let mut out = format!("{} {}", quote_ident(&column.name), column.data_type);
+    if let Some(identity) = column.identity {
+        write!(out, " GENERATED {identity} AS IDENTITY").unwrap();
+    } else if let Some(expression) = column.generated.as_deref().filter(|e| !e.trim().is_empty()) {
+        write!(out, " GENERATED ALWAYS AS ({}) STORED", expression.trim()).unwrap();
+    } else if let Some(default) = column.default.as_deref().filter(|d| !d.trim().is_empty()) {
+        write!(out, " DEFAULT {}", default.trim()).unwrap();
+    }
+    if column.not_null {
+        out.push_str(" NOT NULL");
+    }
+    out
+}
+
+/// A partition is created in its parent's schema and inherits its columns.
+pub fn create_partition(table: &Table, partition: &Partition) -> String {
+    let bound = match partition.bound {
+        PartitionBound::Default => String::from("DEFAULT"),
+        ref bound => format!("FOR VALUES {bound}"),
+    };
+    format!(
+        "CREATE TABLE {} PARTITION OF {}\n    {bound};",
+        qualified(&table.schema, partition.name.trim()),
+        qualified(&table.schema, &table.name)
+    )
+}
+
+pub fn add_constraint(table: &Table, constraint: &Constraint, version: PgVersion) -> String {
+    format!(
+        "ALTER TABLE {}\n    ADD {};",
+        qualified(&table.schema, &table.name),
+        constraint_clause(constraint, version)
+    )
+}
+
+pub fn foreign_keys(table: &Table) -> impl Iterator<Item = &Constraint> {
+    table
+        .constraints
+        .iter()
+        .filter(|c| matches!(c.kind, ConstraintKind::ForeignKey(_)))
+}
+
+/// `[CONSTRAINT name] <definition>` as it appears in a table body or after
+/// `ALTER TABLE ... ADD`.
+pub fn constraint_clause(constraint: &Constraint, version: PgVersion) -> String {
+    let mut out = String::new();
+    if !constraint.name.trim().is_empty() {
+        write!(out, "CONSTRAINT {} ", quote_ident(constraint.name.trim())).unwrap();
+    }
+    out.push_str(&constraint_definition(constraint, version));
+    if constraint.deferrable && constraint.can_defer() {
+        out.push_str(" DEFERRABLE");
+        if constraint.initially_deferred {
+            out.push_str(" INITIALLY DEFERRED");
+        }
+    }
+    out
+}
+
+fn constraint_definition(constraint: &Constraint, version: PgVersion) -> String {
+    match &constraint.kind {
+        ConstraintKind::PrimaryKey { columns, include } => {
+            format!(
+                "PRIMARY KEY ({}){}",
+                ident_list(list_items(columns)),
+                include_clause(include)
+            )
+        }
+        ConstraintKind::Unique {
+            columns,
+            include,
+            nulls_not_distinct,
+        } => {
+            format!(
+                "UNIQUE {}({}){}",
+                if *nulls_not_distinct && version.supports(Feature::NullsNotDistinct) {
+                    "NULLS NOT DISTINCT "
+                } else {
+                    ""
+                },
+                ident_list(list_items(columns)),
+                include_clause(include)
+            )
+        }
+        ConstraintKind::Check {
+            expression,
+            no_inherit,
+        } => {
+            format!(
+                "CHECK ({}){}",
+                expression.trim(),
+                if *no_inherit { " NO INHERIT" } else { "" }
+            )
+        }
+        ConstraintKind::ForeignKey(fk) => {
+            let mut out = format!(
+                "FOREIGN KEY ({}) REFERENCES {}",
+                ident_list(list_items(&fk.columns)),
+                qualified(&fk.ref_schema, &fk.ref_table)
+            );
+            if list_items(&fk.ref_columns).next().is_some() {
+                write!(out, " ({})", ident_list(list_items(&fk.ref_columns))).unwrap();
+            }
+            if fk.on_update != Default::default() {
+                write!(out, " ON UPDATE {}", fk.on_update).unwrap();
+            }
+            if fk.on_delete != Default::default() {
+                write!(out, " ON DELETE {}", fk.on_delete).unwrap();
+            }
+            out
+        }
+        ConstraintKind::Exclusion {
+            method,
+            elements,
+            include,
+            predicate,
+        } => {
+            let elements = elements
+                .iter()
+                .filter(|e| !e.element.trim().is_empty())
+                .map(exclusion_element)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut out = format!(
+                "EXCLUDE USING {method} ({elements}){}",
+                include_clause(include)
+            );
+            if let Some(predicate) = predicate.as_deref().filter(|p| !p.trim().is_empty()) {
+                write!(out, " WHERE ({})", predicate.trim()).unwrap();
+            }
+            out
+        }
+    }
+}
+
+fn exclusion_element(element: &ExclusionElement) -> String {
+    format!(
+        "{} WITH {}",
+        key_expression(&element.element),
+        element.operator.trim()
+    )
+}
+
+fn include_clause(include: &[String]) -> String {
+    if list_items(include).next().is_none() {
+        String::new()
+    } else {
+        format!(" INCLUDE ({})", ident_list(list_items(include)))
+    }
+}
+
+pub fn create_view(view: &View) -> String {
+    let definition = view.definition.trim().trim_end_matches(';').trim_end();
+    let mut out = format!(
+        "CREATE {} {} AS\n{definition}",
+        view.kind().to_uppercase(),
+        qualified(&view.schema, &view.name)
+    );
+    if view.materialized && !view.with_data {
+        out.push_str("\nWITH NO DATA");
+    }
+    out.push(';');
+    out
+}
+
+pub fn create_sequence(sequence: &Sequence) -> String {
+    let mut out = format!(
+        "CREATE SEQUENCE {} AS {}",
+        qualified(&sequence.schema, &sequence.name),
+        sequence.data_type.trim()
+    );
+    if sequence.increment != 1 {
+        write!(out, "\n    INCREMENT BY {}", sequence.increment).unwrap();
+    }
+    if let Some(min_value) = sequence.min_value {
+        write!(out, "\n    MINVALUE {min_value}").unwrap();
+    }
+    if let Some(max_value) = sequence.max_value {
+        write!(out, "\n    MAXVALUE {max_value}").unwrap();
+    }
+    if let Some(start) = sequence.start {
+        write!(out, "\n    START WITH {start}").unwrap();
+    }
+    if sequence.cache != 1 {
+        write!(out, "\n    CACHE {}", sequence.cache).unwrap();
+    }
+    if sequence.cycle {
+        out.push_str("\n    CYCLE");
+    }
+    out.push(';');
+    out
+}
+
+/// Quotes a `[schema.]table.column` reference, if it has all the parts.
+pub fn owned_by(column: &str) -> Option<String> {
+    let (table, column) = column.trim().rsplit_once('.')?;
+    let (schema, table) = data_type::split_qualified(table);
+    let table = qualified(schema.unwrap_or(DEFAULT_SCHEMA), table);
+    Some(format!("{table}.{}", quote_ident(column.trim())))
+}
+
+pub fn create_routine(routine: &Routine) -> String {
+    let mut out = format!(
+        "CREATE {} {}",
+        routine.kind.to_string().to_uppercase(),
+        signature(routine)
+    );
+    if routine.kind == RoutineKind::Function {
+        let returns = routine.returns.trim();
+        write!(
+            out,
+            "\nRETURNS {}",
+            if returns.is_empty() { "void" } else { returns }
+        )
+        .unwrap();
+    }
+    write!(out, "\nLANGUAGE {}", routine.language.trim()).unwrap();
+    if routine.kind == RoutineKind::Function && routine.volatility != Default::default() {
+        write!(out, "\n{}", routine.volatility).unwrap();
+    }
+    let body = routine.body.trim();
+    let tag = dollar_tag(body);
+    write!(out, "\nAS {tag}\n{body}\n{tag};").unwrap();
+    out
+}
+
+/// `name(arguments)`, which is how routines are referred to in DDL.
+pub fn signature(routine: &Routine) -> String {
+    format!(
+        "{}({})",
+        qualified(&routine.schema, &routine.name),
+        routine.arguments.trim()
+    )
+}
+
+/// A dollar quote delimiter that does not occur in `body`.
+fn dollar_tag(body: &str) -> String {
+    let mut tag = String::from("$$");
+    let mut n = 0;
+    while body.contains(&tag) {
+        tag = if n == 0 {
+            String::from("$body$")
+        } else {
+            format!("$body{n}$")
+        };
+        n += 1;
+    }
+    tag
+}
+
+pub fn create_trigger(table: &Table, trigger: &Trigger) -> String {
+    let mut events = Vec::new();
+    if trigger.insert {
+        events.push(String::from("INSERT"));
+    }
+    if trigger.update {
+        if list_items(&trigger.update_columns).next().is_some() {
+            events.push(format!(
+                "UPDATE OF {}",
+                ident_list(list_items(&trigger.update_columns))
+            ));
+        } else {
+            events.push(String::from("UPDATE"));
+        }
+    }
+    if trigger.delete {
+        events.push(String::from("DELETE"));
+    }
+    // Row level triggers can't fire on TRUNCATE.
+    if trigger.truncate && !trigger.for_each_row {
+        events.push(String::from("TRUNCATE"));
+    }
+
+    let mut out = format!(
+        "CREATE TRIGGER {}\n    {} {} ON {}\n    FOR EACH {}",
+        quote_ident(trigger.name.trim()),
+        trigger.timing,
+        events.join(" OR "),
+        qualified(&table.schema, &table.name),
+        if trigger.for_each_row {
+            "ROW"
+        } else {
+            "STATEMENT"
+        }
+    );
+    if let Some(condition) = trigger
+        .condition
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        write!(out, "\n    WHEN ({})", condition.trim()).unwrap();
+    }
+    let function = match data_type::split_qualified(trigger.function.trim()) {
+        (Some(schema), name) => qualified(schema, name),
+        (None, name) => quote_ident(name).into_owned(),
+    };
+    write!(out, "\n    EXECUTE FUNCTION {function}();").unwrap();
+    out
+}
+
+/// `CREATE INDEX` on a table or materialized view.
+pub fn create_index(schema: &str, relation: &str, index: &Index, version: PgVersion) -> String {
+    let mut out = String::from("CREATE ");
+    if index.unique {
+        out.push_str("UNIQUE ");
+    }
+    out.push_str("INDEX ");
+    if !index.name.trim().is_empty() {
+        write!(out, "{} ", quote_ident(index.name.trim())).unwrap();
+    }
+    write!(out, "ON {}", qualified(schema, relation)).unwrap();
+    if !index.method.is_ordered() {
+        write!(out, " USING {}", index.method).unwrap();
+    }
+
+    let elements = index
+        .elements
+        .iter()
+        .filter(|e| !e.expression.trim().is_empty())
+        .map(|e| index_element(e, index.method.is_ordered()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    write!(out, " ({elements})").unwrap();
+
+    if index.method.supports_include() {
+        out.push_str(&include_clause(&index.include));
+    }
+    if index.unique && index.nulls_not_distinct && version.supports(Feature::NullsNotDistinct) {
+        out.push_str(" NULLS NOT DISTINCT");
+    }
+    if let Some(predicate) = index.predicate.as_deref().filter(|p| !p.trim().is_empty()) {
+        write!(out, " WHERE ({})", predicate.trim()).unwrap();
+    }
+    out.push(';');
+    out
+}
+
+fn index_element(element: &IndexElement, ordered: bool) -> String {
+    let mut out = key_expression(&element.expression);
+    if let Some(opclass) = element.opclass.as_deref().filter(|o| !o.trim().is_empty()) {
+        write!(out, " {}", opclass.trim()).unwrap();
+    }
+    if ordered {
+        if element.order == SortOrder::Desc {
+            out.push_str(" DESC");
+        }
+        match element.nulls {
+            NullsOrder::Default => {}
+            NullsOrder::First => out.push_str(" NULLS FIRST"),
+            NullsOrder::Last => out.push_str(" NULLS LAST"),
+        }
+    }
+    out
+}
+
+/// Columns are quoted as identifiers, anything else is an expression and has
+/// to be parenthesized.
+fn key_expression(expression: &str) -> String {
+    let expression = expression.trim();
+    if IndexElement::new(expression).is_column() {
+        if expression.starts_with('"') {
+            expression.to_string()
+        } else {
+            quote_ident(expression).into_owned()
+        }
+    } else {
+        format!("({expression})")
+    }
+}
+
+pub fn create_role(role: &Role) -> String {
+    format!(
+        "CREATE ROLE {}{};",
+        role_name(&role.name),
+        if role.login { " LOGIN" } else { "" }
+    )
+}
+
+/// `PUBLIC` is a keyword rather than a role, so it must not be quoted.
+pub fn role_name(name: &str) -> String {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case("public") {
+        String::from("PUBLIC")
+    } else {
+        quote_ident(name).into_owned()
+    }
+}
+
+/// Row level security switches, policies and privileges of a table, one
+/// statement per line. Revokes go before grants so that a grant can narrow
+/// down a revoke of everything.
+pub fn security(table: &Table) -> String {
+    let mut out = String::new();
+    let name = qualified(&table.schema, &table.name);
+    if table.row_security {
+        writeln!(out, "ALTER TABLE {name} ENABLE ROW LEVEL SECURITY;").unwrap();
+    }
+    if table.force_row_security {
+        writeln!(out, "ALTER TABLE {name} FORCE ROW LEVEL SECURITY;").unwrap();
+    }
+    for policy in &table.policies {
+        writeln!(out, "{}", create_policy(table, policy)).unwrap();
+    }
+    // In order, as a revoke only takes away what was granted before it.
+    for grant in &table.grants {
+        if let Some(statement) = grant_statement(table, grant) {
+            writeln!(out, "{statement}").unwrap();
+        }
+    }
+    out
+}
+
+pub fn create_policy(table: &Table, policy: &Policy) -> String {
+    let mut out = format!(
+        "CREATE POLICY {} ON {}",
+        quote_ident(policy.name.trim()),
+        qualified(&table.schema, &table.name)
+    );
+    if !policy.permissive {
+        out.push_str("\n    AS RESTRICTIVE");
+    }
+    if policy.command != Default::default() {
+        write!(out, "\n    FOR {}", policy.command).unwrap();
+    }
+    if list_items(&policy.roles).next().is_some() {
+        let roles = list_items(&policy.roles)
+            .map(role_name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(out, "\n    TO {roles}").unwrap();
+    }
+    if policy.command.has_using()
+        && let Some(using) = policy.using.as_deref().filter(|u| !u.trim().is_empty())
+    {
+        write!(out, "\n    USING ({})", using.trim()).unwrap();
+    }
+    if policy.command.has_with_check()
+        && let Some(check) = policy
+            .with_check
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+    {
+        write!(out, "\n    WITH CHECK ({})", check.trim()).unwrap();
+    }
+    out.push(';');
+    out
+}
+
+/// `GRANT` or `REVOKE` statement for `grant`, `None` if it grants nothing.
+pub fn grant_statement(table: &Table, grant: &Grant) -> Option<String> {
+    if grant.role.trim().is_empty() {
+        return None;
+    }
+    let columns = list_items(&grant.columns).next().is_some();
+    let mut privileges: Vec<Privilege> = grant
+        .privileges
+        .iter()
+        .copied()
+        .filter(|p| !columns || p.applies_to_columns())
+        .collect();
+    privileges.sort();
+    privileges.dedup();
+    if privileges.is_empty() {
+        return None;
+    }
+
+    let privileges = if columns {
+        let columns = ident_list(list_items(&grant.columns));
+        privileges
+            .iter()
+            .map(|p| format!("{p} ({columns})"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else if privileges.len() == Privilege::ALL.len() {
+        String::from("ALL")
+    } else {
+        privileges
+            .iter()
+            .map(Privilege::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let table = qualified(&table.schema, &table.name);
+    let role = role_name(&grant.role);
+    Some(if grant.revoke {
+        format!("REVOKE {privileges} ON {table} FROM {role};")
+    } else {
+        format!(
+            "GRANT {privileges} ON {table} TO {role}{};",
+            if grant.with_grant_option {
+                " WITH GRANT OPTION"
+            } else {
+                ""
+            }
+        )
+    })
+}
+
+pub fn comments(table: &Table) -> String {
+    let mut out = String::new();
+    let name = qualified(&table.schema, &table.name);
+    if let Some(comment) = &table.comment {
+        writeln!(
+            out,
+            "COMMENT ON TABLE {name} IS {};",
+            quote_literal(comment)
+        )
+        .unwrap();
+    }
+    for column in &table.columns {
+        if let Some(comment) = &column.comment {
+            writeln!(
+                out,
+                "COMMENT ON COLUMN {name}.{} IS {};",
+                quote_ident(&column.name),
+                quote_literal(comment)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
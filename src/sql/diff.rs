@@ -0,0 +1,1836 @@
+use std::{collections::BTreeSet, fmt, fmt::Write};
+
+use crate::model::{
+    data_type, list_items, object_name, Column, Constraint, ConstraintKind, DomainCheck, Extension,
+    Generation, Index, Partition, PgVersion, Policy, Privilege, Privileges, Project, Role, Routine,
+    RoutineKind, Sequence, Table, Trigger, UserType, UserTypeKind, View,
+};
+
+use super::{
+    canonical::canonical,
+    ddl,
+    lineage::{self, Source},
+    qualified, quote_ident, quote_literal,
+};
+
+/// Where a statement goes in a migration script. Everything is dropped
+/// before anything is created, dependents first, and creation follows the
+/// order of [`ddl::project`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    DropViews,
+    DropDependents,
+    /// Indexes and constraints other than foreign keys, which go before the
+    /// columns they use: dropping a column takes them along, after which
+    /// dropping them by name fails.
+    DropKeys,
+    DropMembers,
+    DropTables,
+    DropRoutines,
+    DropTypes,
+    DropGlobals,
+    Globals,
+    Types,
+    Routines,
+    Tables,
+    Constraints,
+    ForeignKeys,
+    Views,
+    Security,
+    Comments,
+}
+
+pub type Statement = (Phase, String);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Makes the database match the model.
+    #[default]
+    ToDatabase,
+    /// Makes a database built from the model match the live one.
+    ToModel,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::ToDatabase, Direction::ToModel];
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::ToDatabase => "Model → Database",
+            Direction::ToModel => "Database → Model",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    OnlyInModel,
+    OnlyInDatabase,
+    Changed,
+}
+
+/// A node of the difference tree. Tables found on both sides have their
+/// columns, constraints, privileges and so on as children.
+#[derive(Clone, Debug)]
+pub struct Difference {
+    pub object: &'static str,
+    pub name: String,
+    /// How the object reads on each side, as DDL.
+    pub model: Option<String>,
+    pub database: Option<String>,
+    pub include: bool,
+    pub children: Vec<Difference>,
+    to_database: Vec<Statement>,
+    to_model: Vec<Statement>,
+}
+
+impl Difference {
+    pub fn change(&self) -> Change {
+        match (&self.model, &self.database) {
+            (Some(_), None) => Change::OnlyInModel,
+            (None, Some(_)) => Change::OnlyInDatabase,
+            _ => Change::Changed,
+        }
+    }
+
+    /// Includes or excludes the node and everything below it.
+    pub fn set_include(&mut self, include: bool) {
+        self.include = include;
+        for child in &mut self.children {
+            child.set_include(include);
+        }
+    }
+
+    fn statements(&self, direction: Direction) -> &[Statement] {
+        match direction {
+            Direction::ToDatabase => &self.to_database,
+            Direction::ToModel => &self.to_model,
+        }
+    }
+}
+
+/// Compares the objects of `model` in `schemas` with those read from the
+/// database. Roles are shared by every database of a cluster, so the ones
+/// only found on the server start out excluded. So do extensions only found
+/// on the server, which the model may just not know about, so that dropping
+/// them takes opting in.
+pub fn compare<'a>(
+    model: &'a Project,
+    database: &'a Project,
+    schemas: &[String],
+) -> Vec<Difference> {
+    let in_scope = |schema: &str| schemas.iter().any(|s| s == schema);
+    // Scripts run against the server, whichever way they migrate.
+    let version = database.version;
+    let mut differences = Vec::new();
+
+    for (name, m, d) in matched(&model.extensions, &database.extensions, |e| {
+        e.name().to_string()
+    }) {
+        if let Some(mut difference) = item("extension", name, m, d, create_extension, |from, to| {
+            recreate(from, to, drop_extension, |e| {
+                vec![(Phase::Globals, create_extension(e))]
+            })
+        }) {
+            if difference.change() == Change::OnlyInDatabase {
+                difference.include = false;
+            }
+            differences.push(difference);
+        }
+    }
+
+    for (name, m, d) in matched(&model.roles, &database.roles, |r| r.name.clone()) {
+        if let Some(mut difference) = item("role", name, m, d, role_summary, migrate_role) {
+            if difference.change() == Change::OnlyInDatabase {
+                difference.include = false;
+            }
+            differences.push(difference);
+        }
+    }
+
+    // Types keep their project along, to find what uses them.
+    let types = |project: &'a Project| -> Vec<(&'a Project, &'a UserType)> {
+        project
+            .sorted_types()
+            .into_iter()
+            .map(|i| (project, &project.types[i]))
+            .filter(|(_, ty)| in_scope(&ty.schema))
+            .collect()
+    };
+    let (model_types, database_types) = (types(model), types(database));
+    for (name, m, d) in matched(&model_types, &database_types, |(_, ty)| {
+        format!("{}.{}", ty.schema, ty.name)
+    }) {
+        differences.extend(item(
+            "type",
+            name,
+            m,
+            d,
+            |(_, ty)| type_summary(ty),
+            migrate_type,
+        ));
+    }
+
+    let sequences = |project: &'a Project| -> Vec<&'a Sequence> {
+        project
+            .sequences
+            .iter()
+            .filter(|s| in_scope(&s.schema))
+            .collect()
+    };
+    for (name, m, d) in matched(sequences(model), sequences(database), |s| {
+        format!("{}.{}", s.schema, s.name)
+    }) {
+        differences.extend(item(
+            "sequence",
+            name,
+            m,
+            d,
+            sequence_summary,
+            migrate_sequence,
+        ));
+    }
+
+    let routines = |project: &'a Project| -> Vec<&'a Routine> {
+        project
+            .routines
+            .iter()
+            .filter(|r| in_scope(&r.schema))
+            .collect()
+    };
+    for (name, m, d) in matched(routines(model), routines(database), |r| {
+        format!("{}.{}", r.schema, r.name)
+    }) {
+        differences.extend(item(
+            routine_object(m.or(d)),
+            name,
+            m,
+            d,
+            routine_summary,
+            migrate_routine,
+        ));
+    }
+
+    // Views first, to know which ones a change of column types needs to
+    // drop and create again besides those changing anyway.
+    let views = |project: &'a Project| -> Vec<&'a View> {
+        project
+            .views
+            .iter()
+            .filter(|v| in_scope(&v.schema))
+            .collect()
+    };
+    let mut view_differences = Vec::new();
+    for (name, m, d) in matched(views(model), views(database), |v| {
+        format!("{}.{}", v.schema, v.name)
+    }) {
+        let object = m.or(d).map_or("view", |v| v.kind());
+        view_differences.extend(item(
+            object,
+            name,
+            m,
+            d,
+            |v| view_summary(v, version),
+            |from, to| recreate(from, to, drop_view, |v| create_view(v, version)),
+        ));
+    }
+    let changed_views: BTreeSet<&str> = view_differences
+        .iter()
+        .map(|difference: &Difference| difference.name.as_str())
+        .collect();
+
+    let tables = |project: &'a Project| -> Vec<&'a Table> {
+        project
+            .tables
+            .iter()
+            .filter(|t| in_scope(&t.schema))
+            .collect()
+    };
+    for (name, m, d) in matched(tables(model), tables(database), |t| {
+        format!("{}.{}", t.schema, t.name)
+    }) {
+        match (m, d) {
+            (Some(m), Some(d)) => {
+                let Some(mut difference) = compare_tables(name, m, d, version) else {
+                    continue;
+                };
+                if retyped(m, d) {
+                    difference.to_database.extend(rebuild_views(
+                        database,
+                        d,
+                        &changed_views,
+                        version,
+                    ));
+                    difference
+                        .to_model
+                        .extend(rebuild_views(model, m, &changed_views, version));
+                }
+                differences.push(difference);
+            }
+            _ => differences.extend(item(
+                "table",
+                name,
+                m,
+                d,
+                |t| table_summary(t, version),
+                |from, to| recreate(from, to, drop_table, |t| create_table(t, version)),
+            )),
+        }
+    }
+
+    differences.extend(view_differences);
+    differences
+}
+
+/// Statements of the included differences in execution order.
+pub fn statements(differences: &[Difference], direction: Direction) -> Vec<String> {
+    fn collect<'a>(
+        differences: &'a [Difference],
+        direction: Direction,
+        out: &mut Vec<&'a Statement>,
+    ) {
+        for difference in differences {
+            if difference.include {
+                out.extend(difference.statements(direction));
+            }
+            collect(&difference.children, direction, out);
+        }
+    }
+
+    let mut statements = Vec::new();
+    collect(differences, direction, &mut statements);
+    statements.sort_by_key(|(phase, _)| *phase);
+    // Views read from several retyped tables are rebuilt by each of them.
+    let mut seen = BTreeSet::new();
+    statements
+        .into_iter()
+        .filter(|statement| seen.insert(*statement))
+        .map(|(_, sql)| sql.clone())
+        .collect()
+}
+
+pub fn script(differences: &[Difference], direction: Direction) -> String {
+    let mut out = format!("-- Migration: {direction}\n\n");
+    for statement in statements(differences, direction) {
+        writeln!(out, "{statement}\n").unwrap();
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+/// Pairs up objects by `key`, in model order followed by the objects only
+/// found in the database.
+fn matched<'a, T: 'a>(
+    model: impl IntoIterator<Item = &'a T>,
+    database: impl IntoIterator<Item = &'a T>,
+    key: impl Fn(&T) -> String,
+) -> Vec<(String, Option<&'a T>, Option<&'a T>)> {
+    let database: Vec<&T> = database.into_iter().collect();
+    let mut pairs: Vec<(String, Option<&T>, Option<&T>)> = model
+        .into_iter()
+        .map(|m| {
+            let name = key(m);
+            let d = database.iter().copied().find(|d| key(d) == name);
+            (name, Some(m), d)
+        })
+        .collect();
+    for d in database {
+        let name = key(d);
+        if !pairs.iter().any(|(n, _, _)| *n == name) {
+            pairs.push((name, None, Some(d)));
+        }
+    }
+    pairs
+}
+
+/// A leaf of the tree, `None` when both sides read the same.
+fn item<T>(
+    object: &'static str,
+    name: String,
+    model: Option<&T>,
+    database: Option<&T>,
+    summary: impl Fn(&T) -> String,
+    migrate: impl Fn(Option<&T>, Option<&T>) -> Vec<Statement>,
+) -> Option<Difference> {
+    let model_summary = model.map(&summary);
+    let database_summary = database.map(&summary);
+    let same = match (&model_summary, &database_summary) {
+        (Some(m), Some(d)) => canonical(m) == canonical(d),
+        (None, None) => true,
+        _ => false,
+    };
+    (!same).then(|| Difference {
+        object,
+        name,
+        model: model_summary,
+        database: database_summary,
+        include: true,
+        children: Vec::new(),
+        to_database: migrate(database, model),
+        to_model: migrate(model, database),
+    })
+}
+
+/// Collapses whitespace, for SQL written into a script.
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Migrates by dropping what is there and creating what should be.
+fn recreate<T>(
+    from: Option<&T>,
+    to: Option<&T>,
+    drop: impl Fn(&T) -> Statement,
+    create: impl Fn(&T) -> Vec<Statement>,
+) -> Vec<Statement> {
+    from.map(drop)
+        .into_iter()
+        .chain(to.map(create).into_iter().flatten())
+        .collect()
+}
+
+fn comment(object: &str, name: &str, comment: Option<&String>) -> String {
+    format!(
+        "COMMENT ON {object} {name} IS {};",
+        comment.map_or(String::from("NULL"), |c| quote_literal(c))
+    )
+}
+
+fn create_extension(extension: &Extension) -> String {
+    format!(
+        "CREATE EXTENSION IF NOT EXISTS {};",
+        quote_ident(extension.name())
+    )
+}
+
+fn drop_extension(extension: &Extension) -> Statement {
+    (
+        Phase::DropGlobals,
+        format!("DROP EXTENSION {};", quote_ident(extension.name())),
+    )
+}
+
+fn role_summary(role: &Role) -> String {
+    let mut out = ddl::create_role(role);
+    for parent in list_items(&role.member_of) {
+        write!(
+            out,
+            "\nGRANT {} TO {};",
+            ddl::role_name(parent),
+            ddl::role_name(&role.name)
+        )
+        .unwrap();
+    }
+    if role.comment.is_some() {
+        out.push('\n');
+        out.push_str(&role_comment(role));
+    }
+    out
+}
+
+fn role_comment(role: &Role) -> String {
+    comment("ROLE", &ddl::role_name(&role.name), role.comment.as_ref())
+}
+
+fn migrate_role(from: Option<&Role>, to: Option<&Role>) -> Vec<Statement> {
+    let name = |role: &Role| ddl::role_name(&role.name);
+    let member_of = |role: Option<&Role>| -> BTreeSet<String> {
+        role.map(|role| list_items(&role.member_of).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    let mut out = Vec::new();
+    match (from, to) {
+        (Some(role), None) => {
+            out.push((Phase::DropGlobals, format!("DROP ROLE {};", name(role))));
+            return out;
+        }
+        (None, Some(role)) => {
+            out.push((Phase::Globals, ddl::create_role(role)));
+            if role.comment.is_some() {
+                out.push((Phase::Comments, role_comment(role)));
+            }
+        }
+        (Some(from), Some(to)) => {
+            if from.login != to.login {
+                out.push((
+                    Phase::Globals,
+                    format!(
+                        "ALTER ROLE {} {};",
+                        name(to),
+                        if to.login { "LOGIN" } else { "NOLOGIN" }
+                    ),
+                ));
+            }
+            if from.comment != to.comment {
+                out.push((Phase::Comments, role_comment(to)));
+            }
+        }
+        (None, None) => return out,
+    }
+    let (before, after) = (member_of(from), member_of(to));
+    let role = to.or(from).map(name).unwrap_or_default();
+    for parent in before.difference(&after) {
+        out.push((
+            Phase::DropDependents,
+            format!("REVOKE {} FROM {role};", ddl::role_name(parent)),
+        ));
+    }
+    for parent in after.difference(&before) {
+        out.push((
+            Phase::Security,
+            format!("GRANT {} TO {role};", ddl::role_name(parent)),
+        ));
+    }
+    out
+}
+
+fn type_object(ty: &UserType) -> &'static str {
+    match ty.kind {
+        UserTypeKind::Domain { .. } => "DOMAIN",
+        _ => "TYPE",
+    }
+}
+
+fn type_comment(ty: &UserType) -> String {
+    comment(
+        type_object(ty),
+        &qualified(&ty.schema, &ty.name),
+        ty.comment.as_ref(),
+    )
+}
+
+fn type_summary(ty: &UserType) -> String {
+    let mut out = ddl::create_type(ty);
+    if ty.comment.is_some() {
+        out.push('\n');
+        out.push_str(&type_comment(ty));
+    }
+    out
+}
+
+fn drop_type(ty: &UserType) -> Statement {
+    (
+        Phase::DropTypes,
+        format!(
+            "DROP {} {};",
+            type_object(ty),
+            qualified(&ty.schema, &ty.name)
+        ),
+    )
+}
+
+fn create_type(ty: &UserType) -> Vec<Statement> {
+    let mut out = vec![(Phase::Types, ddl::create_type(ty))];
+    if ty.comment.is_some() {
+        out.push((Phase::Comments, type_comment(ty)));
+    }
+    out
+}
+
+/// Enum labels can be added in place and domains altered unless their base
+/// type changes. An enum that loses or reorders labels is swapped for a new
+/// one under its columns. Anything else is dropped and created again, which
+/// only works while nothing uses the type.
+fn migrate_type(
+    from: Option<&(&Project, &UserType)>,
+    to: Option<&(&Project, &UserType)>,
+) -> Vec<Statement> {
+    let (Some(&(project, from)), Some(&(_, to))) = (from, to) else {
+        return recreate(
+            from.map(|(_, ty)| *ty),
+            to.map(|(_, ty)| *ty),
+            drop_type,
+            create_type,
+        );
+    };
+    let name = qualified(&to.schema, &to.name);
+    let not_migratable = |reason: &str| vec![(Phase::Types, format!("-- {name}: {reason}"))];
+    let mut out = match (&from.kind, &to.kind) {
+        (UserTypeKind::Enum { labels: before }, UserTypeKind::Enum { labels: after }) => {
+            match added_labels(before, after) {
+                Some(added) => added
+                    .into_iter()
+                    .map(|position| {
+                        (
+                            Phase::Types,
+                            format!("ALTER TYPE {name} ADD VALUE {position};"),
+                        )
+                    })
+                    .collect(),
+                None if types_using(project, from) => not_migratable(
+                    "other types use the enum, so its labels can't be dropped or reordered",
+                ),
+                None => return swap_enum(project, from, to),
+            }
+        }
+        (
+            UserTypeKind::Domain {
+                data_type: before, ..
+            },
+            UserTypeKind::Domain {
+                data_type: after, ..
+            },
+        ) if canonical(before) == canonical(after) => alter_domain(from, to),
+        _ if columns_of_type(project, from).is_empty() && !types_using(project, from) => {
+            return recreate(Some(from), Some(to), drop_type, create_type);
+        }
+        _ => not_migratable("the type is in use, so it can't be dropped and created again"),
+    };
+    if from.comment != to.comment {
+        out.push((Phase::Comments, type_comment(to)));
+    }
+    out
+}
+
+/// Default, `NOT NULL` and checks of a domain, changed in place.
+fn alter_domain(from: &UserType, to: &UserType) -> Vec<Statement> {
+    let (
+        UserTypeKind::Domain {
+            not_null: was_not_null,
+            default: old_default,
+            checks: old_checks,
+            ..
+        },
+        UserTypeKind::Domain {
+            not_null,
+            default,
+            checks,
+            ..
+        },
+    ) = (&from.kind, &to.kind)
+    else {
+        return Vec::new();
+    };
+    let name = qualified(&to.schema, &to.name);
+    let mut out = Vec::new();
+    let present = |default: &Option<String>| {
+        default
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(String::from)
+    };
+    let (old_default, default) = (present(old_default), present(default));
+    if old_default.as_deref().map(canonical) != default.as_deref().map(canonical) {
+        out.push(match default {
+            Some(default) => format!("ALTER DOMAIN {name} SET DEFAULT {};", normalize(&default)),
+            None => format!("ALTER DOMAIN {name} DROP DEFAULT;"),
+        });
+    }
+    if was_not_null != not_null {
+        out.push(format!(
+            "ALTER DOMAIN {name} {} NOT NULL;",
+            if *not_null { "SET" } else { "DROP" }
+        ));
+    }
+
+    let same = |a: &DomainCheck, b: &DomainCheck| {
+        a.name.trim() == b.name.trim() && canonical(&a.expression) == canonical(&b.expression)
+    };
+    let present = |check: &&DomainCheck| !check.expression.trim().is_empty();
+    for check in old_checks.iter().filter(present) {
+        if checks.iter().any(|c| same(c, check)) {
+            continue;
+        }
+        out.push(if check.name.trim().is_empty() {
+            format!("-- {name}: unnamed domain checks can't be dropped by name")
+        } else {
+            format!(
+                "ALTER DOMAIN {name} DROP CONSTRAINT {};",
+                quote_ident(check.name.trim())
+            )
+        });
+    }
+    for check in checks.iter().filter(present) {
+        if old_checks.iter().any(|c| same(c, check)) {
+            continue;
+        }
+        let mut add = format!("ALTER DOMAIN {name} ADD ");
+        if !check.name.trim().is_empty() {
+            write!(add, "CONSTRAINT {} ", quote_ident(check.name.trim())).unwrap();
+        }
+        write!(add, "CHECK ({});", normalize(&check.expression)).unwrap();
+        out.push(add);
+    }
+    out.into_iter().map(|sql| (Phase::Types, sql)).collect()
+}
+
+/// Replaces an enum with one whose labels differ by more than additions:
+/// the old type is renamed out of the way, its columns are cast to the new
+/// one through text, and then it is dropped. Rows holding a dropped label
+/// make the cast fail.
+fn swap_enum(project: &Project, from: &UserType, to: &UserType) -> Vec<Statement> {
+    let name = qualified(&to.schema, &to.name);
+    let old = format!("{}_old", to.name);
+    let mut out = vec![
+        format!("ALTER TYPE {name} RENAME TO {};", quote_ident(&old)),
+        ddl::create_type(to),
+    ];
+    for (table, column) in columns_of_type(project, from) {
+        let table = qualified(&table.schema, &table.name);
+        let column_name = quote_ident(column.name.trim());
+        let arrays = "[]".repeat(column.data_type.matches("[]").count());
+        let default = column
+            .default
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        // The default is of the old type and doesn't cast along.
+        if default.is_some() {
+            out.push(format!(
+                "ALTER TABLE {table} ALTER COLUMN {column_name} DROP DEFAULT;"
+            ));
+        }
+        out.push(format!(
+            "ALTER TABLE {table} ALTER COLUMN {column_name} TYPE {name}{arrays} \
+             USING {column_name}::text{arrays}::{name}{arrays};"
+        ));
+        if let Some(default) = default {
+            out.push(format!(
+                "ALTER TABLE {table} ALTER COLUMN {column_name} SET DEFAULT {};",
+                normalize(default)
+            ));
+        }
+    }
+    out.push(format!("DROP TYPE {};", qualified(&to.schema, &old)));
+    let mut out: Vec<Statement> = out.into_iter().map(|sql| (Phase::Types, sql)).collect();
+    if to.comment.is_some() {
+        out.push((Phase::Comments, type_comment(to)));
+    }
+    out
+}
+
+/// Table columns of `ty` or arrays of it.
+fn columns_of_type<'a>(project: &'a Project, ty: &UserType) -> Vec<(&'a Table, &'a Column)> {
+    let is_ty = |data_type: &str, schema: &str| {
+        project.resolve_type(data_type, schema).is_some_and(|i| {
+            project.types[i].schema == ty.schema && project.types[i].name == ty.name
+        })
+    };
+    project
+        .tables
+        .iter()
+        .flat_map(|table| {
+            table
+                .columns
+                .iter()
+                .filter(move |column| is_ty(&column.data_type, &table.schema))
+                .map(move |column| (table, column))
+        })
+        .collect()
+}
+
+/// Whether a composite type's attribute or a domain is of `ty`.
+fn types_using(project: &Project, ty: &UserType) -> bool {
+    project.types.iter().any(|other| {
+        let data_types: Vec<&str> = match &other.kind {
+            UserTypeKind::Enum { .. } => Vec::new(),
+            UserTypeKind::Composite { attributes } => {
+                attributes.iter().map(|a| a.data_type.as_str()).collect()
+            }
+            UserTypeKind::Domain { data_type, .. } => vec![data_type.as_str()],
+        };
+        data_types.into_iter().any(|data_type| {
+            project
+                .resolve_type(data_type, &other.schema)
+                .is_some_and(|i| {
+                    project.types[i].schema == ty.schema && project.types[i].name == ty.name
+                })
+        })
+    })
+}
+
+/// `'label' [BEFORE | AFTER 'other']` for each label `after` adds, or
+/// `None` if it also drops or reorders some.
+fn added_labels(before: &[String], after: &[String]) -> Option<Vec<String>> {
+    let kept: Vec<&String> = after.iter().filter(|l| before.contains(l)).collect();
+    if kept.len() != before.len() || kept.iter().zip(before).any(|(a, b)| *a != b) {
+        return None;
+    }
+    let mut added = Vec::new();
+    for (i, label) in after.iter().enumerate() {
+        if before.contains(label) {
+            continue;
+        }
+        let mut position = quote_literal(label);
+        if i > 0 {
+            write!(position, " AFTER {}", quote_literal(&after[i - 1])).unwrap();
+        } else if let Some(first) = kept.first() {
+            write!(position, " BEFORE {}", quote_literal(first)).unwrap();
+        }
+        added.push(position);
+    }
+    Some(added)
+}
+
+fn sequence_summary(sequence: &Sequence) -> String {
+    let mut out = ddl::create_sequence(sequence);
+    if let Some(owned_by) = sequence.owned_by.as_deref().and_then(ddl::owned_by) {
+        write!(out, "\nOWNED BY {owned_by}").unwrap();
+    }
+    if sequence.comment.is_some() {
+        out.push('\n');
+        out.push_str(&sequence_comment(sequence));
+    }
+    out
+}
+
+fn sequence_comment(sequence: &Sequence) -> String {
+    comment(
+        "SEQUENCE",
+        &qualified(&sequence.schema, &sequence.name),
+        sequence.comment.as_ref(),
+    )
+}
+
+fn migrate_sequence(from: Option<&Sequence>, to: Option<&Sequence>) -> Vec<Statement> {
+    let owned_by = |sequence: &Sequence| sequence.owned_by.as_deref().and_then(ddl::owned_by);
+    let mut out = Vec::new();
+    match (from, to) {
+        (Some(sequence), None) => {
+            out.push((
+                Phase::DropRoutines,
+                format!(
+                    "DROP SEQUENCE {};",
+                    qualified(&sequence.schema, &sequence.name)
+                ),
+            ));
+        }
+        (None, Some(sequence)) => {
+            out.push((Phase::Routines, ddl::create_sequence(sequence)));
+            if let Some(owned_by) = owned_by(sequence) {
+                out.push((
+                    Phase::Security,
+                    format!(
+                        "ALTER SEQUENCE {} OWNED BY {owned_by};",
+                        qualified(&sequence.schema, &sequence.name)
+                    ),
+                ));
+            }
+            if sequence.comment.is_some() {
+                out.push((Phase::Comments, sequence_comment(sequence)));
+            }
+        }
+        (Some(from), Some(to)) => {
+            let name = qualified(&to.schema, &to.name);
+            if canonical(&ddl::create_sequence(from)) != canonical(&ddl::create_sequence(to)) {
+                out.push((Phase::Routines, alter_sequence(to)));
+            }
+            if owned_by(from) != owned_by(to) {
+                out.push((
+                    Phase::Security,
+                    format!(
+                        "ALTER SEQUENCE {name} OWNED BY {};",
+                        owned_by(to).unwrap_or_else(|| String::from("NONE"))
+                    ),
+                ));
+            }
+            if from.comment != to.comment {
+                out.push((Phase::Comments, sequence_comment(to)));
+            }
+        }
+        (None, None) => {}
+    }
+    out
+}
+
+/// Resets every option, as unset ones don't fall back to their defaults
+/// in `ALTER SEQUENCE`.
+fn alter_sequence(sequence: &Sequence) -> String {
+    let mut out = format!(
+        "ALTER SEQUENCE {} AS {}\n    INCREMENT BY {}",
+        qualified(&sequence.schema, &sequence.name),
+        sequence.data_type.trim(),
+        sequence.increment
+    );
+    match sequence.min_value {
+        Some(min_value) => write!(out, "\n    MINVALUE {min_value}").unwrap(),
+        None => out.push_str("\n    NO MINVALUE"),
+    }
+    match sequence.max_value {
+        Some(max_value) => write!(out, "\n    MAXVALUE {max_value}").unwrap(),
+        None => out.push_str("\n    NO MAXVALUE"),
+    }
+    if let Some(start) = sequence.start {
+        write!(out, "\n    START WITH {start}").unwrap();
+    }
+    write!(
+        out,
+        "\n    CACHE {}\n    {};",
+        sequence.cache,
+        if sequence.cycle { "CYCLE" } else { "NO CYCLE" }
+    )
+    .unwrap();
+    out
+}
+
+fn routine_object(routine: Option<&Routine>) -> &'static str {
+    match routine.map(|r| r.kind) {
+        Some(RoutineKind::Procedure) => "procedure",
+        _ => "function",
+    }
+}
+
+fn routine_comment(routine: &Routine) -> String {
+    comment(
+        &routine.kind.to_string().to_uppercase(),
+        &ddl::signature(routine),
+        routine.comment.as_ref(),
+    )
+}
+
+fn routine_summary(routine: &Routine) -> String {
+    let mut out = ddl::create_routine(routine);
+    if routine.comment.is_some() {
+        out.push('\n');
+        out.push_str(&routine_comment(routine));
+    }
+    out
+}
+
+/// Routines keeping their signature are replaced in place, so that
+/// triggers and defaults using them survive.
+fn migrate_routine(from: Option<&Routine>, to: Option<&Routine>) -> Vec<Statement> {
+    if let (Some(from), Some(to)) = (from, to)
+        && from.kind == to.kind
+        && canonical(&from.arguments) == canonical(&to.arguments)
+        && canonical(&from.returns) == canonical(&to.returns)
+    {
+        let mut out = Vec::new();
+        let (before, after) = (ddl::create_routine(from), ddl::create_routine(to));
+        if canonical(&before) != canonical(&after) {
+            out.push((
+                Phase::Routines,
+                after.replacen("CREATE ", "CREATE OR REPLACE ", 1),
+            ));
+        }
+        if from.comment != to.comment {
+            out.push((Phase::Comments, routine_comment(to)));
+        }
+        return out;
+    }
+
+    recreate(
+        from,
+        to,
+        |routine| {
+            (
+                Phase::DropRoutines,
+                format!(
+                    "DROP {} {};",
+                    routine.kind.to_string().to_uppercase(),
+                    ddl::signature(routine)
+                ),
+            )
+        },
+        |routine| {
+            let mut out = vec![(Phase::Routines, ddl::create_routine(routine))];
+            if routine.comment.is_some() {
+                out.push((Phase::Comments, routine_comment(routine)));
+            }
+            out
+        },
+    )
+}
+
+fn view_summary(view: &View, version: PgVersion) -> String {
+    create_view(view, version)
+        .into_iter()
+        .map(|(_, sql)| sql)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn create_view(view: &View, version: PgVersion) -> Vec<Statement> {
+    let mut out = vec![(Phase::Views, ddl::create_view(view))];
+    for index in &view.indexes {
+        out.push((
+            Phase::Views,
+            ddl::create_index(&view.schema, &view.name, index, version),
+        ));
+    }
+    if view.comment.is_some() {
+        out.push((
+            Phase::Comments,
+            comment(
+                &view.kind().to_uppercase(),
+                &qualified(&view.schema, &view.name),
+                view.comment.as_ref(),
+            ),
+        ));
+    }
+    out
+}
+
+/// Whether a column in both tables changes its type.
+fn retyped(model: &Table, database: &Table) -> bool {
+    model.columns.iter().any(|m| {
+        database
+            .columns
+            .iter()
+            .any(|d| d.name == m.name && column_type(d) != column_type(m))
+    })
+}
+
+/// Drops the views reading from `table`, directly or through other views,
+/// and creates them again after its columns change type, which PostgreSQL
+/// refuses while views use them. Views in `changed` are dropped and created
+/// by their own differences already.
+fn rebuild_views(
+    project: &Project,
+    table: &Table,
+    changed: &BTreeSet<&str>,
+    version: PgVersion,
+) -> Vec<Statement> {
+    let order = lineage::sorted_views(project);
+    let mut dependent = vec![false; project.views.len()];
+    for &i in &order {
+        dependent[i] = lineage::sources(project, &project.views[i])
+            .into_iter()
+            .any(|source| match source {
+                Source::Table(t) => {
+                    let source = &project.tables[t];
+                    source.schema == table.schema && source.name == table.name
+                }
+                Source::View(v) => dependent[v],
+            });
+    }
+    let views: Vec<&View> = order
+        .into_iter()
+        .filter(|&i| dependent[i])
+        .map(|i| &project.views[i])
+        .filter(|view| !changed.contains(format!("{}.{}", view.schema, view.name).as_str()))
+        .collect();
+    views
+        .iter()
+        .rev()
+        .map(|view| drop_view(view))
+        .chain(views.iter().flat_map(|view| create_view(view, version)))
+        .collect()
+}
+
+fn drop_view(view: &View) -> Statement {
+    (
+        Phase::DropViews,
+        format!(
+            "DROP {} {};",
+            view.kind().to_uppercase(),
+            qualified(&view.schema, &view.name)
+        ),
+    )
+}
+
+fn table_summary(table: &Table, version: PgVersion) -> String {
+    create_table(table, version)
+        .into_iter()
+        .map(|(_, sql)| sql.trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn create_table(table: &Table, version: PgVersion) -> Vec<Statement> {
+    let mut out = vec![(Phase::Tables, ddl::create_table(table, version))];
+    for partition in table.partitioning.iter().flat_map(|p| &p.partitions) {
+        out.push((Phase::Tables, ddl::create_partition(table, partition)));
+    }
+    for constraint in ddl::foreign_keys(table) {
+        out.push((
+            Phase::ForeignKeys,
+            ddl::add_constraint(table, constraint, version),
+        ));
+    }
+    for index in &table.indexes {
+        out.push((
+            Phase::Constraints,
+            ddl::create_index(&table.schema, &table.name, index, version),
+        ));
+    }
+    for trigger in &table.triggers {
+        out.push((Phase::Security, ddl::create_trigger(table, trigger)));
+    }
+    for line in ddl::security(table).lines() {
+        out.push((Phase::Security, line.to_string()));
+    }
+    for line in ddl::comments(table).lines() {
+        out.push((Phase::Comments, line.to_string()));
+    }
+    out
+}
+
+fn drop_table(table: &Table) -> Statement {
+    (
+        Phase::DropTables,
+        format!("DROP TABLE {};", qualified(&table.schema, &table.name)),
+    )
+}
+
+/// A table found on both sides, with a child for every member that differs.
+fn compare_tables<'a>(
+    name: String,
+    model: &'a Table,
+    database: &'a Table,
+    version: PgVersion,
+) -> Option<Difference> {
+    let mut children = Vec::new();
+
+    // The catalog marks primary key, serial and identity columns NOT NULL
+    // whether or not the DDL said so.
+    let columns = |table: &Table| -> Vec<Column> {
+        let primary_key: Vec<&str> = match table.primary_key().map(|pk| &pk.kind) {
+            Some(ConstraintKind::PrimaryKey { columns, .. }) => list_items(columns).collect(),
+            _ => Vec::new(),
+        };
+        table
+            .columns
+            .iter()
+            .map(|column| Column {
+                not_null: column.not_null
+                    || primary_key.contains(&column.name.as_str())
+                    || matches!(
+                        column.generation(),
+                        Generation::Serial
+                            | Generation::IdentityAlways
+                            | Generation::IdentityByDefault
+                    ),
+                ..column.clone()
+            })
+            .collect()
+    };
+    let (model_columns, database_columns) = (columns(model), columns(database));
+    for (column, m, d) in matched(&model_columns, &database_columns, |c| c.name.clone()) {
+        children.extend(item(
+            "column",
+            column,
+            m,
+            d,
+            |c| column_summary(model, c),
+            |from, to| migrate_column(model, from, to),
+        ));
+    }
+
+    for (constraint, m, d) in matched(&model.constraints, &database.constraints, |c| {
+        constraint_key(c)
+    }) {
+        children.extend(item(
+            "constraint",
+            constraint,
+            m,
+            d,
+            |c| ddl::constraint_clause(c, version),
+            |from, to| {
+                recreate(
+                    from,
+                    to,
+                    |c| drop_constraint(model, c),
+                    |c| {
+                        let phase = if matches!(c.kind, ConstraintKind::ForeignKey(_)) {
+                            Phase::ForeignKeys
+                        } else {
+                            Phase::Constraints
+                        };
+                        vec![(phase, ddl::add_constraint(model, c, version))]
+                    },
+                )
+            },
+        ));
+    }
+
+    for (index, m, d) in matched(&model.indexes, &database.indexes, |i| i.name.clone()) {
+        children.extend(item(
+            "index",
+            index,
+            m,
+            d,
+            |i| create_index(model, i, version),
+            |from, to| {
+                recreate(
+                    from,
+                    to,
+                    |i: &Index| {
+                        (
+                            Phase::DropKeys,
+                            format!("DROP INDEX {};", qualified(&model.schema, &i.name)),
+                        )
+                    },
+                    |i| vec![(Phase::Constraints, create_index(model, i, version))],
+                )
+            },
+        ));
+    }
+
+    let partitions = |table: &'a Table| -> Vec<&'a Partition> {
+        table
+            .partitioning
+            .iter()
+            .flat_map(|p| &p.partitions)
+            .collect()
+    };
+    for (partition, m, d) in matched(partitions(model), partitions(database), |p| p.name.clone()) {
+        children.extend(item(
+            "partition",
+            partition,
+            m,
+            d,
+            |p| ddl::create_partition(model, p),
+            |from, to| {
+                recreate(
+                    from,
+                    to,
+                    |p: &Partition| {
+                        (
+                            Phase::DropTables,
+                            format!("DROP TABLE {};", qualified(&model.schema, &p.name)),
+                        )
+                    },
+                    |p| vec![(Phase::Tables, ddl::create_partition(model, p))],
+                )
+            },
+        ));
+    }
+
+    for (trigger, m, d) in matched(&model.triggers, &database.triggers, |t| t.name.clone()) {
+        children.extend(item(
+            "trigger",
+            trigger,
+            m,
+            d,
+            |t| ddl::create_trigger(model, t),
+            |from, to| {
+                recreate(
+                    from,
+                    to,
+                    |t: &Trigger| {
+                        (
+                            Phase::DropDependents,
+                            format!(
+                                "DROP TRIGGER {} ON {};",
+                                quote_ident(&t.name),
+                                qualified(&model.schema, &model.name)
+                            ),
+                        )
+                    },
+                    |t| vec![(Phase::Security, ddl::create_trigger(model, t))],
+                )
+            },
+        ));
+    }
+
+    let row_security = |table: &Table| (table.row_security, table.force_row_security);
+    children.extend(item(
+        "row level security",
+        String::from("row level security"),
+        Some(&row_security(model)),
+        Some(&row_security(database)),
+        |&(enabled, forced)| {
+            format!(
+                "{} ROW LEVEL SECURITY\n{} ROW LEVEL SECURITY",
+                if enabled { "ENABLE" } else { "DISABLE" },
+                if forced { "FORCE" } else { "NO FORCE" }
+            )
+        },
+        |from, to| migrate_row_security(model, from, to),
+    ));
+
+    for (policy, m, d) in matched(&model.policies, &database.policies, |p| p.name.clone()) {
+        children.extend(item(
+            "policy",
+            policy,
+            m,
+            d,
+            |p| ddl::create_policy(model, p),
+            |from, to| {
+                recreate(
+                    from,
+                    to,
+                    |p: &Policy| {
+                        (
+                            Phase::DropDependents,
+                            format!(
+                                "DROP POLICY {} ON {};",
+                                quote_ident(&p.name),
+                                qualified(&model.schema, &model.name)
+                            ),
+                        )
+                    },
+                    |p| vec![(Phase::Security, ddl::create_policy(model, p))],
+                )
+            },
+        ));
+    }
+
+    let (model_grants, database_grants) = (privileges(model), privileges(database));
+    for (role, m, d) in matched(&model_grants, &database_grants, |(role, _)| role.clone()) {
+        let m = m.map(|(_, privileges)| privileges);
+        let d = d.map(|(_, privileges)| privileges);
+        children.extend(item(
+            "privileges",
+            format!("privileges of {role}"),
+            Some(m.unwrap_or(&BTreeSet::new())),
+            Some(d.unwrap_or(&BTreeSet::new())),
+            |p| {
+                migrate_privileges(model, &role, &BTreeSet::new(), p)
+                    .into_iter()
+                    .map(|(_, sql)| sql)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+            |from, to| migrate_privileges(model, &role, from.unwrap(), to.unwrap()),
+        ));
+    }
+
+    children.extend(item(
+        "comment",
+        String::from("comment"),
+        model.comment.as_ref(),
+        database.comment.as_ref(),
+        |c| comment("TABLE", &qualified(&model.schema, &model.name), Some(c)),
+        |_, to| {
+            vec![(
+                Phase::Comments,
+                comment("TABLE", &qualified(&model.schema, &model.name), to),
+            )]
+        },
+    ));
+
+    let partitioning = |table: &Table| {
+        table.partitioning.as_ref().map(|p| {
+            format!(
+                "PARTITION BY {} ({})",
+                p.strategy,
+                list_items(&p.key).collect::<Vec<_>>().join(", ")
+            )
+        })
+    };
+    let mut statements = Vec::new();
+    if partitioning(model) != partitioning(database) {
+        statements.push((
+            Phase::Tables,
+            format!(
+                "-- {}: partitioning can't be altered, recreate the table to change it",
+                qualified(&model.schema, &model.name)
+            ),
+        ));
+    }
+
+    if children.is_empty() && statements.is_empty() {
+        return None;
+    }
+    Some(Difference {
+        object: "table",
+        model: Some(partitioning(model).map_or(name.clone(), |p| format!("{name} {p}"))),
+        database: Some(partitioning(database).map_or(name.clone(), |p| format!("{name} {p}"))),
+        name,
+        include: true,
+        children,
+        to_database: statements.clone(),
+        to_model: statements,
+    })
+}
+
+fn column_summary(table: &Table, column: &Column) -> String {
+    let mut out = ddl::column_definition(column);
+    if column.comment.is_some() {
+        out.push('\n');
+        out.push_str(&column_comment(table, column));
+    }
+    out
+}
+
+fn column_comment(table: &Table, column: &Column) -> String {
+    comment(
+        "COLUMN",
+        &format!(
+            "{}.{}",
+            qualified(&table.schema, &table.name),
+            quote_ident(&column.name)
+        ),
+        column.comment.as_ref(),
+    )
+}
+
+/// The type of a column, with serial types as the integer type under them.
+fn column_type(column: &Column) -> String {
+    data_type::serial_base(&column.data_type)
+        .map(String::from)
+        .unwrap_or_else(|| normalize(&column.data_type))
+}
+
+fn migrate_column(table: &Table, from: Option<&Column>, to: Option<&Column>) -> Vec<Statement> {
+    let name = qualified(&table.schema, &table.name);
+    let add = |column: &Column| {
+        let mut out = vec![(
+            Phase::Tables,
+            format!(
+                "ALTER TABLE {name} ADD COLUMN {};",
+                ddl::column_definition(column)
+            ),
+        )];
+        if column.comment.is_some() {
+            out.push((Phase::Comments, column_comment(table, column)));
+        }
+        out
+    };
+    let drop = |column: &Column| {
+        (
+            Phase::DropMembers,
+            format!(
+                "ALTER TABLE {name} DROP COLUMN {};",
+                quote_ident(&column.name)
+            ),
+        )
+    };
+    let (Some(from), Some(to)) = (from, to) else {
+        return recreate(from, to, drop, add);
+    };
+
+    let expression = |column: &Column| column.generated.as_deref().map(canonical);
+    if expression(from) != expression(to) {
+        return recreate(Some(from), Some(to), drop, add);
+    }
+
+    let alter = format!("ALTER TABLE {name} ALTER COLUMN {}", quote_ident(&to.name));
+    let mut out = Vec::new();
+    if column_type(from) != column_type(to) {
+        out.push((Phase::Tables, format!("{alter} TYPE {};", column_type(to))));
+    }
+    let default = |column: &Column| {
+        column
+            .default
+            .as_deref()
+            .map(normalize)
+            .filter(|d| !d.is_empty())
+    };
+    match (from.identity, to.identity) {
+        (Some(_), None) => out.push((Phase::Tables, format!("{alter} DROP IDENTITY;"))),
+        (None, Some(identity)) => {
+            // An identity column can't have a default as well, and a serial
+            // one takes its numbering over from the sequence it had.
+            let serial = from.generation() == Generation::Serial;
+            if serial || default(from).is_some() {
+                out.push((Phase::Tables, format!("{alter} DROP DEFAULT;")));
+            }
+            if serial {
+                let sequence =
+                    object_name(&table.name, std::slice::from_ref(&to.name), "seq", |_| {
+                        false
+                    });
+                out.push((
+                    Phase::Tables,
+                    format!("DROP SEQUENCE {};", qualified(&table.schema, &sequence)),
+                ));
+            }
+            out.push((
+                Phase::Tables,
+                format!("{alter} ADD GENERATED {identity} AS IDENTITY;"),
+            ));
+            if serial {
+                out.push((
+                    Phase::Tables,
+                    format!(
+                        "SELECT setval(pg_get_serial_sequence({}, {}), max({})) FROM {name};",
+                        quote_literal(&name),
+                        quote_literal(&to.name),
+                        quote_ident(&to.name)
+                    ),
+                ));
+            }
+        }
+        (Some(before), Some(after)) if before != after => {
+            out.push((Phase::Tables, format!("{alter} SET GENERATED {after};")))
+        }
+        _ => {}
+    }
+    let same_default = default(from).map(|d| canonical(&d)) == default(to).map(|d| canonical(&d));
+    if to.identity.is_none() && to.generated.is_none() && !same_default {
+        out.push((
+            Phase::Tables,
+            match default(to) {
+                Some(default) => format!("{alter} SET DEFAULT {default};"),
+                None => format!("{alter} DROP DEFAULT;"),
+            },
+        ));
+    }
+    if from.not_null != to.not_null {
+        out.push((
+            Phase::Tables,
+            format!(
+                "{alter} {} NOT NULL;",
+                if to.not_null { "SET" } else { "DROP" }
+            ),
+        ));
+    }
+    if from.comment != to.comment {
+        out.push((Phase::Comments, column_comment(table, to)));
+    }
+    out
+}
+
+/// Constraints are matched by name, unnamed ones by definition.
+fn constraint_key(constraint: &Constraint) -> String {
+    if constraint.name.trim().is_empty() {
+        ddl::constraint_clause(constraint, Default::default())
+    } else {
+        constraint.name.trim().to_string()
+    }
+}
+
+fn drop_constraint(table: &Table, constraint: &Constraint) -> Statement {
+    let phase = if matches!(constraint.kind, ConstraintKind::ForeignKey(_)) {
+        Phase::DropDependents
+    } else {
+        Phase::DropKeys
+    };
+    let table = qualified(&table.schema, &table.name);
+    (
+        phase,
+        if constraint.name.trim().is_empty() {
+            format!("-- {table}: unnamed constraints can't be dropped by name")
+        } else {
+            format!(
+                "ALTER TABLE {table} DROP CONSTRAINT {};",
+                quote_ident(constraint.name.trim())
+            )
+        },
+    )
+}
+
+fn create_index(table: &Table, index: &Index, version: PgVersion) -> String {
+    ddl::create_index(&table.schema, &table.name, index, version)
+}
+
+fn migrate_row_security(
+    table: &Table,
+    from: Option<&(bool, bool)>,
+    to: Option<&(bool, bool)>,
+) -> Vec<Statement> {
+    let name = qualified(&table.schema, &table.name);
+    let (Some(&(enabled, forced)), Some(&(enable, force))) = (from, to) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    if enabled != enable {
+        out.push((
+            Phase::Security,
+            format!(
+                "ALTER TABLE {name} {} ROW LEVEL SECURITY;",
+                if enable { "ENABLE" } else { "DISABLE" }
+            ),
+        ));
+    }
+    if forced != force {
+        out.push((
+            Phase::Security,
+            format!(
+                "ALTER TABLE {name} {} ROW LEVEL SECURITY;",
+                if force { "FORCE" } else { "NO FORCE" }
+            ),
+        ));
+    }
+    out
+}
+
+/// What each role ends up being granted on `table`, with the grants and
+/// revokes applied in order.
+fn privileges(table: &Table) -> Vec<(String, Privileges)> {
+    let mut out: Vec<(String, Privileges)> = Vec::new();
+    for grant in &table.grants {
+        if grant.role.trim().is_empty() {
+            continue;
+        }
+        let role = ddl::role_name(&grant.role);
+        let set = match out.iter_mut().find(|(r, _)| *r == role) {
+            Some((_, set)) => set,
+            None => {
+                out.push((role, Privileges::new()));
+                &mut out.last_mut().unwrap().1
+            }
+        };
+        grant.apply(set);
+    }
+    out
+}
+
+fn migrate_privileges(
+    table: &Table,
+    role: &str,
+    from: &Privileges,
+    to: &Privileges,
+) -> Vec<Statement> {
+    let name = qualified(&table.schema, &table.name);
+    let mut out = Vec::new();
+    let revoked: Vec<_> = from.difference(to).collect();
+    if !revoked.is_empty() {
+        out.push((
+            Phase::DropDependents,
+            format!("REVOKE {} ON {name} FROM {role};", privilege_list(&revoked)),
+        ));
+    }
+    for grant_option in [false, true] {
+        let granted: Vec<_> = to
+            .difference(from)
+            .filter(|(_, _, option)| *option == grant_option)
+            .collect();
+        if !granted.is_empty() {
+            out.push((
+                Phase::Security,
+                format!(
+                    "GRANT {} ON {name} TO {role}{};",
+                    privilege_list(&granted),
+                    if grant_option {
+                        " WITH GRANT OPTION"
+                    } else {
+                        ""
+                    }
+                ),
+            ));
+        }
+    }
+    out
+}
+
+/// `SELECT, UPDATE (a, b)`: table privileges as they are, column ones
+/// grouped per privilege.
+fn privilege_list(privileges: &[&(Privilege, Option<String>, bool)]) -> String {
+    let mut out: Vec<String> = Vec::new();
+    for privilege in Privilege::ALL {
+        if privileges
+            .iter()
+            .any(|(p, column, _)| *p == privilege && column.is_none())
+        {
+            out.push(privilege.to_string());
+        }
+        let columns: BTreeSet<&str> = privileges
+            .iter()
+            .filter(|(p, _, _)| *p == privilege)
+            .filter_map(|(_, column, _)| column.as_deref())
+            .collect();
+        if !columns.is_empty() {
+            out.push(format!(
+                "{privilege} ({})",
+                super::ident_list(columns.into_iter())
+            ));
+        }
+    }
+    out.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::parse;
+
+    fn differences(model: &str, database: &str) -> Vec<Difference> {
+        let model = parse::script(model).project;
+        let database = parse::script(database).project;
+        compare(&model, &database, &[String::from("public")])
+    }
+
+    fn names(differences: &[Difference]) -> Vec<String> {
+        differences
+            .iter()
+            .flat_map(|d| {
+                std::iter::once(format!("{} {}", d.object, d.name)).chain(names(&d.children))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn catalog_forms_compare_equal() {
+        let model = "
+            CREATE TYPE mood AS ENUM ('happy', 'sad');
+            CREATE TABLE orders (
+                id bigint PRIMARY KEY,
+                status text NOT NULL DEFAULT 'new',
+                mood mood DEFAULT 'happy',
+                total numeric(10, 2) CONSTRAINT orders_total_check CHECK (total > 0),
+                doubled numeric GENERATED ALWAYS AS (total * 2) STORED,
+                due date DEFAULT now() + interval '7 days',
+                kind text CONSTRAINT orders_kind_check CHECK (kind IN ('a', 'b') AND kind != 'c'),
+                note text CONSTRAINT orders_note_check CHECK (note LIKE '%x%' OR note IS NULL),
+                tags text[] DEFAULT '{}',
+                amount integer DEFAULT -1
+                    CONSTRAINT orders_amount_check CHECK (amount BETWEEN -1 AND 10)
+            );
+            CREATE INDEX orders_status_idx ON orders (lower(status)) WHERE total > 100;
+            CREATE VIEW big_orders AS
+                SELECT o.id, count(*) FROM orders o INNER JOIN orders p ON p.id = o.id
+                WHERE o.total > 100 GROUP BY o.id;
+        ";
+        // As pg_dump prints it back.
+        let database = "
+            CREATE TYPE public.mood AS ENUM ('happy', 'sad');
+            CREATE TABLE public.orders (
+                id bigint NOT NULL,
+                status text DEFAULT 'new'::text NOT NULL,
+                mood public.mood DEFAULT 'happy'::public.mood,
+                total numeric(10,2),
+                doubled numeric GENERATED ALWAYS AS ((total * (2)::numeric)) STORED,
+                due date DEFAULT (now() + '7 days'::interval),
+                kind text,
+                note text,
+                tags text[] DEFAULT '{}'::text[],
+                amount integer DEFAULT '-1'::integer,
+                CONSTRAINT orders_total_check CHECK ((total > (0)::numeric)),
+                CONSTRAINT orders_kind_check CHECK (((kind = ANY (ARRAY['a'::text, 'b'::text])) AND (kind <> 'c'::text))),
+                CONSTRAINT orders_note_check CHECK (((note ~~ '%x%'::text) OR (note IS NULL))),
+                CONSTRAINT orders_amount_check CHECK (((amount >= '-1'::integer) AND (amount <= 10)))
+            );
+            ALTER TABLE ONLY public.orders ADD CONSTRAINT orders_pkey PRIMARY KEY (id);
+            CREATE INDEX orders_status_idx ON public.orders USING btree (lower(status)) WHERE (total > (100)::numeric);
+            CREATE VIEW public.big_orders AS
+             SELECT o.id,
+                count(*) AS count
+               FROM (public.orders o
+                 JOIN public.orders p ON ((p.id = o.id)))
+              WHERE (o.total > (100)::numeric)
+              GROUP BY o.id;
+        ";
+        assert_eq!(names(&differences(model, database)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn catalog_forms_keep_real_changes() {
+        let found = differences(
+            "CREATE TABLE t (a integer CONSTRAINT t_a_check CHECK ((a + 1) * 2 > 0));",
+            "CREATE TABLE t (a integer CONSTRAINT t_a_check CHECK ((a + (1 * 2)) > 0));",
+        );
+        assert_eq!(names(&found), ["table public.t", "constraint t_a_check"]);
+    }
+
+    #[test]
+    fn keys_are_dropped_before_their_columns() {
+        let found = differences(
+            "CREATE TABLE t (id integer);",
+            "CREATE TABLE t (id integer, tags text[] UNIQUE);
+             CREATE INDEX t_tags_idx ON t (tags);",
+        );
+        let statements = statements(&found, Direction::ToDatabase);
+        let position = |prefix: &str| statements.iter().position(|s| s.contains(prefix)).unwrap();
+        assert!(position("DROP INDEX") < position("DROP COLUMN"));
+        assert!(position("DROP CONSTRAINT") < position("DROP COLUMN"));
+    }
+
+    #[test]
+    fn extensions_only_in_the_database_are_kept_unless_included() {
+        let mut found = differences(
+            "CREATE EXTENSION citext;",
+            "CREATE EXTENSION pg_trgm; CREATE EXTENSION IF NOT EXISTS plpgsql;",
+        );
+        assert_eq!(names(&found), ["extension citext", "extension pg_trgm"]);
+        assert_eq!(
+            statements(&found, Direction::ToDatabase),
+            ["CREATE EXTENSION IF NOT EXISTS citext;"]
+        );
+
+        found[1].include = true;
+        assert_eq!(
+            statements(&found, Direction::ToDatabase),
+            [
+                "DROP EXTENSION pg_trgm;",
+                "CREATE EXTENSION IF NOT EXISTS citext;"
+            ]
+        );
+    }
+
+    #[test]
+    fn serial_columns_become_identity_columns() {
+        let found = differences(
+            "CREATE TABLE t (id integer GENERATED BY DEFAULT AS IDENTITY);",
+            "CREATE TABLE t (id serial);",
+        );
+        assert_eq!(
+            statements(&found, Direction::ToDatabase),
+            [
+                "ALTER TABLE public.t ALTER COLUMN id DROP DEFAULT;",
+                "DROP SEQUENCE public.t_id_seq;",
+                "ALTER TABLE public.t ALTER COLUMN id ADD GENERATED BY DEFAULT AS IDENTITY;",
+                "SELECT setval(pg_get_serial_sequence('public.t', 'id'), max(id)) FROM public.t;",
+            ]
+        );
+    }
+
+    #[test]
+    fn views_on_retyped_columns_are_dropped_and_created_around_it() {
+        let found = differences(
+            "CREATE TABLE t (a bigint, b text);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w AS SELECT a FROM v;
+             CREATE VIEW other AS SELECT 1 AS one;",
+            "CREATE TABLE t (a integer, b text);
+             CREATE VIEW v AS SELECT a FROM t;
+             CREATE VIEW w AS SELECT a FROM v;
+             CREATE VIEW other AS SELECT 1 AS one;",
+        );
+        assert_eq!(
+            statements(&found, Direction::ToDatabase),
+            [
+                "DROP VIEW public.w;",
+                "DROP VIEW public.v;",
+                "ALTER TABLE public.t ALTER COLUMN a TYPE bigint;",
+                "CREATE VIEW public.v AS\nSELECT a FROM t;",
+                "CREATE VIEW public.w AS\nSELECT a FROM v;",
+            ]
+        );
+    }
+
+    #[test]
+    fn domains_are_altered_in_place() {
+        let found = differences(
+            "CREATE DOMAIN price AS numeric NOT NULL DEFAULT 0
+                 CONSTRAINT price_positive CHECK (VALUE >= 0);
+             CREATE TABLE t (p price);",
+            "CREATE DOMAIN price AS numeric CONSTRAINT price_small CHECK (VALUE < 100);
+             CREATE TABLE t (p price);",
+        );
+        assert_eq!(
+            statements(&found, Direction::ToDatabase),
+            [
+                "ALTER DOMAIN public.price SET DEFAULT 0;",
+                "ALTER DOMAIN public.price SET NOT NULL;",
+                "ALTER DOMAIN public.price DROP CONSTRAINT price_small;",
+                "ALTER DOMAIN public.price ADD CONSTRAINT price_positive CHECK (VALUE >= 0);",
+            ]
+        );
+    }
+
+    #[test]
+    fn enums_losing_labels_are_swapped_under_their_columns() {
+        let found = differences(
+            "CREATE TYPE public.mood AS ENUM ('sad', 'happy');
+             CREATE TABLE t (m mood DEFAULT 'sad', ms mood[]);",
+            "CREATE TYPE public.mood AS ENUM ('happy', 'sad', 'meh');
+             CREATE TABLE t (m mood DEFAULT 'sad', ms mood[]);",
+        );
+        let statements = statements(&found, Direction::ToDatabase);
+        assert_eq!(statements[0], "ALTER TYPE public.mood RENAME TO mood_old;");
+        assert!(statements[1].starts_with("CREATE TYPE public.mood AS ENUM"));
+        assert_eq!(
+            statements[2..],
+            [
+                "ALTER TABLE public.t ALTER COLUMN m DROP DEFAULT;",
+                "ALTER TABLE public.t ALTER COLUMN m TYPE public.mood USING m::text::public.mood;",
+                "ALTER TABLE public.t ALTER COLUMN m SET DEFAULT 'sad';",
+                "ALTER TABLE public.t ALTER COLUMN ms TYPE public.mood[] USING ms::text[]::public.mood[];",
+                "DROP TYPE public.mood_old;",
+            ]
+        );
+    }
+
+    #[test]
+    fn types_in_use_are_not_dropped() {
+        let model = "CREATE TYPE pair AS (a integer, b integer);
+             CREATE TABLE t (p pair);";
+        let database = "CREATE TYPE pair AS (a integer);
+             CREATE TABLE t (p pair);";
+        let in_use = statements(&differences(model, database), Direction::ToDatabase);
+        assert_eq!(in_use.len(), 1);
+        assert!(in_use[0].starts_with("-- public.pair: "));
+
+        let unused = differences(
+            &model.replace("CREATE TABLE t (p pair);", ""),
+            &database.replace("CREATE TABLE t (p pair);", ""),
+        );
+        assert!(statements(&unused, Direction::ToDatabase)[0].starts_with("DROP TYPE public.pair"));
+    }
+}
@@ -0,0 +1,304 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Unquoted identifier or keyword.
+    Word,
+    /// `"quoted identifier"`, unescaped.
+    Quoted,
+    /// String constant in any of its forms, including `$$dollar quoted$$`,
+    /// unescaped.
+    String,
+    Number,
+    /// `$1` style positional parameter.
+    Param,
+    /// Punctuation or operator.
+    Symbol,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    /// Byte range of the token in the source.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    pub fn is_symbol(&self, symbol: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == symbol
+    }
+
+    /// The identifier this token names, folded to lower case unless quoted.
+    pub fn ident(&self) -> Option<String> {
+        match self.kind {
+            TokenKind::Word => Some(self.text.to_lowercase()),
+            TokenKind::Quoted => Some(self.text.clone()),
+            _ => None,
+        }
+    }
+}
+
+const OPERATOR_CHARS: &str = "+-*/<>=~!@#%^&|`?";
+
+/// Splits SQL into tokens, dropping whitespace and comments. Unterminated
+/// strings and comments run to the end of the input rather than failing.
+pub fn tokenize(sql: &str) -> Vec<Token> {
+    Tokens { sql, i: 0 }.collect()
+}
+
+/// Splits a script into its statements at top level semicolons, trimmed and
+/// without the semicolon. Comments between statements are dropped. psql
+/// meta-commands such as `\connect` end at the end of their line, and the
+/// data following `COPY ... FROM stdin` is skipped.
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut tokens = Tokens { sql, i: 0 };
+    let mut start = None;
+    let mut previous: Option<Token> = None;
+    // `BEGIN ATOMIC` bodies contain semicolons of their own, and `CASE`
+    // shares their `END`.
+    let mut atomic = 0;
+
+    while let Some(token) = tokens.next() {
+        let begin = *start.get_or_insert(token.start);
+        if token.is_symbol("\\") && begin == token.start {
+            let end = sql[begin..].find('\n').map_or(sql.len(), |n| begin + n);
+            statements.push(sql[begin..end].trim());
+            tokens.i = end;
+            start = None;
+            previous = None;
+            continue;
+        }
+
+        let opens_atomic =
+            token.is_keyword("atomic") && previous.as_ref().is_some_and(|p| p.is_keyword("begin"));
+        if opens_atomic || (atomic > 0 && token.is_keyword("case")) {
+            atomic += 1;
+        } else if atomic > 0 && token.is_keyword("end") {
+            atomic -= 1;
+        } else if atomic == 0 && token.is_symbol(";") {
+            let statement = sql[begin..token.start].trim();
+            if !statement.is_empty() {
+                statements.push(statement);
+            }
+            start = None;
+            let lower = statement.to_lowercase();
+            if lower.starts_with("copy") && lower.ends_with("from stdin") {
+                // The data ends with a line holding only `\.`.
+                let data = sql[token.end..]
+                    .find('\n')
+                    .map_or(sql.len(), |n| token.end + n + 1);
+                tokens.i = if sql[data..].starts_with("\\.") {
+                    data + 2
+                } else {
+                    sql[data..]
+                        .find("\n\\.")
+                        .map_or(sql.len(), |n| data + n + 3)
+                };
+            }
+        }
+        previous = Some(token);
+    }
+
+    if let Some(begin) = start {
+        statements.push(sql[begin..].trim());
+    }
+    statements
+}
+
+struct Tokens<'a> {
+    sql: &'a str,
+    i: usize,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let sql = self.sql;
+        let bytes = sql.as_bytes();
+
+        while self.i < bytes.len() {
+            let i = self.i;
+            let c = sql[i..].chars().next().unwrap_or_default();
+            let start = i;
+
+            let (kind, text, end) = if c.is_whitespace() {
+                self.i += c.len_utf8();
+                continue;
+            } else if sql[i..].starts_with("--") {
+                self.i = sql[i..].find('\n').map_or(sql.len(), |n| i + n + 1);
+                continue;
+            } else if sql[i..].starts_with("/*") {
+                self.i = block_comment_end(sql, i);
+                continue;
+            } else if c == '\'' {
+                let (text, end) = quoted(sql, i, '\'', false);
+                (TokenKind::String, text, end)
+            } else if (c == 'e' || c == 'E') && bytes.get(i + 1) == Some(&b'\'') {
+                let (text, end) = quoted(sql, i + 1, '\'', true);
+                (TokenKind::String, text, end)
+            } else if matches!(c, 'b' | 'B' | 'x' | 'X' | 'n' | 'N')
+                && bytes.get(i + 1) == Some(&b'\'')
+            {
+                let (text, end) = quoted(sql, i + 1, '\'', false);
+                (TokenKind::String, text, end)
+            } else if c == '"' {
+                let (text, end) = quoted(sql, i, '"', false);
+                (TokenKind::Quoted, text, end)
+            } else if c == '$' {
+                if let Some((text, end)) = dollar_quoted(sql, i) {
+                    (TokenKind::String, text, end)
+                } else {
+                    let end = i
+                        + 1
+                        + sql[i + 1..]
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or(sql.len() - i - 1);
+                    (TokenKind::Param, sql[start..end].to_string(), end)
+                }
+            } else if c.is_ascii_digit()
+                || (c == '.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+            {
+                let end = number_end(sql, i);
+                (TokenKind::Number, sql[start..end].to_string(), end)
+            } else if c.is_alphabetic() || c == '_' {
+                let end = i + sql[i..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                    .unwrap_or(sql.len() - i);
+                (TokenKind::Word, sql[start..end].to_string(), end)
+            } else if OPERATOR_CHARS.contains(c) {
+                let mut end = i;
+                while let Some(next) = sql[end..].chars().next() {
+                    let rest = &sql[end..];
+                    if !OPERATOR_CHARS.contains(next)
+                        || (end > i && (rest.starts_with("--") || rest.starts_with("/*")))
+                    {
+                        break;
+                    }
+                    end += next.len_utf8();
+                }
+                (TokenKind::Symbol, sql[start..end].to_string(), end)
+            } else if sql[i..].starts_with("::") || sql[i..].starts_with(":=") {
+                (TokenKind::Symbol, sql[i..i + 2].to_string(), i + 2)
+            } else {
+                let end = i + c.len_utf8();
+                (TokenKind::Symbol, c.to_string(), end)
+            };
+
+            self.i = end;
+            return Some(token(kind, text, start, end));
+        }
+        None
+    }
+}
+
+fn token(kind: TokenKind, text: String, start: usize, end: usize) -> Token {
+    Token {
+        kind,
+        text,
+        start,
+        end,
+    }
+}
+
+fn block_comment_end(sql: &str, start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < sql.len() {
+        if sql[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if sql[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += sql[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    sql.len()
+}
+
+/// Reads a `'...'` or `"..."` token starting at `start`, where doubling the
+/// quote escapes it. With `backslash`, C-style escapes are honoured as in
+/// `E'...'` strings.
+fn quoted(sql: &str, start: usize, quote: char, backslash: bool) -> (String, usize) {
+    let mut text = String::new();
+    let mut chars = sql[start + 1..].char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c == quote {
+            if chars.peek().is_some_and(|(_, next)| *next == quote) {
+                chars.next();
+                text.push(quote);
+            } else {
+                return (text, start + 1 + offset + 1);
+            }
+        } else if backslash && c == '\\' {
+            match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, 'r')) => text.push('\r'),
+                Some((_, other)) => text.push(other),
+                None => {}
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    (text, sql.len())
+}
+
+fn dollar_quoted(sql: &str, start: usize) -> Option<(String, usize)> {
+    let rest = &sql[start + 1..];
+    let tag_len = rest.find('$')?;
+    let tag = &rest[..tag_len];
+    if tag.chars().any(|c| !(c.is_alphanumeric() || c == '_'))
+        || tag.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let delimiter = &sql[start..start + tag_len + 2];
+    let body_start = start + delimiter.len();
+    match sql[body_start..].find(delimiter) {
+        Some(n) => Some((
+            sql[body_start..body_start + n].to_string(),
+            body_start + n + delimiter.len(),
+        )),
+        None => Some((sql[body_start..].to_string(), sql.len())),
+    }
+}
+
+fn number_end(sql: &str, start: usize) -> usize {
+    let bytes = sql.as_bytes();
+    let mut i = start;
+    while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_') {
+        i += 1;
+    }
+    // `1..2` is not a number followed by a fraction
+    if i < bytes.len() && bytes[i] == b'.' && bytes.get(i + 1) != Some(&b'.') {
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        let mut j = i + 1;
+        if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+            j += 1;
+        }
+        if j < bytes.len() && bytes[j].is_ascii_digit() {
+            i = j;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+    }
+    i
+}
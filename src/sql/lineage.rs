@@ -0,0 +1,177 @@
+use super::lexer::{tokenize, Token, TokenKind};
+use crate::model::{topo_sort, Project, View, DEFAULT_SCHEMA};
+
+/// A relation named in the `FROM` clause of a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relation {
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Table(usize),
+    View(usize),
+}
+
+/// Keywords that end a `FROM` list.
+const FROM_END: &[&str] = &[
+    "where",
+    "group",
+    "having",
+    "order",
+    "limit",
+    "offset",
+    "union",
+    "intersect",
+    "except",
+    "window",
+    "fetch",
+    "for",
+    "returning",
+    "on",
+    "using",
+];
+
+/// Relations a query reads from, in order of appearance and without
+/// duplicates. Common table expressions and set returning functions are not
+/// relations and are left out.
+pub fn relations(query: &str) -> Vec<Relation> {
+    let tokens = tokenize(query);
+    let ctes = cte_names(&tokens);
+    let mut relations: Vec<Relation> = Vec::new();
+
+    // Parenthesis depth of every FROM list currently open.
+    let mut from_depths: Vec<i32> = Vec::new();
+    let mut depth = 0;
+    let mut expect_relation = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token.is_symbol("(") {
+            depth += 1;
+            expect_relation = false;
+        } else if token.is_symbol(")") {
+            depth -= 1;
+            while from_depths.last().is_some_and(|d| *d > depth) {
+                from_depths.pop();
+            }
+        } else if token.is_keyword("from") || token.is_keyword("join") {
+            if token.is_keyword("from") {
+                from_depths.push(depth);
+            }
+            expect_relation = true;
+        } else if token.is_symbol(",") && from_depths.last() == Some(&depth) {
+            expect_relation = true;
+        } else if FROM_END.iter().any(|k| token.is_keyword(k)) {
+            let ends_list = !token.is_keyword("on") && !token.is_keyword("using");
+            if ends_list && from_depths.last() == Some(&depth) {
+                from_depths.pop();
+            }
+            expect_relation = false;
+        } else if expect_relation {
+            if token.is_keyword("lateral") || token.is_keyword("only") {
+                i += 1;
+                continue;
+            }
+            expect_relation = false;
+            if let Some((relation, next)) = qualified_name(&tokens, i) {
+                let is_function = tokens.get(next).is_some_and(|t| t.is_symbol("("));
+                let is_cte = relation.schema.is_none() && ctes.contains(&relation.name);
+                if !is_function && !is_cte && !relations.contains(&relation) {
+                    relations.push(relation);
+                }
+                i = next;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    relations
+}
+
+/// Resolves the relations a view reads from against the project, looking up
+/// unqualified names in the view's own schema first and then in `public`.
+pub fn sources(project: &Project, view: &View) -> Vec<Source> {
+    relations(&view.definition)
+        .iter()
+        .filter_map(|relation| resolve(project, relation, &view.schema))
+        .collect()
+}
+
+pub fn resolve(project: &Project, relation: &Relation, schema: &str) -> Option<Source> {
+    let find = |schema: &str| {
+        project
+            .tables
+            .iter()
+            .position(|t| t.schema == schema && t.name == relation.name)
+            .map(Source::Table)
+            .or_else(|| {
+                project
+                    .views
+                    .iter()
+                    .position(|v| v.schema == schema && v.name == relation.name)
+                    .map(Source::View)
+            })
+    };
+    match &relation.schema {
+        Some(schema) => find(schema),
+        None => find(schema).or_else(|| find(DEFAULT_SCHEMA)),
+    }
+}
+
+/// Indexes of [`Project::views`] ordered so that every view comes after the
+/// views it reads from.
+pub fn sorted_views(project: &Project) -> Vec<usize> {
+    topo_sort(project.views.len(), |i| {
+        sources(project, &project.views[i])
+            .into_iter()
+            .filter_map(|source| match source {
+                Source::View(dependency) => Some(dependency),
+                Source::Table(_) => None,
+            })
+            .collect()
+    })
+}
+
+fn qualified_name(tokens: &[Token], i: usize) -> Option<(Relation, usize)> {
+    let first = tokens.get(i).filter(|t| is_name(t))?.ident()?;
+    if tokens.get(i + 1).is_some_and(|t| t.is_symbol("."))
+        && let Some(second) = tokens.get(i + 2).filter(|t| is_name(t))
+    {
+        return Some((
+            Relation {
+                schema: Some(first),
+                name: second.ident()?,
+            },
+            i + 3,
+        ));
+    }
+    Some((
+        Relation {
+            schema: None,
+            name: first,
+        },
+        i + 1,
+    ))
+}
+
+fn is_name(token: &Token) -> bool {
+    match token.kind {
+        TokenKind::Quoted => true,
+        TokenKind::Word => !super::keywords::is_reserved(&token.text),
+        _ => false,
+    }
+}
+
+/// Names bound by `WITH name AS (...)`, which shadow relations of the same
+/// name.
+fn cte_names(tokens: &[Token]) -> Vec<String> {
+    tokens
+        .windows(3)
+        .filter(|w| is_name(&w[0]) && w[1].is_keyword("as") && w[2].is_symbol("("))
+        .filter_map(|w| w[0].ident())
+        .collect()
+}
@@ -0,0 +1,279 @@
+use std::fmt;
+
+use super::{
+    parse::{Parsed, Reader, Skipped},
+    qualified,
+};
+use crate::{
+    datetime::{date, time},
+    model::{Project, DEFAULT_SCHEMA},
+};
+
+/// Migration tools, each with its own file layout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tool {
+    #[default]
+    Sqlx,
+    Diesel,
+    Refinery,
+    Flyway,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Sqlx, Tool::Diesel, Tool::Refinery, Tool::Flyway];
+
+    /// The files it expects, with placeholders for what is only known once
+    /// they are written.
+    pub fn layout(self, down: bool) -> &'static str {
+        match (self, down) {
+            (Tool::Sqlx, true) => "{timestamp}_{name}.up.sql and {timestamp}_{name}.down.sql",
+            (Tool::Sqlx, false) => "{timestamp}_{name}.sql",
+            (Tool::Diesel, _) => "{timestamp}_{name}/up.sql and down.sql",
+            (Tool::Refinery, _) => "V{version}__{name}.sql",
+            (Tool::Flyway, true) => "V{version}__{name}.sql and U{version}__{name}.sql",
+            (Tool::Flyway, false) => "V{version}__{name}.sql",
+        }
+    }
+
+    /// Whether a down migration gets written when `wanted` or not. diesel
+    /// always has one and refinery never does.
+    pub fn down(self, wanted: bool) -> bool {
+        match self {
+            Tool::Diesel => true,
+            Tool::Refinery => false,
+            Tool::Sqlx | Tool::Flyway => wanted,
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tool::Sqlx => "sqlx",
+            Tool::Diesel => "diesel",
+            Tool::Refinery => "refinery",
+            Tool::Flyway => "Flyway",
+        })
+    }
+}
+
+/// The statements of one migration and of its revert.
+#[derive(Clone, Debug, Default)]
+pub struct Migration {
+    pub name: String,
+    pub up: Vec<String>,
+    pub down: Vec<String>,
+}
+
+impl Migration {
+    /// Paths relative to the migrations directory and their contents.
+    /// `existing` names the entries already in the directory, after which
+    /// refinery and Flyway versions continue, and `now` is seconds since
+    /// the epoch for the timestamps of sqlx and diesel.
+    pub fn files(
+        &self,
+        tool: Tool,
+        down: bool,
+        existing: &[String],
+        now: u64,
+    ) -> Vec<(String, String)> {
+        let down = tool.down(down);
+        let name = slug(&self.name);
+        let up = body(&self.up);
+        let down_body = body(&self.down);
+        let days = (now / 86_400) as i64;
+        let seconds = (now % 86_400) as u32;
+        match tool {
+            Tool::Sqlx => {
+                let timestamp = format!("{}{}", date(days), time(seconds)).replace([':', '-'], "");
+                if down {
+                    vec![
+                        (format!("{timestamp}_{name}.up.sql"), up),
+                        (format!("{timestamp}_{name}.down.sql"), down_body),
+                    ]
+                } else {
+                    vec![(format!("{timestamp}_{name}.sql"), up)]
+                }
+            }
+            Tool::Diesel => {
+                let folder = format!("{}-{}_{name}", date(days), time(seconds).replace(':', ""));
+                vec![
+                    (format!("{folder}/up.sql"), up),
+                    (format!("{folder}/down.sql"), down_body),
+                ]
+            }
+            Tool::Refinery => {
+                let version = next_version(existing);
+                vec![(format!("V{version}__{name}.sql"), up)]
+            }
+            Tool::Flyway => {
+                let version = next_version(existing);
+                let mut files = vec![(format!("V{version}__{name}.sql"), up)];
+                if down {
+                    files.push((format!("U{version}__{name}.sql"), down_body));
+                }
+                files
+            }
+        }
+    }
+}
+
+fn body(statements: &[String]) -> String {
+    statements.join("\n\n") + "\n"
+}
+
+/// Lower case words joined by underscores, as the tools name migrations.
+fn slug(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        String::from("migration")
+    } else {
+        words.join("_")
+    }
+}
+
+/// One more than the highest `V<n>__` or `U<n>__` version, whose first part
+/// counts for dotted versions like `V1.2__`.
+fn next_version(existing: &[String]) -> u64 {
+    existing
+        .iter()
+        .filter_map(|name| {
+            let rest = name.strip_prefix(['V', 'U', 'v', 'u'])?;
+            let (version, _) = rest.split_once("__")?;
+            version.split(['.', '_']).next()?.parse::<u64>().ok()
+        })
+        .max()
+        .map_or(1, |version| version + 1)
+}
+
+/// What replaying a migrations folder ends with, and what each migration
+/// added and dropped on the way.
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    pub parsed: Parsed,
+    pub timeline: Vec<Step>,
+}
+
+/// The objects one migration added and dropped, as `table orders` or
+/// `column orders.id`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Step {
+    pub migration: String,
+    pub added: Vec<String>,
+    pub dropped: Vec<String>,
+}
+
+/// Applies the up migrations among `files`, given by name and contents,
+/// in version order to an empty model. Down and undo migrations are left
+/// out.
+pub fn replay(files: &[(String, String)]) -> Replay {
+    let mut files: Vec<&(String, String)> = files
+        .iter()
+        .filter(|(name, _)| {
+            name.ends_with(".sql") && !name.ends_with(".down.sql") && !name.starts_with(['U', 'u'])
+        })
+        .collect();
+    files.sort_by(|(a, _), (b, _)| (version(a), a).cmp(&(version(b), b)));
+
+    let mut reader = Reader::default();
+    let mut timeline = Vec::with_capacity(files.len());
+    // Where the statements each migration skipped start.
+    let mut starts = Vec::with_capacity(files.len());
+    for (name, sql) in files {
+        let before = objects(reader.project());
+        starts.push((reader.skipped().len(), name));
+        reader.read(sql);
+        let after = objects(reader.project());
+        timeline.push(Step {
+            migration: name.clone(),
+            added: after
+                .iter()
+                .filter(|object| !before.contains(object))
+                .cloned()
+                .collect(),
+            dropped: before
+                .into_iter()
+                .filter(|object| !after.contains(object))
+                .collect(),
+        });
+    }
+
+    let mut parsed = reader.finish();
+    for (i, (start, name)) in starts.iter().enumerate().rev() {
+        let end = starts
+            .get(i + 1)
+            .map_or(parsed.skipped.len(), |(end, _)| *end);
+        for Skipped { reason, .. } in &mut parsed.skipped[*start..end] {
+            *reason = format!("{name}: {reason}");
+        }
+    }
+    Replay { parsed, timeline }
+}
+
+/// The version a migration name starts with, as in `20240101120000_users`
+/// or `V2__users`. Names without one go last.
+fn version(name: &str) -> u64 {
+    let digits: String = name
+        .strip_prefix(['V', 'v'])
+        .unwrap_or(name)
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().unwrap_or(u64::MAX)
+}
+
+/// Labels of the objects in `project` that the timeline follows.
+fn objects(project: &Project) -> Vec<String> {
+    let name = |schema: &str, name: &str| {
+        if schema == DEFAULT_SCHEMA {
+            qualified("", name)
+        } else {
+            qualified(schema, name)
+        }
+    };
+    let mut objects = Vec::new();
+    for extension in &project.extensions {
+        objects.push(format!("extension {}", extension.name()));
+    }
+    for ty in &project.types {
+        objects.push(format!("type {}", name(&ty.schema, &ty.name)));
+    }
+    for sequence in &project.sequences {
+        objects.push(format!(
+            "sequence {}",
+            name(&sequence.schema, &sequence.name)
+        ));
+    }
+    for table in &project.tables {
+        let table_name = name(&table.schema, &table.name);
+        objects.push(format!("table {table_name}"));
+        for column in &table.columns {
+            objects.push(format!(
+                "column {table_name}.{}",
+                qualified("", &column.name)
+            ));
+        }
+        for constraint in &table.constraints {
+            objects.push(format!("constraint {}", qualified("", &constraint.name)));
+        }
+        for index in &table.indexes {
+            objects.push(format!("index {}", qualified("", &index.name)));
+        }
+    }
+    for view in &project.views {
+        let kind = if view.materialized {
+            "materialized view"
+        } else {
+            "view"
+        };
+        objects.push(format!("{kind} {}", name(&view.schema, &view.name)));
+        for index in &view.indexes {
+            objects.push(format!("index {}", qualified("", &index.name)));
+        }
+    }
+    objects
+}
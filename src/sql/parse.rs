@@ -0,0 +1,2002 @@
+use super::{
+    lexer::{split_statements, tokenize, Token, TokenKind},
+    qualified, split_top_level, take_group, unwrap_parens,
+};
+use crate::model::{
+    column::Identity, constraint::ForeignKey, data_type, naming, Attribute, Column, Constraint,
+    ConstraintKind, DomainCheck, ExclusionElement, Extension, Index, IndexElement, IndexMethod,
+    NullsOrder, Partition, PartitionBound, PartitionStrategy, Partitioning, PgVersion, Project,
+    ReferentialAction, Sequence, SortOrder, Table, UserType, UserTypeKind, View, DEFAULT_SCHEMA,
+};
+
+/// What a script creates, and the statements that were left out.
+#[derive(Clone, Debug, Default)]
+pub struct Parsed {
+    pub project: Project,
+    pub skipped: Vec<Skipped>,
+}
+
+/// A statement that could not be read into the model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skipped {
+    pub statement: String,
+    pub reason: String,
+}
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Keywords that end a column type or default and start the next column
+/// constraint.
+const COLUMN_CONSTRAINTS: &[&str] = &[
+    "constraint",
+    "not",
+    "null",
+    "default",
+    "primary",
+    "unique",
+    "check",
+    "references",
+    "generated",
+    "collate",
+];
+
+/// Reads the schema objects a script creates, such as `pg_dump
+/// --schema-only` output. Statements that only set up the session or change
+/// ownership are passed over. Statements that can't be read are reported
+/// rather than failing the whole script.
+pub fn script(sql: &str) -> Parsed {
+    let mut reader = Reader::default();
+    if let Some(version) = dumped_from(sql) {
+        reader.project.version = version;
+    }
+    reader.read(sql);
+    reader.finish()
+}
+
+/// Server version from the `-- Dumped from database version 16.2` header of
+/// a dump.
+fn dumped_from(sql: &str) -> Option<PgVersion> {
+    let (_, rest) = sql.split_once("-- Dumped from database version ")?;
+    let major: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    Some(PgVersion::from_major(major.parse().ok()?))
+}
+
+/// Reads scripts into one model, each changing what the ones before it
+/// created, as migrations do.
+#[derive(Default)]
+pub struct Reader {
+    project: Project,
+    skipped: Vec<Skipped>,
+    /// Tables attached as partitions. Their indexes and constraints come
+    /// from the parent, so statements about them are passed over.
+    partitions: Vec<(String, String)>,
+}
+
+impl Reader {
+    /// Applies the statements of `sql` to the model read so far.
+    pub fn read(&mut self, sql: &str) {
+        for statement in split_statements(sql) {
+            let tokens = tokenize(statement);
+            let mut parser = Parser::new(statement, &tokens);
+            if let Err(reason) = self.statement(&mut parser) {
+                self.skipped.push(Skipped {
+                    statement: statement.to_string(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    /// The model as read so far.
+    pub fn project(&self) -> &Project {
+        &self.project
+    }
+
+    /// The statements left out so far.
+    pub fn skipped(&self) -> &[Skipped] {
+        &self.skipped
+    }
+
+    fn statement(&mut self, parser: &mut Parser) -> Result<()> {
+        let Some(first) = parser.peek() else {
+            return Ok(());
+        };
+        // psql meta-commands, session settings, `setval()` and data.
+        if first.is_symbol("\\")
+            || [
+                "set", "reset", "select", "begin", "commit", "start", "end", "copy", "insert",
+                "update", "delete", "truncate", "analyze", "vacuum", "notify",
+            ]
+            .iter()
+            .any(|keyword| first.is_keyword(keyword))
+            || parser.ends_with(&["owner", "to"], 1)
+        {
+            return Ok(());
+        }
+
+        if parser.keyword("create") {
+            parser.keywords(&["or", "replace"]);
+            if parser.keyword("temporary") || parser.keyword("temp") {
+                return Err(String::from("temporary objects are not part of the schema"));
+            }
+            parser.keyword("unlogged");
+            if parser.keyword("table") {
+                self.create_table(parser)
+            } else if parser.keyword("unique") {
+                parser.expect(&["index"])?;
+                self.create_index(parser, true)
+            } else if parser.keyword("index") {
+                self.create_index(parser, false)
+            } else if parser.keyword("type") {
+                self.create_type(parser)
+            } else if parser.keyword("domain") {
+                self.create_domain(parser)
+            } else if parser.keyword("view") {
+                self.create_view(parser, false)
+            } else if parser.keywords(&["materialized", "view"]) {
+                self.create_view(parser, true)
+            } else if parser.keyword("sequence") {
+                self.create_sequence(parser)
+            } else if parser.keyword("extension") {
+                self.create_extension(parser)
+            } else if parser.keyword("schema") {
+                Ok(())
+            } else {
+                Err(parser.unsupported("CREATE"))
+            }
+        } else if parser.keyword("alter") {
+            if parser.keyword("table") {
+                self.alter_table(parser)
+            } else if parser.keyword("sequence") {
+                self.alter_sequence(parser)
+            } else if parser.peek_keyword("index") && parser.contains(&["attach", "partition"]) {
+                Ok(())
+            } else if parser.keyword("index") {
+                self.alter_index(parser)
+            } else if parser.keyword("type") {
+                self.alter_type(parser)
+            } else {
+                Err(parser.unsupported("ALTER"))
+            }
+        } else if parser.keyword("drop") {
+            self.drop(parser)
+        } else if parser.keywords(&["comment", "on"]) {
+            self.comment(parser)
+        } else {
+            Err(parser.unsupported(""))
+        }
+    }
+
+    fn is_partition(&self, schema: &str, name: &str) -> bool {
+        self.partitions
+            .iter()
+            .any(|(s, n)| s == schema && n == name)
+    }
+
+    fn table_mut(&mut self, schema: &str, name: &str) -> Result<&mut Table> {
+        self.project
+            .tables
+            .iter_mut()
+            .find(|table| table.schema == schema && table.name == name)
+            .ok_or_else(|| format!("unknown table {}", qualified(schema, name)))
+    }
+
+    fn create_table(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "not", "exists"]);
+        let (schema, name) = parser.qualified()?;
+        if self.project.table(&schema, &name).is_some() {
+            return Err(format!("{} already exists", qualified(&schema, &name)));
+        }
+
+        if parser.keywords(&["partition", "of"]) {
+            let (parent_schema, parent) = parser.qualified()?;
+            if parser.peek_symbol("(") {
+                parser.group()?;
+            }
+            if parser.contains(&["partition", "by"]) {
+                return Err(String::from(
+                    "partitions partitioned again are not supported",
+                ));
+            }
+            let bound = parser.rest();
+            return self.attach(&parent_schema, &parent, (schema, name), &bound);
+        }
+
+        let mut table = Table::new(schema, name);
+        let mut elements = parser.group()?;
+        for mut element in elements.split() {
+            if element.starts_constraint() {
+                let constraint = constraint(&mut element, &table)?;
+                table.constraints.push(constraint);
+            } else if element.peek_keyword("like") {
+                return Err(String::from("LIKE is not supported"));
+            } else {
+                column(&mut element, &mut table)?;
+            }
+            element.end()?;
+        }
+        elements.end()?;
+
+        while !parser.at_end() {
+            if parser.keywords(&["partition", "by"]) {
+                let definition = parser.until(&["using", "with", "tablespace"]);
+                table.partitioning = Some(
+                    partitioning(&definition)
+                        .ok_or_else(|| format!("unreadable partition key {definition}"))?,
+                );
+            } else if parser.keyword("inherits") {
+                return Err(String::from("table inheritance is not supported"));
+            } else if parser.keyword("using") || parser.keyword("tablespace") {
+                parser.ident()?;
+            } else if parser.keyword("with") || parser.keyword("without") {
+                if !parser.keyword("oids") {
+                    parser.group()?;
+                }
+            } else {
+                return Err(parser.unexpected("expected the end of the table"));
+            }
+        }
+
+        self.project.tables.push(table);
+        Ok(())
+    }
+
+    /// Makes `child` a partition of `parent`, dropping it from the tables if
+    /// it was created on its own first, as `pg_dump` does.
+    fn attach(
+        &mut self,
+        parent_schema: &str,
+        parent: &str,
+        child: (String, String),
+        bound: &str,
+    ) -> Result<()> {
+        let bound = partition_bound(bound).ok_or_else(|| format!("unreadable bound {bound}"))?;
+        let partitioning = self
+            .table_mut(parent_schema, parent)?
+            .partitioning
+            .as_mut()
+            .ok_or_else(|| format!("{} is not partitioned", qualified(parent_schema, parent)))?;
+        partitioning.partitions.push(Partition {
+            name: child.1.clone(),
+            bound,
+        });
+        self.project
+            .tables
+            .retain(|table| table.schema != child.0 || table.name != child.1);
+        self.partitions.push(child);
+        Ok(())
+    }
+
+    fn create_index(&mut self, parser: &mut Parser, unique: bool) -> Result<()> {
+        parser.keyword("concurrently");
+        parser.keywords(&["if", "not", "exists"]);
+        let name = if parser.peek_keyword("on") {
+            None
+        } else {
+            Some(parser.qualified()?.1)
+        };
+        parser.expect(&["on"])?;
+        parser.keyword("only");
+        let (schema, relation) = parser.qualified()?;
+        if self.is_partition(&schema, &relation) {
+            return Ok(());
+        }
+
+        let mut index = Index::new(name.unwrap_or_default());
+        index.unique = unique;
+        if parser.keyword("using") {
+            index.method = parser.index_method()?;
+        }
+        for element in parser.group()?.split() {
+            index.elements.push(index_element(element)?);
+        }
+        while !parser.at_end() {
+            if parser.keyword("include") {
+                index.include = parser.names()?;
+            } else if parser.keywords(&["nulls", "not", "distinct"]) {
+                index.nulls_not_distinct = true;
+            } else if parser.keywords(&["nulls", "distinct"]) {
+            } else if parser.keyword("with") {
+                parser.group()?;
+            } else if parser.keyword("tablespace") {
+                parser.ident()?;
+            } else if parser.keyword("where") {
+                index.predicate = Some(unwrap_parens(&parser.rest()));
+            } else {
+                return Err(parser.unexpected("expected the end of the index"));
+            }
+        }
+
+        if let Some(table) = self
+            .project
+            .tables
+            .iter_mut()
+            .find(|table| table.schema == schema && table.name == relation)
+        {
+            if index.name.is_empty() {
+                index.name = table.default_index_name(&index.elements);
+            }
+            table.indexes.push(index);
+        } else if let Some(view) = self
+            .project
+            .views
+            .iter_mut()
+            .find(|view| view.schema == schema && view.name == relation && view.materialized)
+        {
+            if index.name.is_empty() {
+                index.name = view.default_index_name(&index.elements);
+            }
+            view.indexes.push(index);
+        } else {
+            return Err(format!("unknown table {}", qualified(&schema, &relation)));
+        }
+        Ok(())
+    }
+
+    fn create_type(&mut self, parser: &mut Parser) -> Result<()> {
+        let (schema, name) = parser.qualified()?;
+        if !parser.keyword("as") {
+            return Err(String::from("only enum and composite types are supported"));
+        }
+        let kind = if parser.keyword("enum") {
+            let mut labels = Vec::new();
+            for mut label in parser.group()?.split() {
+                labels.push(label.string()?);
+                label.end()?;
+            }
+            UserTypeKind::Enum { labels }
+        } else if parser.peek_symbol("(") {
+            let mut attributes = Vec::new();
+            for mut attribute in parser.group()?.split() {
+                let name = attribute.ident()?;
+                let data_type = attribute.data_type()?;
+                if attribute.keyword("collate") {
+                    attribute.qualified()?;
+                }
+                attribute.end()?;
+                attributes.push(Attribute { name, data_type });
+            }
+            UserTypeKind::Composite { attributes }
+        } else {
+            return Err(String::from("only enum and composite types are supported"));
+        };
+        parser.end()?;
+        self.project.types.push(UserType::new(schema, name, kind));
+        Ok(())
+    }
+
+    fn create_domain(&mut self, parser: &mut Parser) -> Result<()> {
+        let (schema, name) = parser.qualified()?;
+        parser.keyword("as");
+        let data_type = parser.data_type()?;
+        let mut not_null = false;
+        let mut default = None;
+        let mut checks: Vec<DomainCheck> = Vec::new();
+        while !parser.at_end() {
+            let constraint = if parser.keyword("constraint") {
+                Some(parser.ident()?)
+            } else {
+                None
+            };
+            if parser.keywords(&["not", "null"]) {
+                not_null = true;
+            } else if parser.keyword("null") {
+            } else if parser.keyword("collate") {
+                parser.qualified()?;
+            } else if parser.keyword("default") {
+                default = Some(parser.until(COLUMN_CONSTRAINTS));
+            } else if parser.keyword("check") {
+                let expression = parser.group()?.rest();
+                let name = constraint.unwrap_or_else(|| {
+                    let base = format!("{name}_check");
+                    let mut name = base.clone();
+                    let mut n = 0;
+                    while checks.iter().any(|check| check.name == name) {
+                        n += 1;
+                        name = format!("{base}{n}");
+                    }
+                    name
+                });
+                checks.push(DomainCheck { name, expression });
+                parser.keywords(&["not", "valid"]);
+            } else {
+                return Err(parser.unexpected("expected a domain constraint"));
+            }
+        }
+        self.project.types.push(UserType::new(
+            schema,
+            name,
+            UserTypeKind::Domain {
+                data_type,
+                not_null,
+                default,
+                checks,
+            },
+        ));
+        Ok(())
+    }
+
+    fn create_view(&mut self, parser: &mut Parser, materialized: bool) -> Result<()> {
+        parser.keyword("recursive");
+        parser.keywords(&["if", "not", "exists"]);
+        let (schema, name) = parser.qualified()?;
+        if parser.peek_symbol("(") {
+            return Err(String::from("view column lists are not supported"));
+        }
+        while !parser.keyword("as") {
+            if parser.keyword("with") {
+                parser.group()?;
+            } else if parser.keyword("using") || parser.keyword("tablespace") {
+                parser.ident()?;
+            } else {
+                return Err(parser.unexpected("expected AS"));
+            }
+        }
+
+        let mut view = View::new(schema, name, materialized);
+        if materialized && parser.ends_with(&["with", "no", "data"], 0) {
+            view.with_data = false;
+            parser.truncate(3);
+        } else if materialized && parser.ends_with(&["with", "data"], 0) {
+            parser.truncate(2);
+        }
+        view.definition = parser.rest();
+        if view.definition.is_empty() {
+            return Err(String::from("expected a query"));
+        }
+        self.project.views.push(view);
+        Ok(())
+    }
+
+    fn create_sequence(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "not", "exists"]);
+        let (schema, name) = parser.qualified()?;
+        let mut sequence = Sequence::new(schema, name);
+        let mut min_value = None;
+        let mut max_value = None;
+        while !parser.at_end() {
+            if parser.keyword("as") {
+                sequence.data_type = parser.ident()?;
+            } else if parser.keyword("increment") {
+                parser.keyword("by");
+                sequence.increment = parser.integer()?;
+            } else if parser.keywords(&["no", "minvalue"]) {
+                min_value = None;
+            } else if parser.keyword("minvalue") {
+                min_value = Some(parser.integer()?);
+            } else if parser.keywords(&["no", "maxvalue"]) {
+                max_value = None;
+            } else if parser.keyword("maxvalue") {
+                max_value = Some(parser.integer()?);
+            } else if parser.keyword("start") {
+                parser.keyword("with");
+                sequence.start = Some(parser.integer()?);
+            } else if parser.keyword("cache") {
+                sequence.cache = parser.integer()?;
+            } else if parser.keywords(&["no", "cycle"]) {
+                sequence.cycle = false;
+            } else if parser.keyword("cycle") {
+                sequence.cycle = true;
+            } else if parser.keywords(&["owned", "by"]) {
+                sequence.owned_by = parser.owner()?;
+            } else {
+                return Err(parser.unexpected("expected a sequence option"));
+            }
+        }
+
+        // Keep only what differs from the defaults, like a sequence read
+        // from the catalog.
+        let (default_min, default_max) = sequence.default_bounds();
+        sequence.min_value = min_value.filter(|&min| min != default_min);
+        sequence.max_value = max_value.filter(|&max| max != default_max);
+        let default_start = if sequence.increment > 0 {
+            min_value.unwrap_or(default_min)
+        } else {
+            max_value.unwrap_or(default_max)
+        };
+        sequence.start = sequence.start.filter(|&start| start != default_start);
+        self.project.sequences.push(sequence);
+        Ok(())
+    }
+
+    fn create_extension(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "not", "exists"]);
+        let name = parser.ident()?;
+        // Always there.
+        if name == "plpgsql" {
+            return Ok(());
+        }
+        let extension = Extension::named(&name);
+        if !self.project.extensions.contains(&extension) {
+            self.project.extensions.push(extension);
+        }
+        Ok(())
+    }
+
+    fn alter_table(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "exists"]);
+        parser.keyword("only");
+        let (schema, name) = parser.qualified()?;
+        parser.symbol("*");
+        if self.is_partition(&schema, &name) {
+            return Ok(());
+        }
+
+        if parser.keywords(&["attach", "partition"]) {
+            let child = parser.qualified()?;
+            let bound = parser.rest();
+            return self.attach(&schema, &name, child, &bound);
+        }
+        if parser.keyword("rename") {
+            return self.rename(parser, &schema, &name);
+        }
+
+        // Every action applies or none does.
+        let mut table = self.table_mut(&schema, &name)?.clone();
+        let mut dropped = Vec::new();
+        for mut action in parser.split() {
+            if action.keyword("add") {
+                if action.starts_constraint() {
+                    let constraint = constraint(&mut action, &table)?;
+                    table.constraints.push(constraint);
+                } else {
+                    action.keyword("column");
+                    action.keywords(&["if", "not", "exists"]);
+                    column(&mut action, &mut table)?;
+                }
+            } else if action.keyword("alter") {
+                action.keyword("column");
+                let name = action.ident()?;
+                let column = table
+                    .columns
+                    .iter_mut()
+                    .find(|column| column.name == name)
+                    .ok_or_else(|| format!("unknown column {name}"))?;
+                if action.keywords(&["set", "default"]) {
+                    column.default = Some(action.rest());
+                } else if action.keywords(&["drop", "default"]) {
+                    column.default = None;
+                } else if action.keywords(&["set", "not", "null"]) {
+                    column.not_null = true;
+                } else if action.keywords(&["drop", "not", "null"]) {
+                    column.not_null = false;
+                } else if action.keyword("add") {
+                    column.identity = Some(action.identity()?);
+                    column.not_null = true;
+                    column.default = None;
+                    if action.peek_symbol("(") {
+                        action.group()?;
+                    }
+                } else if action.keywords(&["drop", "identity"]) {
+                    action.keywords(&["if", "exists"]);
+                    column.identity = None;
+                } else if action.keywords(&["set", "data", "type"]) || action.keyword("type") {
+                    let data_type = action.until(&["collate", "using"]);
+                    if data_type.is_empty() {
+                        return Err(action.unexpected("expected a type"));
+                    }
+                    column.data_type = data_type.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if action.keyword("collate") {
+                        action.qualified()?;
+                    }
+                    if action.keyword("using") {
+                        action.rest();
+                    }
+                } else {
+                    return Err(action.unexpected("unsupported column change"));
+                }
+            } else if action.keyword("drop") {
+                if action.keyword("constraint") {
+                    let if_exists = action.keywords(&["if", "exists"]);
+                    let name = action.ident()?;
+                    let before = table.constraints.len();
+                    table
+                        .constraints
+                        .retain(|constraint| constraint.name != name);
+                    if table.constraints.len() == before && !if_exists {
+                        return Err(format!("unknown constraint {name}"));
+                    }
+                } else {
+                    action.keyword("column");
+                    let if_exists = action.keywords(&["if", "exists"]);
+                    let name = action.ident()?;
+                    if drop_column(&mut table, &name) {
+                        dropped.push(name);
+                    } else if !if_exists {
+                        return Err(format!("unknown column {name}"));
+                    }
+                }
+                if !action.keyword("cascade") {
+                    action.keyword("restrict");
+                }
+            } else if action.keywords(&["enable", "row", "level", "security"]) {
+                table.row_security = true;
+            } else if action.keywords(&["disable", "row", "level", "security"]) {
+                table.row_security = false;
+            } else if action.keywords(&["force", "row", "level", "security"]) {
+                table.force_row_security = true;
+            } else if action.keywords(&["no", "force", "row", "level", "security"]) {
+                table.force_row_security = false;
+            } else {
+                return Err(action.unexpected("unsupported table change"));
+            }
+            action.end()?;
+        }
+        *self.table_mut(&schema, &name)? = table;
+
+        // Dropping a column drops what depends on it elsewhere too.
+        for column in dropped {
+            for table in &mut self.project.tables {
+                table
+                    .constraints
+                    .retain(|constraint| match &constraint.kind {
+                        ConstraintKind::ForeignKey(fk) => {
+                            fk.ref_schema != schema
+                                || fk.ref_table != name
+                                || !fk.ref_columns.contains(&column)
+                        }
+                        _ => true,
+                    });
+            }
+            self.project.sequences.retain(|sequence| {
+                owned_by(sequence)
+                    .is_none_or(|owner| owner != (schema.as_str(), name.as_str(), column.as_str()))
+            });
+        }
+        Ok(())
+    }
+
+    /// `RENAME TO`, `RENAME CONSTRAINT` and `RENAME [COLUMN]` of ALTER
+    /// TABLE, which take no other actions.
+    fn rename(&mut self, parser: &mut Parser, schema: &str, name: &str) -> Result<()> {
+        let i = self
+            .project
+            .tables
+            .iter()
+            .position(|table| table.schema == schema && table.name == name)
+            .ok_or_else(|| format!("unknown table {}", qualified(schema, name)))?;
+        if parser.keyword("to") {
+            let to = parser.ident()?;
+            parser.end()?;
+            if self.project.table(schema, &to).is_some() {
+                return Err(format!("{} already exists", qualified(schema, &to)));
+            }
+            naming::rename_table(&mut self.project, i, &to);
+        } else if parser.keyword("constraint") {
+            let from = parser.ident()?;
+            parser.expect(&["to"])?;
+            let to = parser.ident()?;
+            parser.end()?;
+            let constraint = self.project.tables[i]
+                .constraints
+                .iter_mut()
+                .find(|constraint| constraint.name == from)
+                .ok_or_else(|| format!("unknown constraint {from}"))?;
+            constraint.name = to;
+        } else {
+            parser.keyword("column");
+            let from = parser.ident()?;
+            parser.expect(&["to"])?;
+            let to = parser.ident()?;
+            parser.end()?;
+            let j = self.project.tables[i]
+                .columns
+                .iter()
+                .position(|column| column.name == from)
+                .ok_or_else(|| format!("unknown column {from}"))?;
+            naming::rename_column(&mut self.project, i, j, &to);
+        }
+        Ok(())
+    }
+
+    fn alter_sequence(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "exists"]);
+        let (schema, name) = parser.qualified()?;
+        parser.expect(&["owned", "by"])?;
+        let owner = parser.owner()?;
+        let sequence = self
+            .project
+            .sequences
+            .iter_mut()
+            .find(|sequence| sequence.schema == schema && sequence.name == name)
+            .ok_or_else(|| format!("unknown sequence {}", qualified(&schema, &name)))?;
+        sequence.owned_by = owner;
+        Ok(())
+    }
+
+    fn alter_index(&mut self, parser: &mut Parser) -> Result<()> {
+        parser.keywords(&["if", "exists"]);
+        let (schema, name) = parser.qualified()?;
+        parser.expect(&["rename", "to"])?;
+        let to = parser.ident()?;
+        parser.end()?;
+        let index = self
+            .project
+            .tables
+            .iter_mut()
+            .filter(|table| table.schema == schema)
+            .flat_map(|table| &mut table.indexes)
+            .chain(
+                self.project
+                    .views
+                    .iter_mut()
+                    .filter(|view| view.schema == schema)
+                    .flat_map(|view| &mut view.indexes),
+            )
+            .find(|index| index.name == name)
+            .ok_or_else(|| format!("unknown index {}", qualified(&schema, &name)))?;
+        index.name = to;
+        Ok(())
+    }
+
+    /// Adding and renaming enum labels.
+    fn alter_type(&mut self, parser: &mut Parser) -> Result<()> {
+        let (schema, name) = parser.qualified()?;
+        let ty = self
+            .project
+            .types
+            .iter_mut()
+            .find(|ty| ty.schema == schema && ty.name == name)
+            .ok_or_else(|| format!("unknown type {}", qualified(&schema, &name)))?;
+        let UserTypeKind::Enum { labels } = &mut ty.kind else {
+            return Err(format!("{} is not an enum", qualified(&schema, &name)));
+        };
+        if parser.keywords(&["add", "value"]) {
+            let if_not_exists = parser.keywords(&["if", "not", "exists"]);
+            let label = parser.string()?;
+            let position = if parser.keyword("before") || parser.peek_keyword("after") {
+                let after = parser.keyword("after");
+                let neighbour = parser.string()?;
+                let i = labels
+                    .iter()
+                    .position(|l| *l == neighbour)
+                    .ok_or_else(|| format!("unknown label {neighbour}"))?;
+                i + usize::from(after)
+            } else {
+                labels.len()
+            };
+            parser.end()?;
+            if labels.contains(&label) {
+                return if if_not_exists {
+                    Ok(())
+                } else {
+                    Err(format!("label {label} already exists"))
+                };
+            }
+            labels.insert(position, label);
+        } else if parser.keywords(&["rename", "value"]) {
+            let from = parser.string()?;
+            parser.expect(&["to"])?;
+            let to = parser.string()?;
+            parser.end()?;
+            let label = labels
+                .iter_mut()
+                .find(|l| **l == from)
+                .ok_or_else(|| format!("unknown label {from}"))?;
+            *label = to;
+        } else {
+            return Err(parser.unexpected("expected ADD VALUE or RENAME VALUE"));
+        }
+        Ok(())
+    }
+
+    fn drop(&mut self, parser: &mut Parser) -> Result<()> {
+        let kind = if parser.keywords(&["materialized", "view"]) {
+            "materialized view"
+        } else if let Some(kind) = [
+            "table",
+            "view",
+            "index",
+            "type",
+            "domain",
+            "sequence",
+            "extension",
+            "schema",
+        ]
+        .into_iter()
+        .find(|kind| parser.keyword(kind))
+        {
+            kind
+        } else {
+            return Err(parser.unsupported("DROP"));
+        };
+        if kind == "index" {
+            parser.keyword("concurrently");
+        }
+        let if_exists = parser.keywords(&["if", "exists"]);
+        let mut names = Vec::new();
+        loop {
+            names.push(if matches!(kind, "extension" | "schema") {
+                (String::new(), parser.ident()?)
+            } else {
+                parser.qualified()?
+            });
+            if !parser.symbol(",") {
+                break;
+            }
+        }
+        if !parser.keyword("cascade") {
+            parser.keyword("restrict");
+        }
+        parser.end()?;
+
+        for (schema, name) in names {
+            if !self.drop_object(kind, &schema, &name) && !if_exists {
+                return Err(format!("unknown {kind} {}", qualified(&schema, &name)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops an object and what goes with it, such as the foreign keys
+    /// pointing at a table. Returns whether there was one.
+    fn drop_object(&mut self, kind: &str, schema: &str, name: &str) -> bool {
+        let partition = self.is_partition(schema, name);
+        let project = &mut self.project;
+        match kind {
+            "table" if partition => {
+                self.partitions.retain(|(s, n)| s != schema || n != name);
+                for table in project.tables.iter_mut().filter(|t| t.schema == schema) {
+                    if let Some(partitioning) = &mut table.partitioning {
+                        partitioning.partitions.retain(|p| p.name != name);
+                    }
+                }
+                true
+            }
+            "table" => {
+                let Some(i) = project
+                    .tables
+                    .iter()
+                    .position(|t| t.schema == schema && t.name == name)
+                else {
+                    return false;
+                };
+                let table = project.tables.remove(i);
+                if let Some(partitioning) = &table.partitioning {
+                    self.partitions.retain(|(s, n)| {
+                        s != schema || !partitioning.partitions.iter().any(|p| p.name == *n)
+                    });
+                }
+                for table in &mut project.tables {
+                    table
+                        .constraints
+                        .retain(|constraint| match &constraint.kind {
+                            ConstraintKind::ForeignKey(fk) => {
+                                fk.ref_schema != schema || fk.ref_table != name
+                            }
+                            _ => true,
+                        });
+                }
+                project.sequences.retain(|sequence| {
+                    owned_by(sequence).is_none_or(|(s, t, _)| s != schema || t != name)
+                });
+                true
+            }
+            "view" | "materialized view" => {
+                let materialized = kind != "view";
+                let before = project.views.len();
+                project.views.retain(|view| {
+                    view.schema != schema || view.name != name || view.materialized != materialized
+                });
+                project.views.len() < before
+            }
+            "index" => project
+                .tables
+                .iter_mut()
+                .filter(|table| table.schema == schema)
+                .map(|table| &mut table.indexes)
+                .chain(
+                    project
+                        .views
+                        .iter_mut()
+                        .filter(|view| view.schema == schema)
+                        .map(|view| &mut view.indexes),
+                )
+                .any(|indexes| {
+                    let before = indexes.len();
+                    indexes.retain(|index| index.name != name);
+                    indexes.len() < before
+                }),
+            "type" | "domain" => {
+                let domain = kind == "domain";
+                let before = project.types.len();
+                project.types.retain(|ty| {
+                    ty.schema != schema
+                        || ty.name != name
+                        || matches!(ty.kind, UserTypeKind::Domain { .. }) != domain
+                });
+                project.types.len() < before
+            }
+            "sequence" => {
+                let before = project.sequences.len();
+                project
+                    .sequences
+                    .retain(|sequence| sequence.schema != schema || sequence.name != name);
+                project.sequences.len() < before
+            }
+            "extension" => {
+                let before = project.extensions.len();
+                project
+                    .extensions
+                    .retain(|extension| extension.name() != name);
+                project.extensions.len() < before || name == "plpgsql"
+            }
+            // Schemas are not modelled, so there is always one to drop.
+            "schema" => {
+                project.tables.retain(|table| table.schema != name);
+                project.views.retain(|view| view.schema != name);
+                project.types.retain(|ty| ty.schema != name);
+                project.sequences.retain(|sequence| sequence.schema != name);
+                project.routines.retain(|routine| routine.schema != name);
+                self.partitions.retain(|(s, _)| s != name);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn comment(&mut self, parser: &mut Parser) -> Result<()> {
+        if parser.keyword("extension") || parser.keyword("schema") {
+            return Ok(());
+        }
+        let kind = parser
+            .next()
+            .map(|token| token.text.to_lowercase())
+            .unwrap_or_default();
+        let kind = if kind == "materialized" {
+            parser.expect(&["view"])?;
+            String::from("view")
+        } else {
+            kind
+        };
+        let mut names = vec![parser.ident()?];
+        while parser.symbol(".") {
+            names.push(parser.ident()?);
+        }
+        parser.expect(&["is"])?;
+        let text = if parser.keyword("null") {
+            None
+        } else {
+            Some(parser.string()?)
+        };
+        parser.end()?;
+
+        let (schema, name) = match (kind.as_str(), names.as_slice()) {
+            ("column", [table, column]) => {
+                (DEFAULT_SCHEMA.to_string(), format!("{table}.{column}"))
+            }
+            ("column", [schema, table, column]) => (schema.clone(), format!("{table}.{column}")),
+            (_, [name]) => (DEFAULT_SCHEMA.to_string(), name.clone()),
+            (_, [schema, name]) => (schema.clone(), name.clone()),
+            _ => return Err(String::from("unexpected name")),
+        };
+        let unknown = |kind: &str| format!("unknown {kind} {}", qualified(&schema, &name));
+        let project = &mut self.project;
+        match kind.as_str() {
+            "table" => {
+                if self
+                    .partitions
+                    .iter()
+                    .any(|(s, n)| *s == schema && *n == name)
+                {
+                    return Ok(());
+                }
+                let table = project
+                    .tables
+                    .iter_mut()
+                    .find(|t| t.schema == schema && t.name == name)
+                    .ok_or_else(|| unknown("table"))?;
+                table.comment = text;
+            }
+            "column" => {
+                let (table_name, column_name) = name.split_once('.').unwrap_or_default();
+                if self
+                    .partitions
+                    .iter()
+                    .any(|(s, n)| *s == schema && n == table_name)
+                {
+                    return Ok(());
+                }
+                let column = project
+                    .tables
+                    .iter_mut()
+                    .find(|t| t.schema == schema && t.name == table_name)
+                    .and_then(|t| t.columns.iter_mut().find(|c| c.name == column_name))
+                    .ok_or_else(|| unknown("column"))?;
+                column.comment = text;
+            }
+            "view" => {
+                let view = project
+                    .views
+                    .iter_mut()
+                    .find(|v| v.schema == schema && v.name == name)
+                    .ok_or_else(|| unknown("view"))?;
+                view.comment = text;
+            }
+            "type" | "domain" => {
+                let ty = project
+                    .types
+                    .iter_mut()
+                    .find(|t| t.schema == schema && t.name == name)
+                    .ok_or_else(|| unknown("type"))?;
+                ty.comment = text;
+            }
+            "sequence" => {
+                let sequence = project
+                    .sequences
+                    .iter_mut()
+                    .find(|s| s.schema == schema && s.name == name)
+                    .ok_or_else(|| unknown("sequence"))?;
+                sequence.comment = text;
+            }
+            _ => return Err(format!("comments on {kind} are not supported")),
+        }
+        Ok(())
+    }
+
+    /// Folds sequences that only back a column default back into `serial`
+    /// columns and fills in foreign keys that reference a primary key
+    /// without naming its columns.
+    pub fn finish(mut self) -> Parsed {
+        let project = &mut self.project;
+        let mut serials = Vec::new();
+        for (i, sequence) in project.sequences.iter().enumerate() {
+            let Some(owner) = &sequence.owned_by else {
+                continue;
+            };
+            let parts: Vec<&str> = owner.split('.').collect();
+            let (schema, table, column) = match parts.as_slice() {
+                [table, column] => (DEFAULT_SCHEMA, *table, *column),
+                [schema, table, column] => (*schema, *table, *column),
+                _ => continue,
+            };
+            let Some(t) = project
+                .tables
+                .iter()
+                .position(|t| t.schema == schema && t.name == table)
+            else {
+                continue;
+            };
+            let Some(c) = project.tables[t]
+                .columns
+                .iter()
+                .position(|c| c.name == column)
+            else {
+                continue;
+            };
+            if sequence.backs_serial(&project.tables[t].columns[c]) {
+                serials.push((i, t, c));
+            }
+        }
+        for &(_, t, c) in &serials {
+            let column = &mut project.tables[t].columns[c];
+            column.data_type = data_type::serial_for(&column.data_type).to_string();
+            column.default = None;
+        }
+        for &(i, _, _) in serials.iter().rev() {
+            project.sequences.remove(i);
+        }
+
+        let primary_keys: Vec<(String, String, Vec<String>)> = project
+            .tables
+            .iter()
+            .filter_map(|table| match &table.primary_key()?.kind {
+                ConstraintKind::PrimaryKey { columns, .. } => {
+                    Some((table.schema.clone(), table.name.clone(), columns.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        for table in &mut project.tables {
+            for constraint in &mut table.constraints {
+                let ConstraintKind::ForeignKey(foreign_key) = &mut constraint.kind else {
+                    continue;
+                };
+                let primary_key = primary_keys
+                    .iter()
+                    .find(|(s, n, _)| *s == foreign_key.ref_schema && *n == foreign_key.ref_table);
+                if let (true, Some((_, _, columns))) =
+                    (foreign_key.ref_columns.is_empty(), primary_key)
+                {
+                    foreign_key.ref_columns = columns.clone();
+                }
+            }
+        }
+
+        Parsed {
+            project: self.project,
+            skipped: self.skipped,
+        }
+    }
+}
+
+/// `name type [column constraints]` as in CREATE TABLE or ADD COLUMN.
+/// Constraints declared on the column are added to `table`.
+fn column(parser: &mut Parser, table: &mut Table) -> Result<()> {
+    let mut column = Column::new(parser.ident()?, parser.data_type()?);
+    if table.columns.iter().any(|c| c.name == column.name) {
+        return Err(format!("column {} is declared twice", column.name));
+    }
+    let mut constraints = Vec::new();
+    while !parser.at_end() {
+        let name = if parser.keyword("constraint") {
+            Some(parser.ident()?)
+        } else {
+            None
+        };
+        let kind = if parser.keywords(&["not", "null"]) {
+            column.not_null = true;
+            None
+        } else if parser.keyword("null") {
+            None
+        } else if parser.keyword("collate") {
+            parser.qualified()?;
+            None
+        } else if parser.keyword("default") {
+            column.default = Some(parser.until(COLUMN_CONSTRAINTS));
+            None
+        } else if parser.keywords(&["generated", "always", "as", "identity"])
+            || parser.keywords(&["generated", "by", "default", "as", "identity"])
+        {
+            let by_default = parser.tokens[parser.pos - 3].is_keyword("default");
+            column.identity = Some(if by_default {
+                Identity::ByDefault
+            } else {
+                Identity::Always
+            });
+            column.not_null = true;
+            if parser.peek_symbol("(") {
+                parser.group()?;
+            }
+            None
+        } else if parser.keywords(&["generated", "always", "as"]) {
+            column.generated = Some(parser.group()?.rest());
+            parser.keyword("stored");
+            None
+        } else if parser.keywords(&["primary", "key"]) {
+            Some(ConstraintKind::PrimaryKey {
+                columns: vec![column.name.clone()],
+                include: Vec::new(),
+            })
+        } else if parser.keyword("unique") {
+            let nulls_not_distinct = parser.keywords(&["nulls", "not", "distinct"]);
+            parser.keywords(&["nulls", "distinct"]);
+            Some(ConstraintKind::Unique {
+                columns: vec![column.name.clone()],
+                include: Vec::new(),
+                nulls_not_distinct,
+            })
+        } else if parser.keyword("check") {
+            Some(ConstraintKind::Check {
+                expression: parser.group()?.rest(),
+                no_inherit: parser.keywords(&["no", "inherit"]),
+            })
+        } else if parser.keyword("references") {
+            Some(ConstraintKind::ForeignKey(references(
+                parser,
+                vec![column.name.clone()],
+            )?))
+        } else {
+            return Err(parser.unexpected("expected a column constraint"));
+        };
+        if let Some(kind) = kind {
+            let mut constraint = Constraint::new(String::new(), kind);
+            attributes(parser, &mut constraint);
+            constraints.push((name, constraint));
+        }
+    }
+
+    table.columns.push(column);
+    for (name, mut constraint) in constraints {
+        constraint.name = name.unwrap_or_else(|| table.default_constraint_name(&constraint.kind));
+        table.constraints.push(constraint);
+    }
+    Ok(())
+}
+
+/// Drops column `name` of `table` with the keys and indexes that use it.
+/// Returns whether there was one.
+fn drop_column(table: &mut Table, name: &str) -> bool {
+    let before = table.columns.len();
+    table.columns.retain(|column| column.name != name);
+    if table.columns.len() == before {
+        return false;
+    }
+    let uses = |items: &[String]| items.iter().any(|item| item.trim() == name);
+    table
+        .constraints
+        .retain(|constraint| match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns, include }
+            | ConstraintKind::Unique {
+                columns, include, ..
+            } => !uses(columns) && !uses(include),
+            ConstraintKind::ForeignKey(fk) => !uses(&fk.columns),
+            ConstraintKind::Exclusion {
+                elements, include, ..
+            } => !elements.iter().any(|e| e.element.trim() == name) && !uses(include),
+            ConstraintKind::Check { .. } => true,
+        });
+    table.indexes.retain(|index| {
+        !index
+            .elements
+            .iter()
+            .any(|element| element.is_column() && element.expression.trim() == name)
+            && !uses(&index.include)
+    });
+    true
+}
+
+/// The schema, table and column that own a sequence.
+fn owned_by(sequence: &Sequence) -> Option<(&str, &str, &str)> {
+    let parts: Vec<&str> = sequence.owned_by.as_deref()?.split('.').collect();
+    match parts.as_slice() {
+        [table, column] => Some((DEFAULT_SCHEMA, *table, *column)),
+        [schema, table, column] => Some((*schema, *table, *column)),
+        _ => None,
+    }
+}
+
+/// A table constraint, named after the table unless it says otherwise.
+fn constraint(parser: &mut Parser, table: &Table) -> Result<Constraint> {
+    let name = if parser.keyword("constraint") {
+        Some(parser.ident()?)
+    } else {
+        None
+    };
+    let kind = if parser.keywords(&["primary", "key"]) {
+        let columns = parser.names()?;
+        ConstraintKind::PrimaryKey {
+            columns,
+            include: index_parameters(parser)?,
+        }
+    } else if parser.keyword("unique") {
+        let nulls_not_distinct = parser.keywords(&["nulls", "not", "distinct"]);
+        parser.keywords(&["nulls", "distinct"]);
+        let columns = parser.names()?;
+        ConstraintKind::Unique {
+            columns,
+            include: index_parameters(parser)?,
+            nulls_not_distinct,
+        }
+    } else if parser.keyword("check") {
+        ConstraintKind::Check {
+            expression: parser.group()?.rest(),
+            no_inherit: parser.keywords(&["no", "inherit"]),
+        }
+    } else if parser.keywords(&["foreign", "key"]) {
+        let columns = parser.names()?;
+        parser.expect(&["references"])?;
+        ConstraintKind::ForeignKey(references(parser, columns)?)
+    } else if parser.keyword("exclude") {
+        let method = if parser.keyword("using") {
+            parser.index_method()?
+        } else {
+            IndexMethod::Btree
+        };
+        let mut elements = Vec::new();
+        for mut element in parser.group()?.split() {
+            let expression = element.until(&["with"]);
+            element.expect(&["with"])?;
+            elements.push(ExclusionElement {
+                element: expression,
+                operator: element.rest(),
+            });
+        }
+        let include = index_parameters(parser)?;
+        let predicate = if parser.keyword("where") {
+            Some(parser.group()?.rest())
+        } else {
+            None
+        };
+        ConstraintKind::Exclusion {
+            method,
+            elements,
+            include,
+            predicate,
+        }
+    } else {
+        return Err(parser.unexpected("expected a constraint"));
+    };
+
+    let mut constraint = Constraint::new(String::new(), kind);
+    constraint.name = name.unwrap_or_else(|| table.default_constraint_name(&constraint.kind));
+    attributes(parser, &mut constraint);
+    Ok(constraint)
+}
+
+/// `INCLUDE (...)`, `WITH (...)` and `USING INDEX TABLESPACE` of a
+/// constraint backed by an index. Only the included columns are kept.
+fn index_parameters(parser: &mut Parser) -> Result<Vec<String>> {
+    let mut include = Vec::new();
+    loop {
+        if parser.keyword("include") {
+            include = parser.names()?;
+        } else if parser.keyword("with") {
+            parser.group()?;
+        } else if parser.keywords(&["using", "index", "tablespace"]) {
+            parser.ident()?;
+        } else {
+            return Ok(include);
+        }
+    }
+}
+
+/// What follows `REFERENCES` in a foreign key.
+fn references(parser: &mut Parser, columns: Vec<String>) -> Result<ForeignKey> {
+    let (ref_schema, ref_table) = parser.qualified()?;
+    let ref_columns = if parser.peek_symbol("(") {
+        parser.names()?
+    } else {
+        Vec::new()
+    };
+    let mut foreign_key = ForeignKey {
+        columns,
+        ref_schema,
+        ref_table,
+        ref_columns,
+        ..ForeignKey::default()
+    };
+    loop {
+        if parser.keyword("match") {
+            parser.ident()?;
+        } else if parser.keywords(&["on", "delete"]) {
+            foreign_key.on_delete = parser.referential_action()?;
+        } else if parser.keywords(&["on", "update"]) {
+            foreign_key.on_update = parser.referential_action()?;
+        } else {
+            return Ok(foreign_key);
+        }
+    }
+}
+
+/// Deferral and validation options after a constraint.
+fn attributes(parser: &mut Parser, constraint: &mut Constraint) {
+    loop {
+        if parser.keywords(&["not", "deferrable"]) {
+            constraint.deferrable = false;
+        } else if parser.keyword("deferrable") {
+            constraint.deferrable = true;
+        } else if parser.keywords(&["initially", "deferred"]) {
+            constraint.initially_deferred = true;
+        } else if parser.keywords(&["initially", "immediate"]) {
+            constraint.initially_deferred = false;
+        } else if !(parser.keywords(&["not", "valid"])
+            || parser.keywords(&["not", "enforced"])
+            || parser.keyword("enforced"))
+        {
+            return;
+        }
+    }
+}
+
+/// `expression [COLLATE collation] [opclass] [ASC | DESC] [NULLS FIRST | LAST]`
+fn index_element(mut parser: Parser) -> Result<IndexElement> {
+    let start = parser.pos;
+    let expression = if parser.peek_symbol("(") {
+        // Without the parentheses, which writing it out adds back.
+        parser.group()?;
+        unwrap_parens(&parser.text(start, parser.pos))
+    } else {
+        parser.ident()?;
+        while parser.symbol(".") {
+            parser.ident()?;
+        }
+        if parser.peek_symbol("(") {
+            parser.group()?;
+        }
+        parser.text(start, parser.pos)
+    };
+    let mut element = IndexElement::new(expression);
+
+    if parser.keyword("collate") {
+        parser.qualified()?;
+    }
+    if !(parser.at_end()
+        || ["asc", "desc", "nulls"]
+            .iter()
+            .any(|keyword| parser.peek_keyword(keyword)))
+    {
+        let start = parser.pos;
+        parser.qualified()?;
+        if parser.peek_symbol("(") {
+            parser.group()?;
+        }
+        element.opclass = Some(parser.text(start, parser.pos));
+    }
+    let descending = parser.keyword("desc");
+    if descending {
+        element.order = SortOrder::Desc;
+    } else {
+        parser.keyword("asc");
+    }
+    // Left out when it's what the order implies anyway.
+    element.nulls = if parser.keywords(&["nulls", "first"]) {
+        if descending {
+            NullsOrder::Default
+        } else {
+            NullsOrder::First
+        }
+    } else if parser.keywords(&["nulls", "last"]) {
+        if descending {
+            NullsOrder::Last
+        } else {
+            NullsOrder::Default
+        }
+    } else {
+        NullsOrder::Default
+    };
+    parser.end()?;
+    Ok(element)
+}
+
+/// Parses a `PARTITION BY` clause such as `RANGE (created_at)`, also as
+/// `pg_get_partkeydef()` prints it.
+pub fn partitioning(definition: &str) -> Option<Partitioning> {
+    let definition = definition.trim();
+    let at = definition.find(|c: char| c.is_whitespace() || c == '(')?;
+    let (strategy, rest) = definition.split_at(at);
+    let strategy = PartitionStrategy::ALL
+        .into_iter()
+        .find(|s| s.to_string().eq_ignore_ascii_case(strategy))?;
+    let (key, _) = take_group(rest.trim())?;
+    let mut partitioning = Partitioning::new(strategy);
+    partitioning.key = split_top_level(key)
+        .into_iter()
+        .map(unwrap_parens)
+        .collect();
+    Some(partitioning)
+}
+
+/// Parses a partition bound such as
+/// `FOR VALUES FROM ('2024-01-01') TO ('2024-02-01')`, also as
+/// `pg_get_expr(relpartbound)` prints it.
+pub fn partition_bound(bound: &str) -> Option<PartitionBound> {
+    let bound = bound.trim();
+    if bound.eq_ignore_ascii_case("default") {
+        return Some(PartitionBound::Default);
+    }
+    let bound = strip_keyword(strip_keyword(bound, "for")?, "values")?;
+    if let Some(rest) = strip_keyword(bound, "from") {
+        let (from, rest) = take_group(rest)?;
+        let (to, _) = take_group(strip_keyword(rest, "to")?)?;
+        Some(PartitionBound::Range {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    } else if let Some(rest) = strip_keyword(bound, "in") {
+        let (values, _) = take_group(rest)?;
+        Some(PartitionBound::List {
+            values: values.to_string(),
+        })
+    } else {
+        let (options, _) = take_group(strip_keyword(bound, "with")?)?;
+        let mut modulus = None;
+        let mut remainder = None;
+        for option in options.split(',') {
+            match option.trim().split_once(' ')? {
+                (name, value) if name.eq_ignore_ascii_case("modulus") => {
+                    modulus = value.trim().parse().ok()
+                }
+                (name, value) if name.eq_ignore_ascii_case("remainder") => {
+                    remainder = value.trim().parse().ok()
+                }
+                _ => {}
+            }
+        }
+        Some(PartitionBound::Hash {
+            modulus: modulus?,
+            remainder: remainder?,
+        })
+    }
+}
+
+/// What follows `keyword`, in any case, at the start of `value`.
+fn strip_keyword<'a>(value: &'a str, keyword: &str) -> Option<&'a str> {
+    let value = value.trim_start();
+    let rest = value.get(keyword.len()..)?;
+    (value[..keyword.len()].eq_ignore_ascii_case(keyword)
+        && !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    .then(|| rest.trim_start())
+}
+
+/// Walks the tokens of one statement, or of a parenthesized part of it.
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(sql: &'a str, tokens: &'a [Token]) -> Self {
+        Self {
+            sql,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    fn peek_symbol(&self, symbol: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_symbol(symbol))
+    }
+
+    /// Consumes `keywords` if they all come next.
+    fn keywords(&mut self, keywords: &[&str]) -> bool {
+        let matches = keywords.iter().enumerate().all(|(i, keyword)| {
+            self.tokens
+                .get(self.pos + i)
+                .is_some_and(|token| token.is_keyword(keyword))
+        });
+        if matches {
+            self.pos += keywords.len();
+        }
+        matches
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.keywords(&[keyword])
+    }
+
+    fn expect(&mut self, keywords: &[&str]) -> Result<()> {
+        if self.keywords(keywords) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("expected {}", keywords.join(" ").to_uppercase())))
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        let matches = self.peek_symbol(symbol);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    /// Whether `keywords` follow each other anywhere after the current
+    /// position.
+    fn contains(&self, keywords: &[&str]) -> bool {
+        self.tokens[self.pos.min(self.tokens.len())..]
+            .windows(keywords.len())
+            .any(|window| {
+                window
+                    .iter()
+                    .zip(keywords)
+                    .all(|(token, keyword)| token.is_keyword(keyword))
+            })
+    }
+
+    /// Whether the tokens end with `keywords` followed by `skip` more.
+    fn ends_with(&self, keywords: &[&str], skip: usize) -> bool {
+        let Some(start) = self.tokens.len().checked_sub(keywords.len() + skip) else {
+            return false;
+        };
+        start >= self.pos
+            && self.tokens[start..]
+                .iter()
+                .zip(keywords)
+                .all(|(token, keyword)| token.is_keyword(keyword))
+    }
+
+    /// Drops the last `n` tokens.
+    fn truncate(&mut self, n: usize) {
+        self.tokens = &self.tokens[..self.tokens.len().saturating_sub(n)];
+    }
+
+    /// Whether a table constraint, rather than a column, comes next.
+    fn starts_constraint(&self) -> bool {
+        [
+            "constraint",
+            "primary",
+            "unique",
+            "check",
+            "foreign",
+            "exclude",
+        ]
+        .iter()
+        .any(|keyword| self.peek_keyword(keyword))
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("{expected} near \"{}\"", &self.sql[token.start..token.end]),
+            None => format!("{expected} at the end"),
+        }
+    }
+
+    fn unsupported(&self, verb: &str) -> String {
+        let what = self
+            .peek()
+            .map(|token| token.text.to_uppercase())
+            .unwrap_or_default();
+        format!("{verb} {what} is not supported")
+            .trim_start()
+            .to_string()
+    }
+
+    fn end(&self) -> Result<()> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.unexpected("unexpected text"))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.peek().and_then(Token::ident) {
+            Some(ident) => {
+                self.pos += 1;
+                Ok(ident)
+            }
+            None => Err(self.unexpected("expected a name")),
+        }
+    }
+
+    /// `schema.name`, in `public` when unqualified.
+    fn qualified(&mut self) -> Result<(String, String)> {
+        let first = self.ident()?;
+        if self.symbol(".") {
+            Ok((first, self.ident()?))
+        } else {
+            Ok((DEFAULT_SCHEMA.to_string(), first))
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::String => {
+                self.pos += 1;
+                Ok(token.text.clone())
+            }
+            _ => Err(self.unexpected("expected a string")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64> {
+        let negative = self.symbol("-");
+        let value: i64 = self
+            .peek()
+            .and_then(|token| token.text.parse().ok())
+            .ok_or_else(|| self.unexpected("expected a number"))?;
+        self.pos += 1;
+        Ok(if negative { -value } else { value })
+    }
+
+    /// `(a, b)` as a list of names.
+    fn names(&mut self) -> Result<Vec<String>> {
+        self.group()?
+            .split()
+            .into_iter()
+            .map(|mut name| {
+                let ident = name.ident()?;
+                name.end()?;
+                Ok(ident)
+            })
+            .collect()
+    }
+
+    /// The parenthesized group that comes next, as a parser of its own.
+    fn group(&mut self) -> Result<Parser<'a>> {
+        if !self.peek_symbol("(") {
+            return Err(self.unexpected("expected \"(\""));
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            if token.is_symbol("(") {
+                depth += 1;
+            } else if token.is_symbol(")") {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(Parser::new(self.sql, &self.tokens[start..self.pos - 1]));
+                }
+            }
+        }
+        Err(String::from("unbalanced parentheses"))
+    }
+
+    /// The remaining tokens split at top level commas.
+    fn split(&mut self) -> Vec<Parser<'a>> {
+        let mut parts = Vec::new();
+        let mut depth = 0;
+        let mut start = self.pos;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.pos) {
+            if token.is_symbol("(") {
+                depth += 1;
+            } else if token.is_symbol(")") {
+                depth -= 1;
+            } else if depth == 0 && token.is_symbol(",") {
+                parts.push(Parser::new(self.sql, &self.tokens[start..i]));
+                start = i + 1;
+            }
+        }
+        if start < self.tokens.len() {
+            parts.push(Parser::new(self.sql, &self.tokens[start..]));
+        }
+        self.pos = self.tokens.len();
+        parts
+    }
+
+    /// Source text of the tokens from `start` up to `end`.
+    fn text(&self, start: usize, end: usize) -> String {
+        if start >= end {
+            return String::new();
+        }
+        self.sql[self.tokens[start].start..self.tokens[end - 1].end]
+            .trim()
+            .to_string()
+    }
+
+    fn rest(&mut self) -> String {
+        let start = self.pos;
+        self.pos = self.tokens.len();
+        self.text(start, self.pos)
+    }
+
+    /// Source text up to the next top level keyword in `stops`. The first
+    /// token is always taken, so that `DEFAULT NULL` reads `NULL`.
+    fn until(&mut self, stops: &[&str]) -> String {
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            if depth == 0 && self.pos > start && stops.iter().any(|stop| token.is_keyword(stop)) {
+                break;
+            }
+            if token.is_symbol("(") || token.is_keyword("case") {
+                depth += 1;
+            } else if token.is_symbol(")") || token.is_keyword("end") {
+                depth -= 1;
+            }
+            self.pos += 1;
+        }
+        self.text(start, self.pos)
+    }
+
+    /// A column or attribute type, with runs of whitespace collapsed.
+    fn data_type(&mut self) -> Result<String> {
+        let data_type = self.until(COLUMN_CONSTRAINTS);
+        if data_type.is_empty() {
+            return Err(self.unexpected("expected a type"));
+        }
+        Ok(data_type.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// `ALWAYS AS IDENTITY` or `BY DEFAULT AS IDENTITY`, after `GENERATED`.
+    fn identity(&mut self) -> Result<Identity> {
+        self.keyword("generated");
+        let identity = if self.keyword("always") {
+            Identity::Always
+        } else if self.keywords(&["by", "default"]) {
+            Identity::ByDefault
+        } else {
+            return Err(self.unexpected("expected ALWAYS or BY DEFAULT"));
+        };
+        self.expect(&["as", "identity"])?;
+        Ok(identity)
+    }
+
+    fn index_method(&mut self) -> Result<IndexMethod> {
+        let name = self.ident()?;
+        IndexMethod::ALL
+            .into_iter()
+            .find(|method| method.as_str() == name)
+            .ok_or_else(|| format!("unknown index method {name}"))
+    }
+
+    fn referential_action(&mut self) -> Result<ReferentialAction> {
+        let action = if self.keywords(&["no", "action"]) {
+            ReferentialAction::NoAction
+        } else if self.keyword("restrict") {
+            ReferentialAction::Restrict
+        } else if self.keyword("cascade") {
+            ReferentialAction::Cascade
+        } else if self.keywords(&["set", "null"]) {
+            ReferentialAction::SetNull
+        } else if self.keywords(&["set", "default"]) {
+            ReferentialAction::SetDefault
+        } else {
+            return Err(self.unexpected("expected a referential action"));
+        };
+        // Column lists of `SET NULL (...)` are not modelled.
+        if self.peek_symbol("(") {
+            self.group()?;
+        }
+        Ok(action)
+    }
+
+    /// `OWNED BY table.column` as a sequence stores it, or `None` for
+    /// `OWNED BY NONE`.
+    fn owner(&mut self) -> Result<Option<String>> {
+        if self.keyword("none") {
+            return Ok(None);
+        }
+        let mut parts = vec![self.ident()?];
+        while self.symbol(".") {
+            parts.push(self.ident()?);
+        }
+        match parts.as_slice() {
+            [_, _] => Ok(Some(parts.join("."))),
+            [schema, table, column] if schema == DEFAULT_SCHEMA => {
+                Ok(Some(format!("{table}.{column}")))
+            }
+            [_, _, _] => Ok(Some(parts.join("."))),
+            _ => Err(String::from("expected table.column")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::{ddl, diff};
+
+    #[test]
+    fn written_ddl_reads_back_without_differences() {
+        let sql = "
+            CREATE SCHEMA shop;
+            CREATE TYPE shop.status AS ENUM ('new', 'paid');
+            CREATE DOMAIN shop.price AS numeric(10, 2) NOT NULL CHECK (VALUE >= 0);
+            CREATE SEQUENCE shop.order_no START 1000;
+            CREATE TABLE shop.customers (
+                id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                email text NOT NULL UNIQUE,
+                name varchar(100)
+            );
+            CREATE TABLE shop.orders (
+                id bigserial PRIMARY KEY,
+                customer_id bigint NOT NULL REFERENCES shop.customers (id) ON DELETE CASCADE,
+                status shop.status NOT NULL DEFAULT 'new',
+                total shop.price,
+                tags text[] DEFAULT '{}',
+                placed timestamptz DEFAULT now(),
+                CONSTRAINT orders_placed_check CHECK (placed > '2000-01-01')
+            );
+            COMMENT ON TABLE shop.orders IS 'Orders, one per checkout';
+            CREATE UNIQUE INDEX orders_customer_idx ON shop.orders (customer_id, (lower(status::text)))
+                WHERE status <> 'paid';
+            CREATE VIEW shop.open_orders AS SELECT id, total FROM shop.orders WHERE status = 'new';
+        ";
+        let first = script(sql);
+        assert_eq!(first.skipped, []);
+        let second = script(&ddl::project(&first.project));
+        assert_eq!(second.skipped, []);
+
+        let schemas = [String::from("public"), String::from("shop")];
+        let differences = diff::compare(&first.project, &second.project, &schemas);
+        let names: Vec<String> = differences
+            .iter()
+            .map(|d| format!("{} {}", d.object, d.name))
+            .collect();
+        assert_eq!(names, Vec::<String>::new());
+        assert_eq!(second.project.tables.len(), 2);
+        assert_eq!(second.project.views.len(), 1);
+    }
+
+    #[test]
+    fn unnamed_keys_and_indexes_get_the_names_postgres_gives() {
+        let mut reader = Reader::default();
+        reader.read(
+            "CREATE TABLE users (
+                 id integer PRIMARY KEY,
+                 email text UNIQUE,
+                 age integer CHECK (age > 0),
+                 author integer REFERENCES users,
+                 CHECK (age < 200 OR email IS NULL)
+             );
+             CREATE INDEX ON users (lower(email));
+             CREATE INDEX ON users (email, (age + 1));",
+        );
+        let table = &reader.project().tables[0];
+        let constraints: Vec<&str> = table.constraints.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            constraints,
+            [
+                "users_pkey",
+                "users_email_key",
+                "users_age_check",
+                "users_author_fkey",
+                "users_check"
+            ]
+        );
+        let indexes: Vec<&str> = table.indexes.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(indexes, ["users_lower_idx", "users_email_expr_idx"]);
+
+        reader.read(
+            "ALTER TABLE users DROP CONSTRAINT users_email_key;
+             ALTER TABLE users DROP CONSTRAINT users_author_fkey;
+             DROP INDEX users_lower_idx;",
+        );
+        assert_eq!(reader.skipped(), []);
+        let table = &reader.project().tables[0];
+        assert_eq!(table.constraints.len(), 3);
+        assert_eq!(table.indexes.len(), 1);
+    }
+
+    #[test]
+    fn dumps_skip_what_they_cannot_read() {
+        let dump = "
+--
+-- PostgreSQL database dump
+--
+
+-- Dumped from database version 15.4
+
+SET statement_timeout = 0;
+SELECT pg_catalog.set_config('search_path', '', false);
+
+CREATE TABLE public.items (
+    id integer NOT NULL,
+    name text
+);
+
+ALTER TABLE public.items OWNER TO app;
+
+CREATE TEMPORARY TABLE scratch (id integer);
+
+CREATE PUBLICATION everything FOR ALL TABLES;
+
+COPY public.items (id, name) FROM stdin;
+1\tfirst;
+2\tsecond
+\\.
+
+ALTER TABLE ONLY public.items
+    ADD CONSTRAINT items_pkey PRIMARY KEY (id);
+";
+        let parsed = script(dump);
+        assert_eq!(parsed.project.version, PgVersion::from_major(15));
+        assert_eq!(parsed.project.tables.len(), 1);
+        let items = &parsed.project.tables[0];
+        assert_eq!(items.columns.len(), 2);
+        assert!(items.primary_key().is_some());
+
+        let skipped: Vec<&str> = parsed
+            .skipped
+            .iter()
+            .map(|skipped| skipped.statement.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                "CREATE TEMPORARY TABLE scratch (id integer)",
+                "CREATE PUBLICATION everything FOR ALL TABLES"
+            ]
+        );
+        assert!(parsed.skipped.iter().all(|s| !s.reason.is_empty()));
+    }
+
+    #[test]
+    fn index_expressions_keep_their_parentheses_across_round_trips() {
+        let mut sql = String::from(
+            "CREATE TABLE t (a text, b integer);
+             CREATE INDEX t_idx ON t ((a || 'x'), lower(a), ((b + 1)), b DESC);",
+        );
+        for _ in 0..2 {
+            let project = script(&sql).project;
+            let elements: Vec<&str> = project.tables[0].indexes[0]
+                .elements
+                .iter()
+                .map(|element| element.expression.as_str())
+                .collect();
+            assert_eq!(elements, ["a || 'x'", "lower(a)", "(b + 1)", "b"]);
+            sql = ddl::project(&project);
+        }
+    }
+}
@@ -0,0 +1,1248 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{
+    ident_list,
+    lexer::{self, Token, TokenKind},
+    qualified, quote_literal,
+};
+use crate::{
+    datetime::{date, time},
+    model::{
+        data_type, list_items, Column, ConstraintKind, Generation, Project, Table, UserType,
+        UserTypeKind,
+    },
+};
+
+/// Rows for a table pulled in because another one references it.
+pub const DEFAULT_ROWS: usize = 10;
+
+/// Most rows offered for one table.
+pub const MAX_ROWS: usize = 100_000;
+
+/// Tries at a row before giving up on finding unique values for it.
+const ATTEMPTS: usize = 100;
+
+/// Share of NULLs in nullable columns.
+const NULLS: f64 = 0.1;
+
+/// Rows per `INSERT` statement.
+const BATCH: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Insert,
+    Copy,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Insert, Format::Copy];
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Insert => "INSERT",
+            Format::Copy => "COPY",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub format: Format,
+    /// The same seed gives the same data for the same model.
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Sample {
+    pub script: String,
+    /// What the data could not be made to satisfy, and tables that were
+    /// added or cut short.
+    pub notes: Vec<String>,
+}
+
+/// Fake rows for the tables in `rows`, given as table index and row count.
+/// Tables are filled parents first so foreign keys point at rows that
+/// exist, and tables a NOT NULL foreign key needs are added if missing.
+pub fn generate(project: &Project, rows: &[(usize, usize)], options: &Options) -> Sample {
+    let mut generator = Generator {
+        project,
+        rng: StdRng::seed_from_u64(options.seed),
+        rows: HashMap::new(),
+        notes: Vec::new(),
+    };
+
+    let mut counts: Vec<(usize, usize)> = rows.to_vec();
+    let mut k = 0;
+    while k < counts.len() {
+        let table = &project.tables[counts[k].0];
+        for reference in references(project, table) {
+            let required = reference
+                .columns
+                .iter()
+                .flatten()
+                .any(|&j| not_null(project, table, &table.columns[j]));
+            if let Some(parent) = reference.parent
+                && required
+                && !counts.iter().any(|(i, _)| *i == parent)
+            {
+                counts.push((parent, DEFAULT_ROWS));
+                let parent = &project.tables[parent];
+                generator.note(format!(
+                    "Added {}.{} with {DEFAULT_ROWS} rows, since {}.{} references it",
+                    parent.schema, parent.name, table.schema, table.name
+                ));
+            }
+        }
+        k += 1;
+    }
+
+    let order = generator.order(&counts);
+    let mut script = format!("-- Sample data, seed {}\n", options.seed);
+    let mut sequences = Vec::new();
+    for (i, count) in order {
+        let table = &project.tables[i];
+        let plan = generator.plan(i);
+        let rows = generator.table(i, count, &plan);
+        if rows.is_empty() {
+            continue;
+        }
+
+        let included: Vec<usize> = (0..plan.len())
+            .filter(|&j| !matches!(plan[j], Fill::Omit))
+            .collect();
+        let columns = ident_list(included.iter().map(|&j| table.columns[j].name.as_str()));
+        let name = qualified(&table.schema, &table.name);
+        script.push('\n');
+        match options.format {
+            Format::Insert => {
+                let overriding = included
+                    .iter()
+                    .any(|&j| table.columns[j].generation() == Generation::IdentityAlways);
+                for batch in rows.chunks(BATCH) {
+                    script += &format!("INSERT INTO {name} ({columns})");
+                    if overriding {
+                        script += " OVERRIDING SYSTEM VALUE";
+                    }
+                    script += " VALUES\n";
+                    let values: Vec<String> = batch
+                        .iter()
+                        .map(|row| {
+                            let values: Vec<String> =
+                                included.iter().map(|&j| row[j].literal()).collect();
+                            format!("    ({})", values.join(", "))
+                        })
+                        .collect();
+                    script += &values.join(",\n");
+                    script += ";\n";
+                }
+            }
+            Format::Copy => {
+                script += &format!("COPY {name} ({columns}) FROM stdin;\n");
+                for row in &rows {
+                    let values: Vec<String> = included.iter().map(|&j| row[j].copy()).collect();
+                    script += &values.join("\t");
+                    script.push('\n');
+                }
+                script += "\\.\n";
+            }
+        }
+
+        for (j, column) in table.columns.iter().enumerate() {
+            if matches!(plan[j], Fill::Counter { .. })
+                && column.generation() != Generation::None
+                && let Some(last) = rows.last()
+            {
+                sequences.push(format!(
+                    "SELECT setval(pg_get_serial_sequence({}, {}), {});",
+                    quote_literal(&name),
+                    quote_literal(&column.name),
+                    last[j].literal()
+                ));
+            }
+        }
+        generator.rows.insert(i, rows);
+    }
+
+    if !sequences.is_empty() {
+        script.push('\n');
+        script += &sequences.join("\n");
+        script.push('\n');
+    }
+    Sample {
+        script,
+        notes: generator.notes,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Null,
+    /// Written without quotes.
+    Number(String),
+    Text(String),
+}
+
+impl Value {
+    fn literal(&self) -> String {
+        match self {
+            Value::Null => String::from("NULL"),
+            Value::Number(number) => number.clone(),
+            Value::Text(text) => quote_literal(text),
+        }
+    }
+
+    /// The value in `COPY` text format.
+    fn copy(&self) -> String {
+        match self {
+            Value::Null => String::from("\\N"),
+            Value::Number(value) | Value::Text(value) => value
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r"),
+        }
+    }
+
+    /// The value inside an array or composite literal.
+    fn element(&self) -> String {
+        match self {
+            Value::Null => String::from("NULL"),
+            Value::Number(number) => number.clone(),
+            Value::Text(text) => format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")),
+        }
+    }
+}
+
+/// How a column gets its values.
+enum Fill {
+    /// Left to the database, like generated columns.
+    Omit,
+    /// 1, 2, 3, … for serial, identity and single integer primary keys.
+    Counter {
+        start: i64,
+    },
+    Random {
+        rules: Rules,
+        unique: bool,
+    },
+}
+
+/// What check constraints allow for a column.
+#[derive(Clone, Debug, Default)]
+struct Rules {
+    /// Lower bound and whether it is excluded.
+    low: Option<(f64, bool)>,
+    high: Option<(f64, bool)>,
+    choices: Vec<String>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    not_null: bool,
+}
+
+impl Rules {
+    fn add(&mut self, rule: Rule) {
+        match rule {
+            Rule::Low(value, strict) => {
+                if self.low.is_none_or(|(low, _)| value >= low) {
+                    self.low = Some((value, strict));
+                }
+            }
+            Rule::High(value, strict) => {
+                if self.high.is_none_or(|(high, _)| value <= high) {
+                    self.high = Some((value, strict));
+                }
+            }
+            Rule::Choices(choices) => self.choices = choices,
+            Rule::MinLength(length) => {
+                self.min_length = Some(self.min_length.map_or(length, |min| min.max(length)));
+            }
+            Rule::MaxLength(length) => {
+                self.max_length = Some(self.max_length.map_or(length, |max| max.min(length)));
+            }
+            Rule::NotNull => self.not_null = true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Rule {
+    Low(f64, bool),
+    High(f64, bool),
+    Choices(Vec<String>),
+    MinLength(usize),
+    MaxLength(usize),
+    NotNull,
+}
+
+/// A foreign key by column positions, in its table and in the table it
+/// references if that is in the project.
+struct Reference {
+    columns: Vec<Option<usize>>,
+    parent: Option<usize>,
+    parent_columns: Vec<Option<usize>>,
+}
+
+struct Generator<'a> {
+    project: &'a Project,
+    rng: StdRng,
+    /// Rows of the tables filled so far, with a value for every column.
+    rows: HashMap<usize, Vec<Vec<Value>>>,
+    notes: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn note(&mut self, note: String) {
+        if !self.notes.contains(&note) {
+            self.notes.push(note);
+        }
+    }
+
+    /// Parents before children. Tables in a cycle of foreign keys follow in
+    /// the order given.
+    fn order(&mut self, counts: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let selected: HashSet<usize> = counts.iter().map(|(i, _)| *i).collect();
+        let mut order: Vec<(usize, usize)> = Vec::new();
+        let mut left: Vec<(usize, usize)> = counts.to_vec();
+        loop {
+            let ready = left.iter().position(|&(i, _)| {
+                references(self.project, &self.project.tables[i])
+                    .into_iter()
+                    .filter_map(|reference| reference.parent)
+                    .all(|parent| {
+                        parent == i
+                            || !selected.contains(&parent)
+                            || order.iter().any(|(done, _)| *done == parent)
+                    })
+            });
+            match ready {
+                Some(k) => order.push(left.remove(k)),
+                None => break,
+            }
+        }
+        for (i, _) in &left {
+            let table = &self.project.tables[*i];
+            self.note(format!(
+                "{}.{} is in a cycle of foreign keys; its references to tables \
+                 filled later are left NULL",
+                table.schema, table.name
+            ));
+        }
+        order.extend(left);
+        order
+    }
+
+    fn plan(&mut self, i: usize) -> Vec<Fill> {
+        let project = self.project;
+        let table = &project.tables[i];
+        let mut rules = vec![Rules::default(); table.columns.len()];
+        for constraint in &table.constraints {
+            if let ConstraintKind::Check { expression, .. } = &constraint.kind {
+                match read_check(expression) {
+                    Some(read) => {
+                        for (name, rule) in read {
+                            if let Some(j) = table.columns.iter().position(|c| c.name == name) {
+                                rules[j].add(rule);
+                            }
+                        }
+                    }
+                    None => self.note(format!(
+                        "{}.{}: check {} is not understood, rows may break it",
+                        table.schema, table.name, constraint.name
+                    )),
+                }
+            }
+        }
+        // A domain's checks hold for the columns of the domain, with VALUE
+        // standing for the column.
+        for (j, column) in table.columns.iter().enumerate() {
+            for domain in domains(project, &column.data_type, &table.schema) {
+                let UserTypeKind::Domain { checks, .. } = &domain.kind else {
+                    continue;
+                };
+                for check in checks {
+                    match read_check(&check.expression) {
+                        Some(read) => {
+                            for (name, rule) in read {
+                                if name == "value" {
+                                    rules[j].add(rule);
+                                }
+                            }
+                        }
+                        None => self.note(format!(
+                            "{}.{}: check {} is not understood, rows may break it",
+                            domain.schema, domain.name, check.name
+                        )),
+                    }
+                }
+            }
+        }
+
+        let keys = keys(table);
+        let references = references(project, table);
+        let primary_key: Vec<usize> = table
+            .primary_key()
+            .and_then(|pk| match &pk.kind {
+                ConstraintKind::PrimaryKey { columns, .. } => Some(positions(table, columns)),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(j, column)| {
+                let referencing = references
+                    .iter()
+                    .any(|reference| reference.columns.contains(&Some(j)));
+                let integer = matches!(
+                    data_type::canonical(&column.data_type).as_str(),
+                    "smallint" | "integer" | "bigint"
+                );
+                let start = rules[j].low.map_or(1, |(low, strict)| {
+                    if strict {
+                        low.floor() as i64 + 1
+                    } else {
+                        low.ceil() as i64
+                    }
+                });
+                match column.generation() {
+                    Generation::Stored => Fill::Omit,
+                    Generation::Serial
+                    | Generation::IdentityAlways
+                    | Generation::IdentityByDefault => Fill::Counter { start },
+                    Generation::None if primary_key == [j] && integer && !referencing => {
+                        Fill::Counter { start }
+                    }
+                    // Nothing to make up, so the default it is.
+                    Generation::None
+                        if column.default.is_some()
+                            && self
+                                .value(table, column, &column.data_type, &rules[j], 0, false)
+                                .is_none() =>
+                    {
+                        Fill::Omit
+                    }
+                    Generation::None => Fill::Random {
+                        rules: std::mem::take(&mut rules[j]),
+                        unique: keys.iter().any(|(columns, _)| columns.contains(&j)),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn table(&mut self, i: usize, count: usize, plan: &[Fill]) -> Vec<Vec<Value>> {
+        let project = self.project;
+        let table = &project.tables[i];
+        let keys = keys(table);
+        let references = references(project, table);
+        let key_columns: HashSet<usize> = keys.iter().flat_map(|(c, _)| c.clone()).collect();
+        let mut seen: Vec<HashSet<Vec<String>>> = vec![HashSet::new(); keys.len()];
+        let mut rows: Vec<Vec<Value>> = Vec::with_capacity(count);
+
+        'rows: for n in 0..count {
+            for _ in 0..ATTEMPTS {
+                let mut row: Vec<Value> = Vec::with_capacity(plan.len());
+                for (j, fill) in plan.iter().enumerate() {
+                    let column = &table.columns[j];
+                    let nullable = !not_null(project, table, column)
+                        && !key_columns.contains(&j)
+                        && !matches!(fill, Fill::Random { rules, .. } if rules.not_null);
+                    row.push(match fill {
+                        Fill::Omit => Value::Null,
+                        Fill::Counter { start } => Value::Number((start + n as i64).to_string()),
+                        Fill::Random { .. } if nullable && self.rng.gen_bool(NULLS) => Value::Null,
+                        Fill::Random { rules, unique } => {
+                            let value =
+                                self.value(table, column, &column.data_type, rules, n, *unique);
+                            match value {
+                                Some(value) => value,
+                                None if nullable => Value::Null,
+                                None => {
+                                    self.note(format!(
+                                        "{}.{}.{}: no sample values for type {}",
+                                        table.schema, table.name, column.name, column.data_type
+                                    ));
+                                    Value::Text(String::new())
+                                }
+                            }
+                        }
+                    });
+                }
+
+                for reference in &references {
+                    self.reference(table, i, &mut row, &rows, reference);
+                }
+
+                let found: Vec<Option<Vec<String>>> = keys
+                    .iter()
+                    .map(|(columns, nulls_not_distinct)| {
+                        let values: Vec<&Value> = columns.iter().map(|&j| &row[j]).collect();
+                        (*nulls_not_distinct || !values.contains(&&Value::Null))
+                            .then(|| values.iter().map(|value| value.literal()).collect())
+                    })
+                    .collect();
+                let clash = found
+                    .iter()
+                    .zip(&seen)
+                    .any(|(key, seen)| key.as_ref().is_some_and(|key| seen.contains(key)));
+                if !clash {
+                    for (key, seen) in found.into_iter().zip(&mut seen) {
+                        seen.extend(key);
+                    }
+                    rows.push(row);
+                    continue 'rows;
+                }
+            }
+            self.note(format!(
+                "{}.{}: ran out of unique values after {n} of {count} rows",
+                table.schema, table.name
+            ));
+            break;
+        }
+        rows
+    }
+
+    /// Copies the referenced columns of a random parent row into `row`. A
+    /// table referencing itself can also point at the row being made.
+    fn reference(
+        &mut self,
+        table: &Table,
+        i: usize,
+        row: &mut [Value],
+        earlier: &[Vec<Value>],
+        reference: &Reference,
+    ) {
+        let nullable = reference
+            .columns
+            .iter()
+            .flatten()
+            .all(|&j| !not_null(self.project, table, &table.columns[j]));
+        let Some(parent) = reference.parent else {
+            return;
+        };
+        let ref_columns = &reference.parent_columns;
+        let parent_rows: &[Vec<Value>] = if parent == i {
+            earlier
+        } else {
+            self.rows.get(&parent).map_or(&[], Vec::as_slice)
+        };
+
+        let picked: Option<Vec<Value>> =
+            if nullable && (parent_rows.is_empty() || self.rng.gen_bool(NULLS)) {
+                None
+            } else if let Some(parent_row) = parent_rows.choose(&mut self.rng) {
+                Some(
+                    ref_columns
+                        .iter()
+                        .map(|k| k.map_or(Value::Null, |k| parent_row[k].clone()))
+                        .collect(),
+                )
+            } else if parent == i {
+                Some(
+                    ref_columns
+                        .iter()
+                        .map(|k| k.map_or(Value::Null, |k| row[k].clone()))
+                        .collect(),
+                )
+            } else {
+                let parent = &self.project.tables[parent];
+                self.note(format!(
+                    "{}.{} references {}.{}, which gets no rows",
+                    table.schema, table.name, parent.schema, parent.name
+                ));
+                return;
+            };
+        for (k, j) in reference.columns.iter().enumerate() {
+            if let Some(j) = j {
+                row[*j] = picked
+                    .as_ref()
+                    .map_or(Value::Null, |values| values[k].clone());
+            }
+        }
+    }
+
+    /// A random value of `data_type`, or `None` for types without sample
+    /// values.
+    fn value(
+        &mut self,
+        table: &Table,
+        column: &Column,
+        data_type: &str,
+        rules: &Rules,
+        n: usize,
+        unique: bool,
+    ) -> Option<Value> {
+        let project = self.project;
+        let canonical = data_type::canonical(data_type);
+        if let Some(element) = canonical.strip_suffix("[]") {
+            let element = data_type.trim_end().strip_suffix("[]").unwrap_or(element);
+            let length = self.rng.gen_range(1..=3);
+            let mut elements = Vec::with_capacity(length);
+            for _ in 0..length {
+                elements.push(self.value(table, column, element, &Rules::default(), n, false)?);
+            }
+            let elements: Vec<String> = elements.iter().map(Value::element).collect();
+            return Some(Value::Text(format!("{{{}}}", elements.join(","))));
+        }
+
+        if let Some(ty) = project.resolve_type(data_type, &table.schema) {
+            return match &project.types[ty].kind {
+                UserTypeKind::Enum { labels } => {
+                    let allowed: Vec<&String> = labels
+                        .iter()
+                        .filter(|label| rules.choices.is_empty() || rules.choices.contains(label))
+                        .collect();
+                    allowed
+                        .choose(&mut self.rng)
+                        .map(|label| Value::Text(label.to_string()))
+                }
+                UserTypeKind::Domain {
+                    data_type, checks, ..
+                } => {
+                    let mut rules = rules.clone();
+                    for check in checks {
+                        for (name, rule) in read_check(&check.expression).unwrap_or_default() {
+                            if name == "value" {
+                                rules.add(rule);
+                            }
+                        }
+                    }
+                    self.value(table, column, data_type, &rules, n, unique)
+                }
+                UserTypeKind::Composite { attributes } => {
+                    let mut values = Vec::with_capacity(attributes.len());
+                    for attribute in attributes {
+                        let value = self.value(
+                            table,
+                            column,
+                            &attribute.data_type,
+                            &Rules::default(),
+                            n,
+                            false,
+                        )?;
+                        values.push(value.element());
+                    }
+                    Some(Value::Text(format!("({})", values.join(","))))
+                }
+            };
+        }
+
+        let number = match canonical.as_str() {
+            "smallint" => Some((1.0, 1_000.0, 0)),
+            "integer" | "bigint" => Some((1.0, 100_000.0, 0)),
+            "numeric" => {
+                let (precision, scale) = modifiers(data_type);
+                let scale = scale.unwrap_or(2).min(6);
+                let high = match precision {
+                    Some(precision) if precision > scale => {
+                        (10f64.powi((precision - scale).min(12) as i32) - 1.0).min(1_000.0)
+                    }
+                    Some(_) => 0.0,
+                    None => 1_000.0,
+                };
+                Some((0.0, high, scale))
+            }
+            "real" | "double precision" | "money" => Some((0.0, 1_000.0, 2)),
+            _ => None,
+        };
+        if !rules.choices.is_empty() {
+            let choice = rules.choices.choose(&mut self.rng)?.clone();
+            return Some(match number {
+                Some(_) => Value::Number(choice),
+                None => Value::Text(choice),
+            });
+        }
+        if let Some((low, high, scale)) = number {
+            return Some(Value::Number(self.number(rules, low, high, scale)));
+        }
+
+        let rng = &mut self.rng;
+        let value = match canonical.as_str() {
+            "boolean" => Value::Number(rng.gen_bool(0.5).to_string()),
+            "text" | "character varying" | "character" | "citext" | "name" => {
+                let mut text = self.text(&column.name, n, unique);
+                let length = modifiers(data_type).0.map(|length| length as usize);
+                let max = match (length, rules.max_length) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                if let Some(min) = rules.min_length {
+                    while text.chars().count() < min {
+                        text.push('x');
+                    }
+                }
+                if let Some(max) = max {
+                    text = text.chars().take(max).collect();
+                }
+                Value::Text(text)
+            }
+            "uuid" => {
+                let bytes: [u8; 16] = rng.r#gen();
+                let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                Value::Text(format!(
+                    "{}-{}-4{}-{:x}{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[13..16],
+                    8 + (bytes[8] & 3),
+                    &hex[17..20],
+                    &hex[20..32]
+                ))
+            }
+            "date" => Value::Text(date(rng.gen_range(FIRST_DAY..=LAST_DAY))),
+            "timestamp without time zone" | "timestamp with time zone" => {
+                let mut text = format!(
+                    "{} {}",
+                    date(rng.gen_range(FIRST_DAY..=LAST_DAY)),
+                    time(rng.gen_range(0..86_400))
+                );
+                if canonical.ends_with("with time zone") {
+                    text += "+00";
+                }
+                Value::Text(text)
+            }
+            "time without time zone" | "time with time zone" => {
+                let mut text = time(rng.gen_range(0..86_400));
+                if canonical.ends_with("with time zone") {
+                    text += "+00";
+                }
+                Value::Text(text)
+            }
+            "interval" => Value::Text(format!("{} days", rng.gen_range(1..=90))),
+            "json" | "jsonb" => Value::Text(format!(
+                "{{\"{}\": {}}}",
+                WORDS.choose(rng)?,
+                rng.gen_range(1..=100)
+            )),
+            "bytea" => {
+                let bytes: [u8; 8] = rng.r#gen();
+                let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                Value::Text(format!("\\x{hex}"))
+            }
+            "inet" => Value::Text(format!(
+                "192.168.{}.{}",
+                rng.gen_range(0..=255),
+                rng.gen_range(1..=254)
+            )),
+            "cidr" => Value::Text(format!("10.{}.0.0/16", rng.gen_range(0..=255))),
+            "macaddr" => {
+                let bytes: [u8; 6] = rng.r#gen();
+                let parts: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                Value::Text(parts.join(":"))
+            }
+            "tsvector" => {
+                let words: Vec<&str> = WORDS.choose_multiple(rng, 3).copied().collect();
+                Value::Text(words.join(" "))
+            }
+            "xml" => Value::Text(format!("<value>{}</value>", rng.gen_range(1..=100))),
+            "point" => Value::Text(format!(
+                "({},{})",
+                rng.gen_range(-180..=180),
+                rng.gen_range(-90..=90)
+            )),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// A number between `low` and `high` with `scale` decimals, narrowed by
+    /// the column's checks.
+    fn number(&mut self, rules: &Rules, low: f64, high: f64, scale: u32) -> String {
+        let unit = 10f64.powi(scale as i32);
+        let mut min = (low * unit).ceil() as i64;
+        let mut max = (high * unit).floor() as i64;
+        if let Some((value, strict)) = rules.low {
+            let bound = value * unit;
+            min = if strict {
+                bound.floor() as i64 + 1
+            } else {
+                bound.ceil() as i64
+            };
+            max = max.max(min);
+        }
+        if let Some((value, strict)) = rules.high {
+            let bound = value * unit;
+            max = if strict {
+                bound.ceil() as i64 - 1
+            } else {
+                bound.floor() as i64
+            };
+            min = min.min(max);
+        }
+        let units = self.rng.gen_range(min..=max);
+        if scale == 0 {
+            return units.to_string();
+        }
+        let sign = if units < 0 { "-" } else { "" };
+        let units = units.unsigned_abs();
+        let unit = unit as u64;
+        format!(
+            "{sign}{}.{:0width$}",
+            units / unit,
+            units % unit,
+            width = scale as usize
+        )
+    }
+
+    /// Text that fits the column name, such as an email address for `email`.
+    /// Values for unique columns carry the row number.
+    fn text(&mut self, column: &str, n: usize, unique: bool) -> String {
+        let name = column.to_lowercase();
+        let rng = &mut self.rng;
+        let first = *FIRST_NAMES.choose(rng).unwrap_or(&"");
+        let last = *LAST_NAMES.choose(rng).unwrap_or(&"");
+        let has = |words: &[&str]| words.iter().any(|word| name.contains(word));
+        let text = if has(&["email", "mail"]) {
+            return format!(
+                "{}.{}{}@example.com",
+                first.to_lowercase(),
+                last.to_lowercase(),
+                n + 1
+            );
+        } else if has(&["username", "login", "handle"]) {
+            return format!("{}{}", first.to_lowercase(), n + 1);
+        } else if has(&["first_name", "firstname", "given"]) {
+            first.to_string()
+        } else if has(&["last_name", "lastname", "surname", "family"]) {
+            last.to_string()
+        } else if has(&["city", "town"]) {
+            CITIES.choose(rng).unwrap_or(&"").to_string()
+        } else if has(&["country"]) {
+            COUNTRIES.choose(rng).unwrap_or(&"").to_string()
+        } else if has(&["phone", "mobile"]) {
+            format!("+1 555 {:04}", rng.gen_range(0..10_000))
+        } else if has(&["url", "website", "link"]) {
+            format!("https://example.com/{}", WORDS.choose(rng).unwrap_or(&""))
+        } else if has(&["address", "street"]) {
+            format!(
+                "{} {} Street",
+                rng.gen_range(1..=999),
+                LAST_NAMES.choose(rng).unwrap_or(&"")
+            )
+        } else if has(&["zip", "postal"]) {
+            format!("{:05}", rng.gen_range(0..100_000))
+        } else if has(&["password", "hash", "token", "secret"]) {
+            (0..32)
+                .map(|_| format!("{:02x}", rng.r#gen::<u8>()))
+                .collect()
+        } else if has(&["color", "colour"]) {
+            format!("#{:06x}", rng.gen_range(0..0x100_0000))
+        } else if has(&["code", "sku"]) {
+            (0..8)
+                .map(|_| char::from(b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789"[rng.gen_range(0..32)]))
+                .collect()
+        } else if has(&["status", "state"]) {
+            STATUSES.choose(rng).unwrap_or(&"").to_string()
+        } else if has(&["description", "comment", "note", "body", "bio", "summary"]) {
+            let words: Vec<&str> = WORDS.choose_multiple(rng, 8).copied().collect();
+            let sentence = words.join(" ") + ".";
+            sentence[..1].to_uppercase() + &sentence[1..]
+        } else if has(&["name"]) {
+            format!("{first} {last}")
+        } else {
+            let words: Vec<&str> = WORDS.choose_multiple(rng, 2).copied().collect();
+            words.join(" ")
+        };
+        if unique {
+            format!("{text} {}", n + 1)
+        } else {
+            text
+        }
+    }
+}
+
+/// Unique and primary keys by column positions, and whether NULLs count
+/// as equal. Unique indexes on plain columns count too.
+fn keys(table: &Table) -> Vec<(Vec<usize>, bool)> {
+    let mut keys = Vec::new();
+    for constraint in &table.constraints {
+        match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns, .. } => {
+                keys.push((positions(table, columns), true));
+            }
+            ConstraintKind::Unique {
+                columns,
+                nulls_not_distinct,
+                ..
+            } => keys.push((positions(table, columns), *nulls_not_distinct)),
+            _ => {}
+        }
+    }
+    for index in &table.indexes {
+        if index.unique
+            && index.predicate.is_none()
+            && index.elements.iter().all(|element| element.is_column())
+        {
+            let columns: Vec<String> = index
+                .elements
+                .iter()
+                .map(|element| element.expression.trim().trim_matches('"').to_string())
+                .collect();
+            keys.push((positions(table, &columns), index.nulls_not_distinct));
+        }
+    }
+    keys.retain(|(columns, _)| !columns.is_empty());
+    keys
+}
+
+fn positions(table: &Table, columns: &[String]) -> Vec<usize> {
+    list_items(columns)
+        .filter_map(|name| table.columns.iter().position(|c| c.name == name))
+        .collect()
+}
+
+fn references(project: &Project, table: &Table) -> Vec<Reference> {
+    table
+        .constraints
+        .iter()
+        .filter_map(|constraint| match &constraint.kind {
+            ConstraintKind::ForeignKey(fk) => {
+                let columns = list_items(&fk.columns)
+                    .map(|name| table.columns.iter().position(|c| c.name == name))
+                    .collect();
+                let parent = project
+                    .tables
+                    .iter()
+                    .position(|t| t.schema == fk.ref_schema && t.name == fk.ref_table);
+                // Without columns, a foreign key references the primary key.
+                let parent_columns = parent.map_or_else(Vec::new, |parent| {
+                    let parent = &project.tables[parent];
+                    let mut names: Vec<&str> = list_items(&fk.ref_columns).collect();
+                    if names.is_empty()
+                        && let Some(ConstraintKind::PrimaryKey { columns, .. }) =
+                            parent.primary_key().map(|pk| &pk.kind)
+                    {
+                        names = list_items(columns).collect();
+                    }
+                    names
+                        .iter()
+                        .map(|name| parent.columns.iter().position(|c| c.name == *name))
+                        .collect()
+                });
+                Some(Reference {
+                    columns,
+                    parent,
+                    parent_columns,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// `(12, 2)` for `numeric(12,2)`, `(255, None)` for `varchar(255)`.
+fn modifiers(data_type: &str) -> (Option<u32>, Option<u32>) {
+    let Some((_, rest)) = data_type.split_once('(') else {
+        return (None, None);
+    };
+    let inner = rest.split(')').next().unwrap_or_default();
+    let mut parts = inner.split(',').map(|part| part.trim().parse().ok());
+    (parts.next().flatten(), parts.next().flatten())
+}
+
+/// Whether `column` can't be NULL, as it says so or its domain does.
+fn not_null(project: &Project, table: &Table, column: &Column) -> bool {
+    column.not_null
+        || domains(project, &column.data_type, &table.schema)
+            .iter()
+            .any(|domain| matches!(domain.kind, UserTypeKind::Domain { not_null: true, .. }))
+}
+
+/// The domain `data_type` names, followed by the domains it is based on in
+/// turn. Arrays of a domain are none of them.
+fn domains<'a>(project: &'a Project, data_type: &str, schema: &str) -> Vec<&'a UserType> {
+    let mut domains: Vec<&UserType> = Vec::new();
+    let (mut data_type, mut schema) = (data_type, schema);
+    while !data_type.trim_end().ends_with("[]")
+        && domains.len() < project.types.len()
+        && let Some(i) = project.resolve_type(data_type, schema)
+        && let UserTypeKind::Domain {
+            data_type: base, ..
+        } = &project.types[i].kind
+    {
+        domains.push(&project.types[i]);
+        (data_type, schema) = (base, &project.types[i].schema);
+    }
+    domains
+}
+
+/// Reads a check made of simple conditions on single columns joined by
+/// AND: comparisons with numbers, BETWEEN, IN and `= ANY (ARRAY[...])`
+/// lists, and comparisons of `length(column)`. Returns `None` for anything
+/// else.
+fn read_check(expression: &str) -> Option<Vec<(String, Rule)>> {
+    let tokens = simplify(lexer::tokenize(expression));
+    let mut rules = Vec::new();
+    let mut start = 0;
+    let mut between = false;
+    for (k, token) in tokens.iter().enumerate() {
+        if token.is_keyword("between") {
+            between = true;
+        } else if token.is_keyword("and") {
+            if between {
+                between = false;
+            } else {
+                rules.extend(read_condition(&tokens[start..k])?);
+                start = k + 1;
+            }
+        }
+    }
+    rules.extend(read_condition(&tokens[start..])?);
+    Some(rules)
+}
+
+fn read_condition(tokens: &[Token]) -> Option<Vec<(String, Rule)>> {
+    let number = |tokens: &[Token]| -> Option<f64> {
+        match tokens {
+            [token] if token.kind == TokenKind::Number => token.text.parse().ok(),
+            [sign, token] if sign.is_symbol("-") && token.kind == TokenKind::Number => {
+                token.text.parse::<f64>().ok().map(|n| -n)
+            }
+            _ => None,
+        }
+    };
+    let compare = |op: &str, value: f64| -> Option<Vec<Rule>> {
+        Some(match op {
+            ">" => vec![Rule::Low(value, true)],
+            ">=" => vec![Rule::Low(value, false)],
+            "<" => vec![Rule::High(value, true)],
+            "<=" => vec![Rule::High(value, false)],
+            "=" => vec![Rule::Low(value, false), Rule::High(value, false)],
+            _ => return None,
+        })
+    };
+    fn flip(op: &str) -> &str {
+        match op {
+            ">" => "<",
+            ">=" => "<=",
+            "<" => ">",
+            "<=" => ">=",
+            other => other,
+        }
+    }
+    let literals = |tokens: &[Token]| -> Option<Vec<String>> {
+        tokens
+            .iter()
+            .filter(|token| !token.is_symbol(","))
+            .map(|token| match token.kind {
+                TokenKind::String | TokenKind::Number => Some(token.text.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+    let with = |name: String, rules: Vec<Rule>| {
+        Some(rules.into_iter().map(|rule| (name.clone(), rule)).collect())
+    };
+
+    let (first, rest) = tokens.split_first()?;
+    if matches!(first.kind, TokenKind::Number) || first.is_symbol("-") {
+        // `0 < price`
+        let op = rest
+            .iter()
+            .position(|t| t.kind == TokenKind::Symbol && !t.is_symbol("-"))?;
+        let value = number(&tokens[..op + 1])?;
+        let [name] = &rest[op + 1..] else {
+            return None;
+        };
+        return with(name.ident()?, compare(flip(&rest[op].text), value)?);
+    }
+
+    if ["length", "char_length", "character_length"]
+        .iter()
+        .any(|f| first.is_keyword(f))
+    {
+        let (column, rest) = rest.split_first()?;
+        let (op, rest) = rest.split_first()?;
+        let length = number(rest)?.max(0.0);
+        let rules = match op.text.as_str() {
+            ">" => vec![Rule::MinLength(length as usize + 1)],
+            ">=" => vec![Rule::MinLength(length as usize)],
+            "<" => vec![Rule::MaxLength((length as usize).saturating_sub(1))],
+            "<=" => vec![Rule::MaxLength(length as usize)],
+            "=" => vec![
+                Rule::MinLength(length as usize),
+                Rule::MaxLength(length as usize),
+            ],
+            _ => return None,
+        };
+        return with(column.ident()?, rules);
+    }
+
+    let name = first.ident()?;
+    match rest {
+        [is, not, null]
+            if is.is_keyword("is") && not.is_keyword("not") && null.is_keyword("null") =>
+        {
+            with(name, vec![Rule::NotNull])
+        }
+        [between, rest @ ..] if between.is_keyword("between") => {
+            let and = rest.iter().position(|t| t.is_keyword("and"))?;
+            with(
+                name,
+                vec![
+                    Rule::Low(number(&rest[..and])?, false),
+                    Rule::High(number(&rest[and + 1..])?, false),
+                ],
+            )
+        }
+        [in_, rest @ ..] if in_.is_keyword("in") => {
+            with(name, vec![Rule::Choices(literals(rest)?)])
+        }
+        [eq, any, array, open, rest @ .., close]
+            if eq.is_symbol("=")
+                && any.is_keyword("any")
+                && array.is_keyword("array")
+                && open.is_symbol("[")
+                && close.is_symbol("]") =>
+        {
+            with(name, vec![Rule::Choices(literals(rest)?)])
+        }
+        [op, empty]
+            if matches!(op.text.as_str(), "<>" | "!=")
+                && empty.kind == TokenKind::String
+                && empty.text.is_empty() =>
+        {
+            with(name, vec![Rule::MinLength(1)])
+        }
+        [op, rest @ ..] if op.kind == TokenKind::Symbol => {
+            with(name, compare(&op.text, number(rest)?)?)
+        }
+        _ => None,
+    }
+}
+
+/// Drops parentheses and casts, which pg_dump adds generously, as in
+/// `((status)::text = ANY ((ARRAY['a'::character varying])::text[]))`.
+fn simplify(tokens: Vec<Token>) -> Vec<Token> {
+    let mut simplified = Vec::with_capacity(tokens.len());
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if token.is_symbol("(") || token.is_symbol(")") {
+            continue;
+        }
+        if !token.is_symbol("::") {
+            simplified.push(token);
+            continue;
+        }
+        tokens.next_if(|t| matches!(t.kind, TokenKind::Word | TokenKind::Quoted));
+        if tokens.next_if(|t| t.is_symbol(".")).is_some() {
+            tokens.next_if(|t| matches!(t.kind, TokenKind::Word | TokenKind::Quoted));
+        }
+        while tokens
+            .next_if(|t| {
+                ["varying", "precision", "with", "without", "time", "zone"]
+                    .iter()
+                    .any(|word| t.is_keyword(word))
+            })
+            .is_some()
+        {}
+        // Type modifiers, then array brackets.
+        while tokens
+            .next_if(|t| t.kind == TokenKind::Number || t.is_symbol(","))
+            .is_some()
+        {}
+        while tokens.peek().is_some_and(|t| t.is_symbol("[")) {
+            tokens.next();
+            tokens.next_if(|t| t.is_symbol("]"));
+        }
+    }
+    simplified
+}
+
+/// Days since 1970-01-01 of 2020-01-01 and 2025-12-31.
+const FIRST_DAY: i64 = 18_262;
+const LAST_DAY: i64 = 20_453;
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bruno", "Chloe", "Daniel", "Emma", "Felix", "Grace", "Hugo", "Iris", "Jonas", "Kara",
+    "Liam", "Maya", "Noah", "Olga", "Pablo", "Quinn", "Rosa", "Sam", "Tara",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Anderson", "Becker", "Costa", "Dubois", "Evans", "Fischer", "Garcia", "Hansen", "Ito",
+    "Jensen", "Kowalski", "Lopez", "Moreau", "Novak", "Olsen", "Petrov", "Rossi", "Silva",
+];
+
+const CITIES: &[&str] = &[
+    "Amsterdam",
+    "Berlin",
+    "Chicago",
+    "Dublin",
+    "Lisbon",
+    "Madrid",
+    "Oslo",
+    "Paris",
+    "Prague",
+    "Seoul",
+    "Sydney",
+    "Tokyo",
+    "Toronto",
+    "Vienna",
+];
+
+const COUNTRIES: &[&str] = &[
+    "Austria", "Brazil", "Canada", "France", "Germany", "Ireland", "Japan", "Norway", "Portugal",
+    "Spain",
+];
+
+const STATUSES: &[&str] = &["active", "inactive", "pending", "archived"];
+
+const WORDS: &[&str] = &[
+    "alpha", "amber", "anchor", "beacon", "bright", "canyon", "cedar", "cobalt", "delta", "ember",
+    "falcon", "forest", "garden", "harbor", "island", "jade", "lantern", "maple", "meadow", "nova",
+    "orbit", "pepper", "quartz", "river", "silver", "summit", "timber", "velvet", "willow",
+    "zephyr",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::parse;
+
+    /// The rows of the only table, from a `COPY` script.
+    fn rows(sql: &str, count: usize) -> Vec<Vec<String>> {
+        let project = parse::script(sql).project;
+        let options = Options {
+            format: Format::Copy,
+            seed: 7,
+        };
+        let sample = generate(&project, &[(0, count)], &options);
+        sample
+            .script
+            .lines()
+            .skip_while(|line| !line.starts_with("COPY"))
+            .skip(1)
+            .take_while(|line| *line != "\\.")
+            .map(|line| line.split('\t').map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn domains_constrain_their_columns() {
+        let rows = rows(
+            "CREATE DOMAIN score AS integer NOT NULL CHECK (VALUE BETWEEN 1 AND 5);
+             CREATE DOMAIN rating AS score;
+             CREATE TABLE t (a score, b rating, c integer CHECK (c IS NOT NULL));",
+            200,
+        );
+        assert_eq!(rows.len(), 200);
+        for row in rows {
+            for value in &row {
+                assert_ne!(value, "\\N");
+            }
+            for value in &row[..2] {
+                assert!((1..=5).contains(&value.parse::<i64>().unwrap()), "{value}");
+            }
+        }
+    }
+}
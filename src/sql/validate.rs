@@ -0,0 +1,669 @@
+use std::{collections::HashMap, fmt};
+
+use super::{compat, keywords};
+use crate::model::{
+    data_type, list_items,
+    naming::{Rename, Target},
+    ConstraintKind, Project, Table, UserTypeKind,
+};
+
+/// PostgreSQL keeps this many bytes of an identifier and drops the rest.
+const MAX_IDENTIFIER: usize = 63;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The script would fail.
+    Error,
+    /// The script runs, but likely not as intended.
+    Warning,
+    Notice,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Notice => "notice",
+        })
+    }
+}
+
+/// The object a problem was found on, by index into the project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subject {
+    Table(usize),
+    Type(usize),
+    View(usize),
+    Routine(usize),
+    Sequence(usize),
+    Role(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    pub subject: Option<Subject>,
+    /// `schema.relation`, followed by the column, constraint or index name
+    /// when the problem is about one.
+    pub object: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.object, self.message)
+    }
+}
+
+/// Everything wrong with `project`, errors first.
+pub fn check(project: &Project) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for incompatibility in compat::check(project) {
+        problems.push(Problem {
+            severity: Severity::Error,
+            subject: subject_of(project, &incompatibility.object),
+            message: format!(
+                "{} requires PostgreSQL {}",
+                incompatibility.feature,
+                incompatibility.feature.since()
+            ),
+            object: incompatibility.object,
+        });
+    }
+    names(project, &mut problems);
+    duplicates(project, &mut problems);
+    for (i, table) in project.tables.iter().enumerate() {
+        keys(project, i, table, &mut problems);
+    }
+    cycles(project, &mut problems);
+    if let Some(conventions) = &project.conventions {
+        for rename in conventions.renames(project) {
+            problems.push(convention(project, rename));
+        }
+    }
+    problems.sort_by_key(|problem| problem.severity);
+    problems
+}
+
+fn convention(project: &Project, rename: Rename) -> Problem {
+    let (i, what) = match rename.target {
+        Target::Table(i) => (i, "table"),
+        Target::Column(i, _) => (i, "column"),
+        Target::Constraint(i, _) => (i, "constraint"),
+        Target::Index(i, _) => (i, "index"),
+    };
+    let table = &project.tables[i];
+    let object = if matches!(rename.target, Target::Table(_)) {
+        format!("{}.{}", table.schema, table.name)
+    } else {
+        format!("{}.{}.{}", table.schema, table.name, rename.from)
+    };
+    Problem {
+        severity: Severity::Warning,
+        subject: Some(Subject::Table(i)),
+        object,
+        message: format!("{what} should be named {} by the conventions", rename.to),
+    }
+}
+
+/// The table or view `object` is on, from its `schema.relation` prefix.
+fn subject_of(project: &Project, object: &str) -> Option<Subject> {
+    let on = |schema: &str, name: &str| {
+        let relation = format!("{schema}.{name}");
+        object == relation || object.starts_with(&format!("{relation}."))
+    };
+    project
+        .tables
+        .iter()
+        .position(|t| on(&t.schema, &t.name))
+        .map(Subject::Table)
+        .or_else(|| {
+            project
+                .views
+                .iter()
+                .position(|v| on(&v.schema, &v.name))
+                .map(Subject::View)
+        })
+}
+
+/// Reserved words and names too long to be kept whole.
+fn names(project: &Project, problems: &mut Vec<Problem>) {
+    let mut check = |subject: Option<Subject>, object: String, name: &str| {
+        let name = name.trim();
+        if keywords::is_reserved(name) {
+            problems.push(Problem {
+                severity: Severity::Warning,
+                subject,
+                object: object.clone(),
+                message: format!("\"{name}\" is a reserved word and has to be quoted"),
+            });
+        }
+        if name.len() > MAX_IDENTIFIER {
+            problems.push(Problem {
+                severity: Severity::Warning,
+                subject,
+                object,
+                message: format!(
+                    "name is {} bytes, PostgreSQL cuts it to {MAX_IDENTIFIER}",
+                    name.len()
+                ),
+            });
+        }
+    };
+
+    for schema in project.schemas() {
+        check(None, String::from(schema), schema);
+    }
+    for (i, table) in project.tables.iter().enumerate() {
+        let subject = Some(Subject::Table(i));
+        let relation = format!("{}.{}", table.schema, table.name);
+        check(subject, relation.clone(), &table.name);
+        for column in &table.columns {
+            check(subject, format!("{relation}.{}", column.name), &column.name);
+        }
+        for constraint in &table.constraints {
+            check(
+                subject,
+                format!("{relation}.{}", constraint.name),
+                &constraint.name,
+            );
+        }
+        for index in &table.indexes {
+            check(subject, format!("{relation}.{}", index.name), &index.name);
+        }
+        for trigger in &table.triggers {
+            check(
+                subject,
+                format!("{relation}.{}", trigger.name),
+                &trigger.name,
+            );
+        }
+        for policy in &table.policies {
+            check(subject, format!("{relation}.{}", policy.name), &policy.name);
+        }
+        for partition in table.partitioning.iter().flat_map(|p| &p.partitions) {
+            check(
+                subject,
+                format!("{}.{}", table.schema, partition.name),
+                &partition.name,
+            );
+        }
+    }
+    for (i, view) in project.views.iter().enumerate() {
+        let relation = format!("{}.{}", view.schema, view.name);
+        check(Some(Subject::View(i)), relation.clone(), &view.name);
+        for index in &view.indexes {
+            check(
+                Some(Subject::View(i)),
+                format!("{relation}.{}", index.name),
+                &index.name,
+            );
+        }
+    }
+    for (i, ty) in project.types.iter().enumerate() {
+        check(
+            Some(Subject::Type(i)),
+            format!("{}.{}", ty.schema, ty.name),
+            &ty.name,
+        );
+    }
+    for (i, routine) in project.routines.iter().enumerate() {
+        check(
+            Some(Subject::Routine(i)),
+            format!("{}.{}", routine.schema, routine.name),
+            &routine.name,
+        );
+    }
+    for (i, sequence) in project.sequences.iter().enumerate() {
+        check(
+            Some(Subject::Sequence(i)),
+            format!("{}.{}", sequence.schema, sequence.name),
+            &sequence.name,
+        );
+    }
+    for (i, role) in project.roles.iter().enumerate() {
+        check(Some(Subject::Role(i)), role.name.clone(), &role.name);
+    }
+}
+
+/// Names used twice where PostgreSQL wants them to be unique: relations,
+/// indexes and the constraints backed by one share a namespace per schema,
+/// and so do types and the row types of tables and views.
+fn duplicates(project: &Project, problems: &mut Vec<Problem>) {
+    /// `what` is the kind of object, followed by the table it belongs to
+    /// if any, as in `("index", "on orders")`.
+    fn claim<'a>(
+        namespace: &mut HashMap<(&'a str, &'a str), String>,
+        problems: &mut Vec<Problem>,
+        (schema, name): (&'a str, &'a str),
+        (kind, owner): (&str, &str),
+        subject: Option<Subject>,
+    ) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let described = |name: &str| {
+            [kind, name, owner]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match namespace.get(&(schema, name)) {
+            Some(first) => problems.push(Problem {
+                severity: Severity::Error,
+                subject,
+                object: format!("{schema}.{name}"),
+                message: format!("{} has the same name as {first}", described("")),
+            }),
+            None => {
+                namespace.insert((schema, name), described(&format!("{schema}.{name}")));
+            }
+        }
+    }
+
+    let mut relations = HashMap::new();
+    let mut types = HashMap::new();
+    for (i, table) in project.tables.iter().enumerate() {
+        let subject = Some(Subject::Table(i));
+        let schema = table.schema.as_str();
+        claim(
+            &mut relations,
+            problems,
+            (schema, &table.name),
+            ("table", ""),
+            subject,
+        );
+        claim(
+            &mut types,
+            problems,
+            (schema, &table.name),
+            ("table", ""),
+            subject,
+        );
+        for partition in table.partitioning.iter().flat_map(|p| &p.partitions) {
+            let owner = format!("of {}", table.name);
+            let what = ("partition", owner.as_str());
+            claim(
+                &mut relations,
+                problems,
+                (schema, &partition.name),
+                what,
+                subject,
+            );
+            claim(
+                &mut types,
+                problems,
+                (schema, &partition.name),
+                what,
+                subject,
+            );
+        }
+        for constraint in &table.constraints {
+            if matches!(
+                constraint.kind,
+                ConstraintKind::PrimaryKey { .. }
+                    | ConstraintKind::Unique { .. }
+                    | ConstraintKind::Exclusion { .. }
+            ) {
+                claim(
+                    &mut relations,
+                    problems,
+                    (schema, &constraint.name),
+                    ("constraint", &format!("on {}", table.name)),
+                    subject,
+                );
+            }
+        }
+        for index in &table.indexes {
+            claim(
+                &mut relations,
+                problems,
+                (schema, &index.name),
+                ("index", &format!("on {}", table.name)),
+                subject,
+            );
+        }
+    }
+    for (i, view) in project.views.iter().enumerate() {
+        let subject = Some(Subject::View(i));
+        let schema = view.schema.as_str();
+        claim(
+            &mut relations,
+            problems,
+            (schema, &view.name),
+            (view.kind(), ""),
+            subject,
+        );
+        claim(
+            &mut types,
+            problems,
+            (schema, &view.name),
+            (view.kind(), ""),
+            subject,
+        );
+        for index in &view.indexes {
+            claim(
+                &mut relations,
+                problems,
+                (schema, &index.name),
+                ("index", &format!("on {}", view.name)),
+                subject,
+            );
+        }
+    }
+    for (i, sequence) in project.sequences.iter().enumerate() {
+        let subject = Some(Subject::Sequence(i));
+        claim(
+            &mut relations,
+            problems,
+            (&sequence.schema, &sequence.name),
+            ("sequence", ""),
+            subject,
+        );
+    }
+    for (i, ty) in project.types.iter().enumerate() {
+        let subject = Some(Subject::Type(i));
+        let what = ("type", "");
+        // A composite type is a relation as well.
+        if matches!(ty.kind, UserTypeKind::Composite { .. }) {
+            claim(
+                &mut relations,
+                problems,
+                (&ty.schema, &ty.name),
+                what,
+                subject,
+            );
+        }
+        claim(&mut types, problems, (&ty.schema, &ty.name), what, subject);
+    }
+
+    let mut roles = HashMap::new();
+    for (i, role) in project.roles.iter().enumerate() {
+        if roles.insert(role.name.trim(), i).is_some() {
+            problems.push(Problem {
+                severity: Severity::Error,
+                subject: Some(Subject::Role(i)),
+                object: role.name.clone(),
+                message: String::from("role is defined twice"),
+            });
+        }
+    }
+
+    for (i, table) in project.tables.iter().enumerate() {
+        let relation = format!("{}.{}", table.schema, table.name);
+        let mut twice = |what: &str, names: Vec<&str>| {
+            let mut seen = Vec::new();
+            for name in names.into_iter().map(str::trim).filter(|n| !n.is_empty()) {
+                if seen.contains(&name) {
+                    problems.push(Problem {
+                        severity: Severity::Error,
+                        subject: Some(Subject::Table(i)),
+                        object: format!("{relation}.{name}"),
+                        message: format!("{what} is defined twice"),
+                    });
+                } else {
+                    seen.push(name);
+                }
+            }
+        };
+        twice(
+            "column",
+            table.columns.iter().map(|c| c.name.as_str()).collect(),
+        );
+        twice(
+            "constraint",
+            table.constraints.iter().map(|c| c.name.as_str()).collect(),
+        );
+        twice(
+            "trigger",
+            table.triggers.iter().map(|t| t.name.as_str()).collect(),
+        );
+        twice(
+            "policy",
+            table.policies.iter().map(|p| p.name.as_str()).collect(),
+        );
+    }
+}
+
+/// Primary key, and foreign keys: what they reference, whether the types
+/// agree and whether they are indexed.
+fn keys(project: &Project, i: usize, table: &Table, problems: &mut Vec<Problem>) {
+    let subject = Some(Subject::Table(i));
+    let relation = format!("{}.{}", table.schema, table.name);
+    let mut report = |severity: Severity, object: String, message: String| {
+        problems.push(Problem {
+            severity,
+            subject,
+            object,
+            message,
+        });
+    };
+
+    if !table
+        .constraints
+        .iter()
+        .any(|c| matches!(c.kind, ConstraintKind::PrimaryKey { .. }))
+    {
+        report(
+            Severity::Warning,
+            relation.clone(),
+            String::from("table has no primary key"),
+        );
+    }
+
+    for constraint in &table.constraints {
+        let ConstraintKind::ForeignKey(fk) = &constraint.kind else {
+            continue;
+        };
+        let object = format!("{relation}.{}", constraint.name);
+        let columns: Vec<&str> = list_items(&fk.columns).collect();
+        let Some(referenced) = project.table(&fk.ref_schema, &fk.ref_table) else {
+            report(
+                Severity::Error,
+                object,
+                format!(
+                    "references {}.{}, which is not in the model",
+                    fk.ref_schema, fk.ref_table
+                ),
+            );
+            continue;
+        };
+        let ref_columns: Vec<&str> = if list_items(&fk.ref_columns).next().is_some() {
+            list_items(&fk.ref_columns).collect()
+        } else {
+            primary_key(referenced)
+        };
+        if columns.len() != ref_columns.len() {
+            report(
+                Severity::Error,
+                object,
+                format!(
+                    "{} columns reference {} columns",
+                    columns.len(),
+                    ref_columns.len()
+                ),
+            );
+            continue;
+        }
+        if !unique_keys(referenced)
+            .iter()
+            .any(|key| same_columns(key, &ref_columns))
+        {
+            report(
+                Severity::Error,
+                object.clone(),
+                format!(
+                    "{}.{} ({}) is not a primary key or unique",
+                    referenced.schema,
+                    referenced.name,
+                    ref_columns.join(", ")
+                ),
+            );
+        }
+        for (column, ref_column) in columns.iter().zip(&ref_columns) {
+            let local = table.columns.iter().find(|c| c.name == *column);
+            let remote = referenced.columns.iter().find(|c| c.name == *ref_column);
+            match (local, remote) {
+                (None, _) => report(
+                    Severity::Error,
+                    object.clone(),
+                    format!("column {column} does not exist"),
+                ),
+                (_, None) => report(
+                    Severity::Error,
+                    object.clone(),
+                    format!(
+                        "column {}.{}.{ref_column} does not exist",
+                        referenced.schema, referenced.name
+                    ),
+                ),
+                (Some(local), Some(remote)) => {
+                    let (from, to) = (
+                        data_type::canonical(&local.data_type),
+                        data_type::canonical(&remote.data_type),
+                    );
+                    if from != to {
+                        report(
+                            Severity::Warning,
+                            object.clone(),
+                            format!(
+                                "{column} is {from} but references {ref_column}, which is {to}"
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        if !indexed(table, &columns) {
+            report(
+                Severity::Notice,
+                object,
+                format!("foreign key columns {} have no index", columns.join(", ")),
+            );
+        }
+    }
+}
+
+fn primary_key(table: &Table) -> Vec<&str> {
+    table
+        .constraints
+        .iter()
+        .find_map(|c| match &c.kind {
+            ConstraintKind::PrimaryKey { columns, .. } => Some(list_items(columns).collect()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Column lists of the primary key and unique constraints, and of unique
+/// indexes on plain columns.
+fn unique_keys(table: &Table) -> Vec<Vec<&str>> {
+    let constraints = table.constraints.iter().filter_map(|c| match &c.kind {
+        ConstraintKind::PrimaryKey { columns, .. } | ConstraintKind::Unique { columns, .. } => {
+            Some(list_items(columns).collect())
+        }
+        _ => None,
+    });
+    let indexes = table
+        .indexes
+        .iter()
+        .filter(|index| {
+            index.unique
+                && index.predicate.is_none()
+                && index.elements.iter().all(|e| e.is_column())
+        })
+        .map(|index| index.elements.iter().map(|e| e.expression.trim()).collect());
+    constraints.chain(indexes).collect()
+}
+
+fn same_columns(a: &[&str], b: &[&str]) -> bool {
+    a.len() == b.len() && a.iter().all(|column| b.contains(column))
+}
+
+/// Whether an index, or a key backed by one, starts with `columns` in any
+/// order, which is what lookups from the referenced side need.
+fn indexed(table: &Table, columns: &[&str]) -> bool {
+    let leading =
+        |key: &[&str]| key.len() >= columns.len() && same_columns(&key[..columns.len()], columns);
+    let constraints = table.constraints.iter().any(|c| match &c.kind {
+        ConstraintKind::PrimaryKey { columns, .. } | ConstraintKind::Unique { columns, .. } => {
+            leading(&list_items(columns).collect::<Vec<_>>())
+        }
+        _ => false,
+    });
+    constraints
+        || table.indexes.iter().any(|index| {
+            let key: Vec<&str> = index
+                .elements
+                .iter()
+                .take_while(|e| e.is_column())
+                .map(|e| e.expression.trim())
+                .collect();
+            leading(&key)
+        })
+}
+
+/// Tables that reference each other in a loop through foreign keys none of
+/// which can be deferred, so rows can't be inserted in any order.
+fn cycles(project: &Project, problems: &mut Vec<Problem>) {
+    let n = project.tables.len();
+    let mut edges = vec![Vec::new(); n];
+    for (i, table) in project.tables.iter().enumerate() {
+        for constraint in &table.constraints {
+            if let ConstraintKind::ForeignKey(fk) = &constraint.kind
+                && !constraint.deferrable
+                && let Some(j) = project
+                    .tables
+                    .iter()
+                    .position(|t| t.schema == fk.ref_schema && t.name == fk.ref_table)
+                && i != j
+            {
+                edges[i].push(j);
+            }
+        }
+    }
+
+    let reachable: Vec<Vec<bool>> = (0..n)
+        .map(|start| {
+            let mut seen = vec![false; n];
+            let mut stack = edges[start].clone();
+            while let Some(i) = stack.pop() {
+                if !seen[i] {
+                    seen[i] = true;
+                    stack.extend(&edges[i]);
+                }
+            }
+            seen
+        })
+        .collect();
+
+    let mut reported = vec![false; n];
+    for i in 0..n {
+        if reported[i] || !reachable[i][i] {
+            continue;
+        }
+        let members: Vec<usize> = (0..n)
+            .filter(|&j| reachable[i][j] && reachable[j][i])
+            .collect();
+        for &j in &members {
+            reported[j] = true;
+        }
+        let table = &project.tables[i];
+        problems.push(Problem {
+            severity: Severity::Warning,
+            subject: Some(Subject::Table(i)),
+            object: format!("{}.{}", table.schema, table.name),
+            message: format!(
+                "foreign keys form a cycle through {} and none of them is deferrable",
+                members
+                    .iter()
+                    .map(|&j| project.tables[j].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        });
+    }
+}
@@ -1,5 +1,6 @@
 pub mod sidebar;
 pub mod primary;
+pub mod output;
 
 pub use sidebar::Sidebar;
 pub use sidebar::SidebarMessage;
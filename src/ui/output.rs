@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use iced::widget::text_editor;
+
+use crate::utils::FileError;
+
+/// Performs `action` on generated text, which can be selected and scrolled
+/// but not edited.
+pub fn read_only_action(content: &mut text_editor::Content, action: text_editor::Action) {
+    if !action.is_edit() {
+        content.perform(action);
+    }
+}
+
+/// What to tell about saving a file: where it went, or why it failed.
+/// Nothing when the dialog was cancelled.
+pub fn saved_status(result: Result<PathBuf, FileError>) -> Option<String> {
+    match result {
+        Ok(path) => Some(format!("Saved {}", path.display())),
+        Err(FileError::Cancelled) => None,
+        Err(e) => Some(format!("Save failed: {e}")),
+    }
+}
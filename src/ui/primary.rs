@@ -1,39 +1,327 @@
-use content::{Content, ContentMessage};
-use header::{Header, HeaderMessage};
-use iced::{widget::column, Element, Task};
+use std::path::PathBuf;
+
+use content::{
+    diagram::DiagramMessage,
+    explorer::ExplorerMessage,
+    property::{routine::RoutineMessage, view::ViewMessage, PropertyMessage},
+    Content, ContentMessage, Selection,
+};
+use database::{Database, DatabaseMessage};
+use export::{code::CodeMessage, Export, ExportMessage};
+use header::{title::TitleMessage, Header, HeaderMessage};
+use iced::{
+    widget::{column, container, text},
+    Element, Task,
+};
+use problems::{ConventionsMessage, ProblemsMessage};
+use script::{Script, ScriptMessage};
+
+use crate::{
+    model::{history::History, layout, Conventions, Project},
+    sql::{
+        self,
+        validate::{self, Problem, Subject},
+    },
+    ui::output,
+    utils::{self, FileError},
+};
 
 pub mod content;
+pub mod database;
+pub mod export;
 pub mod header;
+pub mod problems;
+pub mod script;
 
 #[derive(Default)]
 pub struct Primary<'a> {
     header: Header<'a>,
     content: Content,
+    database: Database,
+    script: Script,
+    export: Export,
+    page: Page,
+    project: Project,
+    history: History,
+    /// The text field the last message typed into, or the diagram for a
+    /// drag in progress. Changes to it in a row are undone as one.
+    editing: Option<String>,
+    /// Checked again whenever the project changes.
+    problems: Vec<Problem>,
+    show_problems: bool,
+    status: Option<String>,
+}
+
+/// What fills the area below the header, picked from the sidebar.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Page {
+    #[default]
+    Diagram,
+    Database,
+    Script,
+    Export,
 }
 
 #[derive(Clone, Debug)]
 pub enum PrimaryMessage {
     Header(HeaderMessage),
     Content(ContentMessage),
+    Database(DatabaseMessage),
+    Script(ScriptMessage),
+    Export(ExportMessage),
+    Problems(ProblemsMessage),
+    Saved(Result<PathBuf, FileError>),
+}
+
+impl PrimaryMessage {
+    /// Whether the message can change the project. Only those are kept for
+    /// undo and have the project checked again.
+    fn edits(&self) -> bool {
+        match self {
+            Self::Header(message) => matches!(
+                message,
+                HeaderMessage::Table
+                    | HeaderMessage::Layout
+                    | HeaderMessage::Title(TitleMessage::Target(_))
+            ),
+            Self::Content(ContentMessage::Explorer(message)) => !matches!(
+                message,
+                ExplorerMessage::Filter(_)
+                    | ExplorerMessage::Select(_)
+                    | ExplorerMessage::ToggleMatrix
+                    | ExplorerMessage::ViewMode(_)
+            ),
+            Self::Content(ContentMessage::Property(message)) => match message {
+                PropertyMessage::Tab(_) => false,
+                PropertyMessage::View(ViewMessage::Edit(action))
+                | PropertyMessage::Routine(RoutineMessage::Edit(action)) => action.is_edit(),
+                _ => true,
+            },
+            Self::Content(ContentMessage::Diagram(message)) => {
+                matches!(message, DiagramMessage::Move(..))
+            }
+            Self::Content(ContentMessage::Matrix(_)) => false,
+            Self::Database(message) => matches!(message, DatabaseMessage::Imported(Ok(_))),
+            Self::Script(message) => matches!(message, ScriptMessage::Apply),
+            Self::Export(message) => {
+                matches!(message, ExportMessage::Code(CodeMessage::RustType(..)))
+            }
+            Self::Problems(message) => matches!(message, ProblemsMessage::Conventions(_)),
+            Self::Saved(_) => false,
+        }
+    }
 }
 
 impl<'a> Primary<'a> {
     pub fn update(&mut self, message: PrimaryMessage) -> Task<PrimaryMessage> {
-        match message {
+        if let PrimaryMessage::Header(HeaderMessage::Undo | HeaderMessage::Redo) = message {
+            let changed = if matches!(message, PrimaryMessage::Header(HeaderMessage::Undo)) {
+                self.history.undo(&mut self.project)
+            } else {
+                self.history.redo(&mut self.project)
+            };
+            if changed {
+                self.content.deselect();
+                self.content.refresh(&self.project);
+                self.problems = validate::check(&self.project);
+                match self.page {
+                    Page::Script => self.script.refresh(&self.project, None),
+                    Page::Export => self.export.refresh(&self.project, self.content.view_mode()),
+                    Page::Diagram | Page::Database => {}
+                }
+            }
+            self.editing = None;
+            return Task::none();
+        }
+
+        let editing = match &message {
+            PrimaryMessage::Content(ContentMessage::Diagram(DiagramMessage::Move(..))) => {
+                Some(String::from("diagram"))
+            }
+            PrimaryMessage::Content(ContentMessage::Property(message)) => message.text_field(),
+            _ => None,
+        };
+        let edits = message.edits();
+        let before =
+            (edits && (editing.is_none() || editing != self.editing)).then(|| self.project.clone());
+        self.editing = editing;
+
+        let task = match message {
             PrimaryMessage::Header(message) => {
+                match message {
+                    HeaderMessage::Table => {
+                        let i = self.project.add_table();
+                        self.content.select(&self.project, Selection::Table(i));
+                    }
+                    HeaderMessage::Title(TitleMessage::Target(version)) => {
+                        self.project.version = version;
+                    }
+                    HeaderMessage::Property => self.content.toggle_property(),
+                    HeaderMessage::Layout => layout::arrange(&mut self.project),
+                    HeaderMessage::Export => {
+                        let ddl = sql::ddl::project(&self.project);
+                        return Task::perform(
+                            utils::save_file(String::from("tableforge_ddl.sql"), ddl),
+                            PrimaryMessage::Saved,
+                        );
+                    }
+                    _ => {}
+                }
                 self.header.update(message).map(PrimaryMessage::Header)
             }
-            PrimaryMessage::Content(message) => {
-                self.content.update(message).map(PrimaryMessage::Content)
+            PrimaryMessage::Content(message) => self
+                .content
+                .update(message, &mut self.project)
+                .map(PrimaryMessage::Content),
+            PrimaryMessage::Database(message) => {
+                if let DatabaseMessage::Imported(Ok(project)) = &message {
+                    self.import(Project::clone(project));
+                }
+                self.database
+                    .update(message, &self.project)
+                    .map(PrimaryMessage::Database)
+            }
+            PrimaryMessage::Script(message) => self
+                .script
+                .update(message, &mut self.project, self.content.selection())
+                .map(PrimaryMessage::Script),
+            PrimaryMessage::Export(message) => self
+                .export
+                .update(message, &mut self.project, self.content.view_mode())
+                .map(PrimaryMessage::Export),
+            PrimaryMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+                Task::none()
+            }
+            PrimaryMessage::Problems(ProblemsMessage::Toggle) => {
+                self.show_problems = !self.show_problems;
+                Task::none()
+            }
+            PrimaryMessage::Problems(ProblemsMessage::Select(subject)) => {
+                let selection = match subject {
+                    Subject::Table(i) => Selection::Table(i),
+                    Subject::Type(i) => Selection::Type(i),
+                    Subject::View(i) => Selection::View(i),
+                    Subject::Routine(i) => Selection::Routine(i),
+                    Subject::Sequence(i) => Selection::Sequence(i),
+                    Subject::Role(i) => Selection::Role(i),
+                };
+                self.content.select(&self.project, selection);
+                self.page = Page::Diagram;
+                Task::none()
+            }
+            PrimaryMessage::Problems(ProblemsMessage::Conventions(message)) => {
+                self.conventions(message);
+                Task::none()
+            }
+        };
+        let changed = match before {
+            Some(before) if before != self.project => {
+                self.history.record(before);
+                true
+            }
+            Some(_) => false,
+            // More typing into the field the last change was made in.
+            None => edits,
+        };
+        if changed {
+            self.content.refresh(&self.project);
+            self.problems = validate::check(&self.project);
+        }
+        task
+    }
+
+    fn conventions(&mut self, message: ConventionsMessage) {
+        if let ConventionsMessage::Enforce(enforce) = message {
+            self.project.conventions = enforce.then(Box::default);
+            return;
+        }
+        let Some(conventions) = &mut self.project.conventions else {
+            return;
+        };
+        match message {
+            ConventionsMessage::Enforce(_) => {}
+            ConventionsMessage::Case(case) => conventions.case = case,
+            ConventionsMessage::Number(number) => conventions.number = number,
+            ConventionsMessage::TablePrefix(prefix) => conventions.table_prefix = prefix,
+            ConventionsMessage::TableSuffix(suffix) => conventions.table_suffix = suffix,
+            ConventionsMessage::Template(template, value) => {
+                *conventions.template_mut(template) = value;
+            }
+            ConventionsMessage::Apply => {
+                let conventions = Conventions::clone(conventions);
+                conventions.apply(&mut self.project);
+                self.content.deselect();
             }
         }
     }
 
+    pub fn show(&mut self, page: Page) {
+        self.page = page;
+        match page {
+            Page::Script => self.script.refresh(&self.project, self.content.selection()),
+            Page::Export => self.export.refresh(&self.project, self.content.view_mode()),
+            Page::Diagram | Page::Database => {}
+        }
+    }
+
+    /// Lays out an imported project and either replaces the current one with
+    /// it or merges it in, to the right of what is already on the diagram.
+    fn import(&mut self, mut imported: Project) {
+        layout::arrange(&mut imported);
+        if self.database.new_project() {
+            self.content.deselect();
+            self.project = imported;
+            return;
+        }
+
+        let right = self
+            .project
+            .tables
+            .iter()
+            .map(|table| table.position.x)
+            .chain(self.project.views.iter().map(|view| view.position.x))
+            .fold(None, |right: Option<f32>, x| {
+                Some(right.map_or(x, |r| r.max(x)))
+            });
+        if let Some(right) = right {
+            layout::shift(&mut imported, right + layout::COLUMN_WIDTH);
+        }
+        self.project.merge(imported);
+    }
+
     pub fn view(&self) -> Element<PrimaryMessage> {
-        column![
-            self.header.view().map(PrimaryMessage::Header),
-            self.content.view().map(PrimaryMessage::Content)
-        ]
-        .into()
+        let mut primary = column![self
+            .header
+            .view(self.project.version)
+            .map(PrimaryMessage::Header)];
+
+        primary = primary.push(match self.page {
+            Page::Diagram => self
+                .content
+                .view(&self.project)
+                .map(PrimaryMessage::Content),
+            Page::Database => self.database.view().map(PrimaryMessage::Database),
+            Page::Script => self
+                .script
+                .view(&self.project, self.content.selection())
+                .map(PrimaryMessage::Script),
+            Page::Export => self
+                .export
+                .view(&self.project, self.content.view_mode())
+                .map(PrimaryMessage::Export),
+        });
+        primary = primary.push(
+            problems::view(
+                &self.problems,
+                self.project.conventions.as_deref(),
+                self.show_problems,
+            )
+            .map(PrimaryMessage::Problems),
+        );
+        if let Some(status) = &self.status {
+            primary = primary.push(container(text(status).size(12)).padding([4, 12]));
+        }
+        primary.into()
     }
 }
@@ -1,63 +1,242 @@
 pub mod diagram;
 pub mod explorer;
+pub mod matrix;
 pub mod property;
 
-use std::cell::RefCell;
-
+use diagram::{Diagram, DiagramCache, DiagramMessage};
+use explorer::{Explorer, ExplorerMessage};
 use iced::{
-    advanced::text,
-    alignment,
-    widget::{canvas, center, container},
-    Element, Font,
+    widget::{canvas, row},
+    Element,
     Length::Fill,
-    Renderer, Task,
+    Task,
+};
+use property::{Property, PropertyMessage, Tab};
+
+use crate::{
+    erd::ViewMode,
+    model::{area, Project},
 };
 
-#[derive(Default)]
-pub struct Content {}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    Table(usize),
+    Type(usize),
+    View(usize),
+    Routine(usize),
+    Sequence(usize),
+    Role(usize),
+}
 
-#[derive(Clone, Debug, Copy)]
-pub enum ContentMessage {}
+pub struct Content {
+    explorer: Explorer,
+    property: Property,
+    selection: Option<Selection>,
+    show_property: bool,
+    /// Show the roles × tables permission matrix instead of the diagram.
+    show_matrix: bool,
+    mode: ViewMode,
+    diagram: DiagramCache,
+}
+
+#[derive(Clone, Debug)]
+pub enum ContentMessage {
+    Explorer(ExplorerMessage),
+    Property(PropertyMessage),
+    Diagram(DiagramMessage),
+    Matrix(Selection),
+}
 
 impl Content {
-    pub fn update(&mut self, _message: ContentMessage) -> Task<ContentMessage> {
-        Task::none()
+    pub fn update(
+        &mut self,
+        message: ContentMessage,
+        project: &mut Project,
+    ) -> Task<ContentMessage> {
+        // Selection and view mode are drawn on the diagram too.
+        self.diagram.redraw();
+        match message {
+            ContentMessage::Explorer(message) => {
+                match message {
+                    ExplorerMessage::Select(selection) => self.select(project, selection),
+                    ExplorerMessage::AddType(category) => {
+                        let i = project.add_type(category);
+                        self.select(project, Selection::Type(i));
+                    }
+                    ExplorerMessage::AddRole => {
+                        let i = project.add_role();
+                        self.select(project, Selection::Role(i));
+                    }
+                    ExplorerMessage::ToggleMatrix => self.show_matrix = !self.show_matrix,
+                    ExplorerMessage::ViewMode(mode) => self.mode = mode,
+                    ExplorerMessage::Extension(ref extension, enabled) => {
+                        project.extensions.retain(|e| e != extension);
+                        if enabled {
+                            project.extensions.push(extension.clone());
+                        }
+                    }
+                    ExplorerMessage::AddSequence => {
+                        let i = project.add_sequence();
+                        self.select(project, Selection::Sequence(i));
+                    }
+                    ExplorerMessage::AddRoutine(kind) => {
+                        let i = project.add_routine(kind);
+                        self.select(project, Selection::Routine(i));
+                    }
+                    ExplorerMessage::AddView(materialized) => {
+                        let i = project.add_view(materialized);
+                        self.select(project, Selection::View(i));
+                    }
+                    ExplorerMessage::Filter(_) => {}
+                }
+                self.explorer.update(message).map(ContentMessage::Explorer)
+            }
+            ContentMessage::Property(PropertyMessage::Delete) => {
+                match self.selection.take() {
+                    Some(Selection::Table(i)) if i < project.tables.len() => {
+                        let table = project.tables.remove(i);
+                        let member = area::member(&table.schema, &table.name);
+                        for area in &mut project.areas {
+                            area.tables.retain(|t| *t != member);
+                        }
+                    }
+                    Some(Selection::Type(i)) if i < project.types.len() => {
+                        project.types.remove(i);
+                    }
+                    Some(Selection::View(i)) if i < project.views.len() => {
+                        project.views.remove(i);
+                    }
+                    Some(Selection::Routine(i)) if i < project.routines.len() => {
+                        project.routines.remove(i);
+                    }
+                    Some(Selection::Sequence(i)) if i < project.sequences.len() => {
+                        project.sequences.remove(i);
+                    }
+                    Some(Selection::Role(i)) if i < project.roles.len() => {
+                        project.roles.remove(i);
+                    }
+                    _ => {}
+                }
+                Task::none()
+            }
+            ContentMessage::Property(message) => match self.selection {
+                Some(selection) => self
+                    .property
+                    .update(message, project, selection)
+                    .map(ContentMessage::Property),
+                None => Task::none(),
+            },
+            ContentMessage::Matrix(selection) => {
+                self.select(project, selection);
+                self.property.show_tab(Tab::Security);
+                Task::none()
+            }
+            ContentMessage::Diagram(DiagramMessage::Select(selection)) => {
+                match selection {
+                    Some(selection) if self.selection != Some(selection) => {
+                        self.select(project, selection)
+                    }
+                    Some(_) => {}
+                    None => self.selection = None,
+                }
+                Task::none()
+            }
+            ContentMessage::Diagram(DiagramMessage::Move(selection, position)) => {
+                match selection {
+                    Selection::Table(i) => {
+                        if let Some(table) = project.tables.get_mut(i) {
+                            table.position = position;
+                        }
+                    }
+                    Selection::View(i) => {
+                        if let Some(view) = project.views.get_mut(i) {
+                            view.position = position;
+                        }
+                    }
+                    Selection::Type(_)
+                    | Selection::Routine(_)
+                    | Selection::Sequence(_)
+                    | Selection::Role(_) => {}
+                }
+                Task::none()
+            }
+        }
+    }
+
+    pub fn select(&mut self, project: &Project, selection: Selection) {
+        self.diagram.redraw();
+        self.selection = Some(selection);
+        self.property.select(project, selection);
+    }
+
+    pub fn selection(&self) -> Option<Selection> {
+        self.selection
     }
 
-    pub fn view(&self) -> Element<'_, ContentMessage> {
-        container(center(canvas(self).width(Fill).height(Fill)))
-            .padding(20)
-            .width(Fill)
-            .height(Fill)
-            .into()
+    /// How much of each table the diagram shows.
+    pub fn view_mode(&self) -> ViewMode {
+        self.mode
+    }
+
+    pub fn deselect(&mut self) {
+        self.diagram.redraw();
+        self.selection = None;
+    }
+
+    /// Brings the diagram up to date with a change to the project.
+    pub fn refresh(&mut self, project: &Project) {
+        self.diagram.refresh(project);
+    }
+
+    pub fn toggle_property(&mut self) {
+        self.show_property = !self.show_property;
+    }
+
+    pub fn view<'a>(&'a self, project: &'a Project) -> Element<'a, ContentMessage> {
+        let center = if self.show_matrix {
+            matrix::view(project).map(ContentMessage::Matrix)
+        } else {
+            Element::from(
+                canvas(Diagram::new(
+                    project,
+                    &self.diagram,
+                    self.selection,
+                    self.mode,
+                ))
+                .width(Fill)
+                .height(Fill),
+            )
+            .map(ContentMessage::Diagram)
+        };
+
+        let mut content = row![
+            self.explorer
+                .view(project, self.selection, self.show_matrix, self.mode)
+                .map(ContentMessage::Explorer),
+            center
+        ];
+
+        if self.show_property
+            && let Some(selection) = self.selection
+            && let Some(property) = self.property.view(project, selection)
+        {
+            content = content.push(property.map(ContentMessage::Property));
+        }
+
+        content.height(Fill).into()
     }
 }
 
-impl<Message> canvas::Program<Message> for Content {
-    type State = RefCell<Vec<canvas::Cache>>;
-
-    fn draw(
-        &self,
-        _state: &Self::State,
-        renderer: &Renderer,
-        theme: &iced::Theme,
-        bounds: iced::Rectangle,
-        _cursor: iced::advanced::mouse::Cursor,
-    ) -> Vec<canvas::Geometry<Renderer>> {
-        let font = Font::with_name("JetBrainsMono NF");
-        let palette = theme.extended_palette();
-        let mut frame = canvas::Frame::new(renderer, bounds.size());
-        frame.fill_text(canvas::Text {
-            content: String::from("Under development..."),
-            size: 64.0.into(),
-            position: frame.center(),
-            color: palette.background.strongest.color,
-            align_x: text::Alignment::Center,
-            align_y: alignment::Vertical::Center,
-            font: font,
-            ..canvas::Text::default()
-        });
-
-        vec![frame.into_geometry()]
+impl Default for Content {
+    fn default() -> Self {
+        Self {
+            explorer: Explorer::default(),
+            property: Property::default(),
+            selection: None,
+            show_property: true,
+            show_matrix: false,
+            mode: ViewMode::default(),
+            diagram: DiagramCache::default(),
+        }
     }
 }
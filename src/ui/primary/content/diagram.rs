@@ -1 +1,625 @@
+use std::collections::HashSet;
 
+use iced::{
+    advanced::text,
+    alignment, border,
+    mouse::{self, ScrollDelta},
+    widget::canvas::{self, path, Action, Event, Frame, Path, Stroke},
+    Color, Font, Point, Rectangle, Renderer, Size, Theme, Vector,
+};
+
+use super::Selection;
+use crate::{
+    constants,
+    erd::{self, ViewMode},
+    model::{ConstraintKind, Position, Project},
+    sql::lineage::{self, Source},
+};
+
+const CARD_WIDTH: f32 = 220.0;
+const HEADER_HEIGHT: f32 = 28.0;
+const ROW_HEIGHT: f32 = 20.0;
+const PADDING: f32 = 6.0;
+const STACK_OFFSET: f32 = 4.0;
+const AREA_MARGIN: f32 = 16.0;
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 2.5;
+
+pub struct Diagram<'a> {
+    project: &'a Project,
+    cache: &'a DiagramCache,
+    selection: Option<Selection>,
+    mode: ViewMode,
+}
+
+/// What the diagram keeps between frames: the sources of every view, worked
+/// out when the model changes rather than on every frame, and the geometry
+/// drawn last.
+#[derive(Default)]
+pub struct DiagramCache {
+    sources: Vec<Vec<Source>>,
+    geometry: canvas::Cache,
+}
+
+#[derive(Clone, Debug)]
+pub enum DiagramMessage {
+    Select(Option<Selection>),
+    Move(Selection, Position),
+}
+
+pub struct State {
+    offset: Vector,
+    scale: f32,
+    drag: Option<Drag>,
+    /// Titles of the partitioned tables whose partition list is open.
+    expanded: HashSet<String>,
+}
+
+enum Drag {
+    Pan { origin: Point, offset: Vector },
+    Move { selection: Selection, grab: Vector },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CardStyle {
+    Table,
+    View,
+    MaterializedView,
+}
+
+struct Card {
+    selection: Selection,
+    bounds: Rectangle,
+    title: String,
+    style: CardStyle,
+    /// Short tag drawn at the right of the header.
+    badge: Option<String>,
+    rows: Vec<String>,
+    /// Row that opens and closes the partition list of a partitioned table,
+    /// which is drawn as a stack of cards.
+    toggle: Option<usize>,
+}
+
+impl DiagramCache {
+    /// Works out the lineage of the views again after a change to the model.
+    pub fn refresh(&mut self, project: &Project) {
+        self.sources = project
+            .views
+            .iter()
+            .map(|view| lineage::sources(project, view))
+            .collect();
+        self.geometry.clear();
+    }
+
+    /// Draws the diagram again on the next frame, for a change that leaves
+    /// the model as it is.
+    pub fn redraw(&self) {
+        self.geometry.clear();
+    }
+
+    fn sources(&self, view: usize) -> &[Source] {
+        self.sources.get(view).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<'a> Diagram<'a> {
+    pub fn new(
+        project: &'a Project,
+        cache: &'a DiagramCache,
+        selection: Option<Selection>,
+        mode: ViewMode,
+    ) -> Self {
+        Self {
+            project,
+            cache,
+            selection,
+            mode,
+        }
+    }
+
+    fn cards(&self, state: &State) -> Vec<Card> {
+        let mut cards = Vec::new();
+
+        for (i, table) in self.project.tables.iter().enumerate() {
+            let mut rows: Vec<String> = erd::attributes(table, self.mode)
+                .into_iter()
+                .map(|attribute| {
+                    let key = attribute.marker().unwrap_or("  ");
+                    let column = attribute.column;
+                    format!("{key} {}  {}", column.name, column.data_type)
+                })
+                .collect();
+
+            let title = title(&table.schema, &table.name);
+            let mut toggle = None;
+            if let Some(partitioning) = &table.partitioning
+                && self.mode != ViewMode::Names
+            {
+                let expanded = state.expanded.contains(&title);
+                toggle = Some(rows.len());
+                rows.push(format!(
+                    "{} {} partitions by {}",
+                    if expanded { "▾" } else { "▸" },
+                    partitioning.partitions.len(),
+                    partitioning.strategy
+                ));
+                if expanded {
+                    rows.extend(
+                        partitioning
+                            .partitions
+                            .iter()
+                            .map(|p| format!("  {}  {}", p.name, p.bound)),
+                    );
+                }
+            }
+
+            let mut card = card(
+                Selection::Table(i),
+                table.position,
+                title,
+                CardStyle::Table,
+                (!table.triggers.is_empty()).then(|| format!("TRG {}", table.triggers.len())),
+                rows,
+            );
+            card.toggle = toggle;
+            cards.push(card);
+        }
+
+        for (i, view) in self.project.views.iter().enumerate() {
+            let rows = self
+                .cache
+                .sources(i)
+                .iter()
+                .filter_map(|&source| match source {
+                    Source::Table(t) => self.project.tables.get(t).map(|t| t.name.as_str()),
+                    Source::View(v) => self.project.views.get(v).map(|v| v.name.as_str()),
+                })
+                .map(|name| format!("<- {name}"))
+                .collect();
+
+            cards.push(card(
+                Selection::View(i),
+                view.position,
+                title(&view.schema, &view.name),
+                if view.materialized {
+                    CardStyle::MaterializedView
+                } else {
+                    CardStyle::View
+                },
+                Some(String::from(if view.materialized {
+                    "MVIEW"
+                } else {
+                    "VIEW"
+                })),
+                rows,
+            ));
+        }
+
+        cards
+    }
+
+    fn bounds_of(cards: &[Card], selection: Selection) -> Option<Rectangle> {
+        cards
+            .iter()
+            .find(|card| card.selection == selection)
+            .map(|card| card.bounds)
+    }
+
+    /// Subject areas as tinted boxes around their tables, named above the
+    /// top left corner.
+    fn draw_areas(&self, frame: &mut Frame, cards: &[Card]) {
+        for area in &self.project.areas {
+            let Some(bounds) = self
+                .project
+                .tables
+                .iter()
+                .enumerate()
+                .filter(|(_, table)| area.contains(&table.schema, &table.name))
+                .filter_map(|(i, _)| Self::bounds_of(cards, Selection::Table(i)))
+                .reduce(|a, b| a.union(&b))
+            else {
+                continue;
+            };
+            let bounds = bounds.expand(AREA_MARGIN);
+            let body = Path::rounded_rectangle(bounds.position(), bounds.size(), 8.0.into());
+            frame.fill(&body, constants::AREA.scale_alpha(0.08));
+            frame.stroke(
+                &body,
+                Stroke::default()
+                    .with_width(1.0)
+                    .with_color(constants::AREA.scale_alpha(0.6)),
+            );
+            frame.fill_text(canvas::Text {
+                content: area.name.clone(),
+                position: Point::new(bounds.x + PADDING, bounds.y - 4.0),
+                color: constants::AREA,
+                size: 12.0.into(),
+                font: Font::with_name("JetBrainsMono NF"),
+                align_y: alignment::Vertical::Bottom,
+                ..canvas::Text::default()
+            });
+        }
+    }
+
+    fn draw_edges(&self, frame: &mut Frame, cards: &[Card], color: Color) {
+        for (i, table) in self.project.tables.iter().enumerate() {
+            let Some(from) = Self::bounds_of(cards, Selection::Table(i)) else {
+                continue;
+            };
+            for constraint in &table.constraints {
+                let ConstraintKind::ForeignKey(fk) = &constraint.kind else {
+                    continue;
+                };
+                let target = self
+                    .project
+                    .tables
+                    .iter()
+                    .position(|t| t.schema == fk.ref_schema && t.name == fk.ref_table);
+                if let Some(to) = target.and_then(|t| Self::bounds_of(cards, Selection::Table(t))) {
+                    edge(frame, from, to, color, false);
+                }
+            }
+        }
+
+        for i in 0..self.project.views.len() {
+            let Some(to) = Self::bounds_of(cards, Selection::View(i)) else {
+                continue;
+            };
+            for &source in self.cache.sources(i) {
+                let from = match source {
+                    Source::Table(t) => Self::bounds_of(cards, Selection::Table(t)),
+                    Source::View(v) => Self::bounds_of(cards, Selection::View(v)),
+                };
+                if let Some(from) = from {
+                    edge(frame, from, to, constants::VIEW_HEADER, true);
+                }
+            }
+        }
+    }
+
+    fn draw_card(&self, frame: &mut Frame, card: &Card, theme: &Theme) {
+        let palette = theme.extended_palette();
+        let font = Font::with_name("JetBrainsMono NF");
+        let header_color = match card.style {
+            CardStyle::Table => constants::TABLE_HEADER,
+            CardStyle::View => constants::VIEW_HEADER,
+            CardStyle::MaterializedView => constants::MATVIEW_HEADER,
+        };
+
+        if card.toggle.is_some() {
+            for depth in [2.0, 1.0] {
+                let offset = Vector::new(STACK_OFFSET * depth, STACK_OFFSET * depth);
+                let sheet = Path::rounded_rectangle(
+                    card.bounds.position() + offset,
+                    card.bounds.size(),
+                    4.0.into(),
+                );
+                frame.fill(&sheet, palette.background.weak.color);
+                frame.stroke(
+                    &sheet,
+                    Stroke::default().with_width(1.0).with_color(header_color),
+                );
+            }
+        }
+
+        let body = Path::rounded_rectangle(card.bounds.position(), card.bounds.size(), 4.0.into());
+        frame.fill(&body, palette.background.weak.color);
+
+        let header = Path::rounded_rectangle(
+            card.bounds.position(),
+            Size::new(card.bounds.width, HEADER_HEIGHT),
+            border::Radius::default().top(4.0),
+        );
+        frame.fill(&header, header_color);
+
+        frame.fill_text(canvas::Text {
+            content: card.title.clone(),
+            position: Point::new(card.bounds.x + PADDING, card.bounds.y + HEADER_HEIGHT / 2.0),
+            color: Color::WHITE,
+            size: 14.0.into(),
+            font,
+            align_y: alignment::Vertical::Center,
+            ..canvas::Text::default()
+        });
+
+        if let Some(badge) = &card.badge {
+            frame.fill_text(canvas::Text {
+                content: badge.clone(),
+                position: Point::new(
+                    card.bounds.x + card.bounds.width - PADDING,
+                    card.bounds.y + HEADER_HEIGHT / 2.0,
+                ),
+                color: Color::WHITE.scale_alpha(0.8),
+                size: 10.0.into(),
+                font,
+                align_x: text::Alignment::Right,
+                align_y: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+
+        for (i, row) in card.rows.iter().enumerate() {
+            frame.fill_text(canvas::Text {
+                content: row.clone(),
+                position: Point::new(card.bounds.x + PADDING, card.row_bounds(i).center_y()),
+                color: if card.toggle == Some(i) {
+                    header_color
+                } else {
+                    palette.background.base.text
+                },
+                size: 12.0.into(),
+                font,
+                align_y: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+
+        let selected = self.selection == Some(card.selection);
+        let (width, color) = if selected {
+            (2.0, constants::ICON_ACTIVE)
+        } else {
+            (1.0, header_color)
+        };
+        let mut stroke = Stroke::default().with_width(width).with_color(color);
+        // Plain views hold no data, so they get a dashed outline.
+        if card.style == CardStyle::View {
+            stroke.line_dash = canvas::LineDash {
+                segments: &[6.0, 4.0],
+                offset: 0,
+            };
+        }
+        frame.stroke(&body, stroke);
+    }
+}
+
+impl canvas::Program<DiagramMessage> for Diagram<'_> {
+    type State = State;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<DiagramMessage>> {
+        let Event::Mouse(event) = event else {
+            return None;
+        };
+
+        match event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                let position = cursor.position_in(bounds)?;
+                let point = state.to_model(position);
+                let hit = self
+                    .cards(state)
+                    .into_iter()
+                    .rev()
+                    .find(|card| card.bounds.contains(point));
+                if let Some(card) = &hit
+                    && let Some(toggle) = card.toggle
+                    && card.row_bounds(toggle).contains(point)
+                {
+                    if !state.expanded.remove(&card.title) {
+                        state.expanded.insert(card.title.clone());
+                    }
+                    self.cache.redraw();
+                    return Some(Action::request_redraw().and_capture());
+                }
+                match hit {
+                    Some(card) => {
+                        state.drag = Some(Drag::Move {
+                            selection: card.selection,
+                            grab: point - card.bounds.position(),
+                        });
+                        Some(
+                            Action::publish(DiagramMessage::Select(Some(card.selection)))
+                                .and_capture(),
+                        )
+                    }
+                    None => {
+                        state.drag = Some(Drag::Pan {
+                            origin: position,
+                            offset: state.offset,
+                        });
+                        Some(Action::publish(DiagramMessage::Select(None)).and_capture())
+                    }
+                }
+            }
+            mouse::Event::CursorMoved { .. } => {
+                let position = cursor.position_in(bounds)?;
+                match state.drag.as_ref()? {
+                    Drag::Pan { origin, offset } => {
+                        state.offset = *offset + (position - *origin);
+                        self.cache.redraw();
+                        Some(Action::request_redraw())
+                    }
+                    Drag::Move { selection, grab } => {
+                        let point = state.to_model(position) - *grab;
+                        Some(Action::publish(DiagramMessage::Move(
+                            *selection,
+                            Position::new(point.x, point.y),
+                        )))
+                    }
+                }
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                state.drag.take().map(|_| Action::capture())
+            }
+            mouse::Event::WheelScrolled { delta } => {
+                let position = cursor.position_in(bounds)?;
+                let lines = match delta {
+                    ScrollDelta::Lines { y, .. } => *y,
+                    ScrollDelta::Pixels { y, .. } => *y / 60.0,
+                };
+                let before = state.to_model(position);
+                state.scale = (state.scale * (1.0 + lines * 0.1)).clamp(MIN_SCALE, MAX_SCALE);
+                // Keep the point under the cursor where it was.
+                state.offset = Vector::new(
+                    position.x - before.x * state.scale,
+                    position.y - before.y * state.scale,
+                );
+                self.cache.redraw();
+                Some(Action::request_redraw().and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let palette = theme.extended_palette();
+        let geometry = self.cache.geometry.draw(renderer, bounds.size(), |frame| {
+            let cards = self.cards(state);
+            if cards.is_empty() {
+                frame.fill_text(canvas::Text {
+                    content: String::from("Add a table to start modelling"),
+                    size: 32.0.into(),
+                    position: frame.center(),
+                    color: palette.background.strongest.color,
+                    align_x: text::Alignment::Center,
+                    align_y: alignment::Vertical::Center,
+                    font: Font::with_name("JetBrainsMono NF"),
+                    ..canvas::Text::default()
+                });
+                return;
+            }
+
+            frame.with_save(|frame| {
+                frame.translate(state.offset);
+                frame.scale(state.scale);
+                self.draw_areas(frame, &cards);
+                self.draw_edges(frame, &cards, palette.background.strongest.color);
+                for card in &cards {
+                    self.draw_card(frame, card, theme);
+                }
+            });
+        });
+
+        vec![geometry]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        _bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match state.drag {
+            Some(Drag::Pan { .. }) => mouse::Interaction::Grabbing,
+            Some(Drag::Move { .. }) => mouse::Interaction::Move,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
+
+impl State {
+    fn to_model(&self, position: Point) -> Point {
+        Point::new(
+            (position.x - self.offset.x) / self.scale,
+            (position.y - self.offset.y) / self.scale,
+        )
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            offset: Vector::ZERO,
+            scale: 1.0,
+            drag: None,
+            expanded: HashSet::new(),
+        }
+    }
+}
+
+fn title(schema: &str, name: &str) -> String {
+    if schema == crate::model::DEFAULT_SCHEMA {
+        name.to_string()
+    } else {
+        format!("{schema}.{name}")
+    }
+}
+
+fn card(
+    selection: Selection,
+    position: Position,
+    title: String,
+    style: CardStyle,
+    badge: Option<String>,
+    rows: Vec<String>,
+) -> Card {
+    let height = HEADER_HEIGHT + PADDING * 2.0 + ROW_HEIGHT * rows.len().max(1) as f32;
+    Card {
+        selection,
+        bounds: Rectangle::new(
+            Point::new(position.x, position.y),
+            Size::new(CARD_WIDTH, height),
+        ),
+        title,
+        style,
+        badge,
+        rows,
+        toggle: None,
+    }
+}
+
+impl Card {
+    fn row_bounds(&self, i: usize) -> Rectangle {
+        Rectangle::new(
+            Point::new(
+                self.bounds.x,
+                self.bounds.y + HEADER_HEIGHT + PADDING + ROW_HEIGHT * i as f32,
+            ),
+            Size::new(self.bounds.width, ROW_HEIGHT),
+        )
+    }
+}
+
+/// Connects two cards with an elbow line between their closest sides and
+/// an arrow head on `to`.
+fn edge(frame: &mut Frame, from: Rectangle, to: Rectangle, color: Color, dashed: bool) {
+    let from_center = from.center();
+    let to_center = to.center();
+    let (start, end) = if to_center.x >= from_center.x {
+        (
+            Point::new(from.x + from.width, from_center.y),
+            Point::new(to.x, to_center.y),
+        )
+    } else {
+        (
+            Point::new(from.x, from_center.y),
+            Point::new(to.x + to.width, to_center.y),
+        )
+    };
+    let middle = (start.x + end.x) / 2.0;
+
+    let line = Path::new(|p: &mut path::Builder| {
+        p.move_to(start);
+        p.line_to(Point::new(middle, start.y));
+        p.line_to(Point::new(middle, end.y));
+        p.line_to(end);
+    });
+    let mut stroke = Stroke::default().with_width(1.5).with_color(color);
+    if dashed {
+        stroke.line_dash = canvas::LineDash {
+            segments: &[5.0, 4.0],
+            offset: 0,
+        };
+    }
+    frame.stroke(&line, stroke);
+
+    let direction = if end.x >= middle { -1.0 } else { 1.0 };
+    let head = Path::new(|p: &mut path::Builder| {
+        p.move_to(end);
+        p.line_to(Point::new(end.x + direction * 8.0, end.y - 4.0));
+        p.line_to(Point::new(end.x + direction * 8.0, end.y + 4.0));
+        p.close();
+    });
+    frame.fill(&head, color);
+}
@@ -1 +1,242 @@
+use iced::{
+    border,
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input},
+    Element,
+    Length::Fill,
+    Task, Theme,
+};
 
+use crate::{
+    erd::ViewMode,
+    model::{Extension, Project, RoutineKind, TypeCategory},
+};
+
+use super::Selection;
+
+const WIDTH: f32 = 240.0;
+
+#[derive(Default)]
+pub struct Explorer {
+    filter: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum ExplorerMessage {
+    Filter(String),
+    Select(Selection),
+    AddType(TypeCategory),
+    AddView(bool),
+    AddRoutine(RoutineKind),
+    AddSequence,
+    AddRole,
+    ToggleMatrix,
+    ViewMode(ViewMode),
+    Extension(Extension, bool),
+}
+
+impl Explorer {
+    pub fn update(&mut self, message: ExplorerMessage) -> Task<ExplorerMessage> {
+        match message {
+            ExplorerMessage::Filter(filter) => {
+                self.filter = filter;
+                Task::none()
+            }
+            // The project and selection are owned by the content, which
+            // reacts to these.
+            ExplorerMessage::Select(_)
+            | ExplorerMessage::AddType(_)
+            | ExplorerMessage::AddView(_)
+            | ExplorerMessage::AddRoutine(_)
+            | ExplorerMessage::AddSequence
+            | ExplorerMessage::AddRole
+            | ExplorerMessage::ToggleMatrix
+            | ExplorerMessage::ViewMode(_)
+            | ExplorerMessage::Extension(..) => Task::none(),
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        project: &'a Project,
+        selection: Option<Selection>,
+        show_matrix: bool,
+        mode: ViewMode,
+    ) -> Element<'a, ExplorerMessage> {
+        let filter = self.filter.to_lowercase();
+        let mut list = column![].spacing(2);
+
+        for schema in project.schemas() {
+            let tables: Vec<_> = project
+                .tables
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.schema == schema && t.name.to_lowercase().contains(&filter))
+                .collect();
+            let types: Vec<_> = project
+                .types
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.schema == schema && t.name.to_lowercase().contains(&filter))
+                .collect();
+            let views: Vec<_> = project
+                .views
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.schema == schema && v.name.to_lowercase().contains(&filter))
+                .collect();
+            let routines: Vec<_> = project
+                .routines
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.schema == schema && r.name.to_lowercase().contains(&filter))
+                .collect();
+            let sequences: Vec<_> = project
+                .sequences
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.schema == schema && s.name.to_lowercase().contains(&filter))
+                .collect();
+            if tables.is_empty()
+                && types.is_empty()
+                && views.is_empty()
+                && routines.is_empty()
+                && sequences.is_empty()
+            {
+                continue;
+            }
+
+            list = list.push(text(schema).size(12));
+            for (i, table) in tables {
+                list = list.push(item(
+                    table.name.clone(),
+                    Selection::Table(i),
+                    selection == Some(Selection::Table(i)),
+                ));
+            }
+            for (i, view) in views {
+                list = list.push(item(
+                    format!("{} ({})", view.name, view.kind()),
+                    Selection::View(i),
+                    selection == Some(Selection::View(i)),
+                ));
+            }
+            for (i, ty) in types {
+                list = list.push(item(
+                    format!("{} ({})", ty.name, ty.category()),
+                    Selection::Type(i),
+                    selection == Some(Selection::Type(i)),
+                ));
+            }
+            for (i, sequence) in sequences {
+                list = list.push(item(
+                    format!("{} (sequence)", sequence.name),
+                    Selection::Sequence(i),
+                    selection == Some(Selection::Sequence(i)),
+                ));
+            }
+            for (i, routine) in routines {
+                list = list.push(item(
+                    format!("{} ({})", routine.name, routine.kind),
+                    Selection::Routine(i),
+                    selection == Some(Selection::Routine(i)),
+                ));
+            }
+        }
+
+        let roles: Vec<_> = project
+            .roles
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.name.to_lowercase().contains(&filter))
+            .collect();
+        if !roles.is_empty() {
+            list = list.push(text("roles").size(12));
+            for (i, role) in roles {
+                list = list.push(item(
+                    role.name.clone(),
+                    Selection::Role(i),
+                    selection == Some(Selection::Role(i)),
+                ));
+            }
+        }
+
+        list = list.push(text("extensions").size(12));
+        let others = project
+            .extensions
+            .iter()
+            .filter(|extension| matches!(extension, Extension::Other(_)))
+            .cloned();
+        for extension in Extension::ALL.into_iter().chain(others) {
+            list = list.push(
+                checkbox(project.extensions.contains(&extension))
+                    .label(extension.to_string())
+                    .on_toggle(move |v| ExplorerMessage::Extension(extension.clone(), v)),
+            );
+        }
+
+        let add = row![
+            add_button(String::from("+ view"), ExplorerMessage::AddView(false)),
+            add_button(String::from("+ matview"), ExplorerMessage::AddView(true)),
+            add_button(String::from("+ sequence"), ExplorerMessage::AddSequence),
+        ]
+        .extend(TypeCategory::ALL.into_iter().map(|category| {
+            add_button(format!("+ {category}"), ExplorerMessage::AddType(category))
+        }))
+        .extend(
+            RoutineKind::ALL
+                .into_iter()
+                .map(|kind| add_button(format!("+ {kind}"), ExplorerMessage::AddRoutine(kind))),
+        )
+        .push(add_button(String::from("+ role"), ExplorerMessage::AddRole))
+        .spacing(4)
+        .wrap();
+
+        container(
+            column![
+                text_input("Filter", &self.filter).on_input(ExplorerMessage::Filter),
+                scrollable(list).height(Fill),
+                add,
+                pick_list(ViewMode::ALL, Some(mode), ExplorerMessage::ViewMode)
+                    .width(Fill)
+                    .text_size(12),
+                button(text(if show_matrix {
+                    "Show diagram"
+                } else {
+                    "Show permissions"
+                }))
+                .width(Fill)
+                .style(button::secondary)
+                .on_press(ExplorerMessage::ToggleMatrix),
+            ]
+            .spacing(8)
+            .padding(12),
+        )
+        .width(WIDTH)
+        .height(Fill)
+        .style(|theme: &Theme| {
+            let pallete = theme.extended_palette();
+            container::Style::default()
+                .border(border::color(pallete.background.weak.color).width(0.5))
+        })
+        .into()
+    }
+}
+
+fn item<'a>(label: String, selection: Selection, selected: bool) -> Element<'a, ExplorerMessage> {
+    button(text(label).size(14))
+        .width(Fill)
+        .style(if selected {
+            button::primary
+        } else {
+            button::text
+        })
+        .on_press(ExplorerMessage::Select(selection))
+        .into()
+}
+
+fn add_button<'a>(label: String, message: ExplorerMessage) -> Element<'a, ExplorerMessage> {
+    button(text(label).size(12))
+        .style(button::secondary)
+        .on_press(message)
+        .into()
+}
@@ -0,0 +1,132 @@
+use iced::{
+    widget::{button, column, container, row, scrollable, text},
+    Element,
+    Length::Fill,
+};
+
+use super::Selection;
+use crate::model::{Privilege, Privileges, Project, Table, DEFAULT_SCHEMA};
+
+const NAME_WIDTH: f32 = 200.0;
+const CELL_WIDTH: f32 = 96.0;
+
+/// Roles × tables grid of granted privileges in `\dp` notation, e.g. `arwd`.
+/// Privileges granted on some columns only are listed after a `/`. Clicking
+/// a cell selects the table.
+pub fn view(project: &Project) -> Element<'_, Selection> {
+    let roles = project.role_names();
+
+    let mut header = row![text("").width(NAME_WIDTH)];
+    for role in &roles {
+        header = header.push(text(role.clone()).size(12).width(CELL_WIDTH));
+    }
+
+    let mut grid = column![header].spacing(2);
+    for (i, table) in project.tables.iter().enumerate() {
+        let name = if table.schema == DEFAULT_SCHEMA {
+            table.name.clone()
+        } else {
+            format!("{}.{}", table.schema, table.name)
+        };
+        let label = if table.row_security {
+            format!("{name} (RLS)")
+        } else {
+            name
+        };
+
+        let mut line = row![text(label).size(14).width(NAME_WIDTH)];
+        for role in &roles {
+            line = line.push(
+                button(text(cell(table, role)).size(14))
+                    .width(CELL_WIDTH)
+                    .style(button::text)
+                    .on_press(Selection::Table(i)),
+            );
+        }
+        grid = grid.push(line);
+    }
+
+    container(
+        scrollable(grid.padding(12)).direction(scrollable::Direction::Both {
+            vertical: scrollable::Scrollbar::default(),
+            horizontal: scrollable::Scrollbar::default(),
+        }),
+    )
+    .width(Fill)
+    .height(Fill)
+    .into()
+}
+
+/// Role names are case sensitive, except for `PUBLIC`.
+fn same_role(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim(), b.trim());
+    a == b || (a.eq_ignore_ascii_case("public") && b.eq_ignore_ascii_case("public"))
+}
+
+/// What `role` holds on `table` once its grants and revokes are applied in
+/// order.
+fn cell(table: &Table, role: &str) -> String {
+    let mut held = Privileges::new();
+    for grant in table.grants.iter().filter(|g| same_role(&g.role, role)) {
+        grant.apply(&mut held);
+    }
+    let (mut whole, mut columns) = (Vec::new(), Vec::new());
+    for (privilege, column, _) in held {
+        match column {
+            Some(_) => columns.push(privilege),
+            None => whole.push(privilege),
+        }
+    }
+
+    let letters = |privileges: &[Privilege]| {
+        Privilege::ALL
+            .into_iter()
+            .filter(|p| privileges.contains(p))
+            .map(Privilege::acl_char)
+            .collect::<String>()
+    };
+    let mut out = letters(&whole);
+    if !columns.is_empty() {
+        out.push('/');
+        out.push_str(&letters(&columns));
+    }
+    if out.is_empty() {
+        out.push('-');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Grant;
+
+    fn grant(privileges: &[Privilege], columns: &[&str], revoke: bool) -> Grant {
+        Grant {
+            privileges: privileges.to_vec(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            revoke,
+            ..Grant::new("app")
+        }
+    }
+
+    #[test]
+    fn revokes_take_away_what_was_granted_before_them() {
+        let mut table = Table::new(DEFAULT_SCHEMA, "t");
+        table.grants = vec![
+            grant(&[Privilege::Select, Privilege::Delete], &[], false),
+            grant(&[Privilege::Update], &["a", "b"], false),
+            grant(&[Privilege::Delete], &[], true),
+            grant(&[Privilege::Update], &["a"], true),
+        ];
+        assert_eq!(cell(&table, "app"), "r/w");
+
+        table.grants.push(grant(&[Privilege::Update], &[], true));
+        assert_eq!(cell(&table, "app"), "r");
+
+        // Granted again after the revoke.
+        table.grants.push(grant(&[Privilege::Delete], &[], false));
+        assert_eq!(cell(&table, "app"), "rd");
+        assert_eq!(cell(&table, "other"), "-");
+    }
+}
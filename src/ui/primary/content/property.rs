@@ -1 +1,1524 @@
+use iced::{
+    border,
+    widget::{
+        button, checkbox, column, container, horizontal_space, pick_list, row, scrollable, text,
+        text_editor, text_input,
+    },
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Task, Theme,
+};
 
+use role::RoleMessage;
+use routine::RoutineMessage;
+use sequence::SequenceMessage;
+use user_type::TypeMessage;
+use view::ViewMessage;
+
+use super::Selection;
+use crate::model::{
+    join_list, split_list, Column, Constraint, ConstraintKind, ConstraintType, ExclusionElement,
+    Extension, Generation, Grant, Index, IndexElement, IndexMethod, NullsOrder, PartitionBound,
+    PartitionStrategy, Partitioning, Policy, PolicyCommand, Privilege, Project, ReferentialAction,
+    SortOrder, Table, Trigger, TriggerTiming,
+};
+
+pub mod role;
+pub mod routine;
+pub mod sequence;
+pub mod user_type;
+pub mod view;
+
+const LABEL_WIDTH: f32 = 96.0;
+const WIDTH: f32 = 380.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tab {
+    #[default]
+    Columns,
+    Constraints,
+    Indexes,
+    Triggers,
+    Partitions,
+    Security,
+}
+
+#[derive(Default)]
+pub struct Property {
+    tab: Tab,
+    /// Definition of the selected view or body of the selected routine, kept
+    /// here for its cursor state.
+    editor: text_editor::Content,
+}
+
+#[derive(Clone, Debug)]
+pub enum PropertyMessage {
+    Tab(Tab),
+    TableName(String),
+    TableSchema(String),
+    TableComment(String),
+    AddColumn,
+    RemoveColumn(usize),
+    Column(usize, ColumnMessage),
+    AddConstraint(ConstraintType),
+    RemoveConstraint(usize),
+    Constraint(usize, ConstraintMessage),
+    AddIndex,
+    RemoveIndex(usize),
+    Index(usize, IndexMessage),
+    AddTrigger,
+    RemoveTrigger(usize),
+    Trigger(usize, TriggerMessage),
+    Partitioned(bool),
+    PartitionStrategy(PartitionStrategy),
+    PartitionKey(String),
+    AddPartition,
+    RemovePartition(usize),
+    Partition(usize, PartitionMessage),
+    RowSecurity(bool),
+    ForceRowSecurity(bool),
+    AddPolicy,
+    RemovePolicy(usize),
+    Policy(usize, PolicyMessage),
+    AddGrant,
+    RemoveGrant(usize),
+    Grant(usize, GrantMessage),
+    Type(TypeMessage),
+    View(ViewMessage),
+    Routine(RoutineMessage),
+    Sequence(SequenceMessage),
+    Role(RoleMessage),
+    EnableExtension(Extension),
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub enum ColumnMessage {
+    Name(String),
+    DataType(String),
+    NotNull(bool),
+    Default(String),
+    Generation(Generation),
+    Expression(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConstraintMessage {
+    Name(String),
+    Type(ConstraintType),
+    Columns(String),
+    Include(String),
+    NullsNotDistinct(bool),
+    Expression(String),
+    NoInherit(bool),
+    RefTable(String),
+    RefColumns(String),
+    OnUpdate(ReferentialAction),
+    OnDelete(ReferentialAction),
+    Method(IndexMethod),
+    Element(usize, String),
+    Operator(usize, String),
+    AddElement,
+    RemoveElement(usize),
+    Predicate(String),
+    Deferrable(bool),
+    InitiallyDeferred(bool),
+}
+
+#[derive(Clone, Debug)]
+pub enum IndexMessage {
+    Name(String),
+    Unique(bool),
+    NullsNotDistinct(bool),
+    Method(IndexMethod),
+    Expression(usize, String),
+    Opclass(usize, String),
+    Order(usize, SortOrder),
+    Nulls(usize, NullsOrder),
+    AddElement,
+    RemoveElement(usize),
+    Include(String),
+    Predicate(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum TriggerMessage {
+    Name(String),
+    Timing(TriggerTiming),
+    Insert(bool),
+    Update(bool),
+    Delete(bool),
+    Truncate(bool),
+    UpdateColumns(String),
+    ForEachRow(bool),
+    Condition(String),
+    Function(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum PartitionMessage {
+    Name(String),
+    From(String),
+    To(String),
+    Values(String),
+    Modulus(String),
+    Remainder(String),
+    Default(bool),
+}
+
+#[derive(Clone, Debug)]
+pub enum PolicyMessage {
+    Name(String),
+    Permissive(bool),
+    Command(PolicyCommand),
+    Roles(String),
+    Using(String),
+    WithCheck(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum GrantMessage {
+    Role(String),
+    Privilege(Privilege, bool),
+    Columns(String),
+    WithGrantOption(bool),
+    Revoke(bool),
+}
+
+impl PropertyMessage {
+    /// The text field this message types into, with the item it belongs to,
+    /// so that typing into one field is undone as a single change.
+    pub fn text_field(&self) -> Option<String> {
+        let item = |item: &str, i: &usize, field: Option<String>| {
+            field.map(|field| format!("{item} {i} {field}"))
+        };
+        match self {
+            Self::TableName(_) => Some(String::from("name")),
+            Self::TableSchema(_) => Some(String::from("schema")),
+            Self::TableComment(_) => Some(String::from("comment")),
+            Self::PartitionKey(_) => Some(String::from("partition key")),
+            Self::Column(i, message) => item("column", i, message.text_field()),
+            Self::Constraint(i, message) => item("constraint", i, message.text_field()),
+            Self::Index(i, message) => item("index", i, message.text_field()),
+            Self::Trigger(i, message) => item("trigger", i, message.text_field()),
+            Self::Partition(i, message) => item("partition", i, message.text_field()),
+            Self::Policy(i, message) => item("policy", i, message.text_field()),
+            Self::Grant(i, message) => item("grant", i, message.text_field()),
+            Self::Type(message) => message.text_field(),
+            Self::View(message) => message.text_field(),
+            Self::Routine(message) => message.text_field(),
+            Self::Sequence(message) => message.text_field(),
+            Self::Role(message) => message.text_field(),
+            _ => None,
+        }
+    }
+}
+
+impl ColumnMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::DataType(_) => String::from("type"),
+            Self::Default(_) => String::from("default"),
+            Self::Expression(_) => String::from("expression"),
+            Self::NotNull(_) | Self::Generation(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl ConstraintMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Columns(_) => String::from("columns"),
+            Self::Include(_) => String::from("include"),
+            Self::Expression(_) => String::from("expression"),
+            Self::RefTable(_) => String::from("referenced table"),
+            Self::RefColumns(_) => String::from("referenced columns"),
+            Self::Element(i, _) => format!("element {i}"),
+            Self::Operator(i, _) => format!("operator {i}"),
+            Self::Predicate(_) => String::from("predicate"),
+            Self::Type(_)
+            | Self::NullsNotDistinct(_)
+            | Self::NoInherit(_)
+            | Self::OnUpdate(_)
+            | Self::OnDelete(_)
+            | Self::Method(_)
+            | Self::AddElement
+            | Self::RemoveElement(_)
+            | Self::Deferrable(_)
+            | Self::InitiallyDeferred(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl IndexMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Expression(i, _) => format!("expression {i}"),
+            Self::Opclass(i, _) => format!("opclass {i}"),
+            Self::Include(_) => String::from("include"),
+            Self::Predicate(_) => String::from("predicate"),
+            Self::Unique(_)
+            | Self::NullsNotDistinct(_)
+            | Self::Method(_)
+            | Self::Order(..)
+            | Self::Nulls(..)
+            | Self::AddElement
+            | Self::RemoveElement(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl TriggerMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::UpdateColumns(_) => String::from("update columns"),
+            Self::Condition(_) => String::from("condition"),
+            Self::Function(_) => String::from("function"),
+            Self::Timing(_)
+            | Self::Insert(_)
+            | Self::Update(_)
+            | Self::Delete(_)
+            | Self::Truncate(_)
+            | Self::ForEachRow(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl PartitionMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::From(_) => String::from("from"),
+            Self::To(_) => String::from("to"),
+            Self::Values(_) => String::from("values"),
+            Self::Modulus(_) => String::from("modulus"),
+            Self::Remainder(_) => String::from("remainder"),
+            Self::Default(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl PolicyMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Roles(_) => String::from("roles"),
+            Self::Using(_) => String::from("using"),
+            Self::WithCheck(_) => String::from("with check"),
+            Self::Permissive(_) | Self::Command(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl GrantMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Role(_) => String::from("role"),
+            Self::Columns(_) => String::from("columns"),
+            Self::Privilege(..) | Self::WithGrantOption(_) | Self::Revoke(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+impl Property {
+    pub fn update(
+        &mut self,
+        message: PropertyMessage,
+        project: &mut Project,
+        selection: Selection,
+    ) -> Task<PropertyMessage> {
+        match (selection, message) {
+            (_, PropertyMessage::Tab(tab)) => self.tab = tab,
+            (_, PropertyMessage::EnableExtension(extension))
+                if !project.extensions.contains(&extension) =>
+            {
+                project.extensions.push(extension);
+            }
+            (_, PropertyMessage::EnableExtension(_)) => {}
+            // Deleting changes the selection, so the content handles it.
+            (_, PropertyMessage::Delete) => {}
+            (Selection::Type(i), PropertyMessage::Type(message)) => {
+                if let Some(ty) = project.types.get_mut(i) {
+                    user_type::update(ty, message);
+                }
+            }
+            (Selection::Role(i), PropertyMessage::Role(message)) => {
+                if let Some(role) = project.roles.get_mut(i) {
+                    role::update(role, message);
+                }
+            }
+            (Selection::Sequence(i), PropertyMessage::Sequence(message)) => {
+                if let Some(sequence) = project.sequences.get_mut(i) {
+                    sequence::update(sequence, message);
+                }
+            }
+            (Selection::Routine(i), PropertyMessage::Routine(message)) => {
+                if let Some(routine) = project.routines.get_mut(i) {
+                    routine::update(routine, &mut self.editor, message);
+                }
+            }
+            (Selection::View(i), PropertyMessage::View(message)) => {
+                if let Some(view) = project.views.get_mut(i) {
+                    view::update(view, &mut self.editor, message);
+                }
+            }
+            (Selection::View(i), message) => {
+                if let Some(view) = project.views.get_mut(i) {
+                    let name = view.index_name();
+                    update_indexes(&mut view.indexes, name, message);
+                }
+            }
+            (Selection::Table(i), message) => {
+                let functions = project.trigger_functions();
+                if let Some(table) = project.tables.get_mut(i) {
+                    update_table(table, &functions, message);
+                }
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    pub fn show_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+    }
+
+    /// Resets per-object editing state when the selection changes.
+    pub fn select(&mut self, project: &Project, selection: Selection) {
+        let text = match selection {
+            Selection::View(i) => project.views.get(i).map(|v| &v.definition),
+            Selection::Routine(i) => project.routines.get(i).map(|r| &r.body),
+            Selection::Table(_)
+            | Selection::Type(_)
+            | Selection::Sequence(_)
+            | Selection::Role(_) => None,
+        };
+        if let Some(text) = text {
+            self.editor = text_editor::Content::with_text(text);
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        project: &'a Project,
+        selection: Selection,
+    ) -> Option<Element<'a, PropertyMessage>> {
+        let body = match selection {
+            Selection::Table(i) => self.table_view(project, project.tables.get(i)?),
+            Selection::Type(i) => {
+                user_type::view(project, project.types.get(i)?).map(PropertyMessage::Type)
+            }
+            Selection::View(i) => {
+                let view = project.views.get(i)?;
+                let mut body =
+                    column![view::view(view, &self.editor).map(PropertyMessage::View)].spacing(12);
+                if view.materialized {
+                    body = body
+                        .push(text("Indexes").size(12))
+                        .push(indexes_view(&view.indexes));
+                }
+                body.into()
+            }
+            Selection::Role(i) => role::view(project.roles.get(i)?).map(PropertyMessage::Role),
+            Selection::Sequence(i) => {
+                sequence::view(project, project.sequences.get(i)?).map(PropertyMessage::Sequence)
+            }
+            Selection::Routine(i) => {
+                routine::view(project.routines.get(i)?, &self.editor).map(PropertyMessage::Routine)
+            }
+        };
+
+        Some(
+            container(scrollable(
+                column![body, delete_button()].spacing(12).padding(12),
+            ))
+            .width(WIDTH)
+            .height(Fill)
+            .style(|theme: &Theme| {
+                let pallete = theme.extended_palette();
+                container::Style::default()
+                    .border(border::color(pallete.background.weak.color).width(0.5))
+            })
+            .into(),
+        )
+    }
+
+    fn table_view<'a>(
+        &'a self,
+        project: &'a Project,
+        table: &'a Table,
+    ) -> Element<'a, PropertyMessage> {
+        let header = column![
+            field("Name", &table.name, PropertyMessage::TableName),
+            field("Schema", &table.schema, PropertyMessage::TableSchema),
+            field(
+                "Comment",
+                table.comment.as_deref().unwrap_or_default(),
+                PropertyMessage::TableComment
+            ),
+        ]
+        .spacing(6);
+
+        let tabs = row![
+            tab_button("Columns", Tab::Columns, self.tab),
+            tab_button("Constraints", Tab::Constraints, self.tab),
+            tab_button("Indexes", Tab::Indexes, self.tab),
+            tab_button("Triggers", Tab::Triggers, self.tab),
+            tab_button("Partitions", Tab::Partitions, self.tab),
+            tab_button("Security", Tab::Security, self.tab),
+        ]
+        .spacing(4)
+        .wrap();
+
+        let body = match self.tab {
+            Tab::Columns => columns_view(project, table),
+            Tab::Constraints => constraints_view(project, table),
+            Tab::Indexes => indexes_view(&table.indexes),
+            Tab::Triggers => triggers_view(project, table),
+            Tab::Partitions => partitions_view(table),
+            Tab::Security => security_view(project, table),
+        };
+
+        column![header, tabs, body].spacing(12).into()
+    }
+}
+
+/// `functions` are the trigger functions a new trigger can default to.
+fn update_table(table: &mut Table, functions: &[String], message: PropertyMessage) {
+    match message {
+        PropertyMessage::TableName(name) => table.name = name,
+        PropertyMessage::TableSchema(schema) => table.schema = schema,
+        PropertyMessage::TableComment(comment) => table.comment = non_empty(comment),
+        PropertyMessage::AddColumn => {
+            let name = format!("column_{}", table.columns.len() + 1);
+            table.columns.push(Column::new(name, "text"));
+        }
+        PropertyMessage::RemoveColumn(i) => {
+            if i < table.columns.len() {
+                table.columns.remove(i);
+            }
+        }
+        PropertyMessage::Column(i, message) => {
+            if let Some(column) = table.columns.get_mut(i) {
+                update_column(column, message);
+            }
+        }
+        PropertyMessage::AddConstraint(constraint_type) => {
+            let name = table.constraint_name(constraint_type);
+            table
+                .constraints
+                .push(Constraint::new(name, constraint_type.empty()));
+        }
+        PropertyMessage::RemoveConstraint(i) => {
+            if i < table.constraints.len() {
+                table.constraints.remove(i);
+            }
+        }
+        PropertyMessage::Constraint(i, message) => {
+            if let Some(constraint) = table.constraints.get_mut(i) {
+                update_constraint(constraint, message);
+            }
+        }
+        PropertyMessage::AddTrigger => {
+            let name = table.trigger_name();
+            let function = functions.first().cloned().unwrap_or_default();
+            table.triggers.push(Trigger::new(name, function));
+        }
+        PropertyMessage::RemoveTrigger(i) if i < table.triggers.len() => {
+            table.triggers.remove(i);
+        }
+        PropertyMessage::Trigger(i, message) => {
+            if let Some(trigger) = table.triggers.get_mut(i) {
+                update_trigger(trigger, message);
+            }
+        }
+        PropertyMessage::RowSecurity(value) => table.row_security = value,
+        PropertyMessage::ForceRowSecurity(value) => table.force_row_security = value,
+        PropertyMessage::AddPolicy => {
+            let name = table.policy_name();
+            table.policies.push(Policy::new(name));
+        }
+        PropertyMessage::RemovePolicy(i) if i < table.policies.len() => {
+            table.policies.remove(i);
+        }
+        PropertyMessage::Policy(i, message) => {
+            if let Some(policy) = table.policies.get_mut(i) {
+                update_policy(policy, message);
+            }
+        }
+        PropertyMessage::AddGrant => table.grants.push(Grant::new("PUBLIC")),
+        PropertyMessage::RemoveGrant(i) if i < table.grants.len() => {
+            table.grants.remove(i);
+        }
+        PropertyMessage::Grant(i, message) => {
+            if let Some(grant) = table.grants.get_mut(i) {
+                update_grant(grant, message);
+            }
+        }
+        PropertyMessage::Partitioned(partitioned) => {
+            table.partitioning = partitioned.then(|| Partitioning::new(PartitionStrategy::Range));
+        }
+        PropertyMessage::AddPartition => {
+            let name = table.partition_name();
+            if let Some(partitioning) = &mut table.partitioning {
+                partitioning.add_partition(name);
+            }
+        }
+        message @ (PropertyMessage::PartitionStrategy(_)
+        | PropertyMessage::PartitionKey(_)
+        | PropertyMessage::RemovePartition(_)
+        | PropertyMessage::Partition(..)) => {
+            if let Some(partitioning) = &mut table.partitioning {
+                update_partitioning(partitioning, message);
+            }
+        }
+        message => {
+            let name = table.index_name();
+            update_indexes(&mut table.indexes, name, message);
+        }
+    }
+}
+
+/// Handles the index messages shared by tables and materialized views.
+fn update_indexes(indexes: &mut Vec<Index>, new_name: String, message: PropertyMessage) {
+    match message {
+        PropertyMessage::AddIndex => indexes.push(Index::new(new_name).on("")),
+        PropertyMessage::RemoveIndex(i) if i < indexes.len() => {
+            indexes.remove(i);
+        }
+        PropertyMessage::Index(i, message) => {
+            if let Some(index) = indexes.get_mut(i) {
+                update_index(index, message);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn update_column(column: &mut Column, message: ColumnMessage) {
+    match message {
+        ColumnMessage::Name(name) => column.name = name,
+        ColumnMessage::DataType(data_type) => column.data_type = data_type,
+        ColumnMessage::NotNull(not_null) => column.not_null = not_null,
+        ColumnMessage::Default(default) => column.default = non_empty(default),
+        ColumnMessage::Generation(generation) => column.set_generation(generation),
+        ColumnMessage::Expression(expression) => column.generated = Some(expression),
+    }
+}
+
+fn update_constraint(constraint: &mut Constraint, message: ConstraintMessage) {
+    match message {
+        ConstraintMessage::Name(name) => constraint.name = name,
+        ConstraintMessage::Type(constraint_type) => {
+            if constraint_type != constraint.constraint_type() {
+                constraint.kind = constraint_type.empty();
+            }
+        }
+        ConstraintMessage::Deferrable(deferrable) => {
+            constraint.deferrable = deferrable;
+            if !deferrable {
+                constraint.initially_deferred = false;
+            }
+        }
+        ConstraintMessage::InitiallyDeferred(deferred) => constraint.initially_deferred = deferred,
+        message => update_constraint_kind(&mut constraint.kind, message),
+    }
+}
+
+fn update_constraint_kind(kind: &mut ConstraintKind, message: ConstraintMessage) {
+    match (kind, message) {
+        (
+            ConstraintKind::PrimaryKey { columns, .. } | ConstraintKind::Unique { columns, .. },
+            ConstraintMessage::Columns(value),
+        ) => *columns = split_list(&value),
+        (
+            ConstraintKind::PrimaryKey { include, .. }
+            | ConstraintKind::Unique { include, .. }
+            | ConstraintKind::Exclusion { include, .. },
+            ConstraintMessage::Include(value),
+        ) => *include = split_list(&value),
+        (
+            ConstraintKind::Unique {
+                nulls_not_distinct, ..
+            },
+            ConstraintMessage::NullsNotDistinct(value),
+        ) => *nulls_not_distinct = value,
+        (ConstraintKind::Check { expression, .. }, ConstraintMessage::Expression(value)) => {
+            *expression = value
+        }
+        (ConstraintKind::Check { no_inherit, .. }, ConstraintMessage::NoInherit(value)) => {
+            *no_inherit = value
+        }
+        (ConstraintKind::ForeignKey(fk), ConstraintMessage::Columns(value)) => {
+            fk.columns = split_list(&value)
+        }
+        (ConstraintKind::ForeignKey(fk), ConstraintMessage::RefTable(value)) => {
+            let (schema, table) = value.split_once('.').unwrap_or(("", value.as_str()));
+            fk.ref_schema = schema.to_string();
+            fk.ref_table = table.to_string();
+        }
+        (ConstraintKind::ForeignKey(fk), ConstraintMessage::RefColumns(value)) => {
+            fk.ref_columns = split_list(&value)
+        }
+        (ConstraintKind::ForeignKey(fk), ConstraintMessage::OnUpdate(action)) => {
+            fk.on_update = action
+        }
+        (ConstraintKind::ForeignKey(fk), ConstraintMessage::OnDelete(action)) => {
+            fk.on_delete = action
+        }
+        (ConstraintKind::Exclusion { method, .. }, ConstraintMessage::Method(value)) => {
+            *method = value
+        }
+        (ConstraintKind::Exclusion { elements, .. }, ConstraintMessage::Element(i, value)) => {
+            if let Some(element) = elements.get_mut(i) {
+                element.element = value;
+            }
+        }
+        (ConstraintKind::Exclusion { elements, .. }, ConstraintMessage::Operator(i, value)) => {
+            if let Some(element) = elements.get_mut(i) {
+                element.operator = value;
+            }
+        }
+        (ConstraintKind::Exclusion { elements, .. }, ConstraintMessage::AddElement) => {
+            elements.push(ExclusionElement::default())
+        }
+        (ConstraintKind::Exclusion { elements, .. }, ConstraintMessage::RemoveElement(i))
+            if i < elements.len() =>
+        {
+            elements.remove(i);
+        }
+        (ConstraintKind::Exclusion { predicate, .. }, ConstraintMessage::Predicate(value)) => {
+            *predicate = non_empty(value)
+        }
+        _ => {}
+    }
+}
+
+fn update_index(index: &mut Index, message: IndexMessage) {
+    match message {
+        IndexMessage::Name(name) => index.name = name,
+        IndexMessage::Unique(unique) => index.unique = unique,
+        IndexMessage::NullsNotDistinct(value) => index.nulls_not_distinct = value,
+        IndexMessage::Method(method) => {
+            index.method = method;
+            if !method.is_ordered() {
+                index.unique = false;
+            }
+        }
+        IndexMessage::Expression(i, value) => {
+            if let Some(element) = index.elements.get_mut(i) {
+                element.expression = value;
+            }
+        }
+        IndexMessage::Opclass(i, value) => {
+            if let Some(element) = index.elements.get_mut(i) {
+                element.opclass = non_empty(value);
+            }
+        }
+        IndexMessage::Order(i, order) => {
+            if let Some(element) = index.elements.get_mut(i) {
+                element.order = order;
+            }
+        }
+        IndexMessage::Nulls(i, nulls) => {
+            if let Some(element) = index.elements.get_mut(i) {
+                element.nulls = nulls;
+            }
+        }
+        IndexMessage::AddElement => index.elements.push(IndexElement::new("")),
+        IndexMessage::RemoveElement(i) => {
+            if i < index.elements.len() {
+                index.elements.remove(i);
+            }
+        }
+        IndexMessage::Include(value) => index.include = split_list(&value),
+        IndexMessage::Predicate(value) => index.predicate = non_empty(value),
+    }
+}
+
+fn columns_view<'a>(project: &'a Project, table: &'a Table) -> Element<'a, PropertyMessage> {
+    let types = project.type_names();
+    let defaults = project.default_suggestions();
+    let mut list = column![].spacing(8);
+    for (i, c) in table.columns.iter().enumerate() {
+        let on = move |message: fn(String) -> ColumnMessage| {
+            move |value: String| PropertyMessage::Column(i, message(value))
+        };
+        let selected = types.iter().find(|t| **t == c.data_type).cloned();
+        // Identity and serial columns get their values from a sequence, so
+        // there's nothing else to type in.
+        let value: Element<'a, PropertyMessage> = match c.generation() {
+            Generation::None => {
+                let current = c.default.as_ref().filter(|d| defaults.contains(d)).cloned();
+                row![
+                    text_input("default", c.default.as_deref().unwrap_or_default())
+                        .on_input(on(ColumnMessage::Default)),
+                    pick_list(defaults.clone(), current, on(ColumnMessage::Default))
+                        .placeholder("...")
+                        .width(64),
+                ]
+                .spacing(4)
+                .into()
+            }
+            Generation::Stored => {
+                text_input("expression", c.generated.as_deref().unwrap_or_default())
+                    .on_input(on(ColumnMessage::Expression))
+                    .into()
+            }
+            Generation::Serial | Generation::IdentityAlways | Generation::IdentityByDefault => {
+                horizontal_space().into()
+            }
+        };
+        let mut body = column![
+            row![
+                text_input("name", &c.name).on_input(on(ColumnMessage::Name)),
+                text_input("type", &c.data_type).on_input(on(ColumnMessage::DataType)),
+                remove_button(PropertyMessage::RemoveColumn(i)),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            row![
+                pick_list(types.clone(), selected, on(ColumnMessage::DataType))
+                    .placeholder("type")
+                    .width(120),
+                checkbox(c.not_null)
+                    .label("NOT NULL")
+                    .on_toggle(move |v| PropertyMessage::Column(i, ColumnMessage::NotNull(v))),
+            ]
+            .spacing(8)
+            .align_y(Center),
+            row![
+                pick_list(Generation::ALL, Some(c.generation()), move |v| {
+                    PropertyMessage::Column(i, ColumnMessage::Generation(v))
+                })
+                .width(120),
+                value,
+            ]
+            .spacing(8)
+            .align_y(Center),
+        ]
+        .spacing(4);
+        if let Some(extension) = project.missing_extension(c) {
+            body = body.push(
+                row![
+                    text(format!("Needs the {extension} extension"))
+                        .size(12)
+                        .style(text::danger),
+                    button(text("Enable").size(12))
+                        .style(button::secondary)
+                        .on_press(PropertyMessage::EnableExtension(extension)),
+                ]
+                .spacing(8)
+                .align_y(Center),
+            );
+        }
+        list = list.push(card(body));
+    }
+    column![list, add_button("Add column", PropertyMessage::AddColumn)]
+        .spacing(8)
+        .into()
+}
+
+fn constraints_view<'a>(project: &'a Project, table: &'a Table) -> Element<'a, PropertyMessage> {
+    let tables: Vec<String> = project
+        .tables
+        .iter()
+        .map(|t| format!("{}.{}", t.schema, t.name))
+        .collect();
+
+    let mut list = column![].spacing(8);
+    for (i, constraint) in table.constraints.iter().enumerate() {
+        let on = move |message: ConstraintMessage| PropertyMessage::Constraint(i, message);
+        let mut body = column![row![
+            pick_list(
+                ConstraintType::ALL,
+                Some(constraint.constraint_type()),
+                move |t| on(ConstraintMessage::Type(t))
+            ),
+            text_input("name", &constraint.name).on_input(move |v| on(ConstraintMessage::Name(v))),
+            remove_button(PropertyMessage::RemoveConstraint(i)),
+        ]
+        .spacing(4)
+        .align_y(Center)]
+        .spacing(4);
+
+        match &constraint.kind {
+            ConstraintKind::PrimaryKey { columns, include } => {
+                body = body
+                    .push(list_field("Columns", columns, move |v| {
+                        on(ConstraintMessage::Columns(v))
+                    }))
+                    .push(list_field("Include", include, move |v| {
+                        on(ConstraintMessage::Include(v))
+                    }));
+            }
+            ConstraintKind::Unique {
+                columns,
+                include,
+                nulls_not_distinct,
+            } => {
+                body = body
+                    .push(list_field("Columns", columns, move |v| {
+                        on(ConstraintMessage::Columns(v))
+                    }))
+                    .push(list_field("Include", include, move |v| {
+                        on(ConstraintMessage::Include(v))
+                    }))
+                    .push(
+                        checkbox(*nulls_not_distinct)
+                            .label("NULLS NOT DISTINCT")
+                            .on_toggle(move |v| on(ConstraintMessage::NullsNotDistinct(v))),
+                    );
+            }
+            ConstraintKind::Check {
+                expression,
+                no_inherit,
+            } => {
+                body = body
+                    .push(field("Expression", expression, move |v| {
+                        on(ConstraintMessage::Expression(v))
+                    }))
+                    .push(
+                        checkbox(*no_inherit)
+                            .label("NO INHERIT")
+                            .on_toggle(move |v| on(ConstraintMessage::NoInherit(v))),
+                    );
+            }
+            ConstraintKind::ForeignKey(fk) => {
+                let selected = (!fk.ref_table.is_empty())
+                    .then(|| format!("{}.{}", fk.ref_schema, fk.ref_table));
+                body = body
+                    .push(list_field("Columns", &fk.columns, move |v| {
+                        on(ConstraintMessage::Columns(v))
+                    }))
+                    .push(labeled(
+                        "References",
+                        pick_list(tables.clone(), selected, move |v| {
+                            on(ConstraintMessage::RefTable(v))
+                        })
+                        .width(Fill)
+                        .into(),
+                    ))
+                    .push(list_field("Ref columns", &fk.ref_columns, move |v| {
+                        on(ConstraintMessage::RefColumns(v))
+                    }))
+                    .push(labeled(
+                        "On update",
+                        pick_list(ReferentialAction::ALL, Some(fk.on_update), move |v| {
+                            on(ConstraintMessage::OnUpdate(v))
+                        })
+                        .width(Fill)
+                        .into(),
+                    ))
+                    .push(labeled(
+                        "On delete",
+                        pick_list(ReferentialAction::ALL, Some(fk.on_delete), move |v| {
+                            on(ConstraintMessage::OnDelete(v))
+                        })
+                        .width(Fill)
+                        .into(),
+                    ));
+            }
+            ConstraintKind::Exclusion {
+                method,
+                elements,
+                include,
+                predicate,
+            } => {
+                body = body.push(labeled(
+                    "Method",
+                    pick_list(IndexMethod::ALL, Some(*method), move |v| {
+                        on(ConstraintMessage::Method(v))
+                    })
+                    .width(Fill)
+                    .into(),
+                ));
+                for (j, element) in elements.iter().enumerate() {
+                    body = body.push(
+                        row![
+                            text_input("column or expression", &element.element)
+                                .on_input(move |v| on(ConstraintMessage::Element(j, v))),
+                            text("WITH"),
+                            text_input("=", &element.operator)
+                                .on_input(move |v| on(ConstraintMessage::Operator(j, v)))
+                                .width(56),
+                            remove_button(on(ConstraintMessage::RemoveElement(j))),
+                        ]
+                        .spacing(4)
+                        .align_y(Center),
+                    );
+                }
+                body = body
+                    .push(add_button("Add element", on(ConstraintMessage::AddElement)))
+                    .push(list_field("Include", include, move |v| {
+                        on(ConstraintMessage::Include(v))
+                    }))
+                    .push(field(
+                        "Where",
+                        predicate.as_deref().unwrap_or_default(),
+                        move |v| on(ConstraintMessage::Predicate(v)),
+                    ));
+            }
+        }
+
+        if constraint.can_defer() {
+            let mut deferral = row![checkbox(constraint.deferrable)
+                .label("DEFERRABLE")
+                .on_toggle(move |v| on(ConstraintMessage::Deferrable(v)))]
+            .spacing(8);
+            if constraint.deferrable {
+                deferral = deferral.push(
+                    checkbox(constraint.initially_deferred)
+                        .label("INITIALLY DEFERRED")
+                        .on_toggle(move |v| on(ConstraintMessage::InitiallyDeferred(v))),
+                );
+            }
+            body = body.push(deferral);
+        }
+
+        list = list.push(card(body));
+    }
+
+    let add = ConstraintType::ALL
+        .into_iter()
+        .fold(row![].spacing(4), |row, constraint_type| {
+            row.push(add_button(
+                match constraint_type {
+                    ConstraintType::PrimaryKey => "+ PK",
+                    ConstraintType::Unique => "+ Unique",
+                    ConstraintType::Check => "+ Check",
+                    ConstraintType::ForeignKey => "+ FK",
+                    ConstraintType::Exclusion => "+ Exclude",
+                },
+                PropertyMessage::AddConstraint(constraint_type),
+            ))
+        });
+
+    column![list, add].spacing(8).into()
+}
+
+fn indexes_view(indexes: &[Index]) -> Element<'_, PropertyMessage> {
+    let mut list = column![].spacing(8);
+    for (i, index) in indexes.iter().enumerate() {
+        let on = move |message: IndexMessage| PropertyMessage::Index(i, message);
+        let ordered = index.method.is_ordered();
+
+        let mut flags = row![].spacing(8);
+        if ordered {
+            flags = flags.push(
+                checkbox(index.unique)
+                    .label("UNIQUE")
+                    .on_toggle(move |v| on(IndexMessage::Unique(v))),
+            );
+            if index.unique {
+                flags = flags.push(
+                    checkbox(index.nulls_not_distinct)
+                        .label("NULLS NOT DISTINCT")
+                        .on_toggle(move |v| on(IndexMessage::NullsNotDistinct(v))),
+                );
+            }
+        }
+
+        let mut body = column![
+            row![
+                text_input("name", &index.name).on_input(move |v| on(IndexMessage::Name(v))),
+                pick_list(IndexMethod::ALL, Some(index.method), move |v| {
+                    on(IndexMessage::Method(v))
+                }),
+                remove_button(PropertyMessage::RemoveIndex(i)),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            flags,
+        ]
+        .spacing(4);
+
+        for (j, element) in index.elements.iter().enumerate() {
+            let mut line = row![
+                text_input("column or expression", &element.expression)
+                    .on_input(move |v| on(IndexMessage::Expression(j, v))),
+                text_input("opclass", element.opclass.as_deref().unwrap_or_default())
+                    .on_input(move |v| on(IndexMessage::Opclass(j, v)))
+                    .width(72),
+            ]
+            .spacing(4)
+            .align_y(Center);
+            if ordered {
+                line = line
+                    .push(pick_list(SortOrder::ALL, Some(element.order), move |v| {
+                        on(IndexMessage::Order(j, v))
+                    }))
+                    .push(pick_list(NullsOrder::ALL, Some(element.nulls), move |v| {
+                        on(IndexMessage::Nulls(j, v))
+                    }));
+            }
+            body = body.push(line.push(remove_button(on(IndexMessage::RemoveElement(j)))));
+        }
+        body = body.push(add_button("Add key", on(IndexMessage::AddElement)));
+
+        if index.method.supports_include() {
+            body = body.push(list_field("Include", &index.include, move |v| {
+                on(IndexMessage::Include(v))
+            }));
+        }
+        body = body.push(field(
+            "Where",
+            index.predicate.as_deref().unwrap_or_default(),
+            move |v| on(IndexMessage::Predicate(v)),
+        ));
+
+        list = list.push(card(body));
+    }
+    column![list, add_button("Add index", PropertyMessage::AddIndex)]
+        .spacing(8)
+        .into()
+}
+
+fn update_trigger(trigger: &mut Trigger, message: TriggerMessage) {
+    match message {
+        TriggerMessage::Name(name) => trigger.name = name,
+        TriggerMessage::Timing(timing) => trigger.timing = timing,
+        TriggerMessage::Insert(value) => trigger.insert = value,
+        TriggerMessage::Update(value) => trigger.update = value,
+        TriggerMessage::Delete(value) => trigger.delete = value,
+        TriggerMessage::Truncate(value) => {
+            trigger.truncate = value;
+            // TRUNCATE only fires statement level triggers.
+            if value {
+                trigger.for_each_row = false;
+            }
+        }
+        TriggerMessage::UpdateColumns(value) => trigger.update_columns = split_list(&value),
+        TriggerMessage::ForEachRow(value) => {
+            trigger.for_each_row = value;
+            if value {
+                trigger.truncate = false;
+            }
+        }
+        TriggerMessage::Condition(value) => trigger.condition = non_empty(value),
+        TriggerMessage::Function(value) => trigger.function = value,
+    }
+}
+
+fn triggers_view<'a>(project: &'a Project, table: &'a Table) -> Element<'a, PropertyMessage> {
+    let functions = project.trigger_functions();
+    let mut list = column![].spacing(8);
+    for (i, trigger) in table.triggers.iter().enumerate() {
+        let on = move |message: TriggerMessage| PropertyMessage::Trigger(i, message);
+        let selected = functions.iter().find(|f| **f == trigger.function).cloned();
+
+        let mut body = column![
+            row![
+                text_input("name", &trigger.name).on_input(move |v| on(TriggerMessage::Name(v))),
+                pick_list(TriggerTiming::ALL, Some(trigger.timing), move |v| {
+                    on(TriggerMessage::Timing(v))
+                }),
+                remove_button(PropertyMessage::RemoveTrigger(i)),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            row![
+                checkbox(trigger.insert)
+                    .label("INSERT")
+                    .on_toggle(move |v| on(TriggerMessage::Insert(v))),
+                checkbox(trigger.update)
+                    .label("UPDATE")
+                    .on_toggle(move |v| on(TriggerMessage::Update(v))),
+                checkbox(trigger.delete)
+                    .label("DELETE")
+                    .on_toggle(move |v| on(TriggerMessage::Delete(v))),
+                checkbox(trigger.truncate)
+                    .label("TRUNCATE")
+                    .on_toggle(move |v| on(TriggerMessage::Truncate(v))),
+            ]
+            .spacing(8),
+        ]
+        .spacing(4);
+
+        if trigger.update {
+            body = body.push(list_field("Update of", &trigger.update_columns, move |v| {
+                on(TriggerMessage::UpdateColumns(v))
+            }));
+        }
+        body = body
+            .push(
+                checkbox(trigger.for_each_row)
+                    .label("FOR EACH ROW")
+                    .on_toggle(move |v| on(TriggerMessage::ForEachRow(v))),
+            )
+            .push(field(
+                "When",
+                trigger.condition.as_deref().unwrap_or_default(),
+                move |v| on(TriggerMessage::Condition(v)),
+            ))
+            .push(labeled(
+                "Function",
+                row![
+                    text_input("function", &trigger.function)
+                        .on_input(move |v| on(TriggerMessage::Function(v))),
+                    pick_list(functions.clone(), selected, move |v| {
+                        on(TriggerMessage::Function(v))
+                    })
+                    .placeholder("function")
+                    .width(120),
+                ]
+                .spacing(4)
+                .into(),
+            ));
+
+        list = list.push(card(body));
+    }
+    column![list, add_button("Add trigger", PropertyMessage::AddTrigger)]
+        .spacing(8)
+        .into()
+}
+
+fn update_partitioning(partitioning: &mut Partitioning, message: PropertyMessage) {
+    match message {
+        PropertyMessage::PartitionStrategy(strategy) => partitioning.set_strategy(strategy),
+        PropertyMessage::PartitionKey(value) => partitioning.key = split_list(&value),
+        PropertyMessage::RemovePartition(i) if i < partitioning.partitions.len() => {
+            partitioning.partitions.remove(i);
+        }
+        PropertyMessage::Partition(i, message) => {
+            if let Some(partition) = partitioning.partitions.get_mut(i) {
+                match (&mut partition.bound, message) {
+                    (_, PartitionMessage::Name(name)) => partition.name = name,
+                    (PartitionBound::Range { from, .. }, PartitionMessage::From(value)) => {
+                        *from = value
+                    }
+                    (PartitionBound::Range { to, .. }, PartitionMessage::To(value)) => *to = value,
+                    (PartitionBound::List { values }, PartitionMessage::Values(value)) => {
+                        *values = value
+                    }
+                    (PartitionBound::Hash { modulus, .. }, PartitionMessage::Modulus(value)) => {
+                        if let Ok(value) = value.trim().parse() {
+                            *modulus = value;
+                        }
+                    }
+                    (
+                        PartitionBound::Hash { remainder, .. },
+                        PartitionMessage::Remainder(value),
+                    ) => {
+                        if let Ok(value) = value.trim().parse() {
+                            *remainder = value;
+                        }
+                    }
+                    (_, PartitionMessage::Default(true)) => {
+                        partition.bound = PartitionBound::Default
+                    }
+                    (PartitionBound::Default, PartitionMessage::Default(false)) => {
+                        partition.bound = match partitioning.strategy {
+                            PartitionStrategy::List => PartitionBound::List {
+                                values: String::new(),
+                            },
+                            _ => PartitionBound::Range {
+                                from: String::new(),
+                                to: String::new(),
+                            },
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn partitions_view(table: &Table) -> Element<'_, PropertyMessage> {
+    let partitioned = checkbox(table.partitioning.is_some())
+        .label("PARTITION BY")
+        .on_toggle(PropertyMessage::Partitioned);
+    let Some(partitioning) = &table.partitioning else {
+        return partitioned.into();
+    };
+
+    let mut list = column![].spacing(8);
+    for (i, partition) in partitioning.partitions.iter().enumerate() {
+        let on = move |message: fn(String) -> PartitionMessage| {
+            move |value: String| PropertyMessage::Partition(i, message(value))
+        };
+        let mut body = column![row![
+            text_input("name", &partition.name).on_input(on(PartitionMessage::Name)),
+            remove_button(PropertyMessage::RemovePartition(i)),
+        ]
+        .spacing(4)
+        .align_y(Center)]
+        .spacing(4);
+
+        body = match &partition.bound {
+            PartitionBound::Range { from, to } => body.push(
+                row![
+                    text_input("from", from).on_input(on(PartitionMessage::From)),
+                    text_input("to", to).on_input(on(PartitionMessage::To)),
+                ]
+                .spacing(4),
+            ),
+            PartitionBound::List { values } => {
+                body.push(text_input("'a', 'b'", values).on_input(on(PartitionMessage::Values)))
+            }
+            PartitionBound::Hash { modulus, remainder } => body.push(
+                row![
+                    labeled(
+                        "Modulus",
+                        text_input("modulus", &modulus.to_string())
+                            .on_input(on(PartitionMessage::Modulus))
+                            .into()
+                    ),
+                    labeled(
+                        "Remainder",
+                        text_input("remainder", &remainder.to_string())
+                            .on_input(on(PartitionMessage::Remainder))
+                            .into()
+                    ),
+                ]
+                .spacing(4),
+            ),
+            PartitionBound::Default => body,
+        };
+        if partitioning.strategy != PartitionStrategy::Hash {
+            body = body.push(
+                checkbox(partition.bound == PartitionBound::Default)
+                    .label("DEFAULT")
+                    .on_toggle(move |v| {
+                        PropertyMessage::Partition(i, PartitionMessage::Default(v))
+                    }),
+            );
+        }
+        list = list.push(card(body));
+    }
+
+    column![
+        partitioned,
+        labeled(
+            "Strategy",
+            pick_list(
+                PartitionStrategy::ALL,
+                Some(partitioning.strategy),
+                PropertyMessage::PartitionStrategy
+            )
+            .into()
+        ),
+        list_field("Key", &partitioning.key, PropertyMessage::PartitionKey),
+        list,
+        add_button("Add partition", PropertyMessage::AddPartition),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn update_policy(policy: &mut Policy, message: PolicyMessage) {
+    match message {
+        PolicyMessage::Name(name) => policy.name = name,
+        PolicyMessage::Permissive(value) => policy.permissive = value,
+        PolicyMessage::Command(command) => policy.command = command,
+        PolicyMessage::Roles(value) => policy.roles = split_list(&value),
+        PolicyMessage::Using(value) => policy.using = non_empty(value),
+        PolicyMessage::WithCheck(value) => policy.with_check = non_empty(value),
+    }
+}
+
+fn update_grant(grant: &mut Grant, message: GrantMessage) {
+    match message {
+        GrantMessage::Role(role) => grant.role = role,
+        GrantMessage::Privilege(privilege, true) => {
+            if !grant.privileges.contains(&privilege) {
+                grant.privileges.push(privilege);
+                grant.privileges.sort();
+            }
+        }
+        GrantMessage::Privilege(privilege, false) => grant.privileges.retain(|p| *p != privilege),
+        GrantMessage::Columns(value) => grant.columns = split_list(&value),
+        GrantMessage::WithGrantOption(value) => grant.with_grant_option = value,
+        GrantMessage::Revoke(value) => grant.revoke = value,
+    }
+}
+
+fn security_view<'a>(project: &'a Project, table: &'a Table) -> Element<'a, PropertyMessage> {
+    let roles = project.role_names();
+
+    let mut policies = column![].spacing(8);
+    for (i, policy) in table.policies.iter().enumerate() {
+        let on = move |message: fn(String) -> PolicyMessage| {
+            move |value: String| PropertyMessage::Policy(i, message(value))
+        };
+        let mut body = column![
+            row![
+                text_input("name", &policy.name).on_input(on(PolicyMessage::Name)),
+                pick_list(PolicyCommand::ALL, Some(policy.command), move |v| {
+                    PropertyMessage::Policy(i, PolicyMessage::Command(v))
+                }),
+                remove_button(PropertyMessage::RemovePolicy(i)),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            checkbox(policy.permissive)
+                .label("PERMISSIVE")
+                .on_toggle(move |v| PropertyMessage::Policy(i, PolicyMessage::Permissive(v))),
+            list_field("Roles", &policy.roles, on(PolicyMessage::Roles)),
+        ]
+        .spacing(4);
+        if policy.command.has_using() {
+            body = body.push(field(
+                "Using",
+                policy.using.as_deref().unwrap_or_default(),
+                on(PolicyMessage::Using),
+            ));
+        }
+        if policy.command.has_with_check() {
+            body = body.push(field(
+                "With check",
+                policy.with_check.as_deref().unwrap_or_default(),
+                on(PolicyMessage::WithCheck),
+            ));
+        }
+        policies = policies.push(card(body));
+    }
+
+    let mut grants = column![].spacing(8);
+    for (i, grant) in table.grants.iter().enumerate() {
+        let on = move |message: GrantMessage| PropertyMessage::Grant(i, message);
+        let selected = roles.iter().find(|r| **r == grant.role).cloned();
+        let privileges = row(Privilege::ALL.into_iter().map(|privilege| {
+            checkbox(grant.privileges.contains(&privilege))
+                .label(privilege.to_string())
+                .on_toggle(move |v| on(GrantMessage::Privilege(privilege, v)))
+                .into()
+        }))
+        .spacing(8)
+        .wrap();
+        let mut body = column![
+            row![
+                text_input("role", &grant.role).on_input(move |v| on(GrantMessage::Role(v))),
+                pick_list(roles.clone(), selected, move |v| on(GrantMessage::Role(v)))
+                    .placeholder("role")
+                    .width(120),
+                remove_button(PropertyMessage::RemoveGrant(i)),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            checkbox(grant.revoke)
+                .label("REVOKE")
+                .on_toggle(move |v| on(GrantMessage::Revoke(v))),
+            privileges,
+            list_field("Columns", &grant.columns, move |v| {
+                on(GrantMessage::Columns(v))
+            }),
+        ]
+        .spacing(4);
+        if !grant.revoke {
+            body = body.push(
+                checkbox(grant.with_grant_option)
+                    .label("WITH GRANT OPTION")
+                    .on_toggle(move |v| on(GrantMessage::WithGrantOption(v))),
+            );
+        }
+        grants = grants.push(card(body));
+    }
+
+    column![
+        checkbox(table.row_security)
+            .label("ENABLE ROW LEVEL SECURITY")
+            .on_toggle(PropertyMessage::RowSecurity),
+        checkbox(table.force_row_security)
+            .label("FORCE ROW LEVEL SECURITY")
+            .on_toggle(PropertyMessage::ForceRowSecurity),
+        text("Policies").size(12),
+        policies,
+        add_button("Add policy", PropertyMessage::AddPolicy),
+        text("Privileges").size(12),
+        grants,
+        add_button("Add grant", PropertyMessage::AddGrant),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn labeled<'a, Message: 'a>(label: &'a str, content: Element<'a, Message>) -> Element<'a, Message> {
+    row![text(label).width(LABEL_WIDTH), content]
+        .spacing(4)
+        .align_y(Center)
+        .into()
+}
+
+fn field<'a, Message: Clone + 'a>(
+    label: &'a str,
+    value: &'a str,
+    on_input: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    labeled(label, text_input(label, value).on_input(on_input).into())
+}
+
+fn list_field<'a, Message: Clone + 'a>(
+    label: &'a str,
+    items: &[String],
+    on_input: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    labeled(
+        label,
+        text_input("a, b, c", &join_list(items))
+            .on_input(on_input)
+            .into(),
+    )
+}
+
+fn card<'a, Message: 'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    container(content)
+        .padding(8)
+        .width(Fill)
+        .style(container::rounded_box)
+        .into()
+}
+
+fn tab_button(label: &str, tab: Tab, current: Tab) -> Element<'_, PropertyMessage> {
+    button(text(label))
+        .style(if tab == current {
+            button::primary
+        } else {
+            button::text
+        })
+        .on_press(PropertyMessage::Tab(tab))
+        .into()
+}
+
+fn add_button<'a, Message: Clone + 'a>(label: &'a str, message: Message) -> Element<'a, Message> {
+    button(text(label).size(12))
+        .style(button::secondary)
+        .on_press(message)
+        .into()
+}
+
+fn remove_button<'a, Message: Clone + 'a>(message: Message) -> Element<'a, Message> {
+    button(text("×"))
+        .style(button::text)
+        .on_press(message)
+        .into()
+}
+
+fn delete_button<'a>() -> Element<'a, PropertyMessage> {
+    button(text("Delete").size(12))
+        .style(button::danger)
+        .on_press(PropertyMessage::Delete)
+        .into()
+}
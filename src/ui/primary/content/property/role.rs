@@ -0,0 +1,54 @@
+use iced::{
+    widget::{checkbox, column, text},
+    Element,
+};
+
+use super::{field, list_field, non_empty};
+use crate::model::{split_list, Role};
+
+#[derive(Clone, Debug)]
+pub enum RoleMessage {
+    Name(String),
+    Comment(String),
+    Login(bool),
+    MemberOf(String),
+}
+
+impl RoleMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Comment(_) => String::from("comment"),
+            Self::MemberOf(_) => String::from("member of"),
+            Self::Login(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+pub fn update(role: &mut Role, message: RoleMessage) {
+    match message {
+        RoleMessage::Name(name) => role.name = name,
+        RoleMessage::Comment(comment) => role.comment = non_empty(comment),
+        RoleMessage::Login(login) => role.login = login,
+        RoleMessage::MemberOf(value) => role.member_of = split_list(&value),
+    }
+}
+
+pub fn view(role: &Role) -> Element<'_, RoleMessage> {
+    column![
+        text("role").size(12),
+        field("Name", &role.name, RoleMessage::Name),
+        field(
+            "Comment",
+            role.comment.as_deref().unwrap_or_default(),
+            RoleMessage::Comment
+        ),
+        checkbox(role.login)
+            .label("LOGIN")
+            .on_toggle(RoleMessage::Login),
+        list_field("Member of", &role.member_of, RoleMessage::MemberOf),
+    ]
+    .spacing(6)
+    .into()
+}
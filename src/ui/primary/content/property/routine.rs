@@ -0,0 +1,109 @@
+use iced::{
+    widget::{column, pick_list, text, text_editor},
+    Element, Font,
+};
+
+use super::{field, labeled, non_empty};
+use crate::model::{Routine, RoutineKind, Volatility};
+
+#[derive(Clone, Debug)]
+pub enum RoutineMessage {
+    Name(String),
+    Schema(String),
+    Comment(String),
+    Kind(RoutineKind),
+    Arguments(String),
+    Returns(String),
+    Language(String),
+    Volatility(Volatility),
+    Edit(text_editor::Action),
+}
+
+impl RoutineMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Schema(_) => String::from("schema"),
+            Self::Comment(_) => String::from("comment"),
+            Self::Arguments(_) => String::from("arguments"),
+            Self::Returns(_) => String::from("returns"),
+            Self::Language(_) => String::from("language"),
+            Self::Edit(action) if action.is_edit() => String::from("body"),
+            Self::Kind(_) | Self::Volatility(_) | Self::Edit(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+/// Applies `message` to `routine`, with body edits going through `editor`
+/// like view definitions do.
+pub fn update(routine: &mut Routine, editor: &mut text_editor::Content, message: RoutineMessage) {
+    match message {
+        RoutineMessage::Name(name) => routine.name = name,
+        RoutineMessage::Schema(schema) => routine.schema = schema,
+        RoutineMessage::Comment(comment) => routine.comment = non_empty(comment),
+        RoutineMessage::Kind(kind) => routine.kind = kind,
+        RoutineMessage::Arguments(arguments) => routine.arguments = arguments,
+        RoutineMessage::Returns(returns) => routine.returns = returns,
+        RoutineMessage::Language(language) => routine.language = language,
+        RoutineMessage::Volatility(volatility) => routine.volatility = volatility,
+        RoutineMessage::Edit(action) => {
+            let is_edit = action.is_edit();
+            editor.perform(action);
+            if is_edit {
+                routine.body = editor.text();
+            }
+        }
+    }
+}
+
+pub fn view<'a>(
+    routine: &'a Routine,
+    editor: &'a text_editor::Content,
+) -> Element<'a, RoutineMessage> {
+    let mut header = column![
+        labeled(
+            "Kind",
+            pick_list(RoutineKind::ALL, Some(routine.kind), RoutineMessage::Kind).into()
+        ),
+        field("Name", &routine.name, RoutineMessage::Name),
+        field("Schema", &routine.schema, RoutineMessage::Schema),
+        field(
+            "Comment",
+            routine.comment.as_deref().unwrap_or_default(),
+            RoutineMessage::Comment
+        ),
+        field("Arguments", &routine.arguments, RoutineMessage::Arguments),
+    ]
+    .spacing(6);
+
+    if routine.kind == RoutineKind::Function {
+        header = header
+            .push(field("Returns", &routine.returns, RoutineMessage::Returns))
+            .push(labeled(
+                "Volatility",
+                pick_list(
+                    Volatility::ALL,
+                    Some(routine.volatility),
+                    RoutineMessage::Volatility,
+                )
+                .into(),
+            ));
+    }
+    header = header.push(field(
+        "Language",
+        &routine.language,
+        RoutineMessage::Language,
+    ));
+
+    let body = text_editor(editor)
+        .placeholder("BEGIN ... END;")
+        .font(Font::with_name("JetBrainsMono NF"))
+        .height(320)
+        .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+        .on_action(RoutineMessage::Edit);
+
+    column![header, text("Body").size(12), body]
+        .spacing(12)
+        .into()
+}
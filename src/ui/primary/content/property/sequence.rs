@@ -0,0 +1,154 @@
+use iced::{
+    widget::{checkbox, column, pick_list, row, text, text_input},
+    Element,
+};
+
+use super::{field, labeled, non_empty};
+use crate::model::{Project, Sequence};
+
+#[derive(Clone, Debug)]
+pub enum SequenceMessage {
+    Name(String),
+    Schema(String),
+    Comment(String),
+    DataType(&'static str),
+    Start(String),
+    Increment(String),
+    MinValue(String),
+    MaxValue(String),
+    Cache(String),
+    Cycle(bool),
+    OwnedBy(String),
+}
+
+impl SequenceMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Schema(_) => String::from("schema"),
+            Self::Comment(_) => String::from("comment"),
+            Self::Start(_) => String::from("start"),
+            Self::Increment(_) => String::from("increment"),
+            Self::MinValue(_) => String::from("minimum"),
+            Self::MaxValue(_) => String::from("maximum"),
+            Self::Cache(_) => String::from("cache"),
+            Self::OwnedBy(_) => String::from("owned by"),
+            Self::DataType(_) | Self::Cycle(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+pub fn update(sequence: &mut Sequence, message: SequenceMessage) {
+    match message {
+        SequenceMessage::Name(name) => sequence.name = name,
+        SequenceMessage::Schema(schema) => sequence.schema = schema,
+        SequenceMessage::Comment(comment) => sequence.comment = non_empty(comment),
+        SequenceMessage::DataType(data_type) => sequence.data_type = data_type.to_string(),
+        SequenceMessage::Start(value) => set_optional(&mut sequence.start, &value),
+        SequenceMessage::MinValue(value) => set_optional(&mut sequence.min_value, &value),
+        SequenceMessage::MaxValue(value) => set_optional(&mut sequence.max_value, &value),
+        SequenceMessage::Increment(value) => {
+            if let Ok(increment) = value.trim().parse::<i64>()
+                && increment != 0
+            {
+                sequence.increment = increment;
+            }
+        }
+        SequenceMessage::Cache(value) => {
+            if let Ok(cache) = value.trim().parse::<i64>()
+                && cache > 0
+            {
+                sequence.cache = cache;
+            }
+        }
+        SequenceMessage::Cycle(cycle) => sequence.cycle = cycle,
+        SequenceMessage::OwnedBy(owned_by) => sequence.owned_by = non_empty(owned_by),
+    }
+}
+
+/// Empty input clears the value, anything that isn't a number is ignored.
+fn set_optional(target: &mut Option<i64>, value: &str) {
+    if value.trim().is_empty() {
+        *target = None;
+    } else if let Ok(value) = value.trim().parse() {
+        *target = Some(value);
+    }
+}
+
+pub fn view<'a>(project: &'a Project, sequence: &'a Sequence) -> Element<'a, SequenceMessage> {
+    let optional = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let columns = project.column_names();
+    let owner = columns
+        .iter()
+        .find(|c| Some(c.as_str()) == sequence.owned_by.as_deref())
+        .cloned();
+    let data_type = Sequence::TYPES
+        .into_iter()
+        .find(|t| *t == sequence.data_type);
+
+    column![
+        text("sequence").size(12),
+        field("Name", &sequence.name, SequenceMessage::Name),
+        field("Schema", &sequence.schema, SequenceMessage::Schema),
+        field(
+            "Comment",
+            sequence.comment.as_deref().unwrap_or_default(),
+            SequenceMessage::Comment
+        ),
+        labeled(
+            "Type",
+            pick_list(Sequence::TYPES, data_type, SequenceMessage::DataType).into()
+        ),
+        labeled(
+            "Start",
+            text_input("default", &optional(sequence.start))
+                .on_input(SequenceMessage::Start)
+                .into()
+        ),
+        labeled(
+            "Increment",
+            text_input("1", &sequence.increment.to_string())
+                .on_input(SequenceMessage::Increment)
+                .into()
+        ),
+        labeled(
+            "Min value",
+            text_input("default", &optional(sequence.min_value))
+                .on_input(SequenceMessage::MinValue)
+                .into()
+        ),
+        labeled(
+            "Max value",
+            text_input("default", &optional(sequence.max_value))
+                .on_input(SequenceMessage::MaxValue)
+                .into()
+        ),
+        labeled(
+            "Cache",
+            text_input("1", &sequence.cache.to_string())
+                .on_input(SequenceMessage::Cache)
+                .into()
+        ),
+        checkbox(sequence.cycle)
+            .label("CYCLE")
+            .on_toggle(SequenceMessage::Cycle),
+        labeled(
+            "Owned by",
+            row![
+                text_input(
+                    "table.column",
+                    sequence.owned_by.as_deref().unwrap_or_default()
+                )
+                .on_input(SequenceMessage::OwnedBy),
+                pick_list(columns, owner, SequenceMessage::OwnedBy)
+                    .placeholder("column")
+                    .width(120),
+            ]
+            .spacing(4)
+            .into()
+        ),
+    ]
+    .spacing(6)
+    .into()
+}
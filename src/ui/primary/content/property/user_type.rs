@@ -0,0 +1,243 @@
+use iced::{
+    widget::{checkbox, column, pick_list, row, text, text_input},
+    Alignment::Center,
+    Element,
+};
+
+use super::{add_button, card, field, labeled, non_empty, remove_button};
+use crate::model::{Attribute, DomainCheck, Project, UserType, UserTypeKind};
+
+#[derive(Clone, Debug)]
+pub enum TypeMessage {
+    Name(String),
+    Schema(String),
+    Comment(String),
+    Label(usize, String),
+    AddLabel,
+    RemoveLabel(usize),
+    AttributeName(usize, String),
+    AttributeType(usize, String),
+    AddAttribute,
+    RemoveAttribute(usize),
+    DataType(String),
+    NotNull(bool),
+    Default(String),
+    CheckName(usize, String),
+    CheckExpression(usize, String),
+    AddCheck,
+    RemoveCheck(usize),
+}
+
+impl TypeMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Schema(_) => String::from("schema"),
+            Self::Comment(_) => String::from("comment"),
+            Self::Label(i, _) => format!("label {i}"),
+            Self::AttributeName(i, _) => format!("attribute name {i}"),
+            Self::AttributeType(i, _) => format!("attribute type {i}"),
+            Self::DataType(_) => String::from("type"),
+            Self::Default(_) => String::from("default"),
+            Self::CheckName(i, _) => format!("check name {i}"),
+            Self::CheckExpression(i, _) => format!("check expression {i}"),
+            Self::AddLabel
+            | Self::RemoveLabel(_)
+            | Self::AddAttribute
+            | Self::RemoveAttribute(_)
+            | Self::NotNull(_)
+            | Self::AddCheck
+            | Self::RemoveCheck(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+pub fn update(ty: &mut UserType, message: TypeMessage) {
+    match (&mut ty.kind, message) {
+        (_, TypeMessage::Name(name)) => ty.name = name,
+        (_, TypeMessage::Schema(schema)) => ty.schema = schema,
+        (_, TypeMessage::Comment(comment)) => ty.comment = non_empty(comment),
+        (UserTypeKind::Enum { labels }, TypeMessage::Label(i, value)) => {
+            if let Some(label) = labels.get_mut(i) {
+                *label = value;
+            }
+        }
+        (UserTypeKind::Enum { labels }, TypeMessage::AddLabel) => labels.push(String::new()),
+        (UserTypeKind::Enum { labels }, TypeMessage::RemoveLabel(i)) if i < labels.len() => {
+            labels.remove(i);
+        }
+        (UserTypeKind::Composite { attributes }, TypeMessage::AttributeName(i, value)) => {
+            if let Some(attribute) = attributes.get_mut(i) {
+                attribute.name = value;
+            }
+        }
+        (UserTypeKind::Composite { attributes }, TypeMessage::AttributeType(i, value)) => {
+            if let Some(attribute) = attributes.get_mut(i) {
+                attribute.data_type = value;
+            }
+        }
+        (UserTypeKind::Composite { attributes }, TypeMessage::AddAttribute) => {
+            attributes.push(Attribute {
+                name: format!("field_{}", attributes.len() + 1),
+                data_type: String::from("text"),
+            })
+        }
+        (UserTypeKind::Composite { attributes }, TypeMessage::RemoveAttribute(i))
+            if i < attributes.len() =>
+        {
+            attributes.remove(i);
+        }
+        (UserTypeKind::Domain { data_type, .. }, TypeMessage::DataType(value)) => {
+            *data_type = value
+        }
+        (UserTypeKind::Domain { not_null, .. }, TypeMessage::NotNull(value)) => *not_null = value,
+        (UserTypeKind::Domain { default, .. }, TypeMessage::Default(value)) => {
+            *default = non_empty(value)
+        }
+        (UserTypeKind::Domain { checks, .. }, TypeMessage::CheckName(i, value)) => {
+            if let Some(check) = checks.get_mut(i) {
+                check.name = value;
+            }
+        }
+        (UserTypeKind::Domain { checks, .. }, TypeMessage::CheckExpression(i, value)) => {
+            if let Some(check) = checks.get_mut(i) {
+                check.expression = value;
+            }
+        }
+        (UserTypeKind::Domain { checks, .. }, TypeMessage::AddCheck) => checks.push(DomainCheck {
+            name: format!("{}_check", ty.name),
+            expression: String::from("VALUE IS NOT NULL"),
+        }),
+        (UserTypeKind::Domain { checks, .. }, TypeMessage::RemoveCheck(i)) if i < checks.len() => {
+            checks.remove(i);
+        }
+        _ => {}
+    }
+}
+
+pub fn view<'a>(project: &'a Project, ty: &'a UserType) -> Element<'a, TypeMessage> {
+    let header = column![
+        text(format!("{} type", ty.category())).size(12),
+        field("Name", &ty.name, TypeMessage::Name),
+        field("Schema", &ty.schema, TypeMessage::Schema),
+        field(
+            "Comment",
+            ty.comment.as_deref().unwrap_or_default(),
+            TypeMessage::Comment
+        ),
+    ]
+    .spacing(6);
+
+    // A type can't be built from itself.
+    let own_name = Project::type_name(ty);
+    let types: Vec<String> = project
+        .type_names()
+        .into_iter()
+        .filter(|name| *name != own_name)
+        .collect();
+
+    let body: Element<'a, TypeMessage> = match &ty.kind {
+        UserTypeKind::Enum { labels } => {
+            let mut list = column![].spacing(4);
+            for (i, label) in labels.iter().enumerate() {
+                list = list.push(
+                    row![
+                        text_input("label", label).on_input(move |v| TypeMessage::Label(i, v)),
+                        remove_button(TypeMessage::RemoveLabel(i)),
+                    ]
+                    .spacing(4)
+                    .align_y(Center),
+                );
+            }
+            column![list, add_button("Add label", TypeMessage::AddLabel)]
+                .spacing(8)
+                .into()
+        }
+        UserTypeKind::Composite { attributes } => {
+            let mut list = column![].spacing(8);
+            for (i, attribute) in attributes.iter().enumerate() {
+                let selected = types.iter().find(|t| **t == attribute.data_type).cloned();
+                list = list.push(card(
+                    column![
+                        row![
+                            text_input("name", &attribute.name)
+                                .on_input(move |v| TypeMessage::AttributeName(i, v)),
+                            remove_button(TypeMessage::RemoveAttribute(i)),
+                        ]
+                        .spacing(4)
+                        .align_y(Center),
+                        row![
+                            text_input("type", &attribute.data_type)
+                                .on_input(move |v| TypeMessage::AttributeType(i, v)),
+                            pick_list(types.clone(), selected, move |v| {
+                                TypeMessage::AttributeType(i, v)
+                            })
+                            .placeholder("type")
+                            .width(120),
+                        ]
+                        .spacing(4)
+                        .align_y(Center),
+                    ]
+                    .spacing(4),
+                ));
+            }
+            column![list, add_button("Add attribute", TypeMessage::AddAttribute)]
+                .spacing(8)
+                .into()
+        }
+        UserTypeKind::Domain {
+            data_type,
+            not_null,
+            default,
+            checks,
+        } => {
+            let selected = types.iter().find(|t| *t == data_type).cloned();
+            let mut list = column![].spacing(8);
+            for (i, check) in checks.iter().enumerate() {
+                list = list.push(card(
+                    column![
+                        row![
+                            text_input("name", &check.name)
+                                .on_input(move |v| TypeMessage::CheckName(i, v)),
+                            remove_button(TypeMessage::RemoveCheck(i)),
+                        ]
+                        .spacing(4)
+                        .align_y(Center),
+                        text_input("VALUE > 0", &check.expression)
+                            .on_input(move |v| TypeMessage::CheckExpression(i, v)),
+                    ]
+                    .spacing(4),
+                ));
+            }
+            column![
+                labeled(
+                    "Base type",
+                    row![
+                        text_input("type", data_type).on_input(TypeMessage::DataType),
+                        pick_list(types.clone(), selected, TypeMessage::DataType)
+                            .placeholder("type")
+                            .width(120),
+                    ]
+                    .spacing(4)
+                    .into()
+                ),
+                field(
+                    "Default",
+                    default.as_deref().unwrap_or_default(),
+                    TypeMessage::Default
+                ),
+                checkbox(*not_null)
+                    .label("NOT NULL")
+                    .on_toggle(TypeMessage::NotNull),
+                text("Checks").size(12),
+                list,
+                add_button("Add check", TypeMessage::AddCheck),
+            ]
+            .spacing(8)
+            .into()
+        }
+    };
+
+    column![header, body].spacing(12).into()
+}
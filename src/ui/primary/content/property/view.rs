@@ -0,0 +1,91 @@
+use iced::{
+    widget::{checkbox, column, text, text_editor},
+    Element, Font,
+};
+
+use super::{field, non_empty};
+use crate::model::View;
+
+#[derive(Clone, Debug)]
+pub enum ViewMessage {
+    Name(String),
+    Schema(String),
+    Comment(String),
+    Materialized(bool),
+    WithData(bool),
+    Edit(text_editor::Action),
+}
+
+impl ViewMessage {
+    pub fn text_field(&self) -> Option<String> {
+        let field = match self {
+            Self::Name(_) => String::from("name"),
+            Self::Schema(_) => String::from("schema"),
+            Self::Comment(_) => String::from("comment"),
+            Self::Edit(action) if action.is_edit() => String::from("definition"),
+            Self::Materialized(_) | Self::WithData(_) | Self::Edit(_) => return None,
+        };
+        Some(field)
+    }
+}
+
+/// Applies `message` to `view`. Edits go through `editor`, which owns the
+/// cursor, and the resulting text becomes the definition.
+pub fn update(view: &mut View, editor: &mut text_editor::Content, message: ViewMessage) {
+    match message {
+        ViewMessage::Name(name) => view.name = name,
+        ViewMessage::Schema(schema) => view.schema = schema,
+        ViewMessage::Comment(comment) => view.comment = non_empty(comment),
+        ViewMessage::Materialized(materialized) => {
+            view.materialized = materialized;
+            // Plain views have no storage to index.
+            if !materialized {
+                view.indexes.clear();
+            }
+        }
+        ViewMessage::WithData(with_data) => view.with_data = with_data,
+        ViewMessage::Edit(action) => {
+            let is_edit = action.is_edit();
+            editor.perform(action);
+            if is_edit {
+                view.definition = editor.text();
+            }
+        }
+    }
+}
+
+pub fn view<'a>(view: &'a View, editor: &'a text_editor::Content) -> Element<'a, ViewMessage> {
+    let mut header = column![
+        text(view.kind()).size(12),
+        field("Name", &view.name, ViewMessage::Name),
+        field("Schema", &view.schema, ViewMessage::Schema),
+        field(
+            "Comment",
+            view.comment.as_deref().unwrap_or_default(),
+            ViewMessage::Comment
+        ),
+        checkbox(view.materialized)
+            .label("Materialized")
+            .on_toggle(ViewMessage::Materialized),
+    ]
+    .spacing(6);
+
+    if view.materialized {
+        header = header.push(
+            checkbox(view.with_data)
+                .label("WITH DATA")
+                .on_toggle(ViewMessage::WithData),
+        );
+    }
+
+    let definition = text_editor(editor)
+        .placeholder("SELECT ...")
+        .font(Font::with_name("JetBrainsMono NF"))
+        .height(240)
+        .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+        .on_action(ViewMessage::Edit);
+
+    column![header, text("Definition").size(12), definition]
+        .spacing(12)
+        .into()
+}
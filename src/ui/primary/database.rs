@@ -0,0 +1,706 @@
+use std::path::PathBuf;
+
+use compare::{CompareMessage, Comparison};
+use iced::{
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input},
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Task,
+};
+use sqlx::postgres::PgPool;
+
+use crate::{
+    codegen,
+    db::{
+        self, introspect,
+        profile::{self, Profile, ProfileError, Profiles, SslMode},
+        secret::Key,
+        DbError,
+    },
+    dbml,
+    model::{join_list, list_items, split_list, Project, DEFAULT_SCHEMA},
+    sql::{
+        diff,
+        migration::{self, Replay, Step},
+        parse::{self, Parsed, Skipped},
+    },
+    utils::{self, FileError},
+};
+
+pub mod compare;
+
+const LABEL_WIDTH: f32 = 96.0;
+
+/// The Database page: manages connection profiles, connects to a live
+/// server and imports the catalog of the chosen schemas or compares it with
+/// the model.
+#[derive(Default)]
+pub struct Database {
+    profiles: Profiles,
+    /// Set once the master passphrase has been entered.
+    key: Option<Key>,
+    passphrase: String,
+    /// Profile the form was loaded from, `None` for a new one.
+    selected: Option<usize>,
+    form: Profile,
+    /// Password of the form in plain text. It is encrypted on save.
+    password: String,
+    pool: Option<PgPool>,
+    /// Schemas of the connected database and whether to import them.
+    schemas: Vec<(String, bool)>,
+    new_project: bool,
+    comparison: Option<Comparison>,
+    /// What the last file import left out.
+    skipped: Vec<Skipped>,
+    /// What each migration of the last replayed folder added and dropped.
+    timeline: Vec<Step>,
+    show_timeline: bool,
+    busy: bool,
+    status: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum DatabaseMessage {
+    Passphrase(String),
+    Unlock,
+    Loaded(Result<Profiles, ProfileError>),
+    Select(String),
+    NewProfile,
+    DeleteProfile,
+    Profile(ProfileMessage),
+    SaveProfile,
+    ProfilesSaved(Result<PathBuf, ProfileError>),
+    Test,
+    Tested(Result<String, DbError>),
+    Connect,
+    Connected(Result<(PgPool, Vec<String>), DbError>),
+    Schema(usize, bool),
+    NewProject(bool),
+    Import,
+    /// Handled by the owner of the project. Boxed to keep messages small.
+    Imported(Result<Box<Project>, DbError>),
+    OpenFile,
+    FileOpened(Result<(PathBuf, String), FileError>),
+    OpenDiesel,
+    DieselOpened(Result<(PathBuf, String), FileError>),
+    OpenSeaOrm,
+    SeaOrmOpened(Result<(PathBuf, Vec<(String, String)>), FileError>),
+    OpenDbml,
+    DbmlOpened(Result<(PathBuf, String), FileError>),
+    OpenMigrations,
+    MigrationsOpened(Result<(PathBuf, Vec<(String, String)>), FileError>),
+    ShowTimeline(bool),
+    Compare,
+    Compared(Result<Box<Project>, DbError>),
+    Comparison(CompareMessage),
+}
+
+#[derive(Clone, Debug)]
+pub enum ProfileMessage {
+    Name(String),
+    Host(String),
+    Port(String),
+    Database(String),
+    User(String),
+    Password(String),
+    SslMode(SslMode),
+    Schemas(String),
+}
+
+impl Database {
+    pub fn update(&mut self, message: DatabaseMessage, project: &Project) -> Task<DatabaseMessage> {
+        match message {
+            DatabaseMessage::Passphrase(passphrase) => self.passphrase = passphrase,
+            DatabaseMessage::Unlock => {
+                self.busy = true;
+                return Task::perform(profile::load(), DatabaseMessage::Loaded);
+            }
+            DatabaseMessage::Loaded(result) => {
+                self.busy = false;
+                let unlocked = result.and_then(|mut profiles| {
+                    let key = profiles.unlock(&self.passphrase)?;
+                    Ok((profiles, key))
+                });
+                match unlocked {
+                    Ok((profiles, key)) => {
+                        self.passphrase.clear();
+                        self.status = None;
+                        self.profiles = profiles;
+                        self.key = Some(key);
+                        if self.profiles.profiles.is_empty() {
+                            self.new_profile();
+                        } else {
+                            self.select(0);
+                        }
+                    }
+                    Err(e) => self.status = Some(e.to_string()),
+                }
+            }
+            DatabaseMessage::Select(name) => {
+                if let Some(i) = self.profiles.profiles.iter().position(|p| p.name == name) {
+                    self.select(i);
+                }
+            }
+            DatabaseMessage::NewProfile => self.new_profile(),
+            DatabaseMessage::DeleteProfile => {
+                if let Some(i) = self.selected.take() {
+                    self.profiles.profiles.remove(i);
+                    self.new_profile();
+                    return self.save();
+                }
+            }
+            DatabaseMessage::Profile(message) => match message {
+                ProfileMessage::Name(name) => self.form.name = name,
+                ProfileMessage::Host(host) => self.form.host = host,
+                ProfileMessage::Port(port) => {
+                    if let Ok(port) = port.trim().parse() {
+                        self.form.port = port;
+                    }
+                }
+                ProfileMessage::Database(database) => self.form.database = database,
+                ProfileMessage::User(user) => self.form.user = user,
+                ProfileMessage::Password(password) => self.password = password,
+                ProfileMessage::SslMode(ssl_mode) => self.form.ssl_mode = ssl_mode,
+                ProfileMessage::Schemas(schemas) => self.form.schemas = split_list(&schemas),
+            },
+            DatabaseMessage::SaveProfile => {
+                let Some(key) = &self.key else {
+                    return Task::none();
+                };
+                let mut profile = self.form.clone();
+                profile.password = (!self.password.is_empty()).then(|| key.encrypt(&self.password));
+                match self.selected {
+                    Some(i) => self.profiles.profiles[i] = profile,
+                    None => {
+                        self.profiles.profiles.push(profile);
+                        self.selected = Some(self.profiles.profiles.len() - 1);
+                    }
+                }
+                return self.save();
+            }
+            DatabaseMessage::ProfilesSaved(result) => {
+                self.status = match result {
+                    Ok(path) => Some(format!("Saved {}", path.display())),
+                    Err(e) => Some(format!("Saving profiles failed: {e}")),
+                };
+            }
+            DatabaseMessage::Test => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(
+                    db::test(self.form.connect_options(&self.password)),
+                    DatabaseMessage::Tested,
+                );
+            }
+            DatabaseMessage::Tested(result) => {
+                self.busy = false;
+                self.status = Some(match result {
+                    Ok(version) => format!("Connected: {version}"),
+                    Err(e) => e.to_string(),
+                });
+            }
+            DatabaseMessage::Connect => {
+                self.busy = true;
+                self.status = None;
+                let options = self.form.connect_options(&self.password);
+                return Task::perform(
+                    async move {
+                        let pool = db::connect(options).await?;
+                        let schemas = db::schemas(pool.clone()).await?;
+                        Ok((pool, schemas))
+                    },
+                    DatabaseMessage::Connected,
+                );
+            }
+            DatabaseMessage::Connected(result) => {
+                self.busy = false;
+                match result {
+                    Ok((pool, schemas)) => {
+                        let filter: Vec<&str> = list_items(&self.form.schemas).collect();
+                        self.pool = Some(pool);
+                        self.schemas = if filter.is_empty() {
+                            schemas
+                                .into_iter()
+                                .map(|schema| {
+                                    let public = schema == DEFAULT_SCHEMA;
+                                    (schema, public)
+                                })
+                                .collect()
+                        } else {
+                            schemas
+                                .into_iter()
+                                .filter(|schema| filter.contains(&schema.as_str()))
+                                .map(|schema| (schema, true))
+                                .collect()
+                        };
+                    }
+                    Err(e) => {
+                        self.pool = None;
+                        self.schemas.clear();
+                        self.status = Some(e.to_string());
+                    }
+                }
+            }
+            DatabaseMessage::Schema(i, selected) => {
+                if let Some(schema) = self.schemas.get_mut(i) {
+                    schema.1 = selected;
+                }
+            }
+            DatabaseMessage::NewProject(new_project) => self.new_project = new_project,
+            DatabaseMessage::Import => {
+                if let Some(pool) = self.pool.clone() {
+                    self.busy = true;
+                    self.status = None;
+                    self.skipped.clear();
+                    self.timeline.clear();
+                    return Task::perform(
+                        introspect::import(pool, self.selected_schemas()),
+                        |result| DatabaseMessage::Imported(result.map(Box::new)),
+                    );
+                }
+            }
+            DatabaseMessage::OpenFile => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(
+                    utils::open_file("SQL", &["sql"]),
+                    DatabaseMessage::FileOpened,
+                );
+            }
+            DatabaseMessage::FileOpened(result) => {
+                return self.opened(result.map(|(_, sql)| parse::script(&sql)));
+            }
+            DatabaseMessage::OpenDiesel => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(
+                    utils::open_file("diesel schema", &["rs"]),
+                    DatabaseMessage::DieselOpened,
+                );
+            }
+            DatabaseMessage::DieselOpened(result) => {
+                return self.opened(result.map(|(_, source)| codegen::parse::diesel(&source)));
+            }
+            DatabaseMessage::OpenSeaOrm => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(utils::read_folder(".rs"), DatabaseMessage::SeaOrmOpened);
+            }
+            DatabaseMessage::SeaOrmOpened(result) => {
+                return self.opened(result.map(|(_, files)| codegen::parse::sea_orm(&files)));
+            }
+            DatabaseMessage::OpenDbml => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(
+                    utils::open_file("DBML", &["dbml"]),
+                    DatabaseMessage::DbmlOpened,
+                );
+            }
+            DatabaseMessage::DbmlOpened(result) => {
+                return self.opened(result.map(|(_, source)| dbml::parse::read(&source)));
+            }
+            DatabaseMessage::OpenMigrations => {
+                self.busy = true;
+                self.status = None;
+                return Task::perform(
+                    utils::read_folder(".sql"),
+                    DatabaseMessage::MigrationsOpened,
+                );
+            }
+            DatabaseMessage::MigrationsOpened(result) => {
+                let replay = match result {
+                    Ok((folder, files)) if files.is_empty() => {
+                        self.busy = false;
+                        self.status = Some(format!("No .sql files in {}", folder.display()));
+                        return Task::none();
+                    }
+                    result => result.map(|(_, files)| migration::replay(&files)),
+                };
+                let (parsed, timeline) = match replay {
+                    Ok(Replay { parsed, timeline }) => (Ok(parsed), timeline),
+                    Err(e) => (Err(e), Vec::new()),
+                };
+                let task = self.opened(parsed);
+                self.timeline = timeline;
+                return task;
+            }
+            DatabaseMessage::ShowTimeline(show) => self.show_timeline = show,
+            DatabaseMessage::Compare => {
+                if let Some(pool) = self.pool.clone() {
+                    self.busy = true;
+                    self.status = None;
+                    return Task::perform(
+                        introspect::import(pool, self.selected_schemas()),
+                        |result| DatabaseMessage::Compared(result.map(Box::new)),
+                    );
+                }
+            }
+            DatabaseMessage::Compared(result) => {
+                self.busy = false;
+                match result {
+                    Ok(database) => {
+                        if let Some(pool) = self.pool.clone() {
+                            let schemas = self.selected_schemas();
+                            let differences = diff::compare(project, &database, &schemas);
+                            let target = format!(
+                                "{}@{}:{}/{}",
+                                self.form.user, self.form.host, self.form.port, self.form.database
+                            );
+                            match &mut self.comparison {
+                                Some(comparison) => comparison.compared_again(differences),
+                                None => {
+                                    self.comparison =
+                                        Some(Comparison::new(pool, target, schemas, differences))
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => self.status = Some(e.to_string()),
+                }
+            }
+            DatabaseMessage::Comparison(CompareMessage::Close) => self.comparison = None,
+            // The database changed, so what is left to migrate did too.
+            DatabaseMessage::Comparison(CompareMessage::Applied) => {
+                return self.update(DatabaseMessage::Compare, project);
+            }
+            DatabaseMessage::Comparison(message) => {
+                if let Some(comparison) = &mut self.comparison {
+                    return comparison.update(message).map(DatabaseMessage::Comparison);
+                }
+            }
+            DatabaseMessage::Imported(result) => {
+                self.busy = false;
+                self.status = Some(match result {
+                    Ok(project) => format!(
+                        "Imported {} tables, {} views, {} types and {} routines",
+                        project.tables.len(),
+                        project.views.len(),
+                        project.types.len(),
+                        project.routines.len()
+                    ),
+                    Err(e) => e.to_string(),
+                });
+                if !self.skipped.is_empty() {
+                    self.status = self.status.take().map(|status| {
+                        format!("{status}, {} statements left out", self.skipped.len())
+                    });
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// Imports what was read from a file, noting what was left out.
+    fn opened(&mut self, result: Result<Parsed, FileError>) -> Task<DatabaseMessage> {
+        match result {
+            Ok(parsed) => {
+                self.skipped = parsed.skipped;
+                self.timeline.clear();
+                Task::done(DatabaseMessage::Imported(Ok(Box::new(parsed.project))))
+            }
+            Err(e) => {
+                self.busy = false;
+                if !matches!(e, FileError::Cancelled) {
+                    self.status = Some(format!("Opening failed: {e}"));
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Whether imports replace the current project instead of merging.
+    pub fn new_project(&self) -> bool {
+        self.new_project
+    }
+
+    fn selected_schemas(&self) -> Vec<String> {
+        self.schemas
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(schema, _)| schema.clone())
+            .collect()
+    }
+
+    fn select(&mut self, i: usize) {
+        self.selected = Some(i);
+        self.form = self.profiles.profiles[i].clone();
+        self.password = self
+            .form
+            .password
+            .as_deref()
+            .zip(self.key.as_ref())
+            .and_then(|(password, key)| key.decrypt(password))
+            .unwrap_or_default();
+        self.pool = None;
+        self.schemas.clear();
+    }
+
+    fn new_profile(&mut self) {
+        let mut n = self.profiles.profiles.len() + 1;
+        while self
+            .profiles
+            .profiles
+            .iter()
+            .any(|p| p.name == format!("profile_{n}"))
+        {
+            n += 1;
+        }
+        self.selected = None;
+        self.form = Profile::new(format!("profile_{n}"));
+        self.password.clear();
+        self.pool = None;
+        self.schemas.clear();
+    }
+
+    fn save(&self) -> Task<DatabaseMessage> {
+        Task::perform(
+            profile::save(self.profiles.clone()),
+            DatabaseMessage::ProfilesSaved,
+        )
+    }
+
+    pub fn view(&self) -> Element<'_, DatabaseMessage> {
+        if let Some(comparison) = &self.comparison {
+            return comparison.view().map(DatabaseMessage::Comparison);
+        }
+
+        let mut page = column![row![
+            text("Database").size(20).width(Fill),
+            checkbox(self.new_project)
+                .label("Import into a new project")
+                .on_toggle(DatabaseMessage::NewProject),
+        ]
+        .align_y(Center)]
+        .spacing(16);
+
+        if self.key.is_none() {
+            page = page.push(
+                text(
+                    "Passwords are stored encrypted. The first passphrase \
+                     entered becomes the master passphrase.",
+                )
+                .size(12),
+            );
+            page = page.push(
+                row![
+                    text_input("Master passphrase", &self.passphrase)
+                        .secure(true)
+                        .on_input(DatabaseMessage::Passphrase)
+                        .on_submit(DatabaseMessage::Unlock),
+                    button("Unlock").on_press_maybe(
+                        (!self.busy && !self.passphrase.is_empty())
+                            .then_some(DatabaseMessage::Unlock)
+                    ),
+                ]
+                .spacing(8)
+                .align_y(Center),
+            );
+        } else {
+            page = page.push(self.profile_view());
+        }
+
+        if self.pool.is_some() {
+            let schemas = column(
+                self.schemas
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (schema, selected))| {
+                        checkbox(*selected)
+                            .label(schema.clone())
+                            .on_toggle(move |selected| DatabaseMessage::Schema(i, selected))
+                            .into()
+                    }),
+            )
+            .spacing(6);
+
+            let any_selected = self.schemas.iter().any(|(_, selected)| *selected);
+            page = page.push(text("Schemas").size(14)).push(
+                container(scrollable(schemas).height(240))
+                    .padding(8)
+                    .style(container::bordered_box),
+            );
+            page = page.push(
+                row![
+                    button("Import").on_press_maybe(
+                        (!self.busy && any_selected).then_some(DatabaseMessage::Import)
+                    ),
+                    button("Compare with model").on_press_maybe(
+                        (!self.busy && any_selected).then_some(DatabaseMessage::Compare)
+                    ),
+                ]
+                .spacing(16)
+                .align_y(Center),
+            );
+        }
+
+        page = page.push(
+            text(
+                "No connection? Import a schema dump such as pg_dump --schema-only output, \
+                 replay a directory of sqlx migrations, or read a DBML file, a diesel \
+                 schema.rs or a directory of SeaORM entities.",
+            )
+            .size(12),
+        );
+        let idle = !self.busy;
+        page = page.push(
+            row![
+                button("Open SQL file").on_press_maybe(idle.then_some(DatabaseMessage::OpenFile)),
+                button("Open migrations")
+                    .on_press_maybe(idle.then_some(DatabaseMessage::OpenMigrations)),
+                button("Open DBML").on_press_maybe(idle.then_some(DatabaseMessage::OpenDbml)),
+                button("Open schema.rs")
+                    .on_press_maybe(idle.then_some(DatabaseMessage::OpenDiesel)),
+                button("Open entities").on_press_maybe(idle.then_some(DatabaseMessage::OpenSeaOrm)),
+            ]
+            .spacing(16)
+            .align_y(Center),
+        );
+        if !self.timeline.is_empty() {
+            page = page.push(
+                checkbox(self.show_timeline)
+                    .label("Show timeline")
+                    .on_toggle(DatabaseMessage::ShowTimeline),
+            );
+        }
+        if self.show_timeline && !self.timeline.is_empty() {
+            let steps = column(self.timeline.iter().map(|step| {
+                let mut lines = column![text(&step.migration).size(14)].spacing(2);
+                for object in &step.added {
+                    lines = lines.push(text(format!("+ {object}")).size(12));
+                }
+                for object in &step.dropped {
+                    lines = lines.push(text(format!("- {object}")).size(12).style(text::danger));
+                }
+                if step.added.is_empty() && step.dropped.is_empty() {
+                    lines = lines.push(text("No objects added or dropped").size(12));
+                }
+                lines.into()
+            }))
+            .spacing(12);
+            page = page.push(text("Timeline").size(14)).push(
+                container(scrollable(steps).height(320))
+                    .padding(8)
+                    .width(Fill)
+                    .style(container::bordered_box),
+            );
+        }
+        if !self.skipped.is_empty() {
+            let skipped = column(self.skipped.iter().map(|skipped| {
+                let statement = skipped.statement.lines().next().unwrap_or_default();
+                column![
+                    text(statement).size(12),
+                    text(&skipped.reason).size(12).style(text::danger),
+                ]
+                .spacing(2)
+                .into()
+            }))
+            .spacing(8);
+            page = page.push(text("Left out").size(14)).push(
+                container(scrollable(skipped).height(240))
+                    .padding(8)
+                    .width(Fill)
+                    .style(container::bordered_box),
+            );
+        }
+
+        if self.busy {
+            page = page.push(text("Working...").size(12));
+        }
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+
+        container(scrollable(page.max_width(720)))
+            .padding(24)
+            .width(Fill)
+            .height(Fill)
+            .into()
+    }
+
+    fn profile_view(&self) -> Element<'_, DatabaseMessage> {
+        let names: Vec<String> = self
+            .profiles
+            .profiles
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        let selected = self
+            .selected
+            .map(|i| self.profiles.profiles[i].name.clone());
+
+        let profiles = row![
+            pick_list(names, selected, DatabaseMessage::Select)
+                .placeholder("New profile")
+                .width(Fill),
+            button("New").on_press(DatabaseMessage::NewProfile),
+            button("Delete").on_press_maybe(
+                self.selected
+                    .is_some()
+                    .then_some(DatabaseMessage::DeleteProfile)
+            ),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let form = &self.form;
+        let fields = column![
+            field("Name", &form.name, ProfileMessage::Name),
+            field("Host", &form.host, ProfileMessage::Host),
+            field("Port", &form.port.to_string(), ProfileMessage::Port),
+            field("Database", &form.database, ProfileMessage::Database),
+            field("User", &form.user, ProfileMessage::User),
+            row![
+                text("Password").width(LABEL_WIDTH),
+                text_input("Password", &self.password)
+                    .secure(true)
+                    .on_input(ProfileMessage::Password),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            row![
+                text("SSL mode").width(LABEL_WIDTH),
+                pick_list(SslMode::ALL, Some(form.ssl_mode), ProfileMessage::SslMode),
+            ]
+            .spacing(4)
+            .align_y(Center),
+            field(
+                "Schemas",
+                &join_list(&form.schemas),
+                ProfileMessage::Schemas
+            ),
+        ]
+        .spacing(6);
+
+        let idle = !self.busy;
+        let actions = row![
+            button("Save").on_press(DatabaseMessage::SaveProfile),
+            button("Test connection").on_press_maybe(idle.then_some(DatabaseMessage::Test)),
+            button("Connect").on_press_maybe(idle.then_some(DatabaseMessage::Connect)),
+        ]
+        .spacing(8);
+
+        column![
+            profiles,
+            Element::from(fields).map(DatabaseMessage::Profile),
+            actions
+        ]
+        .spacing(12)
+        .into()
+    }
+}
+
+fn field<'a>(
+    label: &'a str,
+    value: &str,
+    on_input: impl Fn(String) -> ProfileMessage + 'a,
+) -> Element<'a, ProfileMessage> {
+    row![
+        text(label).width(LABEL_WIDTH),
+        text_input(label, value).on_input(on_input)
+    ]
+    .spacing(4)
+    .align_y(Center)
+    .into()
+}
@@ -0,0 +1,347 @@
+use std::path::PathBuf;
+
+use iced::{
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text},
+    Alignment::Center,
+    Element,
+    Length::{Fill, FillPortion},
+    Task,
+};
+use sqlx::postgres::PgPool;
+
+use crate::{
+    db::{
+        migrate::{self, Mode, Outcome, Progress},
+        DbError,
+    },
+    sql::diff::{self, Change, Difference, Direction},
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// Differences between the model and the connected database, and the
+/// script that migrates one into the other.
+#[derive(Debug)]
+pub struct Comparison {
+    pool: PgPool,
+    /// `user@host:port/database`, for the migration log.
+    target: String,
+    schemas: Vec<String>,
+    differences: Vec<Difference>,
+    direction: Direction,
+    /// What the included differences take, as run and as shown in `script`.
+    statements: Vec<String>,
+    script: String,
+    run: Option<Run>,
+    status: Option<String>,
+}
+
+/// A script being, or having been, run against the database.
+#[derive(Debug)]
+struct Run {
+    mode: Mode,
+    statements: Vec<String>,
+    results: Vec<Result<u64, String>>,
+    outcome: Option<Result<Outcome, DbError>>,
+    log: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug)]
+pub enum CompareMessage {
+    /// Difference, child of it and whether to include it in the script.
+    Include(usize, Option<usize>, bool),
+    Direction(Direction),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+    Run(Mode),
+    Progress(Progress),
+    /// Back from the run to the script.
+    Dismiss,
+    /// A script was applied and committed. Handled by the owner, which
+    /// compares again.
+    Applied,
+    /// Handled by the owner.
+    Close,
+}
+
+impl Comparison {
+    pub fn new(
+        pool: PgPool,
+        target: String,
+        schemas: Vec<String>,
+        differences: Vec<Difference>,
+    ) -> Self {
+        let mut comparison = Self {
+            pool,
+            target,
+            schemas,
+            differences,
+            direction: Direction::default(),
+            statements: Vec::new(),
+            script: String::new(),
+            run: None,
+            status: None,
+        };
+        comparison.refresh();
+        comparison
+    }
+
+    pub fn update(&mut self, message: CompareMessage) -> Task<CompareMessage> {
+        match message {
+            CompareMessage::Include(i, child, include) => {
+                match child {
+                    Some(child) => self.differences[i].children[child].include = include,
+                    None => self.differences[i].set_include(include),
+                }
+                self.refresh();
+            }
+            CompareMessage::Direction(direction) => {
+                self.direction = direction;
+                self.refresh();
+            }
+            CompareMessage::Copy => return iced::clipboard::write(self.script.clone()),
+            CompareMessage::Save => {
+                return Task::perform(
+                    utils::save_file(
+                        String::from("tableforge_migration.sql"),
+                        self.script.clone(),
+                    ),
+                    CompareMessage::Saved,
+                );
+            }
+            CompareMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+            }
+            CompareMessage::Run(mode) => {
+                let statements = self.statements.clone();
+                self.run = Some(Run {
+                    mode,
+                    statements: statements.clone(),
+                    results: Vec::new(),
+                    outcome: None,
+                    log: None,
+                });
+                return Task::run(
+                    migrate::run(self.pool.clone(), statements, mode, self.target.clone()),
+                    CompareMessage::Progress,
+                );
+            }
+            CompareMessage::Progress(progress) => {
+                if let Some(run) = &mut self.run {
+                    match progress {
+                        Progress::Statement(result) => run.results.push(result),
+                        Progress::Finished { outcome, log } => {
+                            let applied = run.mode == Mode::Apply
+                                && matches!(outcome, Ok(Outcome::Committed));
+                            run.outcome = Some(outcome);
+                            run.log = log;
+                            if applied {
+                                return Task::done(CompareMessage::Applied);
+                            }
+                        }
+                    }
+                }
+            }
+            CompareMessage::Dismiss => {
+                if self.run.as_ref().is_some_and(|run| run.outcome.is_some()) {
+                    self.run = None;
+                }
+            }
+            CompareMessage::Applied | CompareMessage::Close => {}
+        }
+        Task::none()
+    }
+
+    /// Whether a script is running, which must not be interrupted.
+    pub fn is_running(&self) -> bool {
+        self.run.as_ref().is_some_and(|run| run.outcome.is_none())
+    }
+
+    /// Takes the differences found on comparing again after applying a
+    /// script. The run stays on show until dismissed.
+    pub fn compared_again(&mut self, differences: Vec<Difference>) {
+        self.differences = differences;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.statements = diff::statements(&self.differences, self.direction);
+        self.script = diff::script(&self.differences, self.direction);
+    }
+
+    pub fn view(&self) -> Element<'_, CompareMessage> {
+        let idle = !self.is_running();
+        // Only a script towards the model's state makes sense to run here.
+        let runnable =
+            idle && self.direction == Direction::ToDatabase && !self.statements.is_empty();
+        let header = row![
+            text(format!(
+                "Compare with database: {}",
+                self.schemas.join(", ")
+            ))
+            .width(Fill),
+            pick_list(
+                Direction::ALL,
+                Some(self.direction),
+                CompareMessage::Direction
+            ),
+            button("Copy").on_press(CompareMessage::Copy),
+            button("Save").on_press(CompareMessage::Save),
+            button("Dry run").on_press_maybe(runnable.then_some(CompareMessage::Run(Mode::DryRun))),
+            button("Apply").on_press_maybe(runnable.then_some(CompareMessage::Run(Mode::Apply))),
+            button("Close").on_press_maybe(idle.then_some(CompareMessage::Close)),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let tree: Element<CompareMessage> = if self.differences.is_empty() {
+            text("The model and the database match.").size(12).into()
+        } else {
+            let mut tree = column![side_by_side(
+                text("").into(),
+                text("Model").size(14).into(),
+                text("Database").size(14).into()
+            )]
+            .spacing(8);
+            for (i, difference) in self.differences.iter().enumerate() {
+                tree = tree.push(node(difference, 0, move |include| {
+                    CompareMessage::Include(i, None, include)
+                }));
+                for (child, member) in difference.children.iter().enumerate() {
+                    tree = tree.push(node(member, 1, move |include| {
+                        CompareMessage::Include(i, Some(child), include)
+                    }));
+                }
+            }
+            scrollable(tree).height(FillPortion(3)).into()
+        };
+
+        let bottom = match &self.run {
+            Some(run) => run_view(run),
+            None => column![
+                text("Script").size(14),
+                container(scrollable(text(&self.script).size(12)).width(Fill))
+                    .padding(8)
+                    .height(Fill)
+                    .style(container::bordered_box),
+            ]
+            .spacing(12)
+            .into(),
+        };
+
+        let mut page = column![header, tree, container(bottom).height(FillPortion(2))].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+
+        container(page).padding(24).width(Fill).height(Fill).into()
+    }
+}
+
+/// Every statement of the run with how it went, then the outcome.
+fn run_view(run: &Run) -> Element<'_, CompareMessage> {
+    let title = match run.mode {
+        Mode::Apply => "Apply",
+        Mode::DryRun => "Dry run",
+    };
+    let statements = column(run.statements.iter().enumerate().map(|(i, statement)| {
+        let status = match run.results.get(i) {
+            Some(Ok(rows)) => text(format!("ok, {rows} rows")).size(12),
+            Some(Err(e)) => text(format!("error: {e}")).size(12).style(text::danger),
+            None if run.outcome.is_none() && i == run.results.len() => text("running").size(12),
+            None => text("not run").size(12),
+        };
+        column![text(statement).size(12), status].spacing(2).into()
+    }))
+    .spacing(8);
+
+    let mut footer = row![text(format!(
+        "{} of {} statements",
+        run.results.len(),
+        run.statements.len()
+    ))
+    .size(12)
+    .width(Fill)]
+    .spacing(8)
+    .align_y(Center);
+    if let Some(outcome) = &run.outcome {
+        let summary = match outcome {
+            Ok(Outcome::Committed) => String::from("Committed."),
+            Ok(Outcome::RolledBack) => String::from("Dry run succeeded, rolled back."),
+            Ok(Outcome::Failed) => String::from("Failed, rolled back."),
+            Err(e) => e.to_string(),
+        };
+        let logged = run
+            .log
+            .as_ref()
+            .map(|path| format!(" Logged to {}.", path.display()))
+            .unwrap_or_default();
+        footer = footer
+            .push(text(summary + &logged).size(12))
+            .push(button("Back to script").on_press(CompareMessage::Dismiss));
+    }
+
+    column![
+        text(title).size(14),
+        container(scrollable(statements).width(Fill))
+            .padding(8)
+            .height(Fill)
+            .style(container::bordered_box),
+        footer,
+    ]
+    .spacing(12)
+    .into()
+}
+
+fn node<'a>(
+    difference: &'a Difference,
+    depth: u16,
+    on_toggle: impl Fn(bool) -> CompareMessage + 'a,
+) -> Element<'a, CompareMessage> {
+    let change = match difference.change() {
+        Change::OnlyInModel => "+",
+        Change::OnlyInDatabase => "-",
+        Change::Changed => "~",
+    };
+    let label = checkbox(difference.include)
+        .label(format!(
+            "{change} {} {}",
+            difference.object, difference.name
+        ))
+        .text_size(12)
+        .on_toggle(on_toggle);
+    let side = |summary: &'a Option<String>| -> Element<'a, CompareMessage> {
+        container(text(summary.as_deref().unwrap_or("")).size(12))
+            .padding(4)
+            .width(Fill)
+            .style(container::bordered_box)
+            .into()
+    };
+
+    // Tables found on both sides only list what differs below them.
+    let body = if difference.children.is_empty() {
+        side_by_side(
+            label.into(),
+            side(&difference.model),
+            side(&difference.database),
+        )
+    } else {
+        row![label].into()
+    };
+    container(body).padding([0, 24 * depth]).into()
+}
+
+fn side_by_side<'a>(
+    label: Element<'a, CompareMessage>,
+    model: Element<'a, CompareMessage>,
+    database: Element<'a, CompareMessage>,
+) -> Element<'a, CompareMessage> {
+    row![
+        container(label).width(FillPortion(2)),
+        container(model).width(FillPortion(3)),
+        container(database).width(FillPortion(3)),
+    ]
+    .spacing(8)
+    .into()
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+
+use code::{CodeMessage, RustCode};
+use dbml::{Dbml, DbmlMessage};
+use diagram::{DiagramText, DiagramTextMessage};
+use iced::{
+    widget::{column, container, pick_list, row, text},
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Task,
+};
+use migrations::{Migrations, MigrationsMessage};
+use sample::{SampleData, SampleMessage};
+
+use crate::{erd::ViewMode, model::Project};
+
+pub mod code;
+pub mod dbml;
+pub mod diagram;
+pub mod migrations;
+pub mod sample;
+
+/// The Export page: files made from the model, one kind at a time.
+#[derive(Default)]
+pub struct Export {
+    section: Section,
+    sample: SampleData,
+    migrations: Migrations,
+    code: RustCode,
+    dbml: Dbml,
+    diagram: DiagramText,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Section {
+    #[default]
+    SampleData,
+    Migrations,
+    RustCode,
+    Dbml,
+    Diagram,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::SampleData,
+        Section::Migrations,
+        Section::RustCode,
+        Section::Dbml,
+        Section::Diagram,
+    ];
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Section::SampleData => "Sample data",
+            Section::Migrations => "Migration files",
+            Section::RustCode => "Rust code",
+            Section::Dbml => "DBML",
+            Section::Diagram => "Diagram as text",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ExportMessage {
+    Section(Section),
+    Sample(SampleMessage),
+    Migrations(MigrationsMessage),
+    Code(CodeMessage),
+    Dbml(DbmlMessage),
+    Diagram(DiagramTextMessage),
+}
+
+impl Export {
+    /// `mode` is the view mode of the diagram, which diagrams are drawn in.
+    pub fn update(
+        &mut self,
+        message: ExportMessage,
+        project: &mut Project,
+        mode: ViewMode,
+    ) -> Task<ExportMessage> {
+        match message {
+            ExportMessage::Section(section) => {
+                self.section = section;
+                self.refresh(project, mode);
+                Task::none()
+            }
+            ExportMessage::Sample(message) => self
+                .sample
+                .update(message, project)
+                .map(ExportMessage::Sample),
+            ExportMessage::Migrations(message) => self
+                .migrations
+                .update(message, project)
+                .map(ExportMessage::Migrations),
+            ExportMessage::Code(message) => {
+                self.code.update(message, project).map(ExportMessage::Code)
+            }
+            ExportMessage::Dbml(message) => self.dbml.update(message).map(ExportMessage::Dbml),
+            ExportMessage::Diagram(message) => self
+                .diagram
+                .update(message, project, mode)
+                .map(ExportMessage::Diagram),
+        }
+    }
+
+    /// Brings what is shown up to date with the model, which the page is
+    /// not told about changes to.
+    pub fn refresh(&mut self, project: &Project, mode: ViewMode) {
+        match self.section {
+            Section::Migrations => self.migrations.refresh(project),
+            Section::RustCode => self.code.refresh(project),
+            Section::Dbml => self.dbml.refresh(project),
+            Section::Diagram => self.diagram.refresh(project, mode),
+            Section::SampleData => {}
+        }
+    }
+
+    pub fn view<'a>(&'a self, project: &'a Project, mode: ViewMode) -> Element<'a, ExportMessage> {
+        let header = row![
+            text("Export").size(14).width(Fill),
+            pick_list(Section::ALL, Some(self.section), ExportMessage::Section),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let section = match self.section {
+            Section::SampleData => self.sample.view(project).map(ExportMessage::Sample),
+            Section::Migrations => self.migrations.view().map(ExportMessage::Migrations),
+            Section::RustCode => self.code.view(project).map(ExportMessage::Code),
+            Section::Dbml => self.dbml.view().map(ExportMessage::Dbml),
+            Section::Diagram => self.diagram.view(project, mode).map(ExportMessage::Diagram),
+        };
+
+        container(column![header, section].spacing(12))
+            .padding(24)
+            .width(Fill)
+            .height(Fill)
+            .into()
+    }
+}
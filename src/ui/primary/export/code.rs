@@ -0,0 +1,204 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use iced::{
+    widget::{
+        button, checkbox, column, container, pick_list, row, scrollable, text, text_editor,
+        text_input,
+    },
+    Alignment::Center,
+    Element, Font,
+    Length::{Fill, FillPortion},
+    Task,
+};
+
+use crate::{
+    codegen::{self, Target},
+    model::Project,
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// Rust types for chosen tables, for the data access crate of choice.
+#[derive(Default)]
+pub struct RustCode {
+    /// Schema and name of the tables to generate code for.
+    tables: BTreeSet<(String, String)>,
+    options: codegen::Options,
+    /// The table whose column types are listed to override.
+    types_of: Option<String>,
+    output: text_editor::Content,
+    status: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum CodeMessage {
+    Table(usize, bool),
+    AllTables(bool),
+    Target(Target),
+    Serde(bool),
+    TypesOf(String),
+    RustType(usize, usize, String),
+    Action(text_editor::Action),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+}
+
+impl RustCode {
+    /// Overriding a column type changes the model, which is why it is
+    /// passed mutably.
+    pub fn update(&mut self, message: CodeMessage, project: &mut Project) -> Task<CodeMessage> {
+        let key = |table: &crate::model::Table| (table.schema.clone(), table.name.clone());
+        match message {
+            CodeMessage::Table(i, selected) => {
+                let key = key(&project.tables[i]);
+                if selected {
+                    self.tables.insert(key);
+                } else {
+                    self.tables.remove(&key);
+                }
+            }
+            CodeMessage::AllTables(selected) => {
+                self.tables.clear();
+                if selected {
+                    self.tables.extend(project.tables.iter().map(key));
+                }
+            }
+            CodeMessage::Target(target) => self.options.target = target,
+            CodeMessage::Serde(serde) => self.options.serde = serde,
+            CodeMessage::TypesOf(label) => self.types_of = Some(label),
+            CodeMessage::RustType(table, column, rust_type) => {
+                let column = &mut project.tables[table].columns[column];
+                column.rust_type = (!rust_type.trim().is_empty()).then_some(rust_type);
+            }
+            CodeMessage::Action(action) => {
+                output::read_only_action(&mut self.output, action);
+                return Task::none();
+            }
+            CodeMessage::Copy => return iced::clipboard::write(self.output.text()),
+            CodeMessage::Save => {
+                let name = match self.options.target {
+                    Target::Sqlx | Target::Diesel => "models.rs",
+                    Target::SeaOrm => "entities.rs",
+                };
+                return Task::perform(
+                    utils::save_file(String::from(name), self.output.text()),
+                    CodeMessage::Saved,
+                );
+            }
+            CodeMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+                return Task::none();
+            }
+        }
+        self.refresh(project);
+        Task::none()
+    }
+
+    /// Generates the code again.
+    pub fn refresh(&mut self, project: &Project) {
+        let tables: Vec<usize> = project
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, table)| {
+                self.tables
+                    .contains(&(table.schema.clone(), table.name.clone()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let code = if tables.is_empty() {
+            String::from("// Tick the tables to generate code for\n")
+        } else {
+            codegen::generate(project, &tables, &self.options)
+        };
+        self.output = text_editor::Content::with_text(&code);
+    }
+
+    pub fn view<'a>(&'a self, project: &'a Project) -> Element<'a, CodeMessage> {
+        let header = row![
+            text("Code for the ticked tables").size(12).width(Fill),
+            pick_list(Target::ALL, Some(self.options.target), CodeMessage::Target),
+            checkbox(self.options.serde)
+                .label("serde")
+                .on_toggle(CodeMessage::Serde),
+            button("Copy").on_press(CodeMessage::Copy),
+            button("Save").on_press(CodeMessage::Save),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let all = checkbox(!project.tables.is_empty() && self.tables.len() == project.tables.len())
+            .label("All tables")
+            .on_toggle(CodeMessage::AllTables);
+        let tables = column(project.tables.iter().enumerate().map(|(i, table)| {
+            let selected = self
+                .tables
+                .contains(&(table.schema.clone(), table.name.clone()));
+            checkbox(selected)
+                .label(format!("{}.{}", table.schema, table.name))
+                .text_size(12)
+                .on_toggle(move |selected| CodeMessage::Table(i, selected))
+                .into()
+        }))
+        .spacing(4);
+
+        let labels: Vec<String> = project
+            .tables
+            .iter()
+            .map(|table| format!("{}.{}", table.schema, table.name))
+            .collect();
+        let picked = self
+            .types_of
+            .as_ref()
+            .and_then(|label| labels.iter().position(|l| l == label));
+        let mut types = column![pick_list(
+            labels.clone(),
+            picked.map(|i| labels[i].clone()),
+            CodeMessage::TypesOf
+        )
+        .placeholder("Rust types of")
+        .text_size(12)]
+        .spacing(4);
+        if let Some(i) = picked {
+            let table = &project.tables[i];
+            for (j, column) in table.columns.iter().enumerate() {
+                let mapped = codegen::mapped_type(project, table, column, self.options.target);
+                types = types.push(
+                    row![
+                        text(&column.name).size(12).width(FillPortion(2)),
+                        text_input(&mapped, column.rust_type.as_deref().unwrap_or_default())
+                            .size(12)
+                            .width(FillPortion(3))
+                            .on_input(move |rust_type| CodeMessage::RustType(i, j, rust_type)),
+                    ]
+                    .spacing(8)
+                    .align_y(Center),
+                );
+            }
+        }
+
+        let tables = column![
+            all,
+            scrollable(tables).height(FillPortion(1)),
+            scrollable(types).height(FillPortion(1)),
+        ]
+        .spacing(8)
+        .width(FillPortion(1));
+
+        let output = container(
+            text_editor(&self.output)
+                .font(Font::with_name("JetBrainsMono NF"))
+                .height(Fill)
+                .highlight("rs", iced::highlighter::Theme::Base16Ocean)
+                .on_action(CodeMessage::Action),
+        )
+        .width(FillPortion(3));
+
+        let mut page = column![header, row![tables, output].spacing(16)].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+        page.into()
+    }
+}
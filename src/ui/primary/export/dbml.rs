@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use iced::{
+    widget::{button, column, row, text, text_editor},
+    Alignment::Center,
+    Element, Font,
+    Length::Fill,
+    Task,
+};
+
+use crate::{
+    dbml,
+    model::Project,
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// The model as DBML, for dbdiagram.io.
+#[derive(Default)]
+pub struct Dbml {
+    output: text_editor::Content,
+    status: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum DbmlMessage {
+    Action(text_editor::Action),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+}
+
+impl Dbml {
+    pub fn update(&mut self, message: DbmlMessage) -> Task<DbmlMessage> {
+        match message {
+            DbmlMessage::Action(action) => output::read_only_action(&mut self.output, action),
+            DbmlMessage::Copy => return iced::clipboard::write(self.output.text()),
+            DbmlMessage::Save => {
+                return Task::perform(
+                    utils::save_file(String::from("schema.dbml"), self.output.text()),
+                    DbmlMessage::Saved,
+                );
+            }
+            DbmlMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+            }
+        }
+        Task::none()
+    }
+
+    /// Generates the DBML again.
+    pub fn refresh(&mut self, project: &Project) {
+        self.output = text_editor::Content::with_text(&dbml::generate(project));
+    }
+
+    pub fn view(&self) -> Element<'_, DbmlMessage> {
+        let header = row![
+            text("Enums, tables, refs and subject areas as table groups")
+                .size(12)
+                .width(Fill),
+            button("Copy").on_press(DbmlMessage::Copy),
+            button("Save").on_press(DbmlMessage::Save),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let output = text_editor(&self.output)
+            .font(Font::with_name("JetBrainsMono NF"))
+            .height(Fill)
+            .on_action(DbmlMessage::Action);
+
+        let mut page = column![header, output].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+        page.into()
+    }
+}
@@ -0,0 +1,156 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use iced::{
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_editor},
+    Alignment::Center,
+    Element, Font,
+    Length::{Fill, FillPortion},
+    Task,
+};
+
+use crate::{
+    erd::{self, Format, ViewMode},
+    model::Project,
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// The ticked tables as a text diagram, drawn in the view mode of the
+/// diagram.
+#[derive(Default)]
+pub struct DiagramText {
+    /// Schema and name of the tables to draw.
+    tables: BTreeSet<(String, String)>,
+    format: Format,
+    output: text_editor::Content,
+    status: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum DiagramTextMessage {
+    Table(usize, bool),
+    AllTables(bool),
+    Format(Format),
+    Action(text_editor::Action),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+}
+
+impl DiagramText {
+    pub fn update(
+        &mut self,
+        message: DiagramTextMessage,
+        project: &Project,
+        mode: ViewMode,
+    ) -> Task<DiagramTextMessage> {
+        let key = |table: &crate::model::Table| (table.schema.clone(), table.name.clone());
+        match message {
+            DiagramTextMessage::Table(i, selected) => {
+                let key = key(&project.tables[i]);
+                if selected {
+                    self.tables.insert(key);
+                } else {
+                    self.tables.remove(&key);
+                }
+            }
+            DiagramTextMessage::AllTables(selected) => {
+                self.tables.clear();
+                if selected {
+                    self.tables.extend(project.tables.iter().map(key));
+                }
+            }
+            DiagramTextMessage::Format(format) => self.format = format,
+            DiagramTextMessage::Action(action) => {
+                output::read_only_action(&mut self.output, action);
+                return Task::none();
+            }
+            DiagramTextMessage::Copy => return iced::clipboard::write(self.output.text()),
+            DiagramTextMessage::Save => {
+                return Task::perform(
+                    utils::save_file(String::from(self.format.file_name()), self.output.text()),
+                    DiagramTextMessage::Saved,
+                );
+            }
+            DiagramTextMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+                return Task::none();
+            }
+        }
+        self.refresh(project, mode);
+        Task::none()
+    }
+
+    /// Draws the diagram again.
+    pub fn refresh(&mut self, project: &Project, mode: ViewMode) {
+        let tables: Vec<usize> = project
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, table)| {
+                self.tables
+                    .contains(&(table.schema.clone(), table.name.clone()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let diagram = if tables.is_empty() {
+            String::new()
+        } else {
+            erd::generate(project, &tables, mode, self.format)
+        };
+        self.output = text_editor::Content::with_text(&diagram);
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        project: &'a Project,
+        mode: ViewMode,
+    ) -> Element<'a, DiagramTextMessage> {
+        let header = row![
+            text(format!(
+                "The ticked tables, {}",
+                mode.to_string().to_lowercase()
+            ))
+            .size(12)
+            .width(Fill),
+            pick_list(Format::ALL, Some(self.format), DiagramTextMessage::Format),
+            button("Copy").on_press(DiagramTextMessage::Copy),
+            button("Save").on_press(DiagramTextMessage::Save),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let all = checkbox(!project.tables.is_empty() && self.tables.len() == project.tables.len())
+            .label("All tables")
+            .on_toggle(DiagramTextMessage::AllTables);
+        let tables = column(project.tables.iter().enumerate().map(|(i, table)| {
+            let selected = self
+                .tables
+                .contains(&(table.schema.clone(), table.name.clone()));
+            checkbox(selected)
+                .label(format!("{}.{}", table.schema, table.name))
+                .text_size(12)
+                .on_toggle(move |selected| DiagramTextMessage::Table(i, selected))
+                .into()
+        }))
+        .spacing(4);
+        let tables = column![all, scrollable(tables).height(Fill)]
+            .spacing(8)
+            .width(FillPortion(1));
+
+        let output = container(
+            text_editor(&self.output)
+                .placeholder("Tick the tables to draw")
+                .font(Font::with_name("JetBrainsMono NF"))
+                .height(Fill)
+                .on_action(DiagramTextMessage::Action),
+        )
+        .width(FillPortion(3));
+
+        let mut page = column![header, row![tables, output].spacing(16)].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+        page.into()
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::PathBuf;
+
+use iced::{
+    widget::{button, checkbox, column, pick_list, row, text, text_editor, text_input},
+    Alignment::Center,
+    Element, Font,
+    Length::Fill,
+    Task,
+};
+
+use crate::{
+    datetime,
+    model::Project,
+    sql::{
+        diff::{self, Direction},
+        migration::{Migration, Tool},
+        parse,
+    },
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// Migration files for what changed in the model since a baseline.
+pub struct Migrations {
+    tool: Tool,
+    name: String,
+    /// Also write a migration that reverts it, where that is up to the
+    /// user.
+    down: bool,
+    baseline: Baseline,
+    migration: Migration,
+    preview: text_editor::Content,
+    /// The model the files being written were made from.
+    writing: Option<Project>,
+    status: Option<String>,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Self {
+            tool: Tool::default(),
+            name: String::new(),
+            down: true,
+            baseline: Baseline::Empty,
+            migration: Migration::default(),
+            preview: text_editor::Content::new(),
+            writing: None,
+            status: None,
+        }
+    }
+}
+
+/// What the migration starts from.
+enum Baseline {
+    /// An empty database, so the migration creates everything.
+    Empty,
+    /// A schema read from a SQL file.
+    File(PathBuf, Project),
+    /// The model as of the last migration written.
+    Written(Project),
+}
+
+#[derive(Clone, Debug)]
+pub enum MigrationsMessage {
+    Tool(Tool),
+    Name(String),
+    Down(bool),
+    EmptyBaseline,
+    OpenBaseline,
+    BaselineOpened(Result<(PathBuf, String), FileError>),
+    Action(text_editor::Action),
+    Write,
+    Written(Result<Vec<PathBuf>, FileError>),
+}
+
+impl Migrations {
+    pub fn update(
+        &mut self,
+        message: MigrationsMessage,
+        project: &Project,
+    ) -> Task<MigrationsMessage> {
+        match message {
+            MigrationsMessage::Tool(tool) => self.tool = tool,
+            MigrationsMessage::Name(name) => self.name = name,
+            MigrationsMessage::Down(down) => self.down = down,
+            MigrationsMessage::EmptyBaseline => self.baseline = Baseline::Empty,
+            MigrationsMessage::OpenBaseline => {
+                return Task::perform(
+                    utils::open_file("SQL", &["sql"]),
+                    MigrationsMessage::BaselineOpened,
+                );
+            }
+            MigrationsMessage::BaselineOpened(result) => match result {
+                Ok((path, sql)) => {
+                    let mut baseline = parse::script(&sql).project;
+                    baseline.version = project.version;
+                    self.baseline = Baseline::File(path, baseline);
+                    self.status = None;
+                }
+                Err(FileError::Cancelled) => {}
+                Err(e) => self.status = Some(format!("Open failed: {e}")),
+            },
+            MigrationsMessage::Action(action) => {
+                output::read_only_action(&mut self.preview, action);
+                return Task::none();
+            }
+            MigrationsMessage::Write => {
+                self.writing = Some(project.clone());
+                return Task::perform(
+                    write(self.migration.clone(), self.tool, self.down),
+                    MigrationsMessage::Written,
+                );
+            }
+            MigrationsMessage::Written(result) => {
+                let writing = self.writing.take();
+                self.status = match result {
+                    Ok(paths) => {
+                        if let Some(written) = writing {
+                            self.baseline = Baseline::Written(written);
+                        }
+                        let names: Vec<String> = paths
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect();
+                        Some(format!("Wrote {}", names.join(", ")))
+                    }
+                    Err(FileError::Cancelled) => None,
+                    Err(e) => Some(format!("Write failed: {e}")),
+                };
+            }
+        }
+        self.refresh(project);
+        Task::none()
+    }
+
+    /// Compares the model with the baseline again.
+    pub fn refresh(&mut self, project: &Project) {
+        let empty = Project {
+            version: project.version,
+            ..Project::default()
+        };
+        let baseline = match &self.baseline {
+            Baseline::Empty => &empty,
+            Baseline::File(_, baseline) | Baseline::Written(baseline) => baseline,
+        };
+        let mut schemas: Vec<String> = project
+            .schemas()
+            .into_iter()
+            .chain(baseline.schemas())
+            .map(String::from)
+            .collect();
+        schemas.sort();
+        schemas.dedup();
+        let differences = diff::compare(project, baseline, &schemas);
+        self.migration = Migration {
+            name: self.name.clone(),
+            up: diff::statements(&differences, Direction::ToDatabase),
+            down: diff::statements(&differences, Direction::ToModel),
+        };
+
+        let preview = if self.migration.up.is_empty() {
+            String::from("-- No changes since the baseline\n")
+        } else if self.tool.down(self.down) {
+            format!(
+                "-- up\n\n{}\n\n-- down\n\n{}\n",
+                self.migration.up.join("\n\n"),
+                self.migration.down.join("\n\n")
+            )
+        } else {
+            self.migration.up.join("\n\n") + "\n"
+        };
+        self.preview = text_editor::Content::with_text(&preview);
+    }
+
+    pub fn view(&self) -> Element<'_, MigrationsMessage> {
+        let baseline = match &self.baseline {
+            Baseline::Empty => String::from("Since an empty database"),
+            Baseline::File(path, _) => format!("Since {}", path.display()),
+            Baseline::Written(_) => String::from("Since the last migration written"),
+        };
+        let header = row![
+            text(baseline).size(12).width(Fill),
+            button("Empty").on_press(MigrationsMessage::EmptyBaseline),
+            button("Open SQL file").on_press(MigrationsMessage::OpenBaseline),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let mut options = row![
+            pick_list(Tool::ALL, Some(self.tool), MigrationsMessage::Tool),
+            text_input("Migration name", &self.name)
+                .width(240)
+                .on_input(MigrationsMessage::Name),
+        ]
+        .spacing(16)
+        .align_y(Center);
+        if self.tool.down(false) != self.tool.down(true) {
+            options = options.push(
+                checkbox(self.down)
+                    .label("Down migration")
+                    .on_toggle(MigrationsMessage::Down),
+            );
+        }
+        options = options
+            .push(
+                text(self.tool.layout(self.tool.down(self.down)))
+                    .size(12)
+                    .width(Fill),
+            )
+            .push(button("Write files").on_press_maybe(
+                (!self.migration.up.is_empty()).then_some(MigrationsMessage::Write),
+            ));
+
+        let preview = text_editor(&self.preview)
+            .font(Font::with_name("JetBrainsMono NF"))
+            .height(Fill)
+            .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+            .on_action(MigrationsMessage::Action);
+
+        let mut page = column![header, options, preview].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+        page.into()
+    }
+}
+
+/// Asks for the migrations directory and writes the files into it.
+async fn write(migration: Migration, tool: Tool, down: bool) -> Result<Vec<PathBuf>, FileError> {
+    let (folder, existing) = utils::open_folder().await?;
+    let files = migration.files(tool, down, &existing, datetime::now());
+    utils::write_files(folder, files).await
+}
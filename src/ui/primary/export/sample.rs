@@ -0,0 +1,170 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use iced::{
+    widget::{button, checkbox, column, pick_list, row, scrollable, text, text_editor, text_input},
+    Alignment::Center,
+    Element, Font,
+    Length::{Fill, FillPortion},
+    Task,
+};
+
+use crate::{
+    model::Project,
+    sql::sample::{self, Format, DEFAULT_ROWS, MAX_ROWS},
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// Sample data for chosen tables.
+#[derive(Default)]
+pub struct SampleData {
+    /// Row counts as typed, by schema and name of the tables to fill.
+    rows: BTreeMap<(String, String), String>,
+    format: Format,
+    seed: String,
+    output: text_editor::Content,
+    notes: Vec<String>,
+    status: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum SampleMessage {
+    Table(usize, bool),
+    Rows(usize, String),
+    AllTables(bool),
+    Format(Format),
+    Seed(String),
+    Generate,
+    Action(text_editor::Action),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+}
+
+impl SampleData {
+    pub fn update(&mut self, message: SampleMessage, project: &Project) -> Task<SampleMessage> {
+        let key = |i: usize| {
+            let table = &project.tables[i];
+            (table.schema.clone(), table.name.clone())
+        };
+        match message {
+            SampleMessage::Table(i, selected) => {
+                if selected {
+                    self.rows.insert(key(i), DEFAULT_ROWS.to_string());
+                } else {
+                    self.rows.remove(&key(i));
+                }
+            }
+            SampleMessage::Rows(i, rows) => {
+                if rows.is_empty() || rows.parse::<usize>().is_ok_and(|n| n <= MAX_ROWS) {
+                    self.rows.insert(key(i), rows);
+                }
+            }
+            SampleMessage::AllTables(selected) => {
+                self.rows.clear();
+                if selected {
+                    for i in 0..project.tables.len() {
+                        self.rows.insert(key(i), DEFAULT_ROWS.to_string());
+                    }
+                }
+            }
+            SampleMessage::Format(format) => self.format = format,
+            SampleMessage::Seed(seed) => {
+                if seed.chars().all(|c| c.is_ascii_digit()) {
+                    self.seed = seed;
+                }
+            }
+            SampleMessage::Generate => {
+                let rows: Vec<(usize, usize)> = project
+                    .tables
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, table)| {
+                        let rows = self.rows.get(&(table.schema.clone(), table.name.clone()))?;
+                        Some((i, rows.parse().unwrap_or(0)))
+                    })
+                    .collect();
+                let options = sample::Options {
+                    format: self.format,
+                    seed: self.seed.parse().unwrap_or_default(),
+                };
+                let sample = sample::generate(project, &rows, &options);
+                self.output = text_editor::Content::with_text(&sample.script);
+                self.notes = sample.notes;
+                self.status = None;
+            }
+            SampleMessage::Action(action) => output::read_only_action(&mut self.output, action),
+            SampleMessage::Copy => return iced::clipboard::write(self.output.text()),
+            SampleMessage::Save => {
+                return Task::perform(
+                    utils::save_file(String::from("sample_data.sql"), self.output.text()),
+                    SampleMessage::Saved,
+                );
+            }
+            SampleMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+            }
+        }
+        Task::none()
+    }
+
+    pub fn view<'a>(&'a self, project: &'a Project) -> Element<'a, SampleMessage> {
+        let header = row![
+            text("Rows for the ticked tables").size(12).width(Fill),
+            pick_list(Format::ALL, Some(self.format), SampleMessage::Format),
+            text_input("Seed", &self.seed)
+                .width(100)
+                .on_input(SampleMessage::Seed),
+            button("Generate")
+                .on_press_maybe((!self.rows.is_empty()).then_some(SampleMessage::Generate)),
+            button("Copy").on_press(SampleMessage::Copy),
+            button("Save").on_press(SampleMessage::Save),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        let all = checkbox(!project.tables.is_empty() && self.rows.len() == project.tables.len())
+            .label("All tables")
+            .on_toggle(SampleMessage::AllTables);
+        let tables = column(project.tables.iter().enumerate().map(|(i, table)| {
+            let rows = self.rows.get(&(table.schema.clone(), table.name.clone()));
+            let mut line = row![checkbox(rows.is_some())
+                .label(format!("{}.{}", table.schema, table.name))
+                .text_size(12)
+                .on_toggle(move |selected| SampleMessage::Table(i, selected))
+                .width(Fill)]
+            .spacing(8)
+            .align_y(Center);
+            if let Some(rows) = rows {
+                line = line.push(
+                    text_input("Rows", rows)
+                        .size(12)
+                        .width(70)
+                        .on_input(move |rows| SampleMessage::Rows(i, rows)),
+                );
+            }
+            line.into()
+        }))
+        .spacing(4);
+        let tables = column![all, scrollable(tables).height(Fill)]
+            .spacing(8)
+            .width(FillPortion(1));
+
+        let mut output = column![text_editor(&self.output)
+            .font(Font::with_name("JetBrainsMono NF"))
+            .height(Fill)
+            .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+            .on_action(SampleMessage::Action)]
+        .spacing(8)
+        .width(FillPortion(3));
+        for note in &self.notes {
+            output = output.push(text(note).size(12).style(text::warning));
+        }
+
+        let mut page = column![header, row![tables, output].spacing(16)].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+        page.into()
+    }
+}
@@ -8,6 +8,7 @@ use title::{Title, TitleMessage};
 
 use crate::{
     constants,
+    model::PgVersion,
     utils::{line, svg_button, Line, SvgButton, SvgButtonStyle},
 };
 
@@ -160,7 +161,7 @@ impl<'a> Header<'a> {
         }
     }
 
-    pub fn view(&self) -> Element<HeaderMessage> {
+    pub fn view(&self, target: PgVersion) -> Element<HeaderMessage> {
         let vertical_line = Line {
             size: 24.0,
             thick: 0.7,
@@ -170,7 +171,7 @@ impl<'a> Header<'a> {
 
         container(
             row![
-                self.title.view().map(HeaderMessage::Title),
+                self.title.view(target).map(HeaderMessage::Title),
                 container(
                     row![
                         svg_button(self.undo.svg_path, self.undo.style.clone()),
@@ -1,13 +1,17 @@
 use std::cell::Cell;
 
 use iced::{
-    widget::{column, text_input},
+    widget::{column, pick_list, row, text_input},
+    Alignment::Center,
     Border, Color, Element,
     Length::Fill,
     Padding, Task, Theme,
 };
 
-use crate::utils::{self, Line};
+use crate::{
+    model::PgVersion,
+    utils::{self, Line},
+};
 
 pub struct Title {
     name: String,
@@ -21,6 +25,8 @@ pub struct Title {
 pub enum TitleMessage {
     NameInput(String),
     VersionInput(String),
+    /// Handled by the owner of the project.
+    Target(PgVersion),
 }
 
 impl Title {
@@ -42,10 +48,11 @@ impl Title {
                 self.version = message;
                 Task::none()
             }
+            TitleMessage::Target(_) => Task::none(),
         }
     }
 
-    pub fn view(&self) -> Element<TitleMessage> {
+    pub fn view(&self, target: PgVersion) -> Element<TitleMessage> {
         let input_name = text_input("Untitled project*", &self.name)
             .id("title_name")
             .on_input(TitleMessage::NameInput)
@@ -97,7 +104,11 @@ impl Title {
             angle: 0.0.into(),
         };
 
-        let mut col_name = column![input_name,];
+        let target = pick_list(PgVersion::ALL, Some(target), TitleMessage::Target)
+            .text_size(12)
+            .padding([2, 6]);
+
+        let mut col_name = column![row![input_name, target].spacing(8).align_y(Center)];
         if self.show_name_btm_line.get() {
             col_name = col_name.push(utils::line(line));
         }
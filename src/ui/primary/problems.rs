@@ -0,0 +1,129 @@
+use iced::{
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input},
+    Alignment::Center,
+    Element,
+    Length::Fill,
+};
+
+use crate::{
+    model::{
+        naming::{Case, Number, Template},
+        Conventions,
+    },
+    sql::validate::{Problem, Severity, Subject},
+};
+
+#[derive(Clone, Debug)]
+pub enum ProblemsMessage {
+    Toggle,
+    /// Handled by the owner of the selection.
+    Select(Subject),
+    /// Handled by the owner of the project.
+    Conventions(ConventionsMessage),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConventionsMessage {
+    Enforce(bool),
+    Case(Case),
+    Number(Number),
+    TablePrefix(String),
+    TableSuffix(String),
+    Template(Template, String),
+    /// Rename everything that breaks the conventions.
+    Apply,
+}
+
+/// A summary line that expands into the naming conventions and the list of
+/// problems. Problems on an object select it when clicked.
+pub fn view<'a>(
+    problems: &'a [Problem],
+    conventions: Option<&'a Conventions>,
+    expanded: bool,
+) -> Element<'a, ProblemsMessage> {
+    let count = |severity: Severity| problems.iter().filter(|p| p.severity == severity).count();
+    let summary = if problems.is_empty() {
+        String::from("No problems")
+    } else {
+        format!(
+            "Problems: {} errors, {} warnings, {} notices",
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Notice)
+        )
+    };
+    let toggle = button(text(summary).size(12))
+        .style(button::text)
+        .on_press(ProblemsMessage::Toggle);
+
+    let mut panel = column![toggle];
+    if expanded {
+        panel = panel.push(conventions_view(conventions).map(ProblemsMessage::Conventions));
+    }
+    if expanded && !problems.is_empty() {
+        let list = column(problems.iter().map(|problem| {
+            let line = text(format!("{:<8}{problem}", problem.severity.to_string()))
+                .size(12)
+                .style(match problem.severity {
+                    Severity::Error => text::danger,
+                    Severity::Warning => text::warning,
+                    Severity::Notice => text::default,
+                });
+            button(line)
+                .width(Fill)
+                .padding([2, 8])
+                .style(button::text)
+                .on_press_maybe(problem.subject.map(ProblemsMessage::Select))
+                .into()
+        }));
+        panel = panel.push(container(scrollable(list).width(Fill)).max_height(200));
+    }
+    container(panel).padding([4, 12]).width(Fill).into()
+}
+
+fn conventions_view(conventions: Option<&Conventions>) -> Element<'_, ConventionsMessage> {
+    let enforce = checkbox(conventions.is_some())
+        .label("Naming conventions")
+        .text_size(12)
+        .on_toggle(ConventionsMessage::Enforce);
+    let Some(conventions) = conventions else {
+        return container(enforce).padding([4, 8]).into();
+    };
+
+    let names = row![
+        enforce,
+        pick_list(Case::ALL, Some(conventions.case), ConventionsMessage::Case).text_size(12),
+        pick_list(
+            Number::ALL,
+            Some(conventions.number),
+            ConventionsMessage::Number
+        )
+        .text_size(12),
+        text_input("Table prefix", &conventions.table_prefix)
+            .size(12)
+            .width(100)
+            .on_input(ConventionsMessage::TablePrefix),
+        text_input("Table suffix", &conventions.table_suffix)
+            .size(12)
+            .width(100)
+            .on_input(ConventionsMessage::TableSuffix),
+        button(text("Apply conventions").size(12)).on_press(ConventionsMessage::Apply),
+    ]
+    .spacing(12)
+    .align_y(Center);
+
+    let templates = row(Template::ALL.into_iter().map(|template| {
+        column![
+            text(template.to_string()).size(11),
+            text_input("Keep names", conventions.template(template))
+                .size(12)
+                .on_input(move |value| ConventionsMessage::Template(template, value)),
+        ]
+        .spacing(2)
+        .width(Fill)
+        .into()
+    }))
+    .spacing(12);
+
+    column![names, templates].spacing(6).padding([4, 8]).into()
+}
@@ -0,0 +1,455 @@
+use std::{fmt, path::PathBuf};
+
+use iced::{
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_editor},
+    Alignment::Center,
+    Element, Font,
+    Length::{Fill, FillPortion},
+    Task,
+};
+
+use super::content::Selection;
+use crate::{
+    model::Project,
+    sql::{
+        ddl::{self, Options},
+        diff::{self, Change, Difference},
+        parse::{self, Skipped},
+        Qualify, Quoting,
+    },
+    ui::output,
+    utils::{self, FileError},
+};
+
+/// The Script page: DDL for the project or part of it. The DDL of a table or
+/// a schema can also be edited and merged back into the model.
+#[derive(Default)]
+pub struct Script {
+    scope: Scope,
+    /// Schema shown when the scope is [`Scope::Schema`].
+    schema: Option<String>,
+    options: Options,
+    ddl: text_editor::Content,
+    edit: Option<Edit>,
+    status: Option<String>,
+}
+
+/// DDL being edited, and the part of the model it was generated from.
+struct Edit {
+    original: Project,
+    buffer: text_editor::Content,
+    /// What applying the buffer would do, until it is edited again.
+    preview: Option<Preview>,
+}
+
+struct Preview {
+    edited: Project,
+    differences: Vec<Difference>,
+    skipped: Vec<Skipped>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scope {
+    #[default]
+    Project,
+    /// The tables and views drawn on the diagram.
+    Diagram,
+    Schema,
+    Selection,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [
+        Scope::Project,
+        Scope::Diagram,
+        Scope::Schema,
+        Scope::Selection,
+    ];
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scope::Project => "Whole project",
+            Scope::Diagram => "Diagram",
+            Scope::Schema => "Schema",
+            Scope::Selection => "Selection",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ScriptMessage {
+    Scope(Scope),
+    Schema(String),
+    IfNotExists(bool),
+    Drop(bool),
+    Comments(bool),
+    Quoting(Quoting),
+    Qualify(Qualify),
+    Action(text_editor::Action),
+    Copy,
+    Save,
+    Saved(Result<PathBuf, FileError>),
+    Edit,
+    EditAction(text_editor::Action),
+    Preview,
+    Apply,
+    Discard,
+}
+
+impl Script {
+    pub fn update(
+        &mut self,
+        message: ScriptMessage,
+        project: &mut Project,
+        selection: Option<Selection>,
+    ) -> Task<ScriptMessage> {
+        match message {
+            ScriptMessage::Scope(scope) => self.scope = scope,
+            ScriptMessage::Schema(schema) => self.schema = Some(schema),
+            ScriptMessage::IfNotExists(if_not_exists) => self.options.if_not_exists = if_not_exists,
+            ScriptMessage::Drop(drop) => self.options.drop = drop,
+            ScriptMessage::Comments(comments) => self.options.comments = comments,
+            ScriptMessage::Quoting(quoting) => self.options.quoting = quoting,
+            ScriptMessage::Qualify(qualify) => self.options.qualify = qualify,
+            ScriptMessage::Action(action) => {
+                output::read_only_action(&mut self.ddl, action);
+                return Task::none();
+            }
+            ScriptMessage::Copy => return iced::clipboard::write(self.ddl.text()),
+            ScriptMessage::Save => {
+                return Task::perform(
+                    utils::save_file(String::from("tableforge_ddl.sql"), self.ddl.text()),
+                    ScriptMessage::Saved,
+                );
+            }
+            ScriptMessage::Saved(result) => {
+                self.status = output::saved_status(result);
+                return Task::none();
+            }
+            ScriptMessage::Edit => {
+                if self.editable(selection) {
+                    let original = editable(subset(
+                        project,
+                        self.scope,
+                        self.schema.as_deref(),
+                        selection,
+                    ));
+                    // DROP statements would not survive the round trip.
+                    let options = Options {
+                        drop: false,
+                        ..self.options
+                    };
+                    self.edit = Some(Edit {
+                        buffer: text_editor::Content::with_text(&ddl::script(&original, &options)),
+                        original,
+                        preview: None,
+                    });
+                    self.status = None;
+                }
+                return Task::none();
+            }
+            ScriptMessage::EditAction(action) => {
+                if let Some(edit) = &mut self.edit {
+                    if action.is_edit() {
+                        edit.preview = None;
+                    }
+                    edit.buffer.perform(action);
+                }
+                return Task::none();
+            }
+            ScriptMessage::Preview => {
+                if let Some(edit) = &mut self.edit {
+                    let parsed = parse::script(&edit.buffer.text());
+                    let mut edited = parsed.project;
+                    edited.version = project.version;
+                    let mut schemas: Vec<String> = edited
+                        .schemas()
+                        .into_iter()
+                        .chain(edit.original.schemas())
+                        .map(String::from)
+                        .collect();
+                    schemas.sort();
+                    schemas.dedup();
+                    edit.preview = Some(Preview {
+                        differences: diff::compare(&edited, &edit.original, &schemas),
+                        edited,
+                        skipped: parsed.skipped,
+                    });
+                }
+                return Task::none();
+            }
+            ScriptMessage::Apply => {
+                let Some(edit) = self.edit.take_if(|edit| edit.preview.is_some()) else {
+                    return Task::none();
+                };
+                let preview = edit.preview.unwrap();
+                project.replace(&edit.original, preview.edited);
+                self.status = Some(format!(
+                    "Applied {} changes to the model",
+                    preview.differences.len()
+                ));
+            }
+            ScriptMessage::Discard => self.edit = None,
+        }
+        self.refresh(project, selection);
+        Task::none()
+    }
+
+    /// Generates the script again. The page is not told about edits made
+    /// elsewhere, so this also has to happen whenever it is shown.
+    pub fn refresh(&mut self, project: &Project, selection: Option<Selection>) {
+        if self.schema.is_none() {
+            self.schema = project.schemas().first().map(|s| s.to_string());
+        }
+        let subset = subset(project, self.scope, self.schema.as_deref(), selection);
+        self.ddl = text_editor::Content::with_text(&ddl::script(&subset, &self.options));
+    }
+
+    /// Only a table or a schema can be edited, which is what scripts are
+    /// read back for.
+    fn editable(&self, selection: Option<Selection>) -> bool {
+        match self.scope {
+            Scope::Schema => self.schema.is_some(),
+            Scope::Selection => matches!(selection, Some(Selection::Table(_))),
+            Scope::Project | Scope::Diagram => false,
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        project: &'a Project,
+        selection: Option<Selection>,
+    ) -> Element<'a, ScriptMessage> {
+        if let Some(edit) = &self.edit {
+            return edit_view(edit);
+        }
+
+        let mut header = row![
+            text("Script").size(14).width(Fill),
+            pick_list(Scope::ALL, Some(self.scope), ScriptMessage::Scope),
+        ]
+        .spacing(8)
+        .align_y(Center);
+        if self.scope == Scope::Schema {
+            header = header.push(pick_list(
+                project
+                    .schemas()
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>(),
+                self.schema.clone(),
+                ScriptMessage::Schema,
+            ));
+        }
+        header = header
+            .push(
+                button("Edit")
+                    .on_press_maybe(self.editable(selection).then_some(ScriptMessage::Edit)),
+            )
+            .push(button("Copy").on_press(ScriptMessage::Copy))
+            .push(button("Save").on_press(ScriptMessage::Save));
+
+        let options = row![
+            checkbox(self.options.if_not_exists)
+                .label("IF NOT EXISTS")
+                .on_toggle(ScriptMessage::IfNotExists),
+            checkbox(self.options.drop)
+                .label("DROP first")
+                .on_toggle(ScriptMessage::Drop),
+            checkbox(self.options.comments)
+                .label("Comments")
+                .on_toggle(ScriptMessage::Comments),
+            pick_list(
+                Quoting::ALL,
+                Some(self.options.quoting),
+                ScriptMessage::Quoting
+            ),
+            pick_list(
+                Qualify::ALL,
+                Some(self.options.qualify),
+                ScriptMessage::Qualify
+            ),
+        ]
+        .spacing(16)
+        .align_y(Center);
+
+        let ddl = text_editor(&self.ddl)
+            .font(Font::with_name("JetBrainsMono NF"))
+            .height(Fill)
+            .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+            .on_action(ScriptMessage::Action);
+
+        let mut page = column![header, options, ddl].spacing(12);
+        if let Some(status) = &self.status {
+            page = page.push(text(status).size(12));
+        }
+
+        container(page).padding(24).width(Fill).height(Fill).into()
+    }
+}
+
+/// The buffer, and once previewed, the changes it makes to the model and the
+/// statements that could not be read.
+fn edit_view(edit: &Edit) -> Element<'_, ScriptMessage> {
+    let header = row![
+        text("Edit script").size(14).width(Fill),
+        button("Preview").on_press(ScriptMessage::Preview),
+        button("Apply").on_press_maybe(edit.preview.is_some().then_some(ScriptMessage::Apply)),
+        button("Discard").on_press(ScriptMessage::Discard),
+    ]
+    .spacing(8)
+    .align_y(Center);
+
+    let buffer = text_editor(&edit.buffer)
+        .font(Font::with_name("JetBrainsMono NF"))
+        .height(FillPortion(3))
+        .highlight("sql", iced::highlighter::Theme::Base16Ocean)
+        .on_action(ScriptMessage::EditAction);
+
+    let preview: Element<ScriptMessage> = match &edit.preview {
+        None => text(
+            "Preview the changes to apply them. Triggers, policies and privileges \
+             are kept as they are.",
+        )
+        .size(12)
+        .into(),
+        Some(preview) => {
+            let mut lines = column![].spacing(4);
+            if preview.differences.is_empty() {
+                lines = lines.push(text("No changes.").size(12));
+            }
+            for difference in &preview.differences {
+                lines = lines.push(change(difference, 0));
+                for child in &difference.children {
+                    lines = lines.push(change(child, 1));
+                }
+            }
+            for skipped in &preview.skipped {
+                lines = lines.push(
+                    text(format!(
+                        "Left out: {} ({})",
+                        skipped.statement.lines().next().unwrap_or_default(),
+                        skipped.reason
+                    ))
+                    .size(12)
+                    .style(text::danger),
+                );
+            }
+            container(scrollable(lines).width(Fill))
+                .padding(8)
+                .height(FillPortion(2))
+                .style(container::bordered_box)
+                .into()
+        }
+    };
+
+    container(column![header, buffer, preview].spacing(12))
+        .padding(24)
+        .width(Fill)
+        .height(Fill)
+        .into()
+}
+
+fn change(difference: &Difference, depth: u16) -> Element<'_, ScriptMessage> {
+    let change = match difference.change() {
+        Change::OnlyInModel => "+",
+        Change::OnlyInDatabase => "-",
+        Change::Changed => "~",
+    };
+    container(
+        text(format!(
+            "{change} {} {}",
+            difference.object, difference.name
+        ))
+        .size(12),
+    )
+    .padding([0, 24 * depth])
+    .into()
+}
+
+/// The part of `project` that `scope` covers, as a project of its own.
+/// Schemas follow from the objects, while roles and extensions only come
+/// with the whole project or when selected.
+fn subset(
+    project: &Project,
+    scope: Scope,
+    schema: Option<&str>,
+    selection: Option<Selection>,
+) -> Project {
+    let mut subset = Project {
+        version: project.version,
+        ..Project::default()
+    };
+    match scope {
+        Scope::Project => return project.clone(),
+        Scope::Diagram => {
+            subset.tables = project.tables.clone();
+            subset.views = project.views.clone();
+        }
+        Scope::Schema => {
+            let Some(schema) = schema else {
+                return subset;
+            };
+            subset.tables = project
+                .tables
+                .iter()
+                .filter(|t| t.schema == schema)
+                .cloned()
+                .collect();
+            subset.types = project
+                .types
+                .iter()
+                .filter(|t| t.schema == schema)
+                .cloned()
+                .collect();
+            subset.views = project
+                .views
+                .iter()
+                .filter(|v| v.schema == schema)
+                .cloned()
+                .collect();
+            subset.routines = project
+                .routines
+                .iter()
+                .filter(|r| r.schema == schema)
+                .cloned()
+                .collect();
+            subset.sequences = project
+                .sequences
+                .iter()
+                .filter(|s| s.schema == schema)
+                .cloned()
+                .collect();
+        }
+        Scope::Selection => match selection {
+            Some(Selection::Table(i)) => subset.tables.extend(project.tables.get(i).cloned()),
+            Some(Selection::Type(i)) => subset.types.extend(project.types.get(i).cloned()),
+            Some(Selection::View(i)) => subset.views.extend(project.views.get(i).cloned()),
+            Some(Selection::Routine(i)) => subset.routines.extend(project.routines.get(i).cloned()),
+            Some(Selection::Sequence(i)) => {
+                subset.sequences.extend(project.sequences.get(i).cloned())
+            }
+            Some(Selection::Role(i)) => subset.roles.extend(project.roles.get(i).cloned()),
+            None => {}
+        },
+    }
+    subset
+}
+
+/// `subset` without what scripts are not read back for: routines, roles,
+/// extensions, and the triggers and security of tables.
+fn editable(mut subset: Project) -> Project {
+    subset.routines.clear();
+    subset.roles.clear();
+    subset.extensions.clear();
+    for table in &mut subset.tables {
+        table.triggers.clear();
+        table.row_security = false;
+        table.force_row_security = false;
+        table.policies.clear();
+        table.grants.clear();
+    }
+    subset
+}
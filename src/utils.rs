@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use iced::{
     mouse,
     padding::all,
@@ -254,3 +256,109 @@ fn get_handle<'a>(svg_path: &'a str) -> Handle {
         svg_path
     ));
 }
+
+#[derive(Clone, Debug)]
+pub enum FileError {
+    Cancelled,
+    Io(std::io::ErrorKind),
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Cancelled => f.write_str("cancelled"),
+            FileError::Io(kind) => write!(f, "{kind}"),
+        }
+    }
+}
+
+pub async fn save_file(file_name: String, contents: String) -> Result<PathBuf, FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .ok_or(FileError::Cancelled)?;
+    let path = handle.path().to_path_buf();
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| FileError::Io(e.kind()))?;
+    Ok(path)
+}
+
+/// Asks for a file with one of `extensions` and reads it as text.
+pub async fn open_file(
+    filter: &'static str,
+    extensions: &'static [&'static str],
+) -> Result<(PathBuf, String), FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .add_filter(filter, extensions)
+        .pick_file()
+        .await
+        .ok_or(FileError::Cancelled)?;
+    let path = handle.path().to_path_buf();
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| FileError::Io(e.kind()))?;
+    Ok((path, contents))
+}
+
+/// Asks for a directory and lists the names of what is in it.
+pub async fn open_folder() -> Result<(PathBuf, Vec<String>), FileError> {
+    let handle = rfd::AsyncFileDialog::new()
+        .pick_folder()
+        .await
+        .ok_or(FileError::Cancelled)?;
+    let path = handle.path().to_path_buf();
+    let mut entries = tokio::fs::read_dir(&path)
+        .await
+        .map_err(|e| FileError::Io(e.kind()))?;
+    let mut names = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| FileError::Io(e.kind()))?
+    {
+        names.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    Ok((path, names))
+}
+
+/// Asks for a directory and reads the files in it whose names end with
+/// `extension`, sorted by name.
+pub async fn read_folder(
+    extension: &'static str,
+) -> Result<(PathBuf, Vec<(String, String)>), FileError> {
+    let (folder, mut names) = open_folder().await?;
+    names.retain(|name| name.ends_with(extension));
+    names.sort();
+    let mut files = Vec::with_capacity(names.len());
+    for name in names {
+        let contents = tokio::fs::read_to_string(folder.join(&name))
+            .await
+            .map_err(|e| FileError::Io(e.kind()))?;
+        files.push((name, contents));
+    }
+    Ok((folder, files))
+}
+
+/// Writes `files`, given by their path under `folder`, creating the
+/// directories they need.
+pub async fn write_files(
+    folder: PathBuf,
+    files: Vec<(String, String)>,
+) -> Result<Vec<PathBuf>, FileError> {
+    let mut written = Vec::with_capacity(files.len());
+    for (name, contents) in files {
+        let path = folder.join(name);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| FileError::Io(e.kind()))?;
+        }
+        tokio::fs::write(&path, contents)
+            .await
+            .map_err(|e| FileError::Io(e.kind()))?;
+        written.push(path);
+    }
+    Ok(written)
+}